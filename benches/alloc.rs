@@ -0,0 +1,68 @@
+//! The allocations of the lookups, with a counting allocator: `t!` of a literal key without
+//! arguments returns the borrowed translation, so it must not allocate.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    borrow::Cow,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rust_i18n::t;
+
+rust_i18n::i18n!("./tests/locales", fallback = "en");
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// The allocations of 1000 calls of `f`, after a first call initializing the backend.
+fn allocations<T>(f: impl Fn() -> T) -> usize {
+    criterion::black_box(f());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..1000 {
+        criterion::black_box(f());
+    }
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn assert_no_allocations<'a>(name: &str, f: impl Fn() -> Cow<'a, str>) {
+    assert!(matches!(f(), Cow::Borrowed(_)), "{name} isn't borrowed");
+    let allocations = allocations(f);
+    assert_eq!(allocations, 0, "{name} allocates");
+    println!("{name}: no allocations");
+}
+
+fn bench_alloc(c: &mut Criterion) {
+    rust_i18n::set_locale("en");
+    assert_no_allocations("t", || t!("hello"));
+    assert_no_allocations("t_with_locale", || t!("hello", locale = "zh-CN"));
+    assert_no_allocations("t_lorem_ipsum", || t!("lorem-ipsum"));
+    assert_no_allocations("t_territory_fallback", || {
+        t!("messages.zero", locale = "zh-HK")
+    });
+    assert_no_allocations("_rust_i18n_translate", || {
+        crate::_rust_i18n_translate("en", "hello")
+    });
+
+    c.bench_function("t_no_alloc", |b| b.iter(|| t!("hello")));
+    c.bench_function("t_with_locale_no_alloc", |b| {
+        b.iter(|| t!("hello", locale = "zh-CN"))
+    });
+}
+
+criterion_group!(benches, bench_alloc);
+criterion_main!(benches);
@@ -101,7 +101,7 @@ pub fn bench_t(c: &mut Criterion) {
                 city = "Litteltown",
                 zip = 8408,
                 website = "https://snoopy-napkin.name"
-            );
+            )
         })
     });
 }
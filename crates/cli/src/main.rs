@@ -1,9 +1,28 @@
 use anyhow::Error;
-use clap::{Args, Parser};
-use rust_i18n_extract::extractor::Message;
-use rust_i18n_extract::{extractor, generator, iter};
-use rust_i18n_support::{I18nConfig, MinifyKey};
-use std::{collections::HashMap, path::Path};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rust_i18n_extract::cache::{self, ExtractCache};
+use rust_i18n_extract::checker::{Missing, Report, Translations};
+use rust_i18n_extract::converter::{FileFormat, Layout};
+use rust_i18n_extract::extractor::{ConstKeys, DynamicKey, Message};
+use rust_i18n_extract::locale_file::{self, locale_files, LocaleFile};
+use rust_i18n_extract::memory::{self, Memory, FUZZY_COMMENT};
+use rust_i18n_extract::tms::{self, TmsProvider};
+use rust_i18n_extract::translator::{self, TranslationProvider};
+use rust_i18n_extract::{
+    checker, converter, differ, editor, extractor, generator, glossary, iter, keygen, pruner,
+    renamer, sarif, scaffold, spreadsheet, stats, template, tool_config, validator, webgen,
+};
+use rust_i18n_support::{
+    load_locales, try_load_locales_with_conflicts, write_bundle, Conflict, I18nConfig, MinifyKey,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by `--no-cache`, for all the extractions of the command.
+static NO_CACHE: AtomicBool = AtomicBool::new(false);
+/// Set by `--dry-run`, the extraction cache is read but not written.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
 
 #[derive(Parser)]
 #[command(name = "cargo")]
@@ -13,16 +32,19 @@ enum CargoCli {
 }
 
 #[derive(Args)]
-#[command(author, version)]
+#[command(author, version, args_conflicts_with_subcommands = true)]
 // #[command(propagate_version = true)]
 /// Rust I18n command to help you extract all untranslated texts from source code.
 ///
 /// It will iterate all Rust files in the source directory and extract all untranslated texts
 /// that used `t!` macro.
-/// Then it will generate a YAML file and merge with the existing translations.
+/// Then it will add them into the locale files, with the text of the base locale if any,
+/// marked with a `# TODO: translate` comment.
 ///
 /// https://github.com/longbridge/rust-i18n
 struct I18nArgs {
+    #[command(subcommand)]
+    command: Option<Commands>,
     /// Manually add a translation to the localization file.
     ///
     /// This is useful for non-literal values in the `t!` macro.
@@ -34,11 +56,561 @@ struct I18nArgs {
     /// NOTE: The whitespace before and after the key and value will be trimmed.
     #[arg(short, long, default_value = None, name = "TEXT", num_args(1..), value_parser = translate_value_parser, verbatim_doc_comment)]
     translate: Option<Vec<(String, String)>>,
+    /// Watch the Rust files in the source path, and extract again when they change
+    #[arg(short, long)]
+    watch: bool,
+    /// Extract every member of the workspace, into the `load-path` of each member
+    #[arg(long, conflicts_with = "watch")]
+    workspace: bool,
+    /// With `--workspace`, merge the texts of all members into the `load-path` of the workspace root
+    #[arg(long, requires = "workspace")]
+    shared: bool,
+    /// Also print the untranslated texts with their locations in source, as JSON or SARIF
+    #[arg(short, long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+    /// Write the untranslated texts into `TODO.yml`, instead of adding them into the
+    /// locale files of each locale with a `# TODO: translate` comment
+    #[arg(long, verbatim_doc_comment)]
+    todo_file: bool,
+    /// List the files to create or modify and the keys to add or remove, without writing them
+    #[arg(long)]
+    dry_run: bool,
+    /// Add comments like `# src/main.rs:42` above the keys used in source in the YAML
+    /// locale files, to show where each text is used, updated on each run
+    #[arg(long, verbatim_doc_comment)]
+    locations: bool,
+    /// Also report the `t!` calls whose key isn't a string literal, e.g. `t!(key)`,
+    /// their keys can't be extracted
+    #[arg(long, conflicts_with = "watch", verbatim_doc_comment)]
+    deny_dynamic_keys: bool,
+    /// The number of threads to parse the Rust files, default is the number of CPUs
+    #[arg(short, long, global = true)]
+    jobs: Option<usize>,
+    /// Parse every Rust file, without reading or writing the extraction cache in
+    /// `target/rust-i18n` of the workspace
+    #[arg(long, global = true, verbatim_doc_comment)]
+    no_cache: bool,
     /// Extract all untranslated I18n texts from source code
     #[arg(default_value = "./", last = true)]
     source: Option<String>,
 }
 
+#[derive(Subcommand)]
+enum Commands {
+    /// Check the translations are complete, exit with non-zero status if any is missing.
+    ///
+    /// Reports keys used in source but missing in each locale, keys missing relative
+    /// to the base locale (the first fallback locale, or the default locale), and keys
+    /// defined in more than one locale file with different texts.
+    #[command(verbatim_doc_comment)]
+    Check(CheckArgs),
+    /// Print the count and percentage of translated keys in each locale.
+    Stats(StatsArgs),
+    /// Remove the keys no longer used in source from the locale files.
+    ///
+    /// NOTE: Keys only used with dynamic values, e.g. `t!(format!("..."))`, can't be
+    /// extracted and will be removed too, unless matched by `dynamic-keys` in the config,
+    /// use `--dry-run` or `cargo i18n unused` to review first.
+    #[command(verbatim_doc_comment)]
+    Prune(PruneArgs),
+    /// List the keys of the locale files not used in source, grouped by file.
+    ///
+    /// Unlike `prune`, no file is changed. Keys built at runtime can be allowed with
+    /// `dynamic-keys` in the config, or `--allow`, e.g. `--allow "errors.*"`.
+    #[command(verbatim_doc_comment)]
+    Unused(UnusedArgs),
+    /// Format the locale files with sorted keys and consistent nesting.
+    Fmt(FmtArgs),
+    /// Fill the missing translations from the base locale with a machine translation provider.
+    ///
+    /// The translations are written into `machine-translated.yml` in the locales path,
+    /// with a comment above each key to mark it for review.
+    ///
+    /// The API key is read from `DEEPL_API_KEY`, `GOOGLE_TRANSLATE_API_KEY` or `OPENAI_API_KEY`.
+    ///
+    /// With `--from-memory`, the texts are first filled from the translations of identical
+    /// base locale texts in the catalog or a TMX file, written into the locale files.
+    /// The fuzzy matches of similar texts are marked for review.
+    #[command(verbatim_doc_comment)]
+    Translate(TranslateArgs),
+    /// Upload the base locale texts to a translation management system.
+    ///
+    /// The credentials are read from environment variables, see the README.
+    #[command(verbatim_doc_comment)]
+    Push(PushArgs),
+    /// Download the translations from a translation management system into the locale files.
+    ///
+    /// The new and changed texts are written like `cargo i18n set`, the empty texts are skipped.
+    #[command(verbatim_doc_comment)]
+    Pull(PullArgs),
+    /// Convert the locale files to another format or layout.
+    ///
+    /// PO and XLIFF files are written one file per locale, the key is used as the `msgid`
+    /// or the `trans-unit` id.
+    #[command(verbatim_doc_comment)]
+    Convert(ConvertArgs),
+    /// Export the translations for translators, one row per key with a column per locale
+    /// and the source locations.
+    #[command(verbatim_doc_comment)]
+    Export(ExportArgs),
+    /// Import the translations from a CSV exported by `cargo i18n export`.
+    ///
+    /// The texts are merged into the locale files, keeping the untouched keys.
+    /// An existing text different from the imported one is reported as a conflict
+    /// and kept, unless `--overwrite` is used.
+    #[command(verbatim_doc_comment)]
+    Import(ImportArgs),
+    /// List the added, removed and changed keys of each locale, compared with another
+    /// git revision or a directory of locale files.
+    #[command(verbatim_doc_comment)]
+    Diff(DiffArgs),
+    /// Check the texts of each locale use the same `%{...}` placeholders as the base locale,
+    /// exit with non-zero status if any is missing or unknown.
+    ///
+    /// Also check the texts are not longer than the `_max_length` of their key in version 2
+    /// files, or than `max-length-percent` in the config of the base locale text length.
+    ///
+    /// With `--glossary`, also check the translations use the approved terms of the glossary.
+    #[command(verbatim_doc_comment)]
+    Validate(ValidateArgs),
+    /// Set up a new crate with a `locales` directory, a starter `app.yml` and
+    /// the `[package.metadata.i18n]` config, then print the `i18n!` snippet to add.
+    ///
+    /// The existing files are kept untouched.
+    #[command(verbatim_doc_comment)]
+    Init(InitArgs),
+    /// Rename a key in all locale files, and the literal keys of the `t!` calls in source.
+    RenameKey(RenameKeyArgs),
+    /// Restructure the locale files between one file per locale (v1) and all locales
+    /// in one file with `_version: 2` (v2).
+    ///
+    /// The files already in the layout are kept untouched.
+    #[command(verbatim_doc_comment)]
+    Migrate(MigrateArgs),
+    /// Generate the config of an external tool matching the locale files and the config.
+    ///
+    /// For `i18n-ally`, writes `.vscode/i18n-ally-custom-framework.yml` and the `i18n-ally.*`
+    /// entries of `.vscode/settings.json`.
+    #[command(verbatim_doc_comment)]
+    GenConfig(GenConfigArgs),
+    /// Generate a Rust module with a constant (or an enum variant) for each key in the
+    /// locale files, to check the keys at compile time, e.g. `t!(i18n_keys::MESSAGES_TITLE)`.
+    #[command(verbatim_doc_comment)]
+    GenKeys(GenKeysArgs),
+    /// Generate the translations for a web frontend, a JSON file for each locale and
+    /// `keys.ts` with the `Keys` and `Locale` union types, to check the keys used in
+    /// TypeScript, e.g. `t("messages.title" satisfies Keys)`.
+    #[command(verbatim_doc_comment)]
+    GenWeb(GenWebArgs),
+    /// Write the translations into a binary bundle, loaded by `BundleBackend` without parsing
+    /// or copying them, e.g. embedded with `include_bytes!` instead of the locale files.
+    #[command(verbatim_doc_comment)]
+    Bundle(BundleArgs),
+    /// Step through the untranslated texts of a locale in the terminal, showing the text
+    /// of the base locale, its placeholders and where it's used, and write each translation
+    /// into the locale files.
+    ///
+    /// The untranslated texts are the keys missing in the locale, and the keys marked with
+    /// `# TODO: translate`. Press Enter to skip a text, `:q` to quit.
+    #[command(verbatim_doc_comment)]
+    Edit(EditArgs),
+    /// Print the text of a key, in one locale or in all locales.
+    ///
+    /// With `--locale`, only the text is printed, for scripts. Exit with non-zero status
+    /// if the key is not found.
+    #[command(verbatim_doc_comment)]
+    Get(GetArgs),
+    /// Set the text of a key in a locale, in the locale file already containing it,
+    /// keeping its nesting and format, or in the locale file of new keys.
+    #[command(verbatim_doc_comment)]
+    Set(SetArgs),
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ReportFormat::Text)]
+    format: ReportFormat,
+    /// Also report the `t!` calls whose key isn't a string literal, e.g. `t!(key)`
+    #[arg(long)]
+    deny_dynamic_keys: bool,
+    /// Path of the crate to check
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct StatsArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct PruneArgs {
+    /// List the unused keys without changing the locale files
+    #[arg(long)]
+    dry_run: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct UnusedArgs {
+    /// Pattern of the keys built at runtime, in addition to `dynamic-keys` in the config,
+    /// `*` matches any text, can be repeated
+    #[arg(long, value_name = "PATTERN")]
+    allow: Vec<String>,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct FmtArgs {
+    /// Check the locale files are formatted, exit with non-zero status if not
+    #[arg(long)]
+    check: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct TranslateArgs {
+    /// The machine translation provider
+    #[arg(short, long, value_enum, required_unless_present = "from_memory")]
+    provider: Option<Provider>,
+    /// The locales to translate to, default is all available locales
+    #[arg(long, value_delimiter = ',')]
+    to: Vec<String>,
+    /// Fill the texts from the translations of identical or similar base locale texts
+    #[arg(long)]
+    from_memory: bool,
+    /// A TMX file to use as translation memory too
+    #[arg(long, value_name = "PATH", requires = "from_memory")]
+    tmx: Option<String>,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct PushArgs {
+    /// The translation management system
+    #[arg(short, long, value_enum)]
+    provider: Tms,
+    /// Also upload the translations of the other locales
+    #[arg(long)]
+    translations: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct PullArgs {
+    /// The translation management system
+    #[arg(short, long, value_enum)]
+    provider: Tms,
+    /// The locales to download, default is all available locales except the base locale
+    #[arg(long, value_delimiter = ',')]
+    to: Vec<String>,
+    /// List the changed texts without changing the locale files
+    #[arg(long)]
+    dry_run: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Tms {
+    Crowdin,
+    Lokalise,
+    Poeditor,
+    Weblate,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Provider {
+    Deepl,
+    Google,
+    Openai,
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    /// The format of the locale files to read
+    #[arg(long, value_enum)]
+    from: ConvertFormat,
+    /// The format to write
+    #[arg(long, value_enum)]
+    to: ConvertFormat,
+    /// The layout of YAML, JSON and TOML files, default is the layout of the source files
+    #[arg(long, value_enum)]
+    layout: Option<ConvertLayout>,
+    /// The directory to read the files, default is the locales path
+    #[arg(short, long)]
+    input: Option<String>,
+    /// The directory to write the files, default is the locales path
+    #[arg(short, long)]
+    output: Option<String>,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ConvertFormat {
+    Yaml,
+    Json,
+    Toml,
+    Po,
+    Xliff,
+    Properties,
+    Strings,
+    Android,
+    Csv,
+}
+
+impl From<ConvertFormat> for FileFormat {
+    fn from(format: ConvertFormat) -> Self {
+        match format {
+            ConvertFormat::Yaml => FileFormat::Yaml,
+            ConvertFormat::Json => FileFormat::Json,
+            ConvertFormat::Toml => FileFormat::Toml,
+            ConvertFormat::Po => FileFormat::Po,
+            ConvertFormat::Xliff => FileFormat::Xliff,
+            ConvertFormat::Properties => FileFormat::Properties,
+            ConvertFormat::Strings => FileFormat::Strings,
+            ConvertFormat::Android => FileFormat::Android,
+            ConvertFormat::Csv => FileFormat::Csv,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ConvertLayout {
+    /// One file per locale
+    V1,
+    /// All locales in one file, with `_version: 2`
+    V2,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// Export format
+    #[arg(short, long, value_enum, default_value_t = ExportFormat::Csv)]
+    format: ExportFormat,
+    /// The file to write, default is stdout
+    #[arg(short, long)]
+    output: Option<String>,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Csv,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    /// The CSV file to import
+    file: String,
+    /// Overwrite the existing texts different from the imported ones
+    #[arg(long)]
+    overwrite: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// A git revision, e.g. `main` or `v1.0.0`, or a directory of locale files to compare with
+    base: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct ValidateArgs {
+    /// The maximum length of a translation, in percent of the base locale text length,
+    /// overriding `max-length-percent` in the config
+    #[arg(long, value_name = "PERCENT")]
+    max_length_percent: Option<usize>,
+    /// Check the translations use the approved terms of the glossary, relative to the crate,
+    /// default: glossary.yml
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = glossary::GLOSSARY_FILENAME)]
+    glossary: Option<String>,
+    /// Path of the crate to validate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct InitArgs {
+    /// The available locales, the first one is the default locale
+    #[arg(short, long, value_delimiter = ',', default_value = "en")]
+    locales: Vec<String>,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct RenameKeyArgs {
+    /// The key to rename
+    from: String,
+    /// The new key
+    to: String,
+    /// List the changes without writing the files
+    #[arg(long)]
+    dry_run: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct MigrateArgs {
+    /// The layout to migrate to
+    #[arg(long, value_enum)]
+    to: ConvertLayout,
+    /// List the changes without writing the files
+    #[arg(long)]
+    dry_run: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct GenConfigArgs {
+    /// The tool to generate the config for
+    #[arg(long, value_enum)]
+    tool: Tool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Tool {
+    I18nAlly,
+}
+
+#[derive(Args)]
+struct GenKeysArgs {
+    /// The file to write, relative to the crate
+    #[arg(short, long, default_value = "src/i18n_keys.rs")]
+    out: PathBuf,
+    /// Generate constants or an enum
+    #[arg(long, value_enum, default_value_t = KeysStyle::Const)]
+    style: KeysStyle,
+    /// Exit with non-zero status if the file is out of date, without writing it
+    #[arg(long)]
+    check: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct GenWebArgs {
+    /// The directory to write, relative to the crate
+    #[arg(short, long, default_value = "web/src/i18n")]
+    out: PathBuf,
+    /// Exit with non-zero status if a file is out of date, without writing them
+    #[arg(long)]
+    check: bool,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct BundleArgs {
+    /// The file to write, relative to the crate
+    #[arg(short, long, default_value = "locales.bin")]
+    out: PathBuf,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct EditArgs {
+    /// The locale to translate
+    #[arg(short, long)]
+    locale: String,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct GetArgs {
+    /// The key, e.g. `messages.title`
+    key: String,
+    /// Only print the text in this locale
+    #[arg(short, long)]
+    locale: Option<String>,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Args)]
+struct SetArgs {
+    /// The key, e.g. `messages.title`
+    key: String,
+    /// The new text
+    text: String,
+    /// The locale of the text
+    #[arg(short, long)]
+    locale: String,
+    /// Path of the crate
+    #[arg(default_value = "./", last = true)]
+    source: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum KeysStyle {
+    /// `pub const MESSAGES_TITLE: &str = "messages.title";`
+    Const,
+    /// `pub enum Key { MessagesTitle }` with `Key::as_str`
+    Enum,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    Text,
+    Json,
+    /// SARIF 2.1.0, for CI systems and code review bots to annotate the files
+    Sarif,
+}
+
 /// Remove quotes from a string at the start and end.
 fn remove_quotes(s: &str) -> &str {
     let mut start = 0;
@@ -96,38 +668,1501 @@ fn add_translations(
     }
 }
 
+/// Extract all messages used by `t!` in the source path.
+fn extract_messages(
+    source_path: &str,
+    cfg: &I18nConfig,
+) -> Result<HashMap<String, Message>, Error> {
+    let mut cache = open_cache(source_path);
+    let mut results = cache::extract_crate(source_path, cfg, &mut cache)?;
+    if DRY_RUN.load(Ordering::Relaxed) {
+        // Nothing is written with `--dry-run`, the cache included
+    } else if let Err(err) = cache.save() {
+        eprintln!("Failed to write the extraction cache: {}", err);
+    }
+    iter::iter_templates(source_path, &cfg.templates, &cfg.ignore, |path, source| {
+        template::extract(&mut results, path, source, cfg);
+        Ok(())
+    })?;
+
+    Ok(results)
+}
+
+/// Collect the string constants in the Rust files of the crate, to resolve the `t!(NAME)` keys.
+fn collect_const_keys(source_path: &str, cfg: &I18nConfig) -> Result<ConstKeys, Error> {
+    cache::collect_const_keys(source_path, cfg, &mut open_cache(source_path))
+}
+
+/// The extraction cache of the crate, disabled by `--no-cache`.
+fn open_cache(source_path: &str) -> ExtractCache {
+    if NO_CACHE.load(Ordering::Relaxed) {
+        ExtractCache::disabled()
+    } else {
+        ExtractCache::load(source_path)
+    }
+}
+
+/// Find the `t!` calls with a key that isn't a string literal in the Rust files of the crate.
+fn find_dynamic_keys(source_path: &str, cfg: &I18nConfig) -> Result<Vec<DynamicKey>, Error> {
+    let consts = collect_const_keys(source_path, cfg)?;
+    let keys = iter::par_iter_crate(source_path, &cfg.ignore, |path, source| {
+        extractor::find_dynamic_keys(path, source, &consts)
+    })?;
+    Ok(keys.into_iter().flatten().collect())
+}
+
+fn print_dynamic_keys(keys: &[DynamicKey]) {
+    for key in keys {
+        println!(
+            "dynamic key `{}` {}:{}",
+            key.expr,
+            key.location.file.display(),
+            key.location.line
+        );
+    }
+    if !keys.is_empty() {
+        eprintln!(
+            "\nFound {} keys that aren't string literals, they can't be verified by extraction.",
+            keys.len()
+        );
+    }
+}
+
+/// Load the existing translations of the crate, except the untranslated texts.
+fn load_translations(source_path: &str, cfg: &I18nConfig) -> Translations {
+    let locales_path = Path::new(source_path).join(&cfg.load_path);
+    load_locales(&locales_path.display().to_string(), |fname| {
+        fname.ends_with(generator::TODO_FILENAME)
+    })
+}
+
+/// Load the existing translations like [`load_translations`], with the keys defined
+/// in more than one file with different texts.
+fn load_translations_with_conflicts(
+    source_path: &str,
+    cfg: &I18nConfig,
+) -> Result<(Translations, Vec<Conflict>), Error> {
+    let locales_path = Path::new(source_path).join(&cfg.load_path);
+    try_load_locales_with_conflicts(
+        &locales_path.display().to_string(),
+        |fname| fname.ends_with(generator::TODO_FILENAME),
+        false,
+    )
+    .map_err(Error::msg)
+}
+
+fn check(args: CheckArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let results = extract_messages(&args.source, &cfg)?;
+    let (translations, conflicts) = load_translations_with_conflicts(&args.source, &cfg)?;
+
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let mut report = checker::check(&results, &translations, &cfg.available_locales, base_locale);
+    let dynamic_keys = if args.deny_dynamic_keys {
+        find_dynamic_keys(&args.source, &cfg)?
+    } else {
+        vec![]
+    };
+    let has_problems = !report.is_empty() || !conflicts.is_empty() || !dynamic_keys.is_empty();
+
+    if args.format != ReportFormat::Text {
+        let locales_path = Path::new(&args.source).join(&cfg.load_path);
+        let locations = locale_file::text_locations(&locales_path)?;
+        let locations_of = |locale: &str, key: &str| {
+            locations
+                .get(&(locale.to_string(), key.to_string()))
+                .cloned()
+                .unwrap_or_default()
+        };
+        for missing in &mut report.missing_from_base {
+            missing.locations = locations_of(base_locale, &missing.key);
+        }
+
+        if args.format == ReportFormat::Json {
+            let mut value = serde_json::to_value(&report)?;
+            value["conflicts"] = conflicts
+                .iter()
+                .map(|conflict| {
+                    let definitions = conflict
+                        .definitions
+                        .iter()
+                        .map(|(file, text)| serde_json::json!({ "file": file, "text": text }))
+                        .collect::<Vec<_>>();
+                    serde_json::json!({
+                        "locale": conflict.locale,
+                        "key": conflict.key,
+                        "definitions": definitions,
+                        "winner": conflict.winner().0,
+                    })
+                })
+                .collect();
+            if args.deny_dynamic_keys {
+                value["dynamic_keys"] = serde_json::to_value(&dynamic_keys)?;
+            }
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        } else {
+            let mut findings = sarif::findings(&report);
+            findings.extend(conflicts.iter().map(|conflict| sarif::Finding {
+                rule_id: sarif::CONFLICTING_TRANSLATION,
+                message: format!(
+                    "[{}] conflict \"{}\" (using {})",
+                    conflict.locale,
+                    conflict.key,
+                    conflict.winner().0.display()
+                ),
+                locations: locations_of(&conflict.locale, &conflict.key),
+            }));
+            findings.extend(sarif::dynamic_key_findings(&dynamic_keys));
+            let log = sarif::to_sarif(&findings, Path::new(&args.source));
+            println!("{}", serde_json::to_string_pretty(&log)?);
+        }
+
+        if has_problems {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    for missing in &report.missing {
+        let locations = missing
+            .locations
+            .iter()
+            .map(|l| format!("{}:{}", l.file.display(), l.line))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "[{}] missing \"{}\" {}",
+            missing.locale, missing.key, locations
+        );
+    }
+    for missing in &report.missing_from_base {
+        println!(
+            "[{}] missing \"{}\" (exists in {})",
+            missing.locale, missing.key, base_locale
+        );
+    }
+    for conflict in &conflicts {
+        let definitions = conflict
+            .definitions
+            .iter()
+            .map(|(file, text)| format!("{} {:?}", file.display(), text))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "[{}] conflict \"{}\" {} (using {})",
+            conflict.locale,
+            conflict.key,
+            definitions,
+            conflict.winner().0.display()
+        );
+    }
+
+    if !report.is_empty() {
+        eprintln!(
+            "\nFound {} missing translations.",
+            report.missing.len() + report.missing_from_base.len()
+        );
+    }
+    if !conflicts.is_empty() {
+        eprintln!(
+            "\nFound {} keys defined with different texts.",
+            conflicts.len()
+        );
+    }
+    print_dynamic_keys(&dynamic_keys);
+    if has_problems {
+        std::process::exit(1);
+    }
+
+    println!("All translations are complete.");
+    Ok(())
+}
+
+fn stats(args: StatsArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let results = extract_messages(&args.source, &cfg)?;
+    let translations = load_translations(&args.source, &cfg);
+
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let stats = stats::stats(&results, &translations, &cfg.available_locales, base_locale);
+
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+        OutputFormat::Text => {
+            let width = stats
+                .iter()
+                .map(|s| s.locale.len())
+                .max()
+                .unwrap_or_default()
+                .max("Locale".len());
+            println!(
+                "{:<width$}  {:>10}  {:>7}  {:>8}",
+                "Locale", "Translated", "Missing", "Coverage"
+            );
+            for s in &stats {
+                println!(
+                    "{:<width$}  {:>10}  {:>7}  {:>7.1}%",
+                    s.locale,
+                    format!("{}/{}", s.translated, s.total),
+                    s.missing,
+                    s.percentage
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn prune(args: PruneArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let results = extract_messages(&args.source, &cfg)?;
+
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+    let mut files = locale_files(&locales_path)
+        .iter()
+        .map(LocaleFile::load)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut allow = cfg.dynamic_keys.clone();
+    allow.extend(pruner::referenced_keys(&files));
+
+    let mut count = 0;
+    for file in &mut files {
+        let unused = pruner::prune(&results, file, &allow);
+        if unused.is_empty() {
+            continue;
+        }
+
+        for key in &unused {
+            println!("{}: \"{}\"", file.path.display(), key);
+        }
+        count += unused.len();
+
+        if !args.dry_run {
+            file.save()?;
+        }
+    }
+
+    if count == 0 {
+        println!("No unused keys found.");
+    } else if args.dry_run {
+        eprintln!("\nFound {} unused keys.", count);
+    } else {
+        eprintln!("\nRemoved {} unused keys.", count);
+    }
+
+    Ok(())
+}
+
+fn unused(args: UnusedArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let results = extract_messages(&args.source, &cfg)?;
+
+    let locale_files = locale_files(Path::new(&args.source).join(&cfg.load_path))
+        .iter()
+        .map(LocaleFile::load)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut allow = cfg.dynamic_keys.clone();
+    allow.extend(args.allow);
+    allow.extend(pruner::referenced_keys(&locale_files));
+
+    let mut files = BTreeMap::new();
+    for file in &locale_files {
+        let unused = pruner::unused(&results, file, &allow);
+        if !unused.is_empty() {
+            files.insert(file.path.display().to_string(), unused);
+        }
+    }
+
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&files)?),
+        OutputFormat::Text => {
+            if files.is_empty() {
+                println!("No unused keys found.");
+                return Ok(());
+            }
+            for (path, keys) in &files {
+                println!("{}", path);
+                for key in keys {
+                    println!("  {}", key);
+                }
+            }
+            let count = files.values().map(Vec::len).sum::<usize>();
+            eprintln!(
+                "\nFound {} unused keys in {} files, run `cargo i18n prune` to remove them.",
+                count,
+                files.len()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn fmt(args: FmtArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+    let mut unformatted = 0;
+    for path in locale_files(&locales_path) {
+        let content = std::fs::read_to_string(&path)?;
+        let mut file = LocaleFile::parse(&path, &content)?;
+        file.normalize();
+        if file.to_text()? == content {
+            continue;
+        }
+
+        unformatted += 1;
+        if args.check {
+            println!("{} is not formatted", path.display());
+        } else {
+            file.save()?;
+            println!("Formatted {}", path.display());
+        }
+    }
+
+    if args.check && unformatted > 0 {
+        eprintln!("\nRun `cargo i18n fmt` to format {} files.", unformatted);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn translate(args: TranslateArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let mut translations = load_translations(&args.source, &cfg);
+
+    let provider: Option<Box<dyn TranslationProvider>> = match args.provider {
+        Some(Provider::Deepl) => Some(Box::new(translator::DeepL::from_env()?)),
+        Some(Provider::Google) => Some(Box::new(translator::Google::from_env()?)),
+        Some(Provider::Openai) => Some(Box::new(translator::OpenAi::from_env()?)),
+        None => None,
+    };
+
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let locales = if args.to.is_empty() {
+        &cfg.available_locales
+    } else {
+        &args.to
+    };
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+
+    let mut filled = 0;
+    if args.from_memory {
+        let mut memory = Memory::new();
+        memory.add_translations(&translations, base_locale);
+        if let Some(tmx) = &args.tmx {
+            memory.load_tmx(tmx, base_locale, locales)?;
+        }
+
+        let result = memory::fill_missing(&memory, &translations, base_locale, locales);
+        for (trs, comment) in [(&result.exact, None), (&result.fuzzy, Some(FUZZY_COMMENT))] {
+            locale_file::apply_translations(&locales_path, trs, comment)?;
+            for (locale, trs) in trs {
+                filled += trs.len();
+                translations
+                    .entry(locale.clone())
+                    .or_default()
+                    .extend(trs.clone());
+            }
+        }
+        for locale in locales {
+            let exact = result.exact.get(locale).map_or(0, |trs| trs.len());
+            let fuzzy = result.fuzzy.get(locale).map_or(0, |trs| trs.len());
+            if exact + fuzzy > 0 {
+                println!(
+                    "[{}] filled {} texts from memory, {} fuzzy",
+                    locale,
+                    exact + fuzzy,
+                    fuzzy
+                );
+            }
+        }
+    }
+
+    let Some(provider) = provider else {
+        if filled == 0 {
+            println!("No translations found in memory.");
+        } else {
+            eprintln!(
+                "\nFilled {} texts from memory, please review the ones marked with `{}`.",
+                filled, FUZZY_COMMENT
+            );
+        }
+        return Ok(());
+    };
+
+    let result = translator::translate_missing(&*provider, &translations, base_locale, locales)?;
+
+    let count = result.values().map(|trs| trs.len()).sum::<usize>();
+    if count == 0 {
+        println!("All translations are complete.");
+        return Ok(());
+    }
+
+    for (locale, trs) in &result {
+        println!("[{}] translated {} texts", locale, trs.len());
+    }
+
+    translator::write_machine_translations(&locales_path, &result)?;
+    eprintln!(
+        "\nWrote {} machine translations to {}, please review them.",
+        count,
+        locales_path
+            .join(translator::MACHINE_TRANSLATED_FILENAME)
+            .display()
+    );
+
+    Ok(())
+}
+
+fn tms_provider(tms: Tms) -> Result<Box<dyn TmsProvider>, Error> {
+    match tms {
+        #[cfg(feature = "crowdin")]
+        Tms::Crowdin => Ok(Box::new(tms::Crowdin::from_env()?)),
+        #[cfg(feature = "lokalise")]
+        Tms::Lokalise => Ok(Box::new(tms::Lokalise::from_env()?)),
+        #[cfg(feature = "poeditor")]
+        Tms::Poeditor => Ok(Box::new(tms::Poeditor::from_env()?)),
+        #[cfg(feature = "weblate")]
+        Tms::Weblate => Ok(Box::new(tms::Weblate::from_env()?)),
+        #[allow(unreachable_patterns)]
+        tms => Err(Error::msg(format!(
+            "cargo-i18n is built without the `{}` feature",
+            tms.to_possible_value().unwrap().get_name()
+        ))),
+    }
+}
+
+fn push(args: PushArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+    let provider = tms_provider(args.provider)?;
+
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let Some(base_texts) = translations.get(base_locale) else {
+        eprintln!("No texts found in the base locale {}.", base_locale);
+        std::process::exit(1);
+    };
+    provider.push(base_locale, base_texts, true)?;
+    println!("[{}] pushed {} source texts", base_locale, base_texts.len());
+
+    if args.translations {
+        for locale in &cfg.available_locales {
+            let Some(texts) = translations.get(locale).filter(|_| locale != base_locale) else {
+                continue;
+            };
+            provider.push(locale, texts, false)?;
+            println!("[{}] pushed {} texts", locale, texts.len());
+        }
+    }
+
+    Ok(())
+}
+
+fn pull(args: PullArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+    let provider = tms_provider(args.provider)?;
+
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let locales = if args.to.is_empty() {
+        &cfg.available_locales
+    } else {
+        &args.to
+    };
+
+    let mut changes = Translations::new();
+    for locale in locales {
+        if locale == base_locale {
+            continue;
+        }
+        let texts = tms::pulled_changes(&translations, locale, provider.pull(locale)?);
+        println!("[{}] {} new or changed texts", locale, texts.len());
+        if args.dry_run {
+            for key in texts.keys() {
+                println!("  {}", key);
+            }
+        }
+        if !texts.is_empty() {
+            changes.insert(locale.clone(), texts);
+        }
+    }
+
+    let count = changes.values().map(|texts| texts.len()).sum::<usize>();
+    if count == 0 {
+        println!("All translations are up to date.");
+    } else if args.dry_run {
+        eprintln!("\nWould update {} texts.", count);
+    } else {
+        let locales_path = Path::new(&args.source).join(&cfg.load_path);
+        let paths = locale_file::apply_translations(&locales_path, &changes, None)?;
+        eprintln!("\nUpdated {} texts in {} files.", count, paths.len());
+    }
+
+    Ok(())
+}
+
+fn convert(args: ConvertArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+    let input_path = args
+        .input
+        .map(PathBuf::from)
+        .unwrap_or(locales_path.clone());
+    let output_path = args.output.map(PathBuf::from).unwrap_or(locales_path);
+
+    let from = FileFormat::from(args.from);
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let translations = converter::read(&input_path, from, base_locale)?;
+    if translations.is_empty() {
+        eprintln!("No {:?} files found in {}", from, input_path.display());
+        std::process::exit(1);
+    }
+
+    let layout = match args.layout {
+        Some(ConvertLayout::V1) => Layout::V1,
+        Some(ConvertLayout::V2) => Layout::V2,
+        None => converter::detect_layout(&input_path, from),
+    };
+    let written = converter::write(
+        &output_path,
+        args.to.into(),
+        layout,
+        &translations,
+        base_locale,
+    )?;
+
+    for path in written {
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn export(args: ExportArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let results = extract_messages(&args.source, &cfg)?;
+    let translations = load_translations(&args.source, &cfg);
+
+    let writer: Box<dyn std::io::Write> = match &args.output {
+        Some(output) => Box::new(std::fs::File::create(output)?),
+        None => Box::new(std::io::stdout()),
+    };
+
+    match args.format {
+        ExportFormat::Csv => {
+            spreadsheet::export_csv(writer, &results, &translations, &cfg.available_locales)?
+        }
+    }
+
+    if let Some(output) = &args.output {
+        eprintln!("Exported to {}", output);
+    }
+
+    Ok(())
+}
+
+fn import(args: ImportArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+
+    let imported = spreadsheet::import_csv(std::fs::File::open(&args.file)?)?;
+    let (changes, conflicts) = spreadsheet::merge(&imported, &translations, args.overwrite);
+
+    for conflict in &conflicts {
+        println!(
+            "[{}] conflict \"{}\": {:?} => {:?}",
+            conflict.locale, conflict.key, conflict.current, conflict.imported
+        );
+    }
+
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+    for path in locale_file::apply_translations(&locales_path, &changes, None)? {
+        println!("Updated {}", path.display());
+    }
+
+    let count = changes.values().map(|trs| trs.len()).sum::<usize>();
+    eprintln!("\nImported {} texts.", count);
+    if !conflicts.is_empty() && !args.overwrite {
+        eprintln!(
+            "Kept {} existing texts with conflicts, use `--overwrite` to replace them.",
+            conflicts.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Run a git command in the directory, returns the stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<Vec<u8>, Error> {
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Write the locale files at a git revision into a temporary directory.
+fn checkout_locales(locales_path: &Path, rev: &str) -> Result<PathBuf, Error> {
+    let dir = std::env::temp_dir().join(format!("rust-i18n-diff-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // Paths are relative to the locales path
+    let files = git(locales_path, &["ls-tree", "-r", "--name-only", rev, "."])?;
+    for file in String::from_utf8_lossy(&files).lines() {
+        let content = git(locales_path, &["show", &format!("{}:./{}", rev, file)])?;
+        let path = dir.join(file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+    }
+    std::fs::create_dir_all(&dir)?;
+
+    Ok(dir)
+}
+
+fn diff(args: DiffArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+    let ignore_todo = |fname: &str| fname.ends_with(generator::TODO_FILENAME);
+
+    let base_path = Path::new(&args.base);
+    let base_translations = if base_path.is_dir() {
+        load_locales(&base_path.display().to_string(), ignore_todo)
+    } else {
+        let locales_path = Path::new(&args.source).join(&cfg.load_path);
+        let dir = checkout_locales(&locales_path, &args.base)?;
+        let trs = load_locales(&dir.display().to_string(), ignore_todo);
+        std::fs::remove_dir_all(&dir)?;
+        trs
+    };
+
+    let diffs = differ::diff(&base_translations, &translations);
+    match args.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diffs)?),
+        OutputFormat::Text => {
+            for diff in &diffs {
+                match &diff.change {
+                    differ::Change::Added { new } => {
+                        println!("[{}] + {}: {:?}", diff.locale, diff.key, new)
+                    }
+                    differ::Change::Removed { old } => {
+                        println!("[{}] - {}: {:?}", diff.locale, diff.key, old)
+                    }
+                    differ::Change::Changed { old, new } => {
+                        println!("[{}] ~ {}: {:?} => {:?}", diff.locale, diff.key, old, new)
+                    }
+                }
+            }
+            if diffs.is_empty() {
+                println!("No changes.");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate(args: ValidateArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+    let locations = locale_file::text_locations(&locales_path)?;
+
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let mismatches = validator::validate(&translations, base_locale);
+
+    for mismatch in &mismatches {
+        let location = locations
+            .get(&(mismatch.locale.clone(), mismatch.key.clone()))
+            .and_then(|locations| locations.last())
+            .map(|l| format!("{}:{} ", l.file.display(), l.line))
+            .unwrap_or_default();
+        let mut problems = mismatch
+            .missing
+            .iter()
+            .map(|name| format!("missing %{{{}}}", name))
+            .collect::<Vec<_>>();
+        for name in &mismatch.extra {
+            match mismatch.suggestion(name) {
+                Some(suggestion) => problems.push(format!(
+                    "unknown %{{{}}} (did you mean %{{{}}}?)",
+                    name, suggestion
+                )),
+                None => problems.push(format!("unknown %{{{}}}", name)),
+            }
+        }
+        println!(
+            "{}[{}] \"{}\" {}",
+            location,
+            mismatch.locale,
+            mismatch.key,
+            problems.join(", ")
+        );
+    }
+
+    let max_percent = args.max_length_percent.or(cfg.max_length_percent);
+    let max_lengths = locale_file::max_lengths(&locales_path)?;
+    let too_long = validator::check_lengths(&translations, base_locale, max_percent, &max_lengths);
+    for text in &too_long {
+        let location = locations
+            .get(&(text.locale.clone(), text.key.clone()))
+            .and_then(|locations| locations.last())
+            .map(|l| format!("{}:{} ", l.file.display(), l.line))
+            .unwrap_or_default();
+        let limit = if text.explicit {
+            format!("_max_length {}", text.max_length)
+        } else {
+            format!(
+                "{} ({}% of the base text)",
+                text.max_length,
+                max_percent.unwrap_or_default()
+            )
+        };
+        println!(
+            "{}[{}] \"{}\" too long, {} characters > {}",
+            location, text.locale, text.key, text.length, limit
+        );
+    }
+
+    let mut term_mismatches = vec![];
+    if let Some(path) = &args.glossary {
+        let glossary = glossary::load_glossary(Path::new(&args.source).join(path))?;
+        term_mismatches = glossary::check_terms(&translations, base_locale, &glossary);
+    }
+    for mismatch in &term_mismatches {
+        let location = locations
+            .get(&(mismatch.locale.clone(), mismatch.key.clone()))
+            .and_then(|locations| locations.last())
+            .map(|l| format!("{}:{} ", l.file.display(), l.line))
+            .unwrap_or_default();
+        let approved = mismatch
+            .approved
+            .iter()
+            .map(|term| format!("\"{}\"", term))
+            .collect::<Vec<_>>()
+            .join(" or ");
+        println!(
+            "{}[{}] \"{}\" should translate \"{}\" as {}",
+            location, mismatch.locale, mismatch.key, mismatch.term, approved
+        );
+    }
+
+    if !mismatches.is_empty() {
+        eprintln!(
+            "\nFound {} translations with mismatched placeholders.",
+            mismatches.len()
+        );
+    }
+    if !too_long.is_empty() {
+        eprintln!(
+            "\nFound {} translations longer than allowed.",
+            too_long.len()
+        );
+    }
+    if !term_mismatches.is_empty() {
+        eprintln!(
+            "\nFound {} translations not using the glossary terms.",
+            term_mismatches.len()
+        );
+    }
+    if !mismatches.is_empty() || !too_long.is_empty() || !term_mismatches.is_empty() {
+        std::process::exit(1);
+    }
+
+    println!("All placeholders are consistent with {}.", base_locale);
+    Ok(())
+}
+
+fn init(args: InitArgs) -> Result<(), Error> {
+    let load_path = "locales";
+    let result = scaffold::init(Path::new(&args.source), load_path, &args.locales)?;
+
+    for path in &result.written {
+        println!("Wrote {}", path.display());
+    }
+    for path in &result.skipped {
+        println!("Skipped {}, already set up", path.display());
+    }
+
+    println!(
+        "\nAdd this to your `lib.rs` or `main.rs`:\n\n{}",
+        scaffold::snippet(load_path, &args.locales[0])
+    );
+    Ok(())
+}
+
+fn rename_key(args: RenameKeyArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+
+    let mut count = 0;
+    for path in locale_files(&locales_path) {
+        let mut file = LocaleFile::load(&path)?;
+        if !renamer::rename_key(&mut file, &args.from, &args.to) {
+            continue;
+        }
+
+        println!("{}: \"{}\" => \"{}\"", path.display(), args.from, args.to);
+        count += 1;
+        if !args.dry_run {
+            file.save()?;
+        }
+    }
+
+    iter::iter_crate(&args.source, &cfg.ignore, |path, source| {
+        let Some((renamed, lines)) = renamer::rename_in_source(source, &args.from, &args.to) else {
+            return Ok(());
+        };
+
+        for line in &lines {
+            println!(
+                "{}:{}: \"{}\" => \"{}\"",
+                path.display(),
+                line,
+                args.from,
+                args.to
+            );
+        }
+        count += lines.len();
+        if !args.dry_run {
+            std::fs::write(path, renamed)?;
+        }
+        Ok(())
+    })?;
+
+    if count == 0 {
+        println!("Key \"{}\" not found.", args.from);
+    } else if args.dry_run {
+        eprintln!("\nFound {} places to rename.", count);
+    } else {
+        eprintln!("\nRenamed {} places.", count);
+    }
+
+    Ok(())
+}
+
+fn migrate(args: MigrateArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+    let layout = match args.to {
+        ConvertLayout::V1 => Layout::V1,
+        ConvertLayout::V2 => Layout::V2,
+    };
+
+    let migration = converter::migrate(&locales_path, layout)?;
+    for file in &migration.files {
+        println!("Write {}", file.path.display());
+        if !args.dry_run {
+            file.save()?;
+        }
+    }
+    for path in &migration.removed {
+        println!("Remove {}", path.display());
+        if !args.dry_run {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    if migration.files.is_empty() {
+        println!("All locale files are already in the layout.");
+    }
+
+    Ok(())
+}
+
+fn gen_keys(args: GenKeysArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let style = match args.style {
+        KeysStyle::Const => keygen::KeyStyle::Const,
+        KeysStyle::Enum => keygen::KeyStyle::Enum,
+    };
+
+    let code = keygen::generate_keys(&translations, base_locale, style);
+    let path = Path::new(&args.source).join(&args.out);
+    let up_to_date = std::fs::read_to_string(&path).is_ok_and(|existing| existing == code);
+
+    if args.check {
+        if !up_to_date {
+            eprintln!(
+                "{} is out of date, run `cargo i18n gen-keys` to update it.",
+                path.display()
+            );
+            std::process::exit(1);
+        }
+        println!("{} is up to date.", path.display());
+        return Ok(());
+    }
+
+    if up_to_date {
+        println!("{} is up to date.", path.display());
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, code)?;
+    println!("Write {}", path.display());
+    if let Some(name) = args.out.file_stem() {
+        eprintln!(
+            "\nAdd `mod {};` into `lib.rs` or `main.rs` to use it.",
+            name.to_string_lossy()
+        );
+    }
+
+    Ok(())
+}
+
+fn gen_web(args: GenWebArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+
+    let files = webgen::generate_web(&translations, base_locale);
+    let dir = Path::new(&args.source).join(&args.out);
+    let outdated = files
+        .iter()
+        .map(|(name, content)| (dir.join(name), content))
+        .filter(|(path, content)| {
+            !std::fs::read_to_string(path).is_ok_and(|existing| existing == **content)
+        })
+        .collect::<Vec<_>>();
+
+    if args.check {
+        if !outdated.is_empty() {
+            for (path, _) in &outdated {
+                eprintln!("{} is out of date.", path.display());
+            }
+            eprintln!("\nRun `cargo i18n gen-web` to update them.");
+            std::process::exit(1);
+        }
+        println!("{} is up to date.", dir.display());
+        return Ok(());
+    }
+
+    if outdated.is_empty() {
+        println!("{} is up to date.", dir.display());
+        return Ok(());
+    }
+    std::fs::create_dir_all(&dir)?;
+    for (path, content) in outdated {
+        std::fs::write(&path, content)?;
+        println!("Write {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn bundle(args: BundleArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+
+    let path = Path::new(&args.source).join(&args.out);
+    let bundle = write_bundle(&translations);
+    std::fs::write(&path, &bundle)?;
+    println!(
+        "Write {} ({} locales, {} bytes)",
+        path.display(),
+        translations.len(),
+        bundle.len()
+    );
+
+    Ok(())
+}
+
+fn edit(args: EditArgs) -> Result<(), Error> {
+    use std::io::{BufRead, Write};
+
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let results = extract_messages(&args.source, &cfg)?;
+    let translations = load_translations(&args.source, &cfg);
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let locale = &args.locale;
+
+    let entries =
+        editor::untranslated(&results, &translations, &locales_path, locale, base_locale)?;
+    if entries.is_empty() {
+        println!("All texts of [{}] are translated.", locale);
+        return Ok(());
+    }
+    println!(
+        "Found {} untranslated texts in [{}], type the translation and press Enter.",
+        entries.len(),
+        locale
+    );
+    println!("Press Enter to skip a text, `:q` to quit.");
+
+    let mut lines = std::io::stdin().lock().lines();
+    let mut saved = 0;
+    'entries: for (i, entry) in entries.iter().enumerate() {
+        println!("\n[{}/{}] {}", i + 1, entries.len(), entry.key);
+        if let Some(text) = &entry.base_text {
+            println!("  {}: {}", base_locale, text);
+        }
+        let placeholders = entry.placeholders();
+        if !placeholders.is_empty() {
+            let names = placeholders
+                .iter()
+                .map(|name| format!("%{{{}}}", name))
+                .collect::<Vec<_>>();
+            println!("  placeholders: {}", names.join(", "));
+        }
+        if !entry.locations.is_empty() {
+            let locations = entry
+                .locations
+                .iter()
+                .map(|l| format!("{}:{}", l.file.display(), l.line))
+                .collect::<Vec<_>>();
+            println!("  used at: {}", locations.join(", "));
+        }
+        if let Some(current) = &entry.current {
+            println!("  current: {}", current);
+        }
+
+        let mut rejected = None;
+        loop {
+            print!("{}> ", locale);
+            std::io::stdout().flush()?;
+            let Some(line) = lines.next() else {
+                break 'entries;
+            };
+            let text = line?.trim().to_string();
+            match text.as_str() {
+                "" => break,
+                ":q" => break 'entries,
+                _ => {}
+            }
+
+            // Saved anyway if typed again
+            let missing = entry.missing_placeholders(&text);
+            if !missing.is_empty() && rejected.as_ref() != Some(&text) {
+                let names = missing
+                    .iter()
+                    .map(|name| format!("%{{{}}}", name))
+                    .collect::<Vec<_>>();
+                println!(
+                    "  missing {}, type it again to save anyway",
+                    names.join(", ")
+                );
+                rejected = Some(text);
+                continue;
+            }
+
+            let path = editor::save_translation(&locales_path, locale, &entry.key, &text)?;
+            println!("  saved to {}", path.display());
+            saved += 1;
+            break;
+        }
+    }
+
+    println!("\nSaved {} of {} texts.", saved, entries.len());
+    Ok(())
+}
+
+fn get(args: GetArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let translations = load_translations(&args.source, &cfg);
+
+    if let Some(locale) = &args.locale {
+        match translations
+            .get(locale)
+            .and_then(|texts| texts.get(&args.key))
+        {
+            Some(text) => println!("{}", text),
+            None => {
+                eprintln!("Key \"{}\" not found in [{}].", args.key, locale);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    let mut found = false;
+    for (locale, texts) in &translations {
+        if let Some(text) = texts.get(&args.key) {
+            println!("[{}] {}", locale, text);
+            found = true;
+        }
+    }
+    if !found {
+        eprintln!("Key \"{}\" not found.", args.key);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn set(args: SetArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+
+    let path = editor::save_translation(&locales_path, &args.locale, &args.key, &args.text)?;
+    println!(
+        "{}: [{}] \"{}\" => {:?}",
+        path.display(),
+        args.locale,
+        args.key,
+        args.text
+    );
+
+    Ok(())
+}
+
+fn gen_config(args: GenConfigArgs) -> Result<(), Error> {
+    let cfg = I18nConfig::load(Path::new(&args.source))?;
+    let locales_path = Path::new(&args.source).join(&cfg.load_path);
+
+    match args.tool {
+        Tool::I18nAlly => {
+            let config = tool_config::i18n_ally(&cfg, &locales_path);
+            let vscode_path = Path::new(&args.source).join(".vscode");
+            std::fs::create_dir_all(&vscode_path)?;
+
+            let framework_path = vscode_path.join(tool_config::I18N_ALLY_FRAMEWORK_FILENAME);
+            std::fs::write(&framework_path, &config.framework)?;
+            println!("Wrote {}", framework_path.display());
+
+            let settings_path = vscode_path.join("settings.json");
+            let settings = match std::fs::read_to_string(&settings_path) {
+                Ok(content) => serde_json::from_str::<serde_json::Value>(&content).ok(),
+                Err(_) => Some(serde_json::json!({})),
+            };
+            match settings {
+                Some(serde_json::Value::Object(mut settings)) => {
+                    settings.extend(config.settings);
+                    std::fs::write(
+                        &settings_path,
+                        serde_json::to_string_pretty(&settings)? + "\n",
+                    )?;
+                    println!("Wrote {}", settings_path.display());
+                }
+                _ => {
+                    println!(
+                        "\nAdd these to {}:\n\n{}",
+                        settings_path.display(),
+                        serde_json::to_string_pretty(&config.settings)?
+                    );
+                }
+            }
+
+            for warning in &config.warnings {
+                eprintln!("warning: {}", warning);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn main() -> Result<(), Error> {
     let CargoCli::I18n(args) = CargoCli::parse();
 
-    let mut results = HashMap::new();
+    NO_CACHE.store(args.no_cache, Ordering::Relaxed);
+    DRY_RUN.store(
+        match &args.command {
+            Some(Commands::Prune(args)) => args.dry_run,
+            Some(Commands::Pull(args)) => args.dry_run,
+            Some(Commands::RenameKey(args)) => args.dry_run,
+            Some(Commands::Migrate(args)) => args.dry_run,
+            Some(_) => false,
+            None => args.dry_run,
+        },
+        Ordering::Relaxed,
+    );
+    if let Some(jobs) = args.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()?;
+    }
 
+    match args.command {
+        Some(Commands::Check(args)) => check(args),
+        Some(Commands::Stats(args)) => stats(args),
+        Some(Commands::Prune(args)) => prune(args),
+        Some(Commands::Unused(args)) => unused(args),
+        Some(Commands::Fmt(args)) => fmt(args),
+        Some(Commands::Translate(args)) => translate(args),
+        Some(Commands::Push(args)) => push(args),
+        Some(Commands::Pull(args)) => pull(args),
+        Some(Commands::Convert(args)) => convert(args),
+        Some(Commands::Export(args)) => export(args),
+        Some(Commands::Import(args)) => import(args),
+        Some(Commands::Diff(args)) => diff(args),
+        Some(Commands::Validate(args)) => validate(args),
+        Some(Commands::Init(args)) => init(args),
+        Some(Commands::RenameKey(args)) => rename_key(args),
+        Some(Commands::Migrate(args)) => migrate(args),
+        Some(Commands::GenConfig(args)) => gen_config(args),
+        Some(Commands::GenKeys(args)) => gen_keys(args),
+        Some(Commands::GenWeb(args)) => gen_web(args),
+        Some(Commands::Bundle(args)) => bundle(args),
+        Some(Commands::Edit(args)) => edit(args),
+        Some(Commands::Get(args)) => get(args),
+        Some(Commands::Set(args)) => set(args),
+        None => extract(args),
+    }
+}
+
+/// How to generate the untranslated texts.
+struct ExtractOptions {
+    /// The texts added with `--translate`.
+    translate: Vec<(String, String)>,
+    /// Write into `TODO.yml` instead of the locale files.
+    todo_file: bool,
+    /// Only list the changes.
+    dry_run: bool,
+    /// Update the source location comments.
+    locations: bool,
+}
+
+/// What the extraction found and changed.
+#[derive(Default)]
+struct Extraction {
+    /// The texts missing in each locale.
+    missing: Vec<Missing>,
+    /// The locale files written, or to write with `--dry-run`.
+    changes: Vec<generator::FileChange>,
+}
+
+impl Extraction {
+    fn extend(&mut self, other: Extraction) {
+        self.missing.extend(other.missing);
+        self.changes.extend(other.changes);
+    }
+
+    /// Print the count of the added and removed keys of each file.
+    fn print_summary(&self, dry_run: bool) {
+        if self.changes.is_empty() {
+            return;
+        }
+
+        let paths = self
+            .changes
+            .iter()
+            .map(|c| c.file.path.display().to_string())
+            .collect::<Vec<_>>();
+        let width = paths
+            .iter()
+            .map(|p| p.len())
+            .max()
+            .unwrap_or_default()
+            .max("File".len());
+        eprintln!(
+            "{:<width$}  {:<8}  {:>5}  {:>7}",
+            "File", "Status", "Added", "Removed"
+        );
+        for (path, change) in paths.iter().zip(&self.changes) {
+            let status = if change.created {
+                "created"
+            } else {
+                "modified"
+            };
+            eprintln!(
+                "{:<width$}  {:<8}  {:>5}  {:>7}",
+                path,
+                status,
+                change.added.len(),
+                change.removed.len()
+            );
+        }
+        eprintln!(
+            "{:<width$}  {:<8}  {:>5}  {:>7}",
+            "Total",
+            "",
+            self.changes.iter().map(|c| c.added.len()).sum::<usize>(),
+            self.changes.iter().map(|c| c.removed.len()).sum::<usize>()
+        );
+        if dry_run {
+            eprintln!("\nDry run, no file was written.");
+        }
+    }
+}
+
+fn extract(args: I18nArgs) -> Result<(), Error> {
     let source_path = args.source.expect("Missing source path");
+    let options = ExtractOptions {
+        translate: args.translate.unwrap_or_default(),
+        todo_file: args.todo_file,
+        dry_run: args.dry_run,
+        locations: args.locations,
+    };
 
-    let cfg = I18nConfig::load(std::path::Path::new(&source_path))?;
+    if args.watch {
+        return watch(&source_path, &options);
+    }
 
-    iter::iter_crate(&source_path, |path, source| {
-        extractor::extract(&mut results, path, source, cfg.clone())
-    })?;
+    let extraction = if args.workspace {
+        extract_workspace(&source_path, &options, args.shared)?
+    } else {
+        extract_once(&source_path, &options)?
+    };
+    extraction.print_summary(options.dry_run);
 
-    if let Some(list) = args.translate {
-        add_translations(&list, &mut results, &cfg);
+    let report = Report {
+        missing: extraction.missing,
+        ..Default::default()
+    };
+    let dynamic_keys = if args.deny_dynamic_keys {
+        let cfg = I18nConfig::load(Path::new(&source_path))?;
+        find_dynamic_keys(&source_path, &cfg)?
+    } else {
+        vec![]
+    };
+    match args.format {
+        ReportFormat::Text => print_dynamic_keys(&dynamic_keys),
+        ReportFormat::Json => {
+            let mut value = serde_json::to_value(&report)?;
+            if args.deny_dynamic_keys {
+                value["dynamic_keys"] = serde_json::to_value(&dynamic_keys)?;
+            }
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        ReportFormat::Sarif => {
+            let mut findings = sarif::findings(&report);
+            findings.extend(sarif::dynamic_key_findings(&dynamic_keys));
+            let log = sarif::to_sarif(&findings, Path::new(&source_path));
+            println!("{}", serde_json::to_string_pretty(&log)?);
+        }
     }
 
+    // The untranslated texts are expected here, `cargo i18n check` fails on them
+    if !dynamic_keys.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Extract the messages and generate the untranslated texts.
+fn extract_once(source_path: &str, options: &ExtractOptions) -> Result<Extraction, Error> {
+    let cfg = I18nConfig::load(std::path::Path::new(source_path))?;
+    let results = extract_messages(source_path, &cfg)?;
+    generate_untranslated(source_path, &cfg, results, options)
+}
+
+/// Extract each member of the workspace with its own config, or merge all members into
+/// the locales of the workspace root if `shared`.
+fn extract_workspace(
+    source_path: &str,
+    options: &ExtractOptions,
+    shared: bool,
+) -> Result<Extraction, Error> {
+    let members = iter::workspace_members(source_path)?;
+
+    if !shared {
+        let mut extraction = Extraction::default();
+        for member in &members {
+            eprintln!("Extracting {}\n", member.display());
+            extraction.extend(extract_once(&member.display().to_string(), options)?);
+        }
+        return Ok(extraction);
+    }
+
+    let cfg = I18nConfig::load(Path::new(source_path))?;
+    let mut results = HashMap::new();
+    for member in &members {
+        let member = member.display().to_string();
+        let member_cfg = I18nConfig::load(Path::new(&member))?;
+        extractor::merge_results(&mut results, extract_messages(&member, &member_cfg)?);
+    }
+    generate_untranslated(source_path, &cfg, results, options)
+}
+
+/// Generate the untranslated texts into the `load-path`, and list the changes of each file.
+fn generate_untranslated(
+    source_path: &str,
+    cfg: &I18nConfig,
+    mut results: HashMap<String, Message>,
+    options: &ExtractOptions,
+) -> Result<Extraction, Error> {
+    add_translations(&options.translate, &mut results, cfg);
+
     let mut messages: Vec<_> = results.iter().collect();
     messages.sort_by_key(|(_k, m)| m.index);
 
-    let mut has_error = false;
+    let output_path = Path::new(source_path).join(&cfg.load_path);
 
-    let output_path = Path::new(&source_path).join(&cfg.load_path);
+    // Checked before generating, the texts added into the locale files are still untranslated
+    let translations = load_translations(source_path, cfg);
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    let missing =
+        checker::check(&results, &translations, &cfg.available_locales, base_locale).missing;
 
-    let result = generator::generate(output_path, &cfg.available_locales, messages.clone());
-    if result.is_err() {
-        has_error = true;
+    let mut changes = if options.todo_file {
+        generator::plan_todo_file(&output_path, &cfg.available_locales, messages)?
+    } else {
+        generator::plan_locale_files(&output_path, &cfg.available_locales, base_locale, messages)?
+    };
+    if options.locations {
+        generator::plan_locations(&output_path, Path::new(source_path), &results, &mut changes)?;
     }
 
-    if has_error {
-        std::process::exit(1);
+    for change in &changes {
+        let action = match (options.dry_run, change.created) {
+            (true, true) => "Would create",
+            (true, false) => "Would modify",
+            (false, true) => "Create",
+            (false, false) => "Modify",
+        };
+        eprintln!("{} {}", action, change.file.path.display());
+        for (locale, key) in &change.added {
+            eprintln!("  + [{}] {}", locale, key);
+        }
+        for (locale, key) in &change.removed {
+            eprintln!("  - [{}] {}", locale, key);
+        }
+        if !options.dry_run {
+            change.file.save()?;
+        }
+    }
+    if !changes.is_empty() {
+        eprintln!();
     }
 
-    Ok(())
+    Ok(Extraction { missing, changes })
+}
+
+/// Re-run the extraction whenever a Rust file in the source path changes.
+fn watch(source_path: &str, options: &ExtractOptions) -> Result<(), Error> {
+    use notify::{RecursiveMode, Watcher};
+    use std::time::Duration;
+
+    let is_rust_file = |path: &Path| {
+        path.extension() == Some("rs".as_ref())
+            && !path.components().any(|c| c.as_os_str() == "target")
+    };
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(Path::new(source_path), RecursiveMode::Recursive)?;
+
+    loop {
+        match extract_once(source_path, options) {
+            Ok(extraction) => extraction.print_summary(options.dry_run),
+            Err(err) => eprintln!("{}", err),
+        }
+        eprintln!("Watching {} for changes...\n", source_path);
+
+        loop {
+            let event = rx.recv()??;
+            if event.paths.iter().any(|path| is_rust_file(path)) {
+                break;
+            }
+        }
+        // Wait for the burst of events from one save to finish
+        while rx.recv_timeout(Duration::from_millis(200)).is_ok() {}
+    }
 }
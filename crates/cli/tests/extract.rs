@@ -0,0 +1,66 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A crate with `t!("hello")` translated in `en` only, and `t!("bye")` untranslated.
+fn setup(name: &str, source: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust-i18n-cli-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::create_dir_all(dir.join("locales")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        r#"[package]
+name = "app"
+version = "0.1.0"
+
+[package.metadata.i18n]
+available-locales = ["en", "fr"]
+default-locale = "en"
+"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("src/lib.rs"), source).unwrap();
+    std::fs::write(dir.join("locales/en.yml"), "hello: Hello\n").unwrap();
+    dir
+}
+
+fn cargo_i18n(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cargo-i18n"))
+        .arg("i18n")
+        .arg("--no-cache")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_extract_with_untranslated_texts() {
+    let dir = setup("untranslated", r#"fn main() { t!("hello"); t!("bye"); }"#);
+
+    let dry_run = cargo_i18n(&dir, &["--dry-run"]);
+    let first = cargo_i18n(&dir, &[]);
+    let json = cargo_i18n(&dir, &["--format", "json"]);
+    let en = std::fs::read_to_string(dir.join("locales/en.yml")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(dry_run.status.success(), "{:?}", dry_run);
+    assert!(first.status.success(), "{:?}", first);
+    assert!(en.contains("bye"), "{}", en);
+    // Still untranslated on the next run
+    assert!(json.status.success(), "{:?}", json);
+    assert!(String::from_utf8_lossy(&json.stdout).contains("\"missing\""));
+}
+
+#[test]
+fn test_extract_with_deny_dynamic_keys() {
+    let dir = setup(
+        "dynamic",
+        r#"fn main() { let key = "hello"; t!("hello"); t!(key); }"#,
+    );
+
+    let output = cargo_i18n(&dir, &["--deny-dynamic-keys"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(1), "{:?}", output);
+}
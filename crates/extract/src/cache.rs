@@ -0,0 +1,256 @@
+//! Extract the Rust files of a crate in parallel, with an on-disk cache of each file keyed by
+//! its content hash, so the repeated runs only parse the changed files.
+use crate::extractor::{self, ConstKeys, Results};
+use crate::iter;
+use anyhow::Error;
+use rust_i18n_support::I18nConfig;
+use serde::{Deserialize, Serialize};
+use siphasher::sip128::{Hasher128, SipHasher13};
+use std::collections::BTreeMap;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+
+/// The version of the cache file, with the version of this crate, changing either drops the cache.
+const CACHE_VERSION: u32 = 1;
+
+/// The extraction of the Rust files of a crate, stored in
+/// `target/rust-i18n/extract-{hash of the crate path}.json` of the workspace.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExtractCache {
+    version: String,
+    /// The hash of the config and the crate constants the messages depend on.
+    context: String,
+    files: BTreeMap<PathBuf, CachedFile>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CachedFile {
+    hash: String,
+    consts: ConstKeys,
+    /// `None` when extracted with another context.
+    messages: Option<Results>,
+}
+
+impl ExtractCache {
+    /// A cache which is never read nor written, every file is parsed.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Load the cache of the crate in the source path, an invalid or outdated cache is dropped.
+    pub fn load(src_path: &str) -> Self {
+        let path = cache_path(src_path);
+        let cache = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|cache| cache.version == version())
+            .unwrap_or_default();
+        Self {
+            version: version(),
+            path: Some(path),
+            ..cache
+        }
+    }
+
+    /// The cache file, if enabled.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Write the cache file, if enabled.
+    pub fn save(&self) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The cached file with the same content hash.
+    fn get(&self, path: &Path, hash: &str) -> Option<&CachedFile> {
+        self.files.get(path).filter(|file| file.hash == hash)
+    }
+}
+
+/// Collect the string constants of the Rust files of the crate, like
+/// [`extractor::collect_const_keys`], in parallel and with the cache.
+pub fn collect_const_keys(
+    src_path: &str,
+    cfg: &I18nConfig,
+    cache: &mut ExtractCache,
+) -> Result<ConstKeys, Error> {
+    let files = iter::par_iter_crate(src_path, &cfg.ignore, |path, source| {
+        let hash = hash(source.as_bytes());
+        if let Some(file) = cache.get(path, &hash) {
+            return Ok((path.clone(), file.clone()));
+        }
+
+        let mut consts = ConstKeys::new();
+        // The files failing to parse are reported by the extraction
+        let _ = extractor::collect_const_keys(&mut consts, source);
+        let file = CachedFile {
+            hash,
+            consts,
+            messages: None,
+        };
+        Ok((path.clone(), file))
+    })?;
+
+    let mut consts = ConstKeys::new();
+    for (_, file) in &files {
+        extractor::merge_const_keys(&mut consts, file.consts.clone());
+    }
+    if cache.path.is_some() {
+        cache.files = files.into_iter().collect();
+    }
+    Ok(consts)
+}
+
+/// Extract the messages of the Rust files of the crate, like [`extractor::extract_with_consts`],
+/// in parallel, only parsing the files changed since the cached extraction.
+pub fn extract_crate(
+    src_path: &str,
+    cfg: &I18nConfig,
+    cache: &mut ExtractCache,
+) -> Result<Results, Error> {
+    let consts = collect_const_keys(src_path, cfg, cache)?;
+
+    let context = context_hash(cfg, &consts);
+    if cache.context != context {
+        cache.context = context;
+        for file in cache.files.values_mut() {
+            file.messages = None;
+        }
+    }
+
+    let files = iter::par_iter_crate(src_path, &cfg.ignore, |path, source| {
+        let hash = hash(source.as_bytes());
+        if let Some(messages) = cache
+            .get(path, &hash)
+            .and_then(|file| file.messages.clone())
+        {
+            return Ok((path.clone(), hash, messages));
+        }
+
+        let mut messages = Results::new();
+        extractor::extract_with_consts(&mut messages, path, source, cfg.clone(), &consts)?;
+        Ok((path.clone(), hash, messages))
+    })?;
+
+    let mut results = Results::new();
+    for (path, hash, messages) in files {
+        if let Some(file) = cache.files.get_mut(&path).filter(|file| file.hash == hash) {
+            file.messages = Some(messages.clone());
+        }
+        extractor::merge_results(&mut results, messages);
+    }
+    Ok(results)
+}
+
+fn version() -> String {
+    format!("{}-{}", CACHE_VERSION, env!("CARGO_PKG_VERSION"))
+}
+
+/// `target/rust-i18n/extract-{hash}.json` in the workspace root, the closest directory
+/// with a `Cargo.lock`, or `CARGO_TARGET_DIR`.
+fn cache_path(src_path: &str) -> PathBuf {
+    let src_path = Path::new(src_path);
+    let src_path = src_path
+        .canonicalize()
+        .unwrap_or_else(|_| src_path.to_path_buf());
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            src_path
+                .ancestors()
+                .find(|dir| dir.join("Cargo.lock").exists())
+                .unwrap_or(&src_path)
+                .join("target")
+        });
+    let name = &hash(src_path.to_string_lossy().as_bytes())[..16];
+    target_dir
+        .join("rust-i18n")
+        .join(format!("extract-{}.json", name))
+}
+
+/// The hash of the config and the constants the extraction of each file depends on.
+fn context_hash(cfg: &I18nConfig, consts: &ConstKeys) -> String {
+    let consts = consts.iter().collect::<BTreeMap<_, _>>();
+    let context = serde_json::json!({
+        "minify_key": cfg.minify_key,
+        "minify_key_len": cfg.minify_key_len,
+        "minify_key_prefix": cfg.minify_key_prefix,
+        "minify_key_thresh": cfg.minify_key_thresh,
+        "consts": consts,
+    });
+    hash(context.to_string().as_bytes())
+}
+
+fn hash(bytes: &[u8]) -> String {
+    let mut hasher = SipHasher13::new();
+    hasher.write(bytes);
+    format!("{:032x}", hasher.finish128().as_u128())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_crate() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-extract-cache");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("Cargo.lock"), "").unwrap();
+        std::fs::write(
+            dir.join("src/main.rs"),
+            "const TITLE: &str = \"title\";\nfn main() { t!(\"hello\"); }\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("src/view.rs"), "fn view() { t!(TITLE); }\n").unwrap();
+
+        let src_path = dir.display().to_string();
+        let cfg = I18nConfig::default();
+        let keys = |results: &Results| {
+            let mut keys = results.keys().cloned().collect::<Vec<_>>();
+            keys.sort();
+            keys
+        };
+
+        let expected = extract_crate(&src_path, &cfg, &mut ExtractCache::disabled()).unwrap();
+        assert_eq!(keys(&expected), vec!["hello", "title"]);
+
+        let mut cache = ExtractCache::load(&src_path);
+        assert_eq!(
+            extract_crate(&src_path, &cfg, &mut cache).unwrap(),
+            expected
+        );
+        cache.save().unwrap();
+        let path = cache.path().unwrap().to_path_buf();
+        assert!(path.starts_with(dir.canonicalize().unwrap().join("target/rust-i18n")));
+
+        let mut cache = ExtractCache::load(&src_path);
+        assert_eq!(cache.files.len(), 2);
+        assert!(cache.files.values().all(|file| file.messages.is_some()));
+        assert_eq!(
+            extract_crate(&src_path, &cfg, &mut cache).unwrap(),
+            expected
+        );
+
+        // A changed constant extracts the other files again
+        std::fs::write(
+            dir.join("src/main.rs"),
+            "const TITLE: &str = \"heading\";\nfn main() { t!(\"hello\"); }\n",
+        )
+        .unwrap();
+        let results = extract_crate(&src_path, &cfg, &mut cache).unwrap();
+        assert_eq!(keys(&results), vec!["heading", "hello"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
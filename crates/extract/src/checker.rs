@@ -0,0 +1,203 @@
+use crate::extractor::{Location, Results};
+use rust_i18n_support::{parse_interval, INTERVALS_KEY};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub type Translations = BTreeMap<String, BTreeMap<String, String>>;
+
+/// A key missing in a locale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Missing {
+    pub locale: String,
+    pub key: String,
+    /// Where the key is used in source, empty if it's missing relative to the base locale.
+    pub locations: Vec<Location>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Report {
+    /// Keys used in source but missing in a locale.
+    pub missing: Vec<Missing>,
+    /// Keys present in the base locale but missing in other locales.
+    pub missing_from_base: Vec<Missing>,
+}
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.missing_from_base.is_empty()
+    }
+}
+
+/// Whether the key has a text in the translations of a locale, or count intervals, e.g.
+/// `inbox.0` and `inbox.2..` for `t!("inbox", count = n)`.
+pub fn contains_key(trs: &BTreeMap<String, String>, key: &str) -> bool {
+    if trs.contains_key(key) || trs.contains_key(&format!("{}.{}", key, INTERVALS_KEY)) {
+        return true;
+    }
+
+    let prefix = format!("{}.", key);
+    trs.range(prefix.clone()..)
+        .take_while(|(k, _)| k.starts_with(&prefix))
+        .any(|(k, _)| parse_interval(&k[prefix.len()..]).is_some())
+}
+
+/// Check the extracted messages against the translations of each locale.
+///
+/// The `base_locale` is usually the first fallback locale or the default locale,
+/// all other locales should have the same keys as it.
+pub fn check(
+    results: &Results,
+    translations: &Translations,
+    locales: &[String],
+    base_locale: &str,
+) -> Report {
+    let mut report = Report::default();
+
+    let mut messages: Vec<_> = results.iter().collect();
+    messages.sort_by_key(|(_, m)| m.index);
+
+    for locale in locales {
+        let trs = translations.get(locale);
+        for (key, message) in &messages {
+            if trs.is_some_and(|trs| contains_key(trs, key)) {
+                continue;
+            }
+
+            report.missing.push(Missing {
+                locale: locale.clone(),
+                key: key.to_string(),
+                locations: message.locations.clone(),
+            });
+        }
+
+        if locale == base_locale {
+            continue;
+        }
+
+        if let Some(base_trs) = translations.get(base_locale) {
+            for key in base_trs.keys() {
+                if trs.is_some_and(|trs| contains_key(trs, key)) {
+                    continue;
+                }
+
+                report.missing_from_base.push(Missing {
+                    locale: locale.clone(),
+                    key: key.clone(),
+                    locations: vec![],
+                });
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::Message;
+    use std::collections::HashMap;
+
+    fn translations(data: &[(&str, &[&str])]) -> Translations {
+        data.iter()
+            .map(|(locale, keys)| {
+                let trs = keys
+                    .iter()
+                    .map(|k| (k.to_string(), k.to_string()))
+                    .collect();
+                (locale.to_string(), trs)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_check() {
+        let mut results = HashMap::new();
+        results.insert(
+            "hello".to_string(),
+            Message {
+                key: "hello".into(),
+                index: 0,
+                minify_key: false,
+                locations: vec![Location {
+                    file: "src/main.rs".into(),
+                    line: 4,
+                }],
+            },
+        );
+        results.insert(
+            "world".to_string(),
+            Message {
+                key: "world".into(),
+                index: 1,
+                ..Default::default()
+            },
+        );
+
+        let trs = translations(&[
+            ("en", &["hello", "world", "only.en"]),
+            ("zh-CN", &["hello"]),
+        ]);
+        let locales = vec!["en".to_string(), "zh-CN".to_string(), "fr".to_string()];
+
+        let report = check(&results, &trs, &locales, "en");
+        let missing = report
+            .missing
+            .iter()
+            .map(|m| (m.locale.as_str(), m.key.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            missing,
+            vec![("zh-CN", "world"), ("fr", "hello"), ("fr", "world")]
+        );
+        assert_eq!(report.missing[1].locations.len(), 1);
+
+        let missing_from_base = report
+            .missing_from_base
+            .iter()
+            .map(|m| (m.locale.as_str(), m.key.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            missing_from_base,
+            vec![
+                ("zh-CN", "only.en"),
+                ("zh-CN", "world"),
+                ("fr", "hello"),
+                ("fr", "only.en"),
+                ("fr", "world")
+            ]
+        );
+
+        let trs = translations(&[("en", &["hello", "world"])]);
+        assert!(check(&results, &trs, &["en".to_string()], "en").is_empty());
+    }
+
+    #[test]
+    fn test_check_intervals() {
+        let mut results = HashMap::new();
+        results.insert("inbox".to_string(), Message::default());
+        results.insert("days".to_string(), Message::default());
+
+        let trs = translations(&[
+            (
+                "en",
+                &["inbox.0", "inbox.2..", "inbox._intervals", "days.1"],
+            ),
+            (
+                "fr",
+                &["inbox.0", "inbox.2..", "inbox._intervals", "days.one"],
+            ),
+        ]);
+        let report = check(&results, &trs, &["en".to_string(), "fr".to_string()], "en");
+        let missing = report
+            .missing
+            .iter()
+            .map(|m| (m.locale.as_str(), m.key.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(missing, vec![("fr", "days")]);
+
+        assert!(contains_key(&trs["en"], "inbox"));
+        assert!(!contains_key(&trs["en"], "inbox.2"));
+        assert!(!contains_key(&trs["fr"], "days"));
+    }
+}
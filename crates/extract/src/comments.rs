@@ -0,0 +1,362 @@
+//! Keep the comments of YAML files when they are rewritten.
+//!
+//! serde_yaml drops the comments, so they are collected from the original content by the
+//! full key they belong to, and inserted back next to the same keys in the new content.
+use std::collections::BTreeMap;
+
+/// The comments of a YAML file, by the full key they belong to, e.g. `messages.title`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Comments {
+    /// The comment lines at the top of the file, before the first key.
+    header: Vec<String>,
+    /// The comment lines above a key.
+    leading: BTreeMap<String, Vec<String>>,
+    /// The comment at the end of the line of a key.
+    inline: BTreeMap<String, String>,
+    /// The comment lines after the last key.
+    trailing: Vec<String>,
+}
+
+enum Line {
+    Key {
+        key: String,
+        comment: Option<String>,
+    },
+    Comment(String),
+    Other,
+}
+
+impl Comments {
+    /// Collect the comments of the YAML content.
+    pub fn parse(content: &str) -> Self {
+        let mut comments = Self::default();
+        let mut pending = vec![];
+        let mut has_key = false;
+
+        for line in scan(content) {
+            match line {
+                Line::Comment(comment) => pending.push(comment),
+                Line::Key { key, comment } => {
+                    if !has_key {
+                        // The markers right above the first key belong to it
+                        let split = pending.len()
+                            - pending.iter().rev().take_while(|c| is_marker(c)).count();
+                        let markers = pending.split_off(split);
+                        comments.header = std::mem::take(&mut pending);
+                        if !markers.is_empty() {
+                            comments.leading.insert(key.clone(), markers);
+                        }
+                        has_key = true;
+                    } else if !pending.is_empty() {
+                        comments
+                            .leading
+                            .insert(key.clone(), std::mem::take(&mut pending));
+                    }
+                    if let Some(comment) = comment {
+                        comments.inline.insert(key, comment);
+                    }
+                }
+                Line::Other => {}
+            }
+        }
+
+        if has_key {
+            comments.trailing = pending;
+        } else {
+            comments.header = pending;
+        }
+        comments
+    }
+
+    /// Add a comment line above a key, unless it's there already.
+    pub fn add_leading(&mut self, key: &str, comment: &str) {
+        let comments = self.leading.entry(key.to_string()).or_default();
+        if !comments.iter().any(|c| c == comment) {
+            comments.push(comment.to_string());
+        }
+    }
+
+    /// Whether the comment line is above a key.
+    pub fn has_leading(&self, key: &str, comment: &str) -> bool {
+        self.leading
+            .get(key)
+            .is_some_and(|comments| comments.iter().any(|c| c == comment))
+    }
+
+    /// Remove a comment line above a key.
+    pub fn remove_leading(&mut self, key: &str, comment: &str) {
+        if let Some(comments) = self.leading.get_mut(key) {
+            comments.retain(|c| c != comment);
+            if comments.is_empty() {
+                self.leading.remove(key);
+            }
+        }
+    }
+
+    /// Replace the source location comments above a key, e.g. `# src/main.rs:42`,
+    /// keeping the other comments.
+    pub fn set_locations(&mut self, key: &str, locations: &[String]) {
+        let comments = self.leading.entry(key.to_string()).or_default();
+        comments.retain(|c| !is_location(c));
+        comments.extend(locations.iter().map(|location| format!("# {}", location)));
+        if comments.is_empty() {
+            self.leading.remove(key);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.is_empty()
+            && self.leading.is_empty()
+            && self.inline.is_empty()
+            && self.trailing.is_empty()
+    }
+
+    /// Insert the comments into the YAML content, next to the same keys.
+    ///
+    /// The comments of the keys missing in the content are dropped.
+    pub fn apply(&self, content: &str) -> String {
+        if self.is_empty() {
+            return content.to_string();
+        }
+
+        let mut output = vec![];
+        output.extend(self.header.iter().cloned());
+
+        let lines = content.lines().collect::<Vec<_>>();
+        for (line, scanned) in lines.iter().zip(scan(content)) {
+            let Line::Key { key, comment } = scanned else {
+                output.push(line.to_string());
+                continue;
+            };
+
+            let indent = &line[..line.len() - line.trim_start().len()];
+            for comment in self.leading.get(&key).into_iter().flatten() {
+                output.push(format!("{}{}", indent, comment));
+            }
+            match self.inline.get(&key) {
+                Some(inline) if comment.is_none() => output.push(format!("{} {}", line, inline)),
+                _ => output.push(line.to_string()),
+            }
+        }
+
+        output.extend(self.trailing.iter().cloned());
+        let mut text = output.join("\n");
+        if content.ends_with('\n') || content.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Scan the lines of the YAML content, one item per line.
+///
+/// It's a line based scan for the usual formatting of locale files, not a full parser.
+fn scan(content: &str) -> Vec<Line> {
+    let mut lines = vec![];
+    // (indent, key) of the parent mappings
+    let mut stack: Vec<(usize, String)> = vec![];
+    // The indent of the key of a block scalar, the more indented lines are its text
+    let mut block_indent: Option<usize> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if let Some(block) = block_indent {
+            if trimmed.is_empty() || indent > block {
+                lines.push(Line::Other);
+                continue;
+            }
+            block_indent = None;
+        }
+
+        if trimmed.starts_with('#') {
+            lines.push(Line::Comment(trimmed.to_string()));
+            continue;
+        }
+
+        let Some((key, value)) = split_key(trimmed) else {
+            lines.push(Line::Other);
+            continue;
+        };
+
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+        let mut full_key = stack.iter().map(|(_, k)| k.as_str()).collect::<Vec<_>>();
+        full_key.push(&key);
+        let full_key = full_key.join(".");
+
+        let (value, comment) = split_comment(value);
+        if value.is_empty() || value.starts_with('&') {
+            stack.push((indent, key));
+        } else if value.starts_with(['|', '>']) {
+            block_indent = Some(indent);
+        }
+
+        lines.push(Line::Key {
+            key: full_key,
+            comment,
+        });
+    }
+
+    lines
+}
+
+/// Whether the comment is added above a key by the tools, a source location or a `# TODO: ...`.
+fn is_marker(comment: &str) -> bool {
+    is_location(comment) || comment.starts_with("# TODO:")
+}
+
+/// Whether the comment is a source location added by [`Comments::set_locations`].
+fn is_location(comment: &str) -> bool {
+    let Some((file, line)) = comment
+        .strip_prefix("# ")
+        .and_then(|location| location.rsplit_once(':'))
+    else {
+        return false;
+    };
+    !file.is_empty()
+        && !file.contains(char::is_whitespace)
+        && !line.is_empty()
+        && line.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Split a `key: value` line into the key and the rest after `:`.
+fn split_key(line: &str) -> Option<(String, &str)> {
+    if line.starts_with('-') {
+        return None;
+    }
+
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = quoted_end(line, quote)?;
+            (line[1..end].to_string(), &line[end + 1..])
+        }
+        _ => {
+            let i = line
+                .find(": ")
+                .or_else(|| line.strip_suffix(':').map(|l| l.len()))?;
+            (line[..i].trim().to_string(), &line[i..])
+        }
+    };
+
+    let value = rest.trim_start().strip_prefix(':')?;
+    Some((key, value.trim()))
+}
+
+/// Split the value of a key and the comment after it.
+fn split_comment(value: &str) -> (&str, Option<String>) {
+    let end = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => quoted_end(value, quote).map_or(value.len(), |i| i + 1),
+        _ => 0,
+    };
+
+    let comment_start = if value.starts_with('#') && end == 0 {
+        Some(0)
+    } else {
+        value[end..].find(" #").map(|i| end + i + 1)
+    };
+
+    match comment_start {
+        Some(i) => (value[..i].trim(), Some(value[i..].to_string())),
+        None => (value, None),
+    }
+}
+
+/// The index of the closing quote of a quoted text starting at index 0.
+fn quoted_end(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' && quote == '"' {
+            chars.next();
+        } else if c == quote {
+            // `''` is an escaped quote in a single quoted text
+            if quote == '\'' && text[i + 1..].starts_with('\'') {
+                chars.next();
+                continue;
+            }
+            return Some(i);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_comments() {
+        let content = indoc! {r#"
+        # Translations of the app
+        _version: 2
+        # Greetings
+        hello:
+          en: Hello # Keep it short
+          zh-CN: "你好 # not a comment"
+        messages:
+          # Shown in the title bar
+          title:
+            en: |-
+              Title
+              # not a comment either
+        # The end
+        "#};
+
+        let comments = Comments::parse(content);
+        assert_eq!(comments.header, vec!["# Translations of the app"]);
+        assert_eq!(comments.leading["hello"], vec!["# Greetings"]);
+        assert_eq!(
+            comments.leading["messages.title"],
+            vec!["# Shown in the title bar"]
+        );
+        assert_eq!(comments.inline["hello.en"], "# Keep it short");
+        assert!(!comments.inline.contains_key("hello.zh-CN"));
+        assert_eq!(comments.trailing, vec!["# The end"]);
+
+        let mut located =
+            Comments::parse("# Header\n# TODO: translate\n# src/main.rs:1\nhello: Hello\n");
+        assert_eq!(located.header, vec!["# Header"]);
+        assert!(located.has_leading("hello", "# TODO: translate"));
+        located.remove_leading("hello", "# TODO: translate");
+        located.set_locations("hello", &["src/lib.rs:2".into(), "src/lib.rs:8".into()]);
+        assert_eq!(
+            located.apply("hello: Hello\n"),
+            "# Header\n# src/lib.rs:2\n# src/lib.rs:8\nhello: Hello\n"
+        );
+        located.set_locations("hello", &[]);
+        assert_eq!(located.apply("hello: Hello\n"), "# Header\nhello: Hello\n");
+
+        let rewritten = indoc! {r#"
+        _version: 2
+        hello:
+          en: Hello
+          fr: Bonjour
+          zh-CN: '你好 # not a comment'
+        messages.title:
+          en: |-
+            Title
+            # not a comment either
+        "#};
+        assert_eq!(
+            comments.apply(rewritten),
+            indoc! {r#"
+            # Translations of the app
+            _version: 2
+            # Greetings
+            hello:
+              en: Hello # Keep it short
+              fr: Bonjour
+              zh-CN: '你好 # not a comment'
+            # Shown in the title bar
+            messages.title:
+              en: |-
+                Title
+                # not a comment either
+            # The end
+            "#}
+        );
+    }
+}
@@ -0,0 +1,1016 @@
+//! Convert the locale files between formats and layouts.
+use crate::checker::Translations;
+use crate::generator::TODO_FILENAME;
+use crate::locale_file::{locale_files, LocaleFile};
+use anyhow::{anyhow, Error};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The file name (without extension) of a version 2 file.
+const V2_FILENAME: &str = "app";
+/// The file name (without extension) of the Apple files.
+const APPLE_FILENAME: &str = "Localizable";
+/// The file name of the Android files.
+const ANDROID_FILENAME: &str = "strings.xml";
+/// The file name (without extension) of the CSV file.
+const CSV_FILENAME: &str = "all";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Yaml,
+    Json,
+    Toml,
+    /// Gettext PO, one file per locale, the key is the `msgid`.
+    Po,
+    /// XLIFF 1.2, one file per locale, the key is the `trans-unit` id.
+    Xliff,
+    /// Java `.properties`, one file per locale, e.g. `messages_fr.properties`.
+    Properties,
+    /// Apple `.strings` and `.stringsdict`, in a `{locale}.lproj` directory per locale,
+    /// the plural keys like `apples.one` and `apples.other` go into the `.stringsdict`.
+    Strings,
+    /// Android `strings.xml`, in a `values-{qualifier}` directory per locale, e.g.
+    /// `values-zh-rCN`, and the base locale in `values`. The `<plurals>` are the plural keys.
+    Android,
+    /// CSV, all locales in `all.csv`, with a `key` column and a column per locale.
+    Csv,
+}
+
+impl FileFormat {
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::Yaml => &["yml", "yaml"],
+            Self::Json => &["json"],
+            Self::Toml => &["toml"],
+            Self::Po => &["po"],
+            Self::Xliff => &["xliff", "xlf"],
+            Self::Properties => &["properties"],
+            Self::Strings => &["strings", "stringsdict"],
+            Self::Android => &["xml"],
+            Self::Csv => &["csv"],
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.extensions().contains(&ext))
+    }
+}
+
+/// The layout of YAML, JSON and TOML files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// One file per locale.
+    V1,
+    /// All locales in one file, with `_version: 2`.
+    V2,
+}
+
+/// Find the files of the format in the locales path, except the untranslated texts.
+fn files(locales_path: &Path, format: FileFormat) -> Vec<PathBuf> {
+    let paths = match format {
+        FileFormat::Yaml | FileFormat::Json | FileFormat::Toml => locale_files(locales_path),
+        FileFormat::Po
+        | FileFormat::Xliff
+        | FileFormat::Properties
+        | FileFormat::Strings
+        | FileFormat::Android
+        | FileFormat::Csv => {
+            let mut paths = ignore::WalkBuilder::new(locales_path)
+                .standard_filters(false)
+                .build()
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.into_path())
+                .filter(|path| path.is_file())
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        }
+    };
+
+    paths
+        .into_iter()
+        .filter(|path| format.matches(path) && !path.ends_with(TODO_FILENAME))
+        .collect()
+}
+
+/// Detect the layout of the files in the locales path, `V2` if any file is version 2.
+pub fn detect_layout(locales_path: impl AsRef<Path>, format: FileFormat) -> Layout {
+    let is_v2 = files(locales_path.as_ref(), format)
+        .iter()
+        .filter_map(|path| LocaleFile::load(path).ok())
+        .any(|file| file.version() == 2);
+
+    if is_v2 {
+        Layout::V2
+    } else {
+        Layout::V1
+    }
+}
+
+/// Read all translations of the format in the locales path.
+///
+/// The `base_locale` is the locale of the Android `values` directory.
+pub fn read(
+    locales_path: impl AsRef<Path>,
+    format: FileFormat,
+    base_locale: &str,
+) -> Result<Translations, Error> {
+    let mut trs = Translations::new();
+    for path in files(locales_path.as_ref(), format) {
+        let file_trs = match format {
+            FileFormat::Yaml | FileFormat::Json | FileFormat::Toml => {
+                LocaleFile::load(&path)?.translations()
+            }
+            FileFormat::Po => parse_po(&path, &std::fs::read_to_string(&path)?)?,
+            FileFormat::Xliff => parse_xliff(&std::fs::read_to_string(&path)?)?,
+            FileFormat::Properties => parse_properties(&path, &std::fs::read_to_string(&path)?),
+            FileFormat::Strings => parse_strings(&path, &std::fs::read_to_string(&path)?)?,
+            FileFormat::Android => match android_locale(&path, base_locale) {
+                Some(locale) => parse_android(&locale, &std::fs::read_to_string(&path)?),
+                // The resources other than the translations, e.g. `layout` or `values-night`
+                None => continue,
+            },
+            FileFormat::Csv => crate::spreadsheet::import_csv(std::fs::File::open(&path)?)?,
+        };
+
+        for (locale, texts) in file_trs {
+            trs.entry(locale).or_default().extend(texts);
+        }
+    }
+    Ok(trs)
+}
+
+/// Write the translations in the format into the output path, returns the written files.
+///
+/// The `layout` is only used by YAML, JSON and TOML, the `base_locale` is the source language of XLIFF,
+/// and goes into the Android `values` directory.
+pub fn write(
+    output_path: impl AsRef<Path>,
+    format: FileFormat,
+    layout: Layout,
+    translations: &Translations,
+    base_locale: &str,
+) -> Result<Vec<PathBuf>, Error> {
+    let output_path = output_path.as_ref();
+    std::fs::create_dir_all(output_path)?;
+    let ext = format.extensions()[0];
+
+    let mut written = vec![];
+    match (format, layout) {
+        (FileFormat::Yaml | FileFormat::Json | FileFormat::Toml, Layout::V2) => {
+            let path = output_path.join(format!("{}.{}", V2_FILENAME, ext));
+            LocaleFile::from_translations(&path, 2, translations)?.save()?;
+            written.push(path);
+        }
+        (FileFormat::Yaml | FileFormat::Json | FileFormat::Toml, Layout::V1) => {
+            for locale in translations.keys() {
+                let path = output_path.join(format!("{}.{}", locale, ext));
+                LocaleFile::from_translations(&path, 1, translations)?.save()?;
+                written.push(path);
+            }
+        }
+        (FileFormat::Po, _) => {
+            for locale in translations.keys() {
+                let path = output_path.join(format!("{}.{}", locale, ext));
+                std::fs::write(&path, to_po(locale, translations))?;
+                written.push(path);
+            }
+        }
+        (FileFormat::Xliff, _) => {
+            for locale in translations.keys() {
+                let path = output_path.join(format!("{}.{}", locale, ext));
+                std::fs::write(&path, to_xliff(base_locale, locale, translations))?;
+                written.push(path);
+            }
+        }
+        (FileFormat::Properties, _) => {
+            for (locale, texts) in translations {
+                let path = output_path.join(format!("{}.{}", locale.replace('-', "_"), ext));
+                std::fs::write(&path, rust_i18n_support::write_properties(texts))?;
+                written.push(path);
+            }
+        }
+        (FileFormat::Strings, _) => {
+            for (locale, texts) in translations {
+                let dir = output_path.join(format!("{}.lproj", locale));
+                std::fs::create_dir_all(&dir)?;
+                let (texts, plurals) = split_plurals(texts);
+
+                let path = dir.join(APPLE_FILENAME).with_extension("strings");
+                std::fs::write(&path, rust_i18n_support::write_strings(texts))?;
+                written.push(path);
+                if !plurals.is_empty() {
+                    let path = dir.join(APPLE_FILENAME).with_extension("stringsdict");
+                    std::fs::write(&path, rust_i18n_support::write_stringsdict(&plurals))?;
+                    written.push(path);
+                }
+            }
+        }
+        (FileFormat::Android, _) => {
+            for (locale, texts) in translations {
+                let dir = if locale == base_locale {
+                    output_path.join("values")
+                } else {
+                    output_path.join(format!("values-{}", android_qualifier(locale)))
+                };
+                std::fs::create_dir_all(&dir)?;
+                let path = dir.join(ANDROID_FILENAME);
+                std::fs::write(&path, to_android(texts))?;
+                written.push(path);
+            }
+        }
+        (FileFormat::Csv, _) => {
+            let path = output_path.join(format!("{}.{}", CSV_FILENAME, ext));
+            std::fs::write(&path, to_csv(base_locale, translations)?)?;
+            written.push(path);
+        }
+    }
+
+    Ok(written)
+}
+
+/// The locale files restructured by [`migrate`], not written yet.
+#[derive(Debug, Default)]
+pub struct Migration {
+    /// The files in the new layout.
+    pub files: Vec<LocaleFile>,
+    /// The files in the old layout to remove.
+    pub removed: Vec<PathBuf>,
+}
+
+/// Restructure the YAML, JSON and TOML files in the locales path into the layout.
+///
+/// The files are grouped by directory, name and extension, e.g. `view.en.yml` and `view.fr.yml`
+/// become `view.yml`, and back. Version 1 files without a name, e.g. `en.yml`, go into `app.yml`.
+/// The files already in the layout are kept untouched, with their comments, unless the texts
+/// of other files are moved into them.
+pub fn migrate(locales_path: impl AsRef<Path>, layout: Layout) -> Result<Migration, Error> {
+    let version = match layout {
+        Layout::V1 => 1,
+        Layout::V2 => 2,
+    };
+
+    let mut groups: BTreeMap<(PathBuf, String, String), Translations> = BTreeMap::new();
+    let mut migration = Migration::default();
+    for path in locale_files(locales_path.as_ref()) {
+        if path.ends_with(TODO_FILENAME) {
+            continue;
+        }
+        let file = LocaleFile::load(&path)?;
+        if file.version() == version {
+            continue;
+        }
+
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let ext = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let name = match file.version() {
+            2 if stem == V2_FILENAME => "",
+            2 => stem,
+            _ => stem.rsplit_once('.').map_or("", |(name, _)| name),
+        };
+
+        let trs = groups
+            .entry((dir, name.to_string(), ext.to_string()))
+            .or_default();
+        for (locale, texts) in file.translations() {
+            trs.entry(locale).or_default().extend(texts);
+        }
+        migration.removed.push(path);
+    }
+
+    for ((dir, name, ext), trs) in groups {
+        let paths = match layout {
+            Layout::V2 => {
+                let name = if name.is_empty() { V2_FILENAME } else { &name };
+                vec![dir.join(format!("{}.{}", name, ext))]
+            }
+            Layout::V1 => trs
+                .keys()
+                .map(|locale| match name.as_str() {
+                    "" => dir.join(format!("{}.{}", locale, ext)),
+                    name => dir.join(format!("{}.{}.{}", name, locale, ext)),
+                })
+                .collect(),
+        };
+
+        for path in paths {
+            // Keep the texts of an existing file in the layout
+            let mut merged = match path.exists() && !migration.removed.contains(&path) {
+                true => LocaleFile::load(&path)?.translations(),
+                false => Translations::new(),
+            };
+            for (locale, texts) in &trs {
+                merged
+                    .entry(locale.clone())
+                    .or_default()
+                    .extend(texts.clone());
+            }
+            migration
+                .files
+                .push(LocaleFile::from_translations(&path, version, &merged)?);
+        }
+    }
+
+    migration
+        .removed
+        .retain(|path| !migration.files.iter().any(|file| &file.path == path));
+    Ok(migration)
+}
+
+fn escape_po(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+fn unescape_po(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+    result
+}
+
+fn to_po(locale: &str, translations: &Translations) -> String {
+    let mut output = format!(
+        "msgid \"\"\nmsgstr \"\"\n\"Language: {}\\n\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n",
+        locale
+    );
+    for (key, text) in translations.get(locale).into_iter().flatten() {
+        output.push_str(&format!(
+            "\nmsgid \"{}\"\nmsgstr \"{}\"\n",
+            escape_po(key),
+            escape_po(text)
+        ));
+    }
+    output
+}
+
+fn parse_po(path: &Path, content: &str) -> Result<Translations, Error> {
+    let mut entries = vec![];
+    let mut msgid: Option<String> = None;
+    let mut msgstr: Option<String> = None;
+    let mut current: Option<&str> = None;
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        let (field, rest) = if let Some(rest) = line.strip_prefix("msgid ") {
+            if let (Some(id), Some(s)) = (msgid.take(), msgstr.take()) {
+                entries.push((id, s));
+            }
+            (Some("msgid"), rest)
+        } else if let Some(rest) = line.strip_prefix("msgstr ") {
+            (Some("msgstr"), rest)
+        } else if line.starts_with('"') {
+            (None, line)
+        } else {
+            // Comments, empty lines, and unsupported fields like `msgctxt`
+            current = None;
+            continue;
+        };
+
+        let text = rest
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .map(unescape_po)
+            .ok_or_else(|| anyhow!("Invalid string at {}:{}", path.display(), i + 1))?;
+
+        if let Some(field) = field {
+            current = Some(field);
+        }
+        match current {
+            Some("msgid") => msgid.get_or_insert_with(String::new).push_str(&text),
+            Some("msgstr") => msgstr.get_or_insert_with(String::new).push_str(&text),
+            _ => {}
+        }
+    }
+    if let (Some(id), Some(s)) = (msgid, msgstr) {
+        entries.push((id, s));
+    }
+
+    // The header is the entry with an empty `msgid`
+    let header = entries
+        .iter()
+        .find(|(id, _)| id.is_empty())
+        .map(|(_, s)| s.as_str())
+        .unwrap_or_default();
+    let locale = header
+        .lines()
+        .find_map(|line| line.strip_prefix("Language:"))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .or_else(|| Some(path.file_stem()?.to_str()?.to_string()))
+        .ok_or_else(|| anyhow!("Unknown locale of {}", path.display()))?;
+
+    let texts = entries
+        .into_iter()
+        .filter(|(id, s)| !id.is_empty() && !s.is_empty())
+        .collect();
+    Ok(Translations::from([(locale, texts)]))
+}
+
+fn parse_properties(path: &Path, content: &str) -> Translations {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let texts = rust_i18n_support::parse_properties(content)
+        .into_iter()
+        .filter(|(_, text)| !text.is_empty())
+        .collect();
+    Translations::from([(rust_i18n_support::properties_locale(stem), texts)])
+}
+
+fn parse_strings(path: &Path, content: &str) -> Result<Translations, Error> {
+    let locale = rust_i18n_support::lproj_locale(path)
+        .or_else(|| path.file_stem()?.to_str())
+        .ok_or_else(|| anyhow!("Unknown locale of {}", path.display()))?;
+    let pairs = if path.extension().is_some_and(|ext| ext == "stringsdict") {
+        rust_i18n_support::parse_stringsdict(content)
+    } else {
+        rust_i18n_support::parse_strings(content)
+    }
+    .map_err(|err| anyhow!("{}: {}", path.display(), err))?;
+    Ok(Translations::from([(
+        locale.to_string(),
+        pairs.into_iter().collect(),
+    )]))
+}
+
+/// Split the plural keys, the ones with an `other` subkey, e.g. `apples.one` and
+/// `apples.other`, from the other texts. The plurals are the texts by category by key.
+fn split_plurals(texts: &BTreeMap<String, String>) -> (Vec<(&String, &String)>, Translations) {
+    let mut plurals = Translations::new();
+    for (key, text) in texts {
+        if let Some((base, category)) = key.rsplit_once('.') {
+            if rust_i18n_support::PLURAL_CATEGORIES.contains(&category) {
+                plurals
+                    .entry(base.to_string())
+                    .or_default()
+                    .insert(category.to_string(), text.clone());
+            }
+        }
+    }
+    plurals.retain(|_, texts| texts.contains_key("other"));
+
+    let texts = texts
+        .iter()
+        .filter(|(key, _)| {
+            !key.rsplit_once('.').is_some_and(|(base, category)| {
+                rust_i18n_support::PLURAL_CATEGORIES.contains(&category)
+                    && plurals.contains_key(base)
+            })
+        })
+        .collect();
+    (texts, plurals)
+}
+
+/// The Android resource qualifier of a locale, e.g. `zh-rCN` of `zh-CN`, or `b+sr+Latn` of
+/// `sr-Latn`.
+fn android_qualifier(locale: &str) -> String {
+    match locale.split('-').collect::<Vec<_>>()[..] {
+        [language] => language.to_string(),
+        [language, region]
+            if region.len() == 2
+                || (region.len() == 3 && region.chars().all(|c| c.is_ascii_digit())) =>
+        {
+            format!("{}-r{}", language, region)
+        }
+        _ => format!("b+{}", locale.replace('-', "+")),
+    }
+}
+
+/// The locale of an Android resource file from its directory, `None` if it isn't a `values`
+/// directory of a locale, e.g. `layout` or `values-night`.
+fn android_locale(path: &Path, base_locale: &str) -> Option<String> {
+    let dir = path.parent()?.file_name()?.to_str()?;
+    if dir == "values" {
+        return Some(base_locale.to_string());
+    }
+    let qualifier = dir.strip_prefix("values-")?;
+    if let Some(tag) = qualifier.strip_prefix("b+") {
+        return Some(tag.replace('+', "-"));
+    }
+
+    let mut parts = qualifier.split('-');
+    let language = parts
+        .next()
+        .filter(|s| (2..=3).contains(&s.len()) && s.chars().all(|c| c.is_ascii_lowercase()))?;
+    match (parts.next(), parts.next()) {
+        (None, _) => Some(language.to_string()),
+        (Some(region), None) => {
+            let region = region
+                .strip_prefix('r')
+                .filter(|r| r.len() == 2 || r.len() == 3)?;
+            Some(format!("{}-{}", language, region))
+        }
+        _ => None,
+    }
+}
+
+/// Unescape an Android string, the XML entities, the `\` escapes and the quoted parts, where the
+/// whitespace is kept instead of collapsed.
+fn unescape_android(text: &str) -> String {
+    let text = unescape_xml(text.trim());
+    let mut result = String::with_capacity(text.len());
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' => match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('u') => {
+                    let hex = chars.by_ref().take(4).collect::<String>();
+                    match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        Some(c) => result.push(c),
+                        None => result.push_str(&hex),
+                    }
+                }
+                Some(c) => result.push(c),
+                None => {}
+            },
+            c if c.is_whitespace() && !quoted => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                result.push(' ');
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn escape_android(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\'' => result.push_str("\\'"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '@' | '?' if i == 0 => {
+                result.push('\\');
+                result.push(c);
+            }
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            c => result.push(c),
+        }
+    }
+    // The whitespace would be collapsed unless quoted
+    if text.starts_with(' ') || text.ends_with(' ') || text.contains("  ") {
+        format!("\"{}\"", result)
+    } else {
+        result
+    }
+}
+
+fn to_android(texts: &BTreeMap<String, String>) -> String {
+    let (texts, plurals) = split_plurals(texts);
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>\n");
+    for (key, text) in texts {
+        output.push_str(&format!(
+            "    <string name=\"{}\">{}</string>\n",
+            escape_xml(key),
+            escape_android(text)
+        ));
+    }
+    for (key, texts) in plurals {
+        output.push_str(&format!("    <plurals name=\"{}\">\n", escape_xml(&key)));
+        for category in rust_i18n_support::PLURAL_CATEGORIES {
+            if let Some(text) = texts.get(category) {
+                output.push_str(&format!(
+                    "        <item quantity=\"{}\">{}</item>\n",
+                    category,
+                    escape_android(&text.replace("%{count}", "%d"))
+                ));
+            }
+        }
+        output.push_str("    </plurals>\n");
+    }
+    output.push_str("</resources>\n");
+    output
+}
+
+/// Parse the `<string>` and the `<plurals>` of an Android resource file, the plurals into
+/// the `key.{category}` subkeys with the number `%d` as `%{count}`.
+fn parse_android(locale: &str, content: &str) -> Translations {
+    let comment_re = Regex::new(r#"(?s)<!--.*?-->"#).unwrap();
+    let name_re = Regex::new(r#"\bname="([^"]*)""#).unwrap();
+    let string_re = Regex::new(r#"(?s)<string\b([^>]*?)(?:/>|>(.*?)</string>)"#).unwrap();
+    let plurals_re = Regex::new(r#"(?s)<plurals\b([^>]*)>(.*?)</plurals>"#).unwrap();
+    let item_re = Regex::new(r#"(?s)<item\b[^>]*\bquantity="([^"]*)"[^>]*>(.*?)</item>"#).unwrap();
+
+    let content = comment_re.replace_all(content, "");
+    let name = |attrs: &str| name_re.captures(attrs).map(|caps| unescape_xml(&caps[1]));
+
+    let mut texts = BTreeMap::new();
+    for caps in string_re.captures_iter(&content) {
+        if let Some(key) = name(&caps[1]) {
+            let text = caps.get(2).map_or("", |m| m.as_str());
+            texts.insert(key, unescape_android(text));
+        }
+    }
+    for caps in plurals_re.captures_iter(&content) {
+        let Some(key) = name(&caps[1]) else {
+            continue;
+        };
+        for item in item_re.captures_iter(&caps[2]) {
+            let text = unescape_android(&item[2]).replace("%d", "%{count}");
+            texts.insert(format!("{}.{}", key, &item[1]), text);
+        }
+    }
+    texts.retain(|_, text| !text.is_empty());
+    Translations::from([(locale.to_string(), texts)])
+}
+
+/// A CSV with a row per key and a column per locale, the base locale first.
+fn to_csv(base_locale: &str, translations: &Translations) -> Result<String, Error> {
+    let mut locales = translations.keys().map(|l| l.as_str()).collect::<Vec<_>>();
+    locales.sort_by_key(|locale| *locale != base_locale);
+    let keys = translations
+        .values()
+        .flat_map(|trs| trs.keys())
+        .collect::<std::collections::BTreeSet<_>>();
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(std::iter::once("key").chain(locales.iter().copied()))?;
+    for key in keys {
+        let texts = locales.iter().map(|locale| {
+            translations[*locale]
+                .get(key)
+                .map(|s| s.as_str())
+                .unwrap_or_default()
+        });
+        writer.write_record(std::iter::once(key.as_str()).chain(texts))?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn to_xliff(base_locale: &str, locale: &str, translations: &Translations) -> String {
+    let base = translations.get(base_locale);
+    let target = translations.get(locale);
+
+    let mut keys = base
+        .into_iter()
+        .flatten()
+        .map(|(k, _)| k)
+        .collect::<Vec<_>>();
+    keys.extend(target.into_iter().flatten().map(|(k, _)| k));
+    keys.sort();
+    keys.dedup();
+
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str("<xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n");
+    output.push_str(&format!(
+        "  <file original=\"{}\" source-language=\"{}\" target-language=\"{}\" datatype=\"plaintext\">\n    <body>\n",
+        V2_FILENAME, base_locale, locale
+    ));
+    for key in keys {
+        output.push_str(&format!("      <trans-unit id=\"{}\">\n", escape_xml(key)));
+        if let Some(source) = base.and_then(|trs| trs.get(key)) {
+            output.push_str(&format!(
+                "        <source>{}</source>\n",
+                escape_xml(source)
+            ));
+        }
+        if let Some(text) = target.and_then(|trs| trs.get(key)) {
+            output.push_str(&format!("        <target>{}</target>\n", escape_xml(text)));
+        }
+        output.push_str("      </trans-unit>\n");
+    }
+    output.push_str("    </body>\n  </file>\n</xliff>\n");
+    output
+}
+
+fn parse_xliff(content: &str) -> Result<Translations, Error> {
+    let file_re = Regex::new(r#"(?s)<file\b([^>]*)>(.*?)</file>"#).unwrap();
+    let attr_re = Regex::new(r#"([\w:-]+)="([^"]*)""#).unwrap();
+    let unit_re = Regex::new(r#"(?s)<trans-unit\b([^>]*)>(.*?)</trans-unit>"#).unwrap();
+    let source_re = Regex::new(r#"(?s)<source\b[^>]*>(.*?)</source>"#).unwrap();
+    let target_re = Regex::new(r#"(?s)<target\b[^>]*>(.*?)</target>"#).unwrap();
+
+    let attr = |attrs: &str, name: &str| {
+        attr_re
+            .captures_iter(attrs)
+            .find(|caps| &caps[1] == name)
+            .map(|caps| unescape_xml(&caps[2]))
+    };
+
+    let mut trs = Translations::new();
+    for file in file_re.captures_iter(content) {
+        let source_locale = attr(&file[1], "source-language")
+            .ok_or_else(|| anyhow!("Missing source-language in XLIFF file"))?;
+        let target_locale = attr(&file[1], "target-language");
+
+        for unit in unit_re.captures_iter(&file[2]) {
+            let Some(key) = attr(&unit[1], "id") else {
+                continue;
+            };
+            if let Some(source) = source_re.captures(&unit[2]) {
+                trs.entry(source_locale.clone())
+                    .or_default()
+                    .entry(key.clone())
+                    .or_insert_with(|| unescape_xml(&source[1]));
+            }
+            if let (Some(locale), Some(target)) = (&target_locale, target_re.captures(&unit[2])) {
+                trs.entry(locale.clone())
+                    .or_default()
+                    .insert(key, unescape_xml(&target[1]));
+            }
+        }
+    }
+    Ok(trs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn translations() -> Translations {
+        Translations::from([
+            (
+                "en".to_string(),
+                BTreeMap::from([
+                    ("hello".to_string(), "Hello, \"%{name}\"!".to_string()),
+                    (
+                        "messages.body".to_string(),
+                        "Line 1\nLine 2 & <b>".to_string(),
+                    ),
+                ]),
+            ),
+            (
+                "fr".to_string(),
+                BTreeMap::from([("hello".to_string(), "Bonjour, \"%{name}\" !".to_string())]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_po() {
+        let trs = translations();
+        let po = to_po("fr", &trs);
+        assert_eq!(
+            po,
+            "msgid \"\"\nmsgstr \"\"\n\"Language: fr\\n\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\nmsgid \"hello\"\nmsgstr \"Bonjour, \\\"%{name}\\\" !\"\n"
+        );
+
+        for locale in ["en", "fr"] {
+            let parsed = parse_po(Path::new("x.po"), &to_po(locale, &trs)).unwrap();
+            assert_eq!(parsed[locale], trs[locale]);
+        }
+
+        let po = "# comment\nmsgid \"\"\nmsgstr \"\"\n\nmsgid \"\"\n\"multi\"\nmsgstr \"\"\n\"A \"\n\"B\"\n";
+        let parsed = parse_po(Path::new("locales/de.po"), po).unwrap();
+        assert_eq!(parsed["de"]["multi"], "A B");
+    }
+
+    #[test]
+    fn test_xliff() {
+        let trs = translations();
+        let mut parsed = Translations::new();
+        for locale in ["en", "fr"] {
+            for (locale, texts) in parse_xliff(&to_xliff("en", locale, &trs)).unwrap() {
+                parsed.entry(locale).or_default().extend(texts);
+            }
+        }
+        assert_eq!(parsed, trs);
+    }
+
+    #[test]
+    fn test_properties() {
+        let parsed = parse_properties(
+            Path::new("locales/messages_zh_CN.properties"),
+            "# Checkout\ncheckout.title = \\u7ed3\\u8d26\nempty =\n",
+        );
+        assert_eq!(
+            parsed,
+            Translations::from([(
+                "zh-CN".to_string(),
+                BTreeMap::from([("checkout.title".to_string(), "结账".to_string())]),
+            )])
+        );
+    }
+
+    #[test]
+    fn test_strings() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-strings");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut trs = translations();
+        let en = trs.get_mut("en").unwrap();
+        en.insert("apples.one".into(), "%{count} apple".into());
+        en.insert("apples.other".into(), "%{count} apples".into());
+        en.insert("sort.one".into(), "Not a plural".into());
+
+        let written = write(&dir, FileFormat::Strings, Layout::V1, &trs, "en").unwrap();
+        assert_eq!(
+            written,
+            vec![
+                dir.join("en.lproj/Localizable.strings"),
+                dir.join("en.lproj/Localizable.stringsdict"),
+                dir.join("fr.lproj/Localizable.strings"),
+            ]
+        );
+        let strings = std::fs::read_to_string(&written[0]).unwrap();
+        assert!(strings.contains("\"sort.one\" = \"Not a plural\";"));
+        assert!(!strings.contains("apples"));
+        let stringsdict = std::fs::read_to_string(&written[1]).unwrap();
+        assert!(stringsdict.contains("<string>%d apples</string>"));
+
+        assert_eq!(read(&dir, FileFormat::Strings, "en").unwrap(), trs);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_android() {
+        let content = r#"<?xml version="1.0" encoding="utf-8"?>
+<resources>
+    <!-- <string name="commented">Commented</string> -->
+    <string name="app_name" translatable="false">My App</string>
+    <string name="hello">Hello, %{name}!</string>
+    <string name="escaped">It\'s \"quoted\" \@home\n&amp; more</string>
+    <string name="spaces">"  Kept   spaces "</string>
+    <string name="collapsed">
+        Collapsed
+        spaces
+    </string>
+    <string name="empty"/>
+    <plurals name="apples">
+        <item quantity="one">%d apple</item>
+        <item quantity="other">%d apples</item>
+    </plurals>
+</resources>
+"#;
+        let parsed = parse_android("zh-CN", content);
+        assert_eq!(
+            parsed["zh-CN"],
+            BTreeMap::from([
+                ("app_name".to_string(), "My App".to_string()),
+                ("hello".to_string(), "Hello, %{name}!".to_string()),
+                (
+                    "escaped".to_string(),
+                    "It's \"quoted\" @home\n& more".to_string()
+                ),
+                ("spaces".to_string(), "  Kept   spaces ".to_string()),
+                ("collapsed".to_string(), "Collapsed spaces".to_string()),
+                ("apples.one".to_string(), "%{count} apple".to_string()),
+                ("apples.other".to_string(), "%{count} apples".to_string()),
+            ])
+        );
+
+        let texts = &parsed["zh-CN"];
+        assert_eq!(&parse_android("zh-CN", &to_android(texts))["zh-CN"], texts);
+
+        for (locale, qualifier) in [
+            ("fr", "fr"),
+            ("zh-CN", "zh-rCN"),
+            ("es-419", "es-r419"),
+            ("sr-Latn", "b+sr+Latn"),
+        ] {
+            assert_eq!(android_qualifier(locale), qualifier);
+            let path = PathBuf::from(format!("res/values-{}/strings.xml", qualifier));
+            assert_eq!(android_locale(&path, "en").as_deref(), Some(locale));
+        }
+        assert_eq!(
+            android_locale(Path::new("res/values/strings.xml"), "en").as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            android_locale(Path::new("res/values-night/colors.xml"), "en"),
+            None
+        );
+        assert_eq!(android_locale(Path::new("res/layout/main.xml"), "en"), None);
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            to_csv("fr", &translations()).unwrap(),
+            "key,fr,en\n\
+             hello,\"Bonjour, \"\"%{name}\"\" !\",\"Hello, \"\"%{name}\"\"!\"\n\
+             messages.body,,\"Line 1\nLine 2 & <b>\"\n"
+        );
+    }
+
+    #[test]
+    fn test_convert() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-convert");
+        let _ = std::fs::remove_dir_all(&dir);
+        let trs = translations();
+
+        for (format, layout) in [
+            (FileFormat::Yaml, Layout::V1),
+            (FileFormat::Json, Layout::V2),
+            (FileFormat::Toml, Layout::V1),
+            (FileFormat::Po, Layout::V1),
+            (FileFormat::Xliff, Layout::V1),
+            (FileFormat::Properties, Layout::V1),
+            (FileFormat::Strings, Layout::V1),
+            (FileFormat::Android, Layout::V1),
+            (FileFormat::Csv, Layout::V1),
+        ] {
+            let output = dir.join(format.extensions()[0]);
+            write(&output, format, layout, &trs, "en").unwrap();
+            assert_eq!(read(&output, format, "en").unwrap(), trs, "{:?}", format);
+        }
+
+        assert_eq!(
+            detect_layout(dir.join("json"), FileFormat::Json),
+            Layout::V2
+        );
+        assert_eq!(detect_layout(dir.join("yml"), FileFormat::Yaml), Layout::V1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-migrate");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("view")).unwrap();
+        std::fs::write(dir.join("en.yml"), "hello: Hello\n").unwrap();
+        std::fs::write(dir.join("fr.yml"), "hello: Bonjour\n").unwrap();
+        std::fs::write(dir.join("view/title.en.json"), r#"{"title": "Title"}"#).unwrap();
+        std::fs::write(dir.join("TODO.yml"), "_version: 2\n").unwrap();
+
+        let migration = migrate(&dir, Layout::V2).unwrap();
+        let paths = migration
+            .files
+            .iter()
+            .map(|f| f.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![dir.join("app.yml"), dir.join("view/title.json")]
+        );
+        assert_eq!(migration.removed.len(), 3);
+        for file in &migration.files {
+            assert_eq!(file.version(), 2);
+            file.save().unwrap();
+        }
+        for path in &migration.removed {
+            std::fs::remove_file(path).unwrap();
+        }
+        assert_eq!(
+            read(&dir, FileFormat::Yaml, "en").unwrap()["fr"]["hello"],
+            "Bonjour"
+        );
+
+        let migration = migrate(&dir, Layout::V1).unwrap();
+        let paths = migration
+            .files
+            .iter()
+            .map(|f| f.path.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                dir.join("en.yml"),
+                dir.join("fr.yml"),
+                dir.join("view/title.en.json")
+            ]
+        );
+        assert_eq!(migration.files[1].translations()["fr"]["hello"], "Bonjour");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
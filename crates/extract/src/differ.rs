@@ -0,0 +1,141 @@
+use crate::checker::Translations;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// The change of a key in a locale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum Change {
+    Added { new: String },
+    Removed { old: String },
+    Changed { old: String, new: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyDiff {
+    pub locale: String,
+    pub key: String,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+/// Compare two versions of the translations, sorted by locale and key.
+pub fn diff(old: &Translations, new: &Translations) -> Vec<KeyDiff> {
+    let locales = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+
+    let mut result = vec![];
+    for locale in locales {
+        let old_trs = old.get(locale);
+        let new_trs = new.get(locale);
+        let keys = old_trs
+            .into_iter()
+            .chain(new_trs)
+            .flat_map(|trs| trs.keys())
+            .collect::<BTreeSet<_>>();
+
+        for key in keys {
+            let change = match (
+                old_trs.and_then(|trs| trs.get(key)),
+                new_trs.and_then(|trs| trs.get(key)),
+            ) {
+                (None, Some(new)) => Change::Added { new: new.clone() },
+                (Some(old), None) => Change::Removed { old: old.clone() },
+                (Some(old), Some(new)) if old != new => Change::Changed {
+                    old: old.clone(),
+                    new: new.clone(),
+                },
+                _ => continue,
+            };
+
+            result.push(KeyDiff {
+                locale: locale.clone(),
+                key: key.clone(),
+                change,
+            });
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn translations(data: &[(&str, &[(&str, &str)])]) -> Translations {
+        data.iter()
+            .map(|(locale, texts)| {
+                let texts = texts
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect::<BTreeMap<_, _>>();
+                (locale.to_string(), texts)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = translations(&[
+            (
+                "en",
+                &[("hello", "Hello"), ("old", "Old"), ("same", "Same")],
+            ),
+            ("fr", &[("hello", "Bonjour")]),
+        ]);
+        let new = translations(&[
+            ("en", &[("hello", "Hi"), ("new", "New"), ("same", "Same")]),
+            ("zh", &[("hello", "你好")]),
+        ]);
+
+        let result = diff(&old, &new)
+            .into_iter()
+            .map(|d| (d.locale, d.key, d.change))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            result,
+            vec![
+                (
+                    "en".into(),
+                    "hello".into(),
+                    Change::Changed {
+                        old: "Hello".into(),
+                        new: "Hi".into()
+                    }
+                ),
+                (
+                    "en".into(),
+                    "new".into(),
+                    Change::Added { new: "New".into() }
+                ),
+                (
+                    "en".into(),
+                    "old".into(),
+                    Change::Removed { old: "Old".into() }
+                ),
+                (
+                    "fr".into(),
+                    "hello".into(),
+                    Change::Removed {
+                        old: "Bonjour".into()
+                    }
+                ),
+                (
+                    "zh".into(),
+                    "hello".into(),
+                    Change::Added {
+                        new: "你好".into()
+                    }
+                ),
+            ]
+        );
+        assert!(diff(&new, &new).is_empty());
+
+        let json = serde_json::to_string(&diff(&old, &new)[0]).unwrap();
+        assert_eq!(
+            json,
+            r#"{"locale":"en","key":"hello","change":"changed","old":"Hello","new":"Hi"}"#
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Step through the untranslated keys of a locale and write the translations back,
+//! for `cargo i18n edit`.
+use crate::checker::{self, Translations};
+use crate::extractor::{Location, Results};
+use crate::generator::{TODO_COMMENT, TODO_FILENAME};
+use crate::locale_file::{locale_files, plan_translations, LocaleFile};
+use crate::validator::placeholders;
+use anyhow::Error;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// An untranslated key of a locale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub key: String,
+    /// The text in the base locale, if any.
+    pub base_text: Option<String>,
+    /// The current text in the locale, marked with [`TODO_COMMENT`].
+    pub current: Option<String>,
+    /// Where the key is used in source.
+    pub locations: Vec<Location>,
+}
+
+impl Entry {
+    /// The `%{...}` placeholders of the base locale text.
+    pub fn placeholders(&self) -> BTreeSet<String> {
+        self.base_text
+            .as_deref()
+            .map(placeholders)
+            .unwrap_or_default()
+    }
+
+    /// The placeholders of the base locale text missing in the translation.
+    pub fn missing_placeholders(&self, text: &str) -> Vec<String> {
+        let used = placeholders(text);
+        self.placeholders()
+            .into_iter()
+            .filter(|name| !used.contains(name))
+            .collect()
+    }
+}
+
+/// The untranslated keys of the locale, sorted by key: the keys used in source or in the base
+/// locale but missing in the locale, and the keys marked with [`TODO_COMMENT`] in the locale files.
+pub fn untranslated(
+    results: &Results,
+    translations: &Translations,
+    locales_path: &Path,
+    locale: &str,
+    base_locale: &str,
+) -> Result<Vec<Entry>, Error> {
+    let base_texts = translations.get(base_locale);
+    let texts = translations.get(locale);
+
+    let report = checker::check(results, translations, &[locale.to_string()], base_locale);
+    let mut keys = report
+        .missing
+        .into_iter()
+        .chain(report.missing_from_base)
+        .map(|missing| missing.key)
+        .collect::<BTreeSet<_>>();
+
+    for path in locale_files(locales_path) {
+        if path.ends_with(TODO_FILENAME) {
+            continue;
+        }
+        let file = LocaleFile::load(&path)?;
+        for key in file
+            .translations()
+            .get(locale)
+            .into_iter()
+            .flat_map(|t| t.keys())
+        {
+            if file.has_comment(key, locale, TODO_COMMENT) {
+                keys.insert(key.clone());
+            }
+        }
+    }
+
+    Ok(keys
+        .into_iter()
+        .map(|key| Entry {
+            base_text: base_texts.and_then(|t| t.get(&key)).cloned(),
+            current: texts.and_then(|t| t.get(&key)).cloned(),
+            locations: results
+                .get(&key)
+                .map(|m| m.locations.clone())
+                .unwrap_or_default(),
+            key,
+        })
+        .collect())
+}
+
+/// Write the translation of a key into the locale files like
+/// [`crate::locale_file::apply_translations`], and remove its [`TODO_COMMENT`].
+///
+/// Returns the file written.
+pub fn save_translation(
+    locales_path: &Path,
+    locale: &str,
+    key: &str,
+    text: &str,
+) -> Result<PathBuf, Error> {
+    let translations = Translations::from([(
+        locale.to_string(),
+        BTreeMap::from([(key.to_string(), text.to_string())]),
+    )]);
+    let mut files = plan_translations(locales_path, &translations, None)?;
+    let mut file = files
+        .pop()
+        .ok_or_else(|| Error::msg(format!("No locale file to write \"{}\"", key)))?;
+    file.remove_comment(key, locale, TODO_COMMENT);
+    file.save()?;
+    Ok(file.path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::Message;
+    use rust_i18n_support::load_locales;
+
+    #[test]
+    fn test_untranslated() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-editor");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("en.yml"),
+            "hello: Hello, %{name}!\ntitle: Title\nbody: Body\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("de.yml"),
+            "hello: Hallo, %{name}!\n# TODO: translate\ntitle: Title\n",
+        )
+        .unwrap();
+
+        let results = Results::from([(
+            "used".to_string(),
+            Message {
+                key: "used".into(),
+                locations: vec![Location {
+                    file: "src/main.rs".into(),
+                    line: 3,
+                }],
+                ..Default::default()
+            },
+        )]);
+        let load = || load_locales(&dir.display().to_string(), |_| false);
+
+        let entries = untranslated(&results, &load(), &dir, "de", "en").unwrap();
+        let keys = entries.iter().map(|e| e.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, vec!["body", "title", "used"]);
+        assert_eq!(entries[0].base_text.as_deref(), Some("Body"));
+        assert_eq!(entries[1].current.as_deref(), Some("Title"));
+        assert_eq!(entries[2].locations.len(), 1);
+
+        let entry = Entry {
+            key: "hello".into(),
+            base_text: Some("Hello, %{name}!".into()),
+            current: None,
+            locations: vec![],
+        };
+        assert_eq!(entry.missing_placeholders("Hallo!"), vec!["name"]);
+        assert!(entry.missing_placeholders("Hallo, %{name}!").is_empty());
+
+        let path = save_translation(&dir, "de", "title", "Titel").unwrap();
+        assert_eq!(path, dir.join("de.yml"));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "hello: Hallo, %{name}!\ntitle: Titel\n"
+        );
+        let entries = untranslated(&results, &load(), &dir, "de", "en").unwrap();
+        let keys = entries.iter().map(|e| e.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, vec!["body", "used"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
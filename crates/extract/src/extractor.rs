@@ -2,18 +2,22 @@ use anyhow::Error;
 use proc_macro2::{TokenStream, TokenTree};
 use quote::ToTokens;
 use rust_i18n_support::I18nConfig;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
 pub type Results = HashMap<String, Message>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The string constants of the crate by name, `None` if the name is defined with different values.
+pub type ConstKeys = HashMap<String, Option<String>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Location {
     pub file: std::path::PathBuf,
     pub line: usize,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Message {
     pub key: String,
     pub index: usize,
@@ -32,7 +36,16 @@ impl Message {
     }
 }
 
-static METHOD_NAMES: &[&str] = &["t", "tr"];
+/// A `t!` call with a key that isn't a string literal, which can't be extracted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DynamicKey {
+    pub location: Location,
+    /// The key expression as written in source, on one line.
+    pub expr: String,
+}
+
+static METHOD_NAMES: &[&str] = &["t", "tr", "t_lazy"];
+static KEY_MACRO_NAMES: &[&str] = &["key"];
 
 #[allow(clippy::ptr_arg)]
 pub fn extract(
@@ -41,12 +54,351 @@ pub fn extract(
     source: &str,
     cfg: I18nConfig,
 ) -> Result<(), Error> {
-    let mut ex = Extractor { results, path, cfg };
+    extract_with_consts(results, path, source, cfg, &ConstKeys::new())
+}
 
+/// Extract like [`extract`], the `t!(NAME)` keys are resolved with the constants of the file,
+/// or else the constants of the crate from [`collect_const_keys`].
+#[allow(clippy::ptr_arg)]
+pub fn extract_with_consts(
+    results: &mut Results,
+    path: &PathBuf,
+    source: &str,
+    cfg: I18nConfig,
+    consts: &ConstKeys,
+) -> Result<(), Error> {
     let file = syn::parse_file(source)
         .unwrap_or_else(|_| panic!("Failed to parse file, file: {}", path.display()));
+    let mut localized_errors = vec![];
+    collect_localized_error_keys(&file.items, &mut localized_errors);
     let stream = file.into_token_stream();
-    ex.invoke(stream)
+
+    let mut ex = Extractor {
+        results,
+        path,
+        cfg,
+        consts: Consts::new(stream.clone(), consts),
+    };
+    ex.invoke(stream)?;
+
+    for (key, line) in localized_errors {
+        add_message(ex.results, &key, ex.path, line, &ex.cfg);
+    }
+    Ok(())
+}
+
+/// Collect the keys of the variants of the `#[derive(LocalizedError)]` enums, with their lines,
+/// like the derive: the `#[i18n(key = "...")]` of a variant, or else the snake case name of
+/// the variant under the `#[i18n(prefix = "...")]` of the enum, or its snake case name.
+fn collect_localized_error_keys(items: &[syn::Item], keys: &mut Vec<(String, usize)>) {
+    let i18n_attr = |attrs: &[syn::Attribute], name: &str| {
+        let mut value = None;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("i18n")) {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident(name) {
+                    value = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                }
+                Ok(())
+            });
+        }
+        value
+    };
+
+    for item in items {
+        match item {
+            syn::Item::Enum(item) if derives_localized_error(&item.attrs) => {
+                let prefix = i18n_attr(&item.attrs, "prefix")
+                    .unwrap_or_else(|| to_snake_case(&item.ident.to_string()));
+                for variant in &item.variants {
+                    let key = i18n_attr(&variant.attrs, "key").unwrap_or_else(|| {
+                        format!("{}.{}", prefix, to_snake_case(&variant.ident.to_string()))
+                    });
+                    keys.push((key, variant.ident.span().start().line));
+                }
+            }
+            syn::Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    collect_localized_error_keys(items, keys);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn derives_localized_error(attrs: &[syn::Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| {
+            let mut derives = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                derives |= meta
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "LocalizedError");
+                Ok(())
+            });
+            derives
+        })
+}
+
+/// `NotFound` => `not_found`, like the `LocalizedError` derive.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut chars = name.chars().peekable();
+    let mut prev_lower = false;
+    while let Some(c) = chars.next() {
+        if c.is_uppercase() {
+            let next_lower = chars.peek().is_some_and(|next| next.is_lowercase());
+            if !snake.is_empty() && (prev_lower || next_lower) && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+            prev_lower = false;
+        } else {
+            snake.push(c);
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        }
+    }
+    snake
+}
+
+/// Collect the `const NAME: &str = "..."` and `static NAME: &str = "..."` definitions of the
+/// source, with a literal or `key!("...")` value, to resolve the `t!(NAME)` keys.
+pub fn collect_const_keys(consts: &mut ConstKeys, source: &str) -> Result<(), Error> {
+    let file = syn::parse_file(source)?;
+    scan_consts(file.into_token_stream(), consts);
+    Ok(())
+}
+
+/// Merge the constants collected from another file, like [`collect_const_keys`] for both files.
+pub fn merge_const_keys(consts: &mut ConstKeys, other: ConstKeys) {
+    for (name, value) in other {
+        match consts.get(&name) {
+            Some(existing) if *existing != value => {
+                consts.insert(name, None);
+            }
+            _ => {
+                consts.insert(name, value);
+            }
+        }
+    }
+}
+
+/// Find the `t!` and `tr!` calls with a key that isn't a string literal, e.g. `t!(key)`
+/// or `t!(format!("error.{}", code))`, their keys can't be verified by extraction.
+///
+/// The `key!("...")` keys and the constants resolved like [`extract_with_consts`] are not reported.
+#[allow(clippy::ptr_arg)]
+pub fn find_dynamic_keys(
+    path: &PathBuf,
+    source: &str,
+    consts: &ConstKeys,
+) -> Result<Vec<DynamicKey>, Error> {
+    let file = syn::parse_file(source)
+        .map_err(|err| Error::msg(format!("Failed to parse {}: {}", path.display(), err)))?;
+    let stream = file.into_token_stream();
+    let consts = Consts::new(stream.clone(), consts);
+
+    let mut keys = vec![];
+    visit_calls(stream, METHOD_NAMES, &mut |_, stream| {
+        let key = first_argument(stream);
+        let (Some(first), Some(last)) = (key.first(), key.last()) else {
+            return;
+        };
+        if consts.resolve(&key).is_some() {
+            return;
+        }
+
+        let start = first.span().start();
+        let expr = source_text(source, start, last.span().end())
+            .unwrap_or_else(|| key.iter().map(|t| t.to_string()).collect());
+        keys.push(DynamicKey {
+            location: Location {
+                file: path.clone(),
+                line: start.line,
+            },
+            expr: format_message_key(&expr),
+        });
+    });
+
+    Ok(keys)
+}
+
+/// Call `f` with the arguments of every call of the macros in the token stream,
+/// e.g. the `t!` and `tr!` calls.
+///
+/// The tokens of every group are scanned recursively, so the calls nested in the arguments
+/// of other macros, e.g. `println!("{}", t!("hello"))`, `vec![t!("a")]`, custom macros, or
+/// the arguments of `t!` itself, are found too.
+fn visit_calls(stream: TokenStream, names: &[&str], f: &mut impl FnMut(&str, TokenStream)) {
+    let mut token_iter = stream.into_iter().peekable();
+
+    while let Some(token) = token_iter.next() {
+        match token {
+            TokenTree::Group(group) => visit_calls(group.stream(), names, f),
+            TokenTree::Ident(ident) => {
+                let mut is_macro = false;
+                if let Some(TokenTree::Punct(punct)) = token_iter.peek() {
+                    if punct.to_string() == "!" {
+                        is_macro = true;
+                        token_iter.next();
+                    }
+                }
+
+                let ident_str = ident.to_string();
+                if names.contains(&ident_str.as_str()) && is_macro {
+                    if let Some(TokenTree::Group(group)) = token_iter.peek() {
+                        f(&ident_str, group.stream());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The tokens of the first argument of a macro call, before the first `,`.
+fn first_argument(stream: TokenStream) -> Vec<TokenTree> {
+    stream
+        .into_iter()
+        .take_while(|token| !matches!(token, TokenTree::Punct(p) if p.as_char() == ','))
+        .collect()
+}
+
+/// Find the string constants in the token stream, see [`collect_const_keys`].
+fn scan_consts(stream: TokenStream, consts: &mut ConstKeys) {
+    let tokens = stream.into_iter().collect::<Vec<_>>();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => scan_consts(group.stream(), consts),
+            TokenTree::Ident(ident) if ident == "const" || ident == "static" => {
+                let Some((name, value)) = const_definition(&tokens[i + 1..]) else {
+                    continue;
+                };
+                match consts.get(&name) {
+                    Some(existing) if existing.as_ref() != Some(&value) => {
+                        consts.insert(name, None);
+                    }
+                    _ => {
+                        consts.insert(name, Some(value));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parse `NAME: &str = "...";` after `const` or `static`.
+fn const_definition(tokens: &[TokenTree]) -> Option<(String, String)> {
+    let is_punct =
+        |token: &TokenTree, c: char| matches!(token, TokenTree::Punct(p) if p.as_char() == c);
+
+    let [TokenTree::Ident(name), colon, rest @ ..] = tokens else {
+        return None;
+    };
+    if !is_punct(colon, ':') {
+        return None;
+    }
+    let eq = rest.iter().position(|token| is_punct(token, '='))?;
+    let ty = rest[..eq]
+        .iter()
+        .map(|token| token.to_string())
+        .collect::<String>();
+    if ty != "&str" && ty != "&'staticstr" {
+        return None;
+    }
+    let end = rest.iter().position(|token| is_punct(token, ';'))?;
+    Some((name.to_string(), key_value(rest.get(eq + 1..end)?)?))
+}
+
+/// The key of a string literal, or a `key!("...")` call.
+fn key_value(tokens: &[TokenTree]) -> Option<String> {
+    match tokens {
+        [TokenTree::Literal(lit)] => literal_to_string(lit),
+        _ => key_macro_literal(tokens).and_then(|lit| literal_to_string(&lit)),
+    }
+}
+
+/// The literal of a `key!("...")` call.
+fn key_macro_literal(tokens: &[TokenTree]) -> Option<proc_macro2::Literal> {
+    let [TokenTree::Ident(name), TokenTree::Punct(bang), TokenTree::Group(group)] = tokens else {
+        return None;
+    };
+    if !KEY_MACRO_NAMES.contains(&name.to_string().as_str()) || bang.as_char() != '!' {
+        return None;
+    }
+    match first_argument(group.stream()).as_slice() {
+        [TokenTree::Literal(lit)] => Some(lit.clone()),
+        _ => None,
+    }
+}
+
+/// The constants to resolve the keys of a file, the ones of the file first.
+struct Consts<'a> {
+    local: ConstKeys,
+    crate_consts: &'a ConstKeys,
+}
+
+impl<'a> Consts<'a> {
+    fn new(stream: TokenStream, crate_consts: &'a ConstKeys) -> Self {
+        let mut local = ConstKeys::new();
+        scan_consts(stream, &mut local);
+        Self {
+            local,
+            crate_consts,
+        }
+    }
+
+    /// Resolve the key of the first argument of a `t!` call: a string literal, a `key!("...")`
+    /// call, or the path of a constant, e.g. `KEY` or `keys::KEY`.
+    fn resolve(&self, tokens: &[TokenTree]) -> Option<String> {
+        if let Some(key) = key_value(tokens) {
+            return Some(key);
+        }
+
+        let is_path = tokens.iter().all(|token| match token {
+            TokenTree::Ident(_) => true,
+            TokenTree::Punct(p) => p.as_char() == ':',
+            _ => false,
+        });
+        let Some(TokenTree::Ident(name)) = tokens.last() else {
+            return None;
+        };
+        if !is_path {
+            return None;
+        }
+
+        let name = name.to_string();
+        match self.local.get(&name) {
+            Some(Some(value)) => Some(value.clone()),
+            _ => self.crate_consts.get(&name).cloned().flatten(),
+        }
+    }
+}
+
+/// The text of the source between two positions of the spans.
+fn source_text(
+    source: &str,
+    start: proc_macro2::LineColumn,
+    end: proc_macro2::LineColumn,
+) -> Option<String> {
+    let offset = |pos: proc_macro2::LineColumn| {
+        let line_start = source
+            .split_inclusive('\n')
+            .take(pos.line.checked_sub(1)?)
+            .map(str::len)
+            .sum::<usize>();
+        let column = source[line_start..]
+            .char_indices()
+            .nth(pos.column)
+            .map_or(source.len() - line_start, |(i, _)| i);
+        Some(line_start + column)
+    };
+    source.get(offset(start)?..offset(end)?).map(str::to_string)
 }
 
 #[allow(dead_code)]
@@ -54,89 +406,116 @@ struct Extractor<'a> {
     results: &'a mut Results,
     path: &'a PathBuf,
     cfg: I18nConfig,
+    consts: Consts<'a>,
 }
 
 impl<'a> Extractor<'a> {
+    /// Find the `t!` and `tr!` calls in the token stream, see [`visit_calls`].
     fn invoke(&mut self, stream: TokenStream) -> Result<(), Error> {
-        let mut token_iter = stream.into_iter().peekable();
-
-        while let Some(token) = token_iter.next() {
-            match token {
-                TokenTree::Group(group) => self.invoke(group.stream())?,
-                TokenTree::Ident(ident) => {
-                    let mut is_macro = false;
-                    if let Some(TokenTree::Punct(punct)) = token_iter.peek() {
-                        if punct.to_string() == "!" {
-                            is_macro = true;
-                            token_iter.next();
-                        }
-                    }
-
-                    let ident_str = ident.to_string();
-                    if METHOD_NAMES.contains(&ident_str.as_str()) && is_macro {
-                        if let Some(TokenTree::Group(group)) = token_iter.peek() {
-                            self.take_message(group.stream());
-                        }
-                    }
-                }
-                _ => {}
+        let names = [METHOD_NAMES, KEY_MACRO_NAMES].concat();
+        visit_calls(stream, &names, &mut |name, stream| {
+            if KEY_MACRO_NAMES.contains(&name) {
+                self.take_key(stream)
+            } else {
+                self.take_message(stream)
             }
-        }
-
+        });
         Ok(())
     }
 
     fn take_message(&mut self, stream: TokenStream) {
-        let mut token_iter = stream.into_iter().peekable();
+        let tokens = first_argument(stream);
+        // Taken as a `key!` call by itself
+        if key_macro_literal(&tokens).is_some() {
+            return;
+        }
 
-        let literal = if let Some(TokenTree::Literal(literal)) = token_iter.next() {
-            literal
-        } else {
+        let Some(first) = tokens.first() else {
             return;
         };
+        let key = match first {
+            TokenTree::Literal(lit) => literal_to_string(lit),
+            _ => self.consts.resolve(&tokens),
+        };
 
-        let I18nConfig {
-            minify_key,
-            minify_key_len,
-            minify_key_prefix,
-            minify_key_thresh,
-            ..
-        } = &self.cfg;
-        let key: Option<proc_macro2::Literal> = Some(literal);
-
-        if let Some(lit) = key {
-            if let Some(key) = literal_to_string(&lit) {
-                let (message_key, message_content) = if *minify_key {
-                    let hashed_key = rust_i18n_support::MinifyKey::minify_key(
-                        &key,
-                        *minify_key_len,
-                        minify_key_prefix,
-                        *minify_key_thresh,
-                    );
-                    (hashed_key.to_string(), key.clone())
-                } else {
-                    let message_key = format_message_key(&key);
-                    (message_key.clone(), message_key)
-                };
-                let index = self.results.len();
-                let message = self
-                    .results
-                    .entry(message_key)
-                    .or_insert_with(|| Message::new(&message_content, index, *minify_key));
-
-                let span = lit.span();
-                let line = span.start().line;
-                if line > 0 {
-                    message.locations.push(Location {
-                        file: self.path.clone(),
-                        line,
-                    });
-                }
+        if let Some(key) = key {
+            let line = first.span().start().line;
+            add_message(self.results, &key, self.path, line, &self.cfg);
+        }
+    }
+
+    /// Take the literal of a `key!("...")` call as a used key.
+    fn take_key(&mut self, stream: TokenStream) {
+        if let [TokenTree::Literal(lit)] = first_argument(stream).as_slice() {
+            if let Some(key) = literal_to_string(lit) {
+                let line = lit.span().start().line;
+                add_message(self.results, &key, self.path, line, &self.cfg);
+            }
+        }
+    }
+}
+
+/// Merge the messages extracted from another crate, keeping the order of extraction.
+pub fn merge_results(results: &mut Results, other: Results) {
+    let mut messages = other.into_iter().collect::<Vec<_>>();
+    messages.sort_by_key(|(_, m)| m.index);
+
+    for (key, other) in messages {
+        let index = results.len();
+        let message = results.entry(key).or_insert_with(|| Message {
+            index,
+            locations: vec![],
+            ..other.clone()
+        });
+        for location in other.locations {
+            if !message.locations.contains(&location) {
+                message.locations.push(location);
             }
         }
     }
 }
 
+/// Add a message found at the line of the file to the results.
+pub(crate) fn add_message(
+    results: &mut Results,
+    key: &str,
+    path: &std::path::Path,
+    line: usize,
+    cfg: &I18nConfig,
+) {
+    let I18nConfig {
+        minify_key,
+        minify_key_len,
+        minify_key_prefix,
+        minify_key_thresh,
+        ..
+    } = cfg;
+
+    let (message_key, message_content) = if *minify_key {
+        let hashed_key = rust_i18n_support::MinifyKey::minify_key(
+            key,
+            *minify_key_len,
+            minify_key_prefix,
+            *minify_key_thresh,
+        );
+        (hashed_key.to_string(), key.to_string())
+    } else {
+        let message_key = format_message_key(key);
+        (message_key.clone(), message_key)
+    };
+    let index = results.len();
+    let message = results
+        .entry(message_key)
+        .or_insert_with(|| Message::new(&message_content, index, *minify_key));
+
+    if line > 0 {
+        message.locations.push(Location {
+            file: path.to_path_buf(),
+            line,
+        });
+    }
+}
+
 fn literal_to_string(lit: &proc_macro2::Literal) -> Option<String> {
     match syn::parse_str::<syn::LitStr>(&lit.to_string()) {
         Ok(lit) => Some(lit.value()),
@@ -235,11 +614,13 @@ mod tests {
         ];
 
         let mut results = HashMap::new();
+        let consts = ConstKeys::new();
 
         let mut ex = Extractor {
             results: &mut results,
             path: &"hello.rs".to_owned().into(),
             cfg: I18nConfig::default(),
+            consts: Consts::new(TokenStream::new(), &consts),
         };
 
         ex.invoke(stream).unwrap();
@@ -255,4 +636,226 @@ mod tests {
             assert_eq!(*expected_message, actually_message);
         }
     }
+
+    #[test]
+    fn test_extract_nested_macros() {
+        let source = r#"
+        fn main() {
+            println!("{}", t!("println"));
+            let items = vec![t!("vec"), tr!("tr")];
+            format!("{}", t!("format", name = t!("nested")));
+            my_macro! { title => t!("custom") };
+            Builder::new().title(t!("builder")).build();
+            html! { p { (t!("html")) } };
+            assert_eq!(t!("assert"), "Assert", "{}", t!("assert.message"));
+            write!(f, "{}", rust_i18n::t!("path"))?;
+            Err(Error::NotFound(t_lazy!("lazy", id = id)))
+        }
+        "#;
+
+        let mut results = HashMap::new();
+        extract(
+            &mut results,
+            &"hello.rs".into(),
+            source,
+            I18nConfig::default(),
+        )
+        .unwrap();
+
+        let mut messages: Vec<_> = results.values().collect();
+        messages.sort_by_key(|m| m.index);
+        let keys = messages.iter().map(|m| m.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                "println",
+                "vec",
+                "tr",
+                "format",
+                "nested",
+                "custom",
+                "builder",
+                "html",
+                "assert",
+                "assert.message",
+                "path",
+                "lazy"
+            ]
+        );
+        assert_eq!(results["nested"].locations[0].line, 5);
+    }
+
+    #[test]
+    fn test_extract_attributes() {
+        let source = r#"
+        #[derive(Parser)]
+        #[command(about = t!("cli.about").to_string())]
+        struct Cli {
+            #[arg(short, long, help = t!("cli.args.verbose.help").to_string())]
+            verbose: bool,
+        }
+        "#;
+
+        let mut results = HashMap::new();
+        extract(
+            &mut results,
+            &"cli.rs".into(),
+            source,
+            I18nConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(results["cli.about"].locations[0].line, 3);
+        assert_eq!(results["cli.args.verbose.help"].locations[0].line, 5);
+    }
+
+    #[test]
+    fn test_extract_localized_errors() {
+        let source = r#"
+        #[derive(Debug, rust_i18n::LocalizedError)]
+        enum AppError {
+            NotFound,
+            #[i18n(key = "errors.custom")]
+            Custom { id: u32 },
+        }
+
+        mod api {
+            #[derive(LocalizedError)]
+            #[i18n(prefix = "errors.api")]
+            enum ApiError {
+                HTTPTimeout(u64),
+            }
+        }
+
+        #[derive(Debug)]
+        enum Other {
+            Ignored,
+        }
+        "#;
+
+        let mut results = HashMap::new();
+        extract(
+            &mut results,
+            &"errors.rs".into(),
+            source,
+            I18nConfig::default(),
+        )
+        .unwrap();
+
+        let mut keys = results.keys().map(String::as_str).collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "app_error.not_found",
+                "errors.api.http_timeout",
+                "errors.custom"
+            ]
+        );
+        assert_eq!(results["app_error.not_found"].locations[0].line, 4);
+    }
+
+    #[test]
+    fn test_merge_results() {
+        let mut results = Results::new();
+        let cfg = I18nConfig::default();
+        extract(
+            &mut results,
+            &"a.rs".into(),
+            r#"t!("a"); t!("shared");"#,
+            cfg.clone(),
+        )
+        .unwrap();
+
+        let mut other = Results::new();
+        extract(&mut other, &"b.rs".into(), r#"t!("shared"); t!("b");"#, cfg).unwrap();
+        merge_results(&mut results, other);
+
+        let mut messages: Vec<_> = results.values().collect();
+        messages.sort_by_key(|m| m.index);
+        let keys = messages.iter().map(|m| m.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, vec!["a", "shared", "b"]);
+        assert_eq!(results["shared"].locations.len(), 2);
+    }
+
+    #[test]
+    fn test_find_dynamic_keys() {
+        let source = r#"
+        fn main() {
+            t!("literal", name = name);
+            t!(key);
+            println!("{}", tr!(format!("error.{}",
+                code), locale = "en"));
+            t!(r"raw");
+        }
+        "#;
+
+        let keys = find_dynamic_keys(&"a.rs".into(), source, &ConstKeys::new()).unwrap();
+        let found = keys
+            .iter()
+            .map(|k| (k.location.line, k.expr.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![(4, "key"), (5, r#"format!("error.{}", code)"#)]);
+    }
+
+    #[test]
+    fn test_extract_const_keys() {
+        let mut consts = ConstKeys::new();
+        collect_const_keys(
+            &mut consts,
+            r#"
+            pub const TITLE: &str = "messages.title";
+            pub static BODY: &'static str = key!("messages.body");
+            const SHADOWED: &str = "a";
+            mod other { const SHADOWED: &str = "b"; }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(consts["TITLE"].as_deref(), Some("messages.title"));
+        assert_eq!(consts["BODY"].as_deref(), Some("messages.body"));
+        assert_eq!(consts["SHADOWED"], None);
+
+        let source = r#"
+        const LOCAL: &str = "local";
+        static ERRORS: &[&str] = &[key!("errors.not_found")];
+        fn main() {
+            t!(LOCAL);
+            t!(keys::TITLE, name = "Jason");
+            t!(key!("inline"));
+            t!(SHADOWED);
+        }
+        "#;
+        let mut results = Results::new();
+        extract_with_consts(
+            &mut results,
+            &"a.rs".into(),
+            source,
+            I18nConfig::default(),
+            &consts,
+        )
+        .unwrap();
+
+        let mut messages = results.values().collect::<Vec<_>>();
+        messages.sort_by_key(|m| m.index);
+        let found = messages
+            .iter()
+            .map(|m| (m.key.as_str(), m.locations[0].line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                ("errors.not_found", 3),
+                ("local", 5),
+                ("messages.title", 6),
+                ("inline", 7)
+            ]
+        );
+        assert_eq!(results["inline"].locations.len(), 1);
+
+        let keys = find_dynamic_keys(&"a.rs".into(), source, &consts).unwrap();
+        assert_eq!(
+            keys.iter().map(|k| k.expr.as_str()).collect::<Vec<_>>(),
+            vec!["SHADOWED"]
+        );
+    }
 }
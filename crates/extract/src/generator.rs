@@ -1,40 +1,240 @@
+use crate::comments::Comments;
 use crate::extractor::Message;
+use crate::locale_file::{Format, LocaleFile};
 use rust_i18n_support::load_locales;
 use std::collections::HashMap;
-use std::io::prelude::*;
 use std::io::Result;
 use std::path::Path;
 
 type Translations = HashMap<String, HashMap<String, String>>;
 
+/// The file for untranslated texts, it's ignored when checking existing translations.
+pub const TODO_FILENAME: &str = "TODO.yml";
+
+/// The comment above the untranslated texts added into the locale files.
+pub const TODO_COMMENT: &str = "# TODO: translate";
+
+/// A locale file the extraction writes, with the texts it adds and removes.
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    /// The new content of the file, not written yet.
+    pub file: LocaleFile,
+    /// Whether the file doesn't exist yet.
+    pub created: bool,
+    /// The `(locale, key)` of the added texts.
+    pub added: Vec<(String, String)>,
+    /// The `(locale, key)` of the removed texts.
+    pub removed: Vec<(String, String)>,
+}
+
+impl FileChange {
+    fn new(file: LocaleFile) -> Self {
+        let (created, old) = match LocaleFile::load(&file.path) {
+            Ok(old) => (false, old.translations()),
+            Err(_) => (true, Default::default()),
+        };
+        let new = file.translations();
+
+        let diff = |a: &crate::checker::Translations, b: &crate::checker::Translations| {
+            a.iter()
+                .flat_map(|(locale, texts)| {
+                    texts
+                        .keys()
+                        .filter(|key| !b.get(locale).is_some_and(|texts| texts.contains_key(*key)))
+                        .map(|key| (locale.clone(), key.clone()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Self {
+            added: diff(&new, &old),
+            removed: diff(&old, &new),
+            created,
+            file,
+        }
+    }
+}
+
 pub fn generate<'a, P: AsRef<Path>>(
     output_path: P,
     all_locales: &Vec<String>,
     messages: impl IntoIterator<Item = (&'a String, &'a Message)> + Clone,
 ) -> Result<()> {
-    let filename = "TODO.yml";
+    let changes = plan_todo_file(output_path, all_locales, messages)?;
+    write_changes(&changes)
+}
+
+/// Plan the [`TODO_FILENAME`] file of the untranslated texts, nothing is written.
+///
+/// Returns no change if all texts are translated.
+pub fn plan_todo_file<'a, P: AsRef<Path>>(
+    output_path: P,
+    all_locales: &Vec<String>,
+    messages: impl IntoIterator<Item = (&'a String, &'a Message)> + Clone,
+) -> Result<Vec<FileChange>> {
+    let filename = TODO_FILENAME;
     let format = "yaml";
 
     let trs = generate_result(&output_path, filename, all_locales, messages);
 
     if trs.is_empty() {
-        println!("All thing done.\n");
+        eprintln!("All thing done.\n");
 
-        return Ok(());
+        return Ok(vec![]);
+    }
+
+    eprintln!("Found {} new texts need to translate.", trs.len());
+    eprintln!("----------------------------------------");
+
+    let path = output_path.as_ref().join(filename);
+    let mut file = LocaleFile::parse(&path, &convert_text(&trs, format)).map_err(other_error)?;
+    // Keep the notes in the existing file
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        file.comments = Comments::parse(&existing);
+    }
+
+    Ok(vec![FileChange::new(file)])
+}
+
+/// Add the untranslated texts into the locale files of each locale, marked with [`TODO_COMMENT`],
+/// instead of writing them into [`TODO_FILENAME`].
+pub fn generate_in_locale_files<'a, P: AsRef<Path>>(
+    output_path: P,
+    all_locales: &Vec<String>,
+    base_locale: &str,
+    messages: impl IntoIterator<Item = (&'a String, &'a Message)> + Clone,
+) -> Result<()> {
+    let changes = plan_locale_files(output_path, all_locales, base_locale, messages)?;
+    write_changes(&changes)
+}
+
+/// Plan the locale files with the untranslated texts added, nothing is written.
+///
+/// The text is the one of the `base_locale` if any, or the same as in [`generate`].
+/// The keys go into the files like [`crate::locale_file::apply_translations`].
+pub fn plan_locale_files<'a, P: AsRef<Path>>(
+    output_path: P,
+    all_locales: &Vec<String>,
+    base_locale: &str,
+    messages: impl IntoIterator<Item = (&'a String, &'a Message)> + Clone,
+) -> Result<Vec<FileChange>> {
+    let trs = generate_result(&output_path, TODO_FILENAME, all_locales, messages);
+
+    if trs.is_empty() {
+        eprintln!("All thing done.\n");
+
+        return Ok(vec![]);
+    }
+
+    let data = load_locales(&output_path.as_ref().display().to_string(), |fname| {
+        fname.ends_with(TODO_FILENAME)
+    });
+    let base_trs = data.get(base_locale);
+
+    let mut locale_trs = crate::checker::Translations::new();
+    for (key, texts) in &trs {
+        let base_text = base_trs.and_then(|base_trs| base_trs.get(key));
+        for (locale, text) in texts {
+            locale_trs
+                .entry(locale.clone())
+                .or_default()
+                .insert(key.clone(), base_text.unwrap_or(text).clone());
+        }
     }
 
     eprintln!("Found {} new texts need to translate.", trs.len());
     eprintln!("----------------------------------------");
-    eprintln!("Writing to {}\n", filename);
+    let files =
+        crate::locale_file::plan_translations(&output_path, &locale_trs, Some(TODO_COMMENT))
+            .map_err(other_error)?;
+
+    Ok(files.into_iter().map(FileChange::new).collect())
+}
+
+/// Update the source location comments, e.g. `# src/main.rs:42`, above the keys used in
+/// source in the YAML locale files, with the file paths relative to the `base_path`.
+///
+/// The locations of the keys no longer used are removed. The files planned in `changes` are
+/// updated, and the other files changed only by the comments are added.
+pub fn plan_locations<'a, P: AsRef<Path>>(
+    output_path: P,
+    base_path: &Path,
+    messages: impl IntoIterator<Item = (&'a String, &'a Message)>,
+    changes: &mut Vec<FileChange>,
+) -> Result<()> {
+    let locations = messages
+        .into_iter()
+        .map(|(key, m)| {
+            let mut locations = m
+                .locations
+                .iter()
+                .map(|l| {
+                    let file = l.file.strip_prefix(base_path).unwrap_or(&l.file);
+                    format!(
+                        "{}:{}",
+                        file.display().to_string().replace('\\', "/"),
+                        l.line
+                    )
+                })
+                .collect::<Vec<_>>();
+            locations.dedup();
+            (key.as_str(), locations)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let set_locations = |file: &mut LocaleFile| {
+        for key in file.keys() {
+            let key_locations = locations.get(key.as_str()).map_or(&[][..], |l| l);
+            file.comments.set_locations(&key, key_locations);
+        }
+    };
+
+    for change in changes.iter_mut() {
+        if change.file.format == Format::Yaml {
+            set_locations(&mut change.file);
+        }
+    }
+
+    for path in crate::locale_file::locale_files(&output_path) {
+        if changes.iter().any(|change| change.file.path == path) {
+            continue;
+        }
+        let mut file = LocaleFile::load(&path).map_err(other_error)?;
+        if file.format != Format::Yaml {
+            continue;
+        }
+
+        let comments = file.comments.clone();
+        set_locations(&mut file);
+        if file.comments != comments {
+            changes.push(FileChange::new(file));
+        }
+    }
+
+    Ok(())
+}
 
-    let text = convert_text(&trs, format);
-    write_file(&output_path, filename, &text)?;
+/// Write the planned files, returns error if any, for let CI fail.
+fn write_changes(changes: &[FileChange]) -> Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    for change in changes {
+        eprintln!("Writing to {}", change.file.path.display());
+        change.file.save().map_err(other_error)?;
+    }
+    eprintln!();
 
     // Finally, return error for let CI fail
-    let err = std::io::Error::new(std::io::ErrorKind::Other, "");
+    let err = std::io::Error::other("");
     Err(err)
 }
 
+fn other_error(err: anyhow::Error) -> std::io::Error {
+    std::io::Error::other(err.to_string())
+}
+
 fn convert_text(trs: &Translations, format: &str) -> String {
     let mut value = serde_json::Value::Object(serde_json::Map::new());
     value["_version"] = serde_json::Value::Number(serde_json::Number::from(2));
@@ -68,7 +268,7 @@ fn generate_result<'a, P: AsRef<Path>>(
     let mut trs = Translations::new();
 
     for locale in all_locales {
-        println!("Checking [{}] and generating untranslated texts...", locale);
+        eprintln!("Checking [{}] and generating untranslated texts...", locale);
 
         // ~/work/my-project/locales
         let output_path = output_path.as_ref().display().to_string();
@@ -86,7 +286,7 @@ fn generate_result<'a, P: AsRef<Path>>(
             }
 
             if let Some(trs) = data.get(locale) {
-                if trs.get(key).is_some() {
+                if crate::checker::contains_key(trs, key) {
                     continue;
                 }
             }
@@ -94,7 +294,7 @@ fn generate_result<'a, P: AsRef<Path>>(
             let value = if m.minify_key {
                 m.key.to_owned()
             } else {
-                m.key.split('.').last().unwrap_or_default().to_string()
+                m.key.split('.').next_back().unwrap_or_default().to_string()
             };
 
             trs.entry(key.clone())
@@ -106,23 +306,6 @@ fn generate_result<'a, P: AsRef<Path>>(
     trs
 }
 
-fn write_file<P: AsRef<Path>>(output: &P, filename: &str, data: &str) -> Result<()> {
-    let output_file = std::path::Path::new(output.as_ref()).join(String::from(filename));
-    let folder = output_file.parent().unwrap();
-
-    // Ensure create folder
-    if !folder.exists() {
-        std::fs::create_dir_all(folder).unwrap();
-    }
-
-    let mut output = ::std::fs::File::create(&output_file)
-        .unwrap_or_else(|_| panic!("Unable to create {} file", &output_file.display()));
-
-    writeln!(output, "{}", data).expect("Write YAML file error");
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,4 +370,52 @@ mod tests {
         "#};
         assert_eq!(&result, &expect);
     }
+
+    #[test]
+    fn test_plan_locale_files() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-plan-locale-files");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("en.yml"), "hello: Hello\n").unwrap();
+
+        let messages = ["hello", "messages.title"]
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let message = Message {
+                    key: key.to_string(),
+                    index,
+                    minify_key: false,
+                    locations: vec![],
+                };
+                (key.to_string(), message)
+            })
+            .collect::<Vec<_>>();
+        let locales = vec!["en".to_string(), "fr".to_string()];
+        let changes = plan_locale_files(
+            &dir,
+            &locales,
+            "en",
+            messages.iter().map(|(key, message)| (key, message)),
+        )
+        .unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].file.path, dir.join("en.yml"));
+        assert!(!changes[0].created);
+        assert_eq!(
+            changes[0].added,
+            vec![("en".to_string(), "messages.title".to_string())]
+        );
+        assert_eq!(changes[1].file.path, dir.join("fr.yml"));
+        assert!(changes[1].created);
+        assert_eq!(changes[1].added.len(), 2);
+        assert!(changes[1].removed.is_empty());
+        // The base locale text is used if any
+        assert_eq!(changes[1].file.translations()["fr"]["hello"], "Hello");
+        // Nothing is written
+        assert!(!dir.join("fr.yml").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
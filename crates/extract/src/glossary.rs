@@ -0,0 +1,178 @@
+//! Check the translations use the approved terms of a glossary, for `cargo i18n validate --glossary`.
+//!
+//! The glossary maps the terms of the base locale to their approved translations in each locale,
+//! a locale may have several approved forms, e.g. for the plural:
+//!
+//! ```yml
+//! Workspace:
+//!   de: [Arbeitsbereich, Arbeitsbereiche]
+//!   fr: Espace de travail
+//! ```
+use crate::checker::Translations;
+use anyhow::{anyhow, Error};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The glossary file in the crate root, used by `--glossary` without a path.
+pub const GLOSSARY_FILENAME: &str = "glossary.yml";
+
+/// The approved translations of the terms of the base locale, by term and locale.
+pub type Glossary = BTreeMap<String, BTreeMap<String, Vec<String>>>;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Terms {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// A translation of a text with a glossary term, not using an approved translation of the term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermMismatch {
+    pub locale: String,
+    pub key: String,
+    /// The term in the base locale text.
+    pub term: String,
+    /// The approved translations of the term in the locale.
+    pub approved: Vec<String>,
+}
+
+/// Load a glossary file, see the [module](self) docs for the format.
+pub fn load_glossary(path: impl AsRef<Path>) -> Result<Glossary, Error> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| anyhow!("Failed to read {}: {}", path.display(), err))?;
+    parse_glossary(&content).map_err(|err| anyhow!("Failed to parse {}: {}", path.display(), err))
+}
+
+/// Parse the content of a glossary file.
+pub fn parse_glossary(content: &str) -> Result<Glossary, Error> {
+    let data = serde_yaml::from_str::<Option<BTreeMap<String, BTreeMap<String, Terms>>>>(content)?;
+    Ok(data
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(term, locales)| {
+            let locales = locales
+                .into_iter()
+                .map(|(locale, terms)| match terms {
+                    Terms::One(term) => (locale, vec![term]),
+                    Terms::Many(terms) => (locale, terms),
+                })
+                .collect();
+            (term, locales)
+        })
+        .collect())
+}
+
+/// Check the texts of the other locales use an approved translation of each glossary term
+/// in the base locale text.
+///
+/// The terms are matched as whole words in the base locale text, and anywhere in the
+/// translation, both ignoring case.
+pub fn check_terms(
+    translations: &Translations,
+    base_locale: &str,
+    glossary: &Glossary,
+) -> Vec<TermMismatch> {
+    let mut mismatches = vec![];
+    let Some(base_trs) = translations.get(base_locale) else {
+        return mismatches;
+    };
+
+    let terms = glossary
+        .iter()
+        .filter_map(|(term, locales)| {
+            let re = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(term))).ok()?;
+            Some((term, re, locales))
+        })
+        .collect::<Vec<_>>();
+
+    for (locale, trs) in translations {
+        if locale == base_locale {
+            continue;
+        }
+
+        for (key, text) in trs {
+            let Some(base_text) = base_trs.get(key) else {
+                continue;
+            };
+
+            let text = text.to_lowercase();
+            for (term, re, locales) in &terms {
+                let Some(approved) = locales.get(locale) else {
+                    continue;
+                };
+                if !re.is_match(base_text)
+                    || approved.iter().any(|t| text.contains(&t.to_lowercase()))
+                {
+                    continue;
+                }
+
+                mismatches.push(TermMismatch {
+                    locale: locale.clone(),
+                    key: key.clone(),
+                    term: term.to_string(),
+                    approved: approved.clone(),
+                });
+            }
+        }
+    }
+
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_check_terms() {
+        let glossary = parse_glossary(indoc! {r#"
+        Workspace:
+          de: [Arbeitsbereich, Arbeitsbereiche]
+          fr: Espace de travail
+        "#})
+        .unwrap();
+        assert_eq!(
+            glossary["Workspace"]["fr"],
+            vec!["Espace de travail".to_string()]
+        );
+
+        let translations = Translations::from([
+            (
+                "en".to_string(),
+                BTreeMap::from([
+                    ("open".to_string(), "Open workspace".to_string()),
+                    ("list".to_string(), "All Workspace items".to_string()),
+                    ("other".to_string(), "Workspaces".to_string()),
+                ]),
+            ),
+            (
+                "de".to_string(),
+                BTreeMap::from([
+                    ("open".to_string(), "Arbeitsbereich öffnen".to_string()),
+                    ("list".to_string(), "Alle Elemente im Projekt".to_string()),
+                    ("other".to_string(), "Projekte".to_string()),
+                ]),
+            ),
+            (
+                "fr".to_string(),
+                BTreeMap::from([("open".to_string(), "Ouvrir l'espace de travail".to_string())]),
+            ),
+        ]);
+
+        assert_eq!(
+            check_terms(&translations, "en", &glossary),
+            vec![TermMismatch {
+                locale: "de".into(),
+                key: "list".into(),
+                term: "Workspace".into(),
+                approved: vec!["Arbeitsbereich".into(), "Arbeitsbereiche".into()],
+            }]
+        );
+        assert!(parse_glossary("").unwrap().is_empty());
+    }
+}
@@ -1,21 +1,52 @@
 use anyhow::Error;
-use std::fs::File;
-use std::io::Read;
-use std::path::PathBuf;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
 
-pub fn iter_crate<F>(src_path: &str, mut callback: F) -> Result<(), Error>
+/// Iterate the Rust files in the source path.
+///
+/// The files matching the `ignore` glob patterns, relative to the source path, are skipped,
+/// and so are the files in `.gitignore`, even outside a git repository.
+pub fn iter_crate<F>(src_path: &str, ignore: &[String], mut callback: F) -> Result<(), Error>
 where
     F: FnMut(&PathBuf, &str) -> Result<(), Error>,
 {
+    for path in crate_files(src_path, ignore)? {
+        callback(&path, &read_source(&path))?;
+    }
+    Ok(())
+}
+
+/// Like [`iter_crate`], but the files are read and handled in parallel, on the rayon thread pool.
+///
+/// Returns the results in the order of [`iter_crate`], or the first error.
+pub fn par_iter_crate<T, F>(src_path: &str, ignore: &[String], callback: F) -> Result<Vec<T>, Error>
+where
+    T: Send,
+    F: Fn(&PathBuf, &str) -> Result<T, Error> + Sync,
+{
+    crate_files(src_path, ignore)?
+        .par_iter()
+        .map(|path| callback(path, &read_source(path)))
+        .collect()
+}
+
+/// The Rust files in the source path, in the order of [`iter_crate`].
+pub fn crate_files(src_path: &str, ignore: &[String]) -> Result<Vec<PathBuf>, Error> {
     let src_path = src_path.trim_end_matches('/');
 
+    let mut overrides = ignore::overrides::OverrideBuilder::new(src_path);
+    add_ignore_patterns(&mut overrides, ignore)?;
+
     let mut walker = ignore::WalkBuilder::new(src_path);
     walker
         .skip_stdout(true)
         .parents(true)
         .git_ignore(true)
+        .require_git(false)
+        .overrides(overrides.build()?)
         .follow_links(false);
 
+    let mut files = vec![];
     for result in walker.build() {
         match result {
             Ok(entry) => {
@@ -28,18 +59,140 @@ where
                     continue;
                 }
 
-                let filepath = String::from(path.to_str().unwrap());
-
-                let mut s = String::new();
-                let mut f = File::open(&filepath).expect("Failed to open file");
-                f.read_to_string(&mut s).expect("Failed to read file");
-
-                callback(&PathBuf::from(filepath), &s)?;
+                files.push(path.to_path_buf());
             }
             Err(err) => {
                 eprintln!("{}", err);
             }
         }
     }
+    Ok(files)
+}
+
+fn read_source(path: &Path) -> String {
+    std::fs::read_to_string(path).expect("Failed to read file")
+}
+
+/// Iterate the template files matching the glob patterns, relative to the source path.
+///
+/// The files matching the `ignore` glob patterns and the files that are not valid UTF-8
+/// are skipped.
+pub fn iter_templates<F>(
+    src_path: &str,
+    patterns: &[String],
+    ignore: &[String],
+    mut callback: F,
+) -> Result<(), Error>
+where
+    F: FnMut(&Path, &str) -> Result<(), Error>,
+{
+    if patterns.is_empty() {
+        return Ok(());
+    }
+
+    let src_path = src_path.trim_end_matches('/');
+    let mut overrides = ignore::overrides::OverrideBuilder::new(src_path);
+    for pattern in patterns {
+        overrides.add(pattern)?;
+    }
+    add_ignore_patterns(&mut overrides, ignore)?;
+
+    let walker = ignore::WalkBuilder::new(src_path)
+        .overrides(overrides.build()?)
+        .follow_links(false)
+        .build();
+
+    for entry in walker.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        if let Ok(source) = std::fs::read_to_string(path) {
+            callback(path, &source)?;
+        }
+    }
     Ok(())
 }
+
+/// Add the glob patterns as ignored, a pattern like `target/**` also skips the directory itself.
+fn add_ignore_patterns(
+    overrides: &mut ignore::overrides::OverrideBuilder,
+    patterns: &[String],
+) -> Result<(), Error> {
+    for pattern in patterns {
+        overrides.add(&format!("!{}", pattern))?;
+        if let Some(dir) = pattern.strip_suffix("/**") {
+            overrides.add(&format!("!{}", dir))?;
+        }
+    }
+    Ok(())
+}
+
+/// Find the directories of the workspace members with `cargo metadata`.
+pub fn workspace_members(src_path: &str) -> Result<Vec<PathBuf>, Error> {
+    let output = std::process::Command::new(std::env::var("CARGO").unwrap_or("cargo".into()))
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(src_path)
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::msg(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let mut members = metadata["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|package| package["manifest_path"].as_str())
+        .filter_map(|manifest| Path::new(manifest).parent().map(Path::to_path_buf))
+        .collect::<Vec<_>>();
+    members.sort();
+    Ok(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iter_crate_ignore() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-iter-ignore");
+        let _ = std::fs::remove_dir_all(&dir);
+        for file in [
+            "src/main.rs",
+            "generated/a.rs",
+            "vendor/b.rs",
+            "templates/c.html",
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        std::fs::write(dir.join(".gitignore"), "vendor/\n").unwrap();
+
+        let src_path = dir.display().to_string();
+        let ignore = vec!["generated/**".to_string()];
+        let mut files = vec![];
+        iter_crate(&src_path, &ignore, |path, _| {
+            files.push(path.strip_prefix(&dir).unwrap().to_path_buf());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(files, vec![PathBuf::from("src/main.rs")]);
+        let files = par_iter_crate(&src_path, &ignore, |path, _| Ok(path.clone())).unwrap();
+        assert_eq!(files, vec![dir.join("src/main.rs")]);
+
+        let mut templates = vec![];
+        let ignore = vec!["templates/**".to_string()];
+        iter_templates(&src_path, &["templates/**/*".into()], &ignore, |path, _| {
+            templates.push(path.to_path_buf());
+            Ok(())
+        })
+        .unwrap();
+        assert!(templates.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,223 @@
+//! Generate a Rust module with the keys of the locale files, for `t!` calls checked by the compiler.
+use crate::checker::Translations;
+use std::collections::{BTreeSet, HashSet};
+
+/// How the keys are written in the generated module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStyle {
+    /// A `pub const MESSAGES_TITLE: &str = "messages.title";` for each key, resolved by
+    /// the extraction when used as `t!(MESSAGES_TITLE)`.
+    Const,
+    /// A `Key` enum with a variant for each key, and `Key::as_str` to get the key.
+    Enum,
+}
+
+/// Generate the module of all keys in the translations, sorted by key.
+///
+/// Each key is documented with its text in the `base_locale`, if any.
+pub fn generate_keys(translations: &Translations, base_locale: &str, style: KeyStyle) -> String {
+    let keys = translations
+        .values()
+        .flat_map(|texts| texts.keys())
+        .collect::<BTreeSet<_>>();
+    let base_texts = translations.get(base_locale);
+
+    let mut used = HashSet::new();
+    let items = keys
+        .into_iter()
+        .map(|key| {
+            let name = match style {
+                KeyStyle::Const => unique_name(const_name(key), "_", &mut used),
+                KeyStyle::Enum => unique_name(variant_name(key), "", &mut used),
+            };
+            let text = base_texts.and_then(|texts| texts.get(key));
+            (key.as_str(), name, text.map(String::as_str))
+        })
+        .collect::<Vec<_>>();
+
+    let mut code = String::from(
+        "// This file is generated by `cargo i18n gen-keys`, do not edit it by hand.\n\n",
+    );
+    match style {
+        KeyStyle::Const => {
+            for (key, name, text) in &items {
+                code.push_str(&doc(text, ""));
+                code.push_str(&format!("pub const {}: &str = {:?};\n", name, key));
+            }
+        }
+        KeyStyle::Enum => {
+            code.push_str("/// The keys of the locale files.\n");
+            code.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n");
+            code.push_str("pub enum Key {\n");
+            for (_, name, text) in &items {
+                code.push_str(&doc(text, "    "));
+                code.push_str(&format!("    {},\n", name));
+            }
+            code.push_str("}\n\n");
+
+            code.push_str("impl Key {\n");
+            code.push_str("    /// All the keys, sorted by key.\n");
+            code.push_str("    pub const ALL: &'static [Key] = &[\n");
+            for (_, name, _) in &items {
+                code.push_str(&format!("        Key::{},\n", name));
+            }
+            code.push_str("    ];\n\n");
+            code.push_str("    /// The key in the locale files.\n");
+            code.push_str("    pub const fn as_str(self) -> &'static str {\n");
+            code.push_str("        match self {\n");
+            for (key, name, _) in &items {
+                code.push_str(&format!("            Key::{} => {:?},\n", name, key));
+            }
+            code.push_str("        }\n    }\n}\n\n");
+
+            code.push_str("impl AsRef<str> for Key {\n");
+            code.push_str("    fn as_ref(&self) -> &str {\n        self.as_str()\n    }\n}\n\n");
+            code.push_str("impl std::fmt::Display for Key {\n");
+            code.push_str(
+                "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n",
+            );
+            code.push_str("        f.write_str(self.as_str())\n    }\n}\n");
+        }
+    }
+    code
+}
+
+/// `messages.title` => `MESSAGES_TITLE`
+fn const_name(key: &str) -> String {
+    let name = words(key)
+        .iter()
+        .map(|word| word.to_uppercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    ident(name, "KEY", "_")
+}
+
+/// `messages.title` => `MessagesTitle`
+fn variant_name(key: &str) -> String {
+    let name = words(key)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    ident(name, "Key", "")
+}
+
+/// The ASCII alphanumeric words of the key, split at the other characters and the lower
+/// to upper case changes, e.g. `messages.newTitle` => `messages`, `new`, `Title`.
+fn words(key: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in key.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Make the name a valid identifier, `fallback` is used for the keys without ASCII letters,
+/// or before a leading digit.
+fn ident(name: String, fallback: &str, separator: &str) -> String {
+    match name.chars().next() {
+        None => fallback.to_string(),
+        Some(c) if c.is_ascii_digit() => format!("{}{}{}", fallback, separator, name),
+        _ if name == "Self" => format!("{}_", name),
+        _ => name,
+    }
+}
+
+/// Add a number to the names used by another key, e.g. `hello_world` after `hello-world`.
+fn unique_name(name: String, separator: &str, used: &mut HashSet<String>) -> String {
+    let mut unique = name.clone();
+    let mut n = 2;
+    while !used.insert(unique.clone()) {
+        unique = format!("{}{}{}", name, separator, n);
+        n += 1;
+    }
+    unique
+}
+
+fn doc(text: &Option<&str>, indent: &str) -> String {
+    let Some(text) = text else {
+        return String::new();
+    };
+    text.lines()
+        .map(|line| format!("{}/// {}\n", indent, line).replace("/// \n", "///\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use std::collections::BTreeMap;
+
+    fn translations() -> Translations {
+        Translations::from([
+            (
+                "en".to_string(),
+                BTreeMap::from([
+                    ("hello".to_string(), "Hello, %{name}!".to_string()),
+                    (
+                        "messages.newTitle".to_string(),
+                        "Title\nSecond line".to_string(),
+                    ),
+                ]),
+            ),
+            (
+                "fr".to_string(),
+                BTreeMap::from([
+                    ("hello".to_string(), "Bonjour, %{name} !".to_string()),
+                    ("404".to_string(), "Introuvable".to_string()),
+                    ("hello-world".to_string(), "Bonjour".to_string()),
+                    ("hello_world".to_string(), "Bonjour".to_string()),
+                ]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_generate_consts() {
+        assert_eq!(
+            generate_keys(&translations(), "en", KeyStyle::Const),
+            indoc! {r#"
+            // This file is generated by `cargo i18n gen-keys`, do not edit it by hand.
+
+            pub const KEY_404: &str = "404";
+            /// Hello, %{name}!
+            pub const HELLO: &str = "hello";
+            pub const HELLO_WORLD: &str = "hello-world";
+            pub const HELLO_WORLD_2: &str = "hello_world";
+            /// Title
+            /// Second line
+            pub const MESSAGES_NEW_TITLE: &str = "messages.newTitle";
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_generate_enum() {
+        let code = generate_keys(&translations(), "en", KeyStyle::Enum);
+        assert!(code.contains("pub enum Key {\n    Key404,\n    /// Hello, %{name}!\n    Hello,\n"));
+        assert!(code.contains("            Key::MessagesNewTitle => \"messages.newTitle\",\n"));
+        assert!(code.contains("        Key::HelloWorld2,\n"));
+    }
+}
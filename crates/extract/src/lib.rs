@@ -1,3 +1,25 @@
+pub mod cache;
+pub mod checker;
+pub mod comments;
+pub mod converter;
+pub mod differ;
+pub mod editor;
 pub mod extractor;
 pub mod generator;
+pub mod glossary;
 pub mod iter;
+pub mod keygen;
+pub mod locale_file;
+pub mod memory;
+pub mod pruner;
+pub mod renamer;
+pub mod sarif;
+pub mod scaffold;
+pub mod spreadsheet;
+pub mod stats;
+pub mod template;
+pub mod tms;
+pub mod tool_config;
+pub mod translator;
+pub mod validator;
+pub mod webgen;
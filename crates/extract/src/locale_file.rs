@@ -0,0 +1,1215 @@
+//! Read and write a single locale file, keeping the order of its keys.
+//!
+//! Both file layouts are supported:
+//!
+//! - Version 1, one locale per file, e.g. `en.yml` with `hello: Hello`.
+//! - Version 2, all locales in one file, e.g. `app.yml` with `_version: 2` and `hello: { en: Hello }`.
+use crate::checker::Translations;
+use crate::comments::Comments;
+use crate::extractor::Location;
+use anyhow::{anyhow, Error};
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const VERSION_KEY: &str = "_version";
+/// The maximum length of the texts of a key in a version 2 file, next to the texts,
+/// e.g. `save: { _max_length: 12, en: Save }`.
+pub const MAX_LENGTH_KEY: &str = "_max_length";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// Get the format from the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "yml" | "yaml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LocaleFile {
+    pub path: PathBuf,
+    pub format: Format,
+    pub data: Value,
+    /// The comments of a YAML file, written back next to the same keys.
+    pub comments: Comments,
+}
+
+impl LocaleFile {
+    /// Load a locale file, the format is detected from the file extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("Failed to read {}: {}", path.display(), err))?;
+        Self::parse(path, &content)
+    }
+
+    /// Parse the content of a locale file, the format is detected from the file extension.
+    pub fn parse(path: impl AsRef<Path>, content: &str) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let format = Format::from_path(path)
+            .ok_or_else(|| anyhow!("Unsupported locale file: {}", path.display()))?;
+
+        let data = match format {
+            // The `<<` merge keys are resolved, like by the loader of rust-i18n
+            Format::Yaml => serde_yaml::from_str::<Value>(content)
+                .and_then(|mut data| data.apply_merge().map(|_| data))
+                .map_err(Error::from),
+            Format::Json => serde_json::from_str::<Value>(content).map_err(Error::from),
+            Format::Toml => toml::from_str::<Value>(content).map_err(Error::from),
+        }
+        .map_err(|err| anyhow!("Failed to parse {}: {}", path.display(), err))?;
+
+        // An empty YAML file
+        let data = if data.is_null() {
+            Value::Mapping(Mapping::new())
+        } else {
+            data
+        };
+
+        let comments = match format {
+            Format::Yaml => Comments::parse(content),
+            _ => Comments::default(),
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            data,
+            comments,
+        })
+    }
+
+    /// The version of the file layout, `1` or `2`.
+    pub fn version(&self) -> usize {
+        self.data
+            .get(VERSION_KEY)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1) as usize
+    }
+
+    /// The locale of a version 1 file, from the file name, e.g. `view.en.yml` is `en`.
+    pub fn locale(&self) -> Option<&str> {
+        self.path.file_stem()?.to_str()?.split('.').next_back()
+    }
+
+    /// All translation keys in the file, in file order.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys = vec![];
+        if let Value::Mapping(map) = &self.data {
+            if self.version() == 2 {
+                collect_keys_v2("", map, &mut keys);
+            } else {
+                collect_keys_v1("", map, &mut keys);
+            }
+        }
+        keys
+    }
+
+    /// All texts in the file, by locale and key.
+    pub fn translations(&self) -> Translations {
+        let mut trs = Translations::new();
+        let Value::Mapping(map) = &self.data else {
+            return trs;
+        };
+
+        if self.version() == 2 {
+            collect_texts_v2("", map, &mut trs);
+        } else if let Some(locale) = self.locale() {
+            let mut keys = vec![];
+            collect_keys_v1("", map, &mut keys);
+            let texts = trs.entry(locale.to_string()).or_default();
+            for key in keys {
+                if let Some(text) = self.get_v1(&key) {
+                    texts.insert(key, text);
+                }
+            }
+        }
+        trs
+    }
+
+    /// The `_max_length` of the keys of a version 2 file, see [`MAX_LENGTH_KEY`].
+    pub fn max_lengths(&self) -> BTreeMap<String, usize> {
+        let mut max_lengths = BTreeMap::new();
+        if let (Value::Mapping(map), 2) = (&self.data, self.version()) {
+            collect_max_lengths_v2("", map, &mut max_lengths);
+        }
+        max_lengths
+    }
+
+    /// Build a file from the translations.
+    ///
+    /// A version 1 file only contains the texts of the locale from the file name,
+    /// a version 2 file contains all locales. The keys are nested and sorted like [`Self::normalize`].
+    pub fn from_translations(
+        path: impl AsRef<Path>,
+        version: usize,
+        translations: &Translations,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let format = Format::from_path(path)
+            .ok_or_else(|| anyhow!("Unsupported locale file: {}", path.display()))?;
+
+        let mut file = Self {
+            path: path.to_path_buf(),
+            format,
+            data: Value::Null,
+            comments: Comments::default(),
+        };
+
+        let mut map = Mapping::new();
+        if version == 2 {
+            map.insert(VERSION_KEY.into(), 2.into());
+            for (locale, texts) in translations {
+                for (key, text) in texts {
+                    let entry = map
+                        .entry(key.as_str().into())
+                        .or_insert_with(|| Value::Mapping(Mapping::new()));
+                    if let Value::Mapping(entry) = entry {
+                        entry.insert(locale.as_str().into(), text.as_str().into());
+                    }
+                }
+            }
+        } else {
+            let locale = file.locale().unwrap_or_default();
+            for (key, text) in translations.get(locale).into_iter().flatten() {
+                map.insert(key.as_str().into(), text.as_str().into());
+            }
+        }
+
+        file.data = Value::Mapping(map);
+        file.normalize();
+        Ok(file)
+    }
+
+    /// Get the text of a key in a version 1 file.
+    fn get_v1(&self, key: &str) -> Option<String> {
+        let mut map = self.data.as_mapping()?;
+        let mut key = key;
+        loop {
+            let (k, rest) = find_child(map, key)?;
+            match (rest, map.get(&k)?) {
+                (None, v) => return value_str(v),
+                (Some(rest), Value::Mapping(sub)) => {
+                    map = sub;
+                    key = rest;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Set the text of a key in a locale, the key is added if missing.
+    ///
+    /// The `locale` is ignored in a version 1 file, it's the locale of the file.
+    pub fn set(&mut self, key: &str, locale: &str, text: &str) {
+        let version = self.version();
+        if !self.data.is_mapping() {
+            self.data = Value::Mapping(Mapping::new());
+        }
+        let Value::Mapping(map) = &mut self.data else {
+            return;
+        };
+
+        let entry = entry_mut(map, key);
+        if version == 2 {
+            if !entry.is_mapping() {
+                *entry = Value::Mapping(Mapping::new());
+            }
+            if let Value::Mapping(texts) = entry {
+                texts.insert(locale.into(), text.into());
+            }
+        } else {
+            *entry = text.into();
+        }
+    }
+
+    /// Add a comment line above the text of a key in a locale, only kept in YAML files.
+    pub fn add_comment(&mut self, key: &str, locale: &str, comment: &str) {
+        self.comments
+            .add_leading(&self.comment_key(key, locale), comment);
+    }
+
+    /// Whether the comment line is above the text of a key in a locale.
+    pub fn has_comment(&self, key: &str, locale: &str, comment: &str) -> bool {
+        self.comments
+            .has_leading(&self.comment_key(key, locale), comment)
+    }
+
+    /// Remove a comment line above the text of a key in a locale.
+    pub fn remove_comment(&mut self, key: &str, locale: &str, comment: &str) {
+        self.comments
+            .remove_leading(&self.comment_key(key, locale), comment);
+    }
+
+    fn comment_key(&self, key: &str, locale: &str) -> String {
+        match self.version() {
+            2 => format!("{}.{}", key, locale),
+            _ => key.to_string(),
+        }
+    }
+
+    /// Remove a translation key (all of its locales in a version 2 file).
+    ///
+    /// Returns `true` if the key was found, parent mappings left empty are removed too.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let version = self.version();
+        match &mut self.data {
+            Value::Mapping(map) if version == 2 => remove_key_v2(map, key),
+            Value::Mapping(map) => remove_key_v1(map, key),
+            _ => false,
+        }
+    }
+
+    /// Normalize the file content: expand dotted keys into nested mappings and sort the keys,
+    /// with `_version` first.
+    ///
+    /// A dotted key is kept as is if it conflicts with a text of the same name.
+    pub fn normalize(&mut self) {
+        if let Value::Mapping(map) = &mut self.data {
+            let mut normalized = normalize_mapping(std::mem::take(map));
+            if let Some(version) = normalized.shift_remove(VERSION_KEY) {
+                map.insert(VERSION_KEY.into(), version);
+            }
+            map.extend(normalized);
+        }
+    }
+
+    /// Serialize the file content in its format, with the comments of a YAML file.
+    pub fn to_text(&self) -> Result<String, Error> {
+        let text = match self.format {
+            Format::Yaml => {
+                let text = serde_yaml::to_string(&self.data)?;
+                self.comments
+                    .apply(text.trim_start_matches("---").trim_start())
+            }
+            Format::Json => serde_json::to_string_pretty(&self.data)? + "\n",
+            Format::Toml => toml::to_string_pretty(&self.data)?,
+        };
+        Ok(text)
+    }
+
+    /// Write the file content back to its path, creating the directory if needed.
+    pub fn save(&self) -> Result<(), Error> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|err| anyhow!("Failed to create {}: {}", dir.display(), err))?;
+        }
+        std::fs::write(&self.path, self.to_text()?)
+            .map_err(|err| anyhow!("Failed to write {}: {}", self.path.display(), err))
+    }
+}
+
+/// Find where the texts are defined in the locale files of the locales path, by locale and key.
+///
+/// A text defined in more than one file has all the locations, in the order of loading,
+/// so the last one is the one used. The untranslated texts file is skipped.
+pub fn text_locations(
+    locales_path: impl AsRef<Path>,
+) -> Result<BTreeMap<(String, String), Vec<Location>>, Error> {
+    let mut locations = BTreeMap::<(String, String), Vec<Location>>::new();
+    for path in locale_files(locales_path) {
+        if path.ends_with(crate::generator::TODO_FILENAME) {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let file = LocaleFile::parse(&path, &content)?;
+        let lines = key_lines(&content, file.format);
+        let version = file.version();
+
+        for (locale, texts) in file.translations() {
+            for key in texts.keys() {
+                let full_key = if version == 2 {
+                    join_key(key, &locale)
+                } else {
+                    key.clone()
+                };
+                locations
+                    .entry((locale.clone(), key.clone()))
+                    .or_default()
+                    .push(Location {
+                        file: path.clone(),
+                        line: find_line(&lines, &full_key).unwrap_or_default(),
+                    });
+            }
+        }
+    }
+    Ok(locations)
+}
+
+/// The `_max_length` of the keys in the locale files of the locales path, see [`MAX_LENGTH_KEY`].
+///
+/// The untranslated texts file is skipped.
+pub fn max_lengths(locales_path: impl AsRef<Path>) -> Result<BTreeMap<String, usize>, Error> {
+    let mut max_lengths = BTreeMap::new();
+    for path in locale_files(locales_path) {
+        if path.ends_with(crate::generator::TODO_FILENAME) {
+            continue;
+        }
+        max_lengths.extend(LocaleFile::load(&path)?.max_lengths());
+    }
+    Ok(max_lengths)
+}
+
+/// Find the line of a key, or the closest parent key for the keys in inline mappings.
+fn find_line(lines: &BTreeMap<String, usize>, key: &str) -> Option<usize> {
+    let mut key = key;
+    loop {
+        if let Some(line) = lines.get(key) {
+            return Some(*line);
+        }
+        key = &key[..key.rfind('.')?];
+    }
+}
+
+/// The line numbers (1-based) of the keys in the content, by the full key, e.g. `messages.title`.
+///
+/// It's a line based scan for the usual formatting of locale files, not a full parser.
+pub fn key_lines(content: &str, format: Format) -> BTreeMap<String, usize> {
+    match format {
+        Format::Yaml => yaml_key_lines(content),
+        Format::Json => json_key_lines(content),
+        Format::Toml => toml_key_lines(content),
+    }
+}
+
+/// Read a key at the start of the text, quoted or not, ending with `end`. Returns the key and the rest.
+fn read_key<'a>(text: &'a str, end: &[char]) -> Option<(String, &'a str)> {
+    let text = text.trim_start();
+    if let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let mut key = String::new();
+        let mut chars = text[1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' if quote == '"' => {
+                    if let Some((_, c)) = chars.next() {
+                        key.push(c);
+                    }
+                }
+                c if c == quote => return Some((key, &text[i + 2..])),
+                c => key.push(c),
+            }
+        }
+        None
+    } else {
+        let i = text.find(end)?;
+        let key = text[..i].trim();
+        (!key.is_empty()).then(|| (key.to_string(), &text[i..]))
+    }
+}
+
+fn yaml_key_lines(content: &str) -> BTreeMap<String, usize> {
+    let mut lines = BTreeMap::new();
+    // (indent, key) of the parent mappings
+    let mut stack: Vec<(usize, String)> = vec![];
+
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with(['#', '-']) {
+            continue;
+        }
+
+        let Some((key, rest)) = read_key(trimmed, &[':']) else {
+            continue;
+        };
+        let Some(value) = rest.trim_start().strip_prefix(':') else {
+            continue;
+        };
+
+        let indent = line.len() - trimmed.len();
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+
+        let mut full_key = stack.iter().map(|(_, k)| k.as_str()).collect::<Vec<_>>();
+        full_key.push(&key);
+        lines.insert(full_key.join("."), i + 1);
+
+        // A key without value on the same line starts a nested mapping
+        let value = value.trim();
+        if value.is_empty() || value.starts_with(['#', '&']) {
+            stack.push((indent, key));
+        }
+    }
+
+    lines
+}
+
+fn json_key_lines(content: &str) -> BTreeMap<String, usize> {
+    let mut lines = BTreeMap::new();
+    // The keys of the open objects and arrays
+    let mut stack: Vec<Option<String>> = vec![];
+    let mut pending_key: Option<String> = None;
+    let mut line = 1;
+
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '"' => {
+                let mut s = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some(c) = chars.next() {
+                                s.push(c);
+                            }
+                        }
+                        '"' => break,
+                        '\n' => {
+                            line += 1;
+                            s.push(c);
+                        }
+                        c => s.push(c),
+                    }
+                }
+
+                while chars.peek().is_some_and(|c| *c == ' ' || *c == '\t') {
+                    chars.next();
+                }
+                if chars.peek() == Some(&':') {
+                    let mut full_key = stack.iter().flatten().cloned().collect::<Vec<_>>();
+                    full_key.push(s.clone());
+                    lines.insert(full_key.join("."), line);
+                    pending_key = Some(s);
+                }
+            }
+            '{' | '[' => stack.push(pending_key.take()),
+            '}' | ']' => {
+                stack.pop();
+            }
+            ',' => pending_key = None,
+            _ => {}
+        }
+    }
+
+    lines
+}
+
+/// Read the dotted keys of TOML, ending with `end`. Returns the keys and the rest.
+fn read_toml_keys(mut text: &str, end: char) -> Option<(Vec<String>, &str)> {
+    let mut keys = vec![];
+    while let Some((key, rest)) = read_key(text, &['.', end]) {
+        keys.push(key);
+        let rest = rest.trim_start();
+        match rest.strip_prefix('.') {
+            Some(rest) => text = rest,
+            None => return Some((keys, rest)),
+        }
+    }
+    None
+}
+
+fn toml_key_lines(content: &str) -> BTreeMap<String, usize> {
+    let mut lines = BTreeMap::new();
+    let mut table: Vec<String> = vec![];
+
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = trimmed.strip_prefix('[') {
+            if let Some((keys, _)) = read_toml_keys(header.trim_start_matches('['), ']') {
+                table = keys;
+                lines.insert(table.join("."), i + 1);
+            }
+            continue;
+        }
+
+        if let Some((keys, rest)) = read_toml_keys(trimmed, '=') {
+            if rest.starts_with('=') {
+                let mut full_key = table.clone();
+                full_key.extend(keys);
+                lines.insert(full_key.join("."), i + 1);
+            }
+        }
+    }
+
+    lines
+}
+
+/// Write the translations into the locale files in the locales path, returns the changed files.
+///
+/// The text of an existing key is updated in the file containing it, a new key is added to
+/// the version 1 file of the locale, or the first version 2 file, or a new `{locale}.yml`.
+/// The `comment` is added above each text in YAML files. The untranslated texts file is never changed.
+pub fn apply_translations(
+    locales_path: impl AsRef<Path>,
+    translations: &Translations,
+    comment: Option<&str>,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = vec![];
+    for file in plan_translations(locales_path, translations, comment)? {
+        file.save()?;
+        paths.push(file.path);
+    }
+    Ok(paths)
+}
+
+/// The locale files changed by [`apply_translations`], with the new content, nothing is written.
+pub fn plan_translations(
+    locales_path: impl AsRef<Path>,
+    translations: &Translations,
+    comment: Option<&str>,
+) -> Result<Vec<LocaleFile>, Error> {
+    let locales_path = locales_path.as_ref();
+    let mut files = locale_files(locales_path)
+        .into_iter()
+        .filter(|path| !path.ends_with(crate::generator::TODO_FILENAME))
+        .map(LocaleFile::load)
+        .collect::<Result<Vec<_>, _>>()?;
+    let file_trs = files.iter().map(|f| f.translations()).collect::<Vec<_>>();
+    let mut changed = vec![false; files.len()];
+
+    for (locale, texts) in translations {
+        for (key, text) in texts {
+            let index = file_trs
+                .iter()
+                .position(|trs| trs.get(locale).is_some_and(|trs| trs.contains_key(key)))
+                .or_else(|| {
+                    files.iter().position(|f| {
+                        f.version() == 1 && f.path.file_stem() == Some(locale.as_ref())
+                    })
+                })
+                .or_else(|| files.iter().position(|f| f.version() == 2));
+
+            let index = match index {
+                Some(index) => index,
+                None => {
+                    let path = locales_path.join(format!("{}.yml", locale));
+                    files.push(LocaleFile::from_translations(
+                        path,
+                        1,
+                        &Translations::new(),
+                    )?);
+                    changed.push(false);
+                    files.len() - 1
+                }
+            };
+
+            files[index].set(key, locale, text);
+            if let Some(comment) = comment {
+                files[index].add_comment(key, locale, comment);
+            }
+            changed[index] = true;
+        }
+    }
+
+    Ok(files
+        .into_iter()
+        .zip(changed)
+        .filter_map(|(file, changed)| changed.then_some(file))
+        .collect())
+}
+
+/// Find all locale files in the locales path, sorted by path.
+pub fn locale_files(locales_path: impl AsRef<Path>) -> Vec<PathBuf> {
+    let mut files = ignore::WalkBuilder::new(locales_path)
+        .standard_filters(false)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && Format::from_path(path).is_some())
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+fn key_str(key: &Value) -> Option<String> {
+    match key {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn collect_keys_v1(prefix: &str, map: &Mapping, keys: &mut Vec<String>) {
+    for (k, v) in map {
+        let Some(k) = key_str(k) else { continue };
+        if prefix.is_empty() && k == VERSION_KEY {
+            continue;
+        }
+
+        let key = join_key(prefix, &k);
+        match v {
+            Value::Mapping(sub) => collect_keys_v1(&key, sub, keys),
+            _ => keys.push(key),
+        }
+    }
+}
+
+fn collect_keys_v2(prefix: &str, map: &Mapping, keys: &mut Vec<String>) {
+    for (k, v) in map {
+        let Some(k) = key_str(k) else { continue };
+        let Value::Mapping(sub) = v else { continue };
+
+        let key = join_key(prefix, &k);
+        if sub.values().any(|v| !v.is_mapping()) {
+            keys.push(key.clone());
+        }
+        collect_keys_v2(&key, sub, keys);
+    }
+}
+
+fn collect_texts_v2(prefix: &str, map: &Mapping, trs: &mut Translations) {
+    for (k, v) in map {
+        let Some(k) = key_str(k) else { continue };
+        let Value::Mapping(sub) = v else { continue };
+
+        let key = join_key(prefix, &k);
+        for (locale, text) in sub {
+            if let (Some(locale), Some(text)) = (key_str(locale), value_str(text)) {
+                // Metadata like `_max_length`
+                if locale.starts_with('_') {
+                    continue;
+                }
+                trs.entry(locale).or_default().insert(key.clone(), text);
+            }
+        }
+        collect_texts_v2(&key, sub, trs);
+    }
+}
+
+fn collect_max_lengths_v2(prefix: &str, map: &Mapping, max_lengths: &mut BTreeMap<String, usize>) {
+    for (k, v) in map {
+        let Some(k) = key_str(k) else { continue };
+        let Value::Mapping(sub) = v else { continue };
+
+        let key = join_key(prefix, &k);
+        if let Some(max_length) = sub.get(MAX_LENGTH_KEY).and_then(Value::as_u64) {
+            max_lengths.insert(key.clone(), max_length as usize);
+        }
+        collect_max_lengths_v2(&key, sub, max_lengths);
+    }
+}
+
+fn value_str(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null => Some("".into()),
+        _ => None,
+    }
+}
+
+/// Find the child matching the start of `key`, the mapping keys may contain `.` too.
+fn find_child<'a>(map: &Mapping, key: &'a str) -> Option<(Value, Option<&'a str>)> {
+    map.keys().find_map(|k| {
+        let name = key_str(k)?;
+        if name == key {
+            return Some((k.clone(), None));
+        }
+        key.strip_prefix(&name)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .map(|rest| (k.clone(), Some(rest)))
+    })
+}
+
+/// Get the value of a key, or add it as `null`, nesting the new keys by `.`.
+fn entry_mut<'a>(map: &'a mut Mapping, key: &str) -> &'a mut Value {
+    match find_child(map, key) {
+        Some((k, None)) => map.get_mut(&k).unwrap(),
+        Some((k, Some(rest))) if map.get(&k).is_some_and(Value::is_mapping) => {
+            entry_mut(map.get_mut(&k).unwrap().as_mapping_mut().unwrap(), rest)
+        }
+        Some(_) => map.entry(key.into()).or_insert(Value::Null),
+        None => match key.split_once('.') {
+            Some((head, rest)) if !head.is_empty() && !rest.is_empty() => {
+                let sub = map
+                    .entry(head.into())
+                    .or_insert_with(|| Value::Mapping(Mapping::new()));
+                entry_mut(sub.as_mapping_mut().unwrap(), rest)
+            }
+            _ => map.entry(key.into()).or_insert(Value::Null),
+        },
+    }
+}
+
+fn remove_key_v1(map: &mut Mapping, key: &str) -> bool {
+    let Some((k, rest)) = find_child(map, key) else {
+        return false;
+    };
+
+    let removed = match (rest, map.get_mut(&k)) {
+        (None, Some(v)) if !v.is_mapping() => {
+            map.shift_remove(&k);
+            return true;
+        }
+        (Some(rest), Some(Value::Mapping(sub))) => remove_key_v1(sub, rest),
+        _ => false,
+    };
+
+    if removed && map.get(&k).is_some_and(is_empty_mapping) {
+        map.shift_remove(&k);
+    }
+    removed
+}
+
+fn remove_key_v2(map: &mut Mapping, key: &str) -> bool {
+    let Some((k, rest)) = find_child(map, key) else {
+        return false;
+    };
+    let Some(Value::Mapping(sub)) = map.get_mut(&k) else {
+        return false;
+    };
+
+    let removed = match rest {
+        None => {
+            let len = sub.len();
+            sub.retain(|_, v| v.is_mapping());
+            sub.len() < len
+        }
+        Some(rest) => remove_key_v2(sub, rest),
+    };
+
+    if removed && map.get(&k).is_some_and(is_empty_mapping) {
+        map.shift_remove(&k);
+    }
+    removed
+}
+
+fn normalize_mapping(map: Mapping) -> Mapping {
+    let mut result = Mapping::new();
+    for (k, v) in map {
+        let expanded = key_str(&k).and_then(|name| {
+            let (head, tail) = name.split_once('.')?;
+            (!head.is_empty() && !tail.is_empty()).then(|| (head.to_string(), tail.to_string()))
+        });
+
+        match expanded {
+            Some((head, tail)) if result.get(head.as_str()).is_none_or(Value::is_mapping) => {
+                let entry = result
+                    .entry(head.into())
+                    .or_insert_with(|| Value::Mapping(Mapping::new()));
+                if let Value::Mapping(entry) = entry {
+                    merge_into(entry, tail.into(), v);
+                }
+            }
+            _ => merge_into(&mut result, k, v),
+        }
+    }
+
+    let mut entries = result
+        .into_iter()
+        .map(|(k, v)| match v {
+            Value::Mapping(sub) => (k, Value::Mapping(normalize_mapping(sub))),
+            v => (k, v),
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|(k, _)| key_str(k).unwrap_or_default());
+    entries.into_iter().collect()
+}
+
+/// Insert into a mapping, merging with an existing mapping of the same key.
+fn merge_into(map: &mut Mapping, key: Value, value: Value) {
+    match (map.get_mut(&key), value) {
+        (Some(Value::Mapping(existing)), Value::Mapping(value)) => {
+            for (k, v) in value {
+                merge_into(existing, k, v);
+            }
+        }
+        (_, value) => {
+            map.insert(key, value);
+        }
+    }
+}
+
+fn is_empty_mapping(value: &Value) -> bool {
+    value.as_mapping().is_some_and(|m| m.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_v1() {
+        let content = indoc! {r#"
+        hello: Hello
+        messages:
+          title: Title
+          "a.b": AB
+        "#};
+        let mut file = LocaleFile::parse("locales/view.en.yml", content).unwrap();
+        assert_eq!(file.version(), 1);
+        assert_eq!(file.locale(), Some("en"));
+        assert_eq!(file.keys(), vec!["hello", "messages.title", "messages.a.b"]);
+
+        assert!(file.remove("messages.a.b"));
+        assert!(!file.remove("messages.unknown"));
+        assert_eq!(file.keys(), vec!["hello", "messages.title"]);
+        assert!(file.remove("messages.title"));
+        assert_eq!(file.to_text().unwrap(), "hello: Hello\n");
+    }
+
+    #[test]
+    fn test_v2() {
+        let content = indoc! {r#"
+        {
+          "_version": 2,
+          "hello": { "en": "Hello", "zh": "你好" },
+          "messages": {
+            "title": { "en": "Title" },
+            "body": { "en": "Body" }
+          }
+        }
+        "#};
+        let mut file = LocaleFile::parse("locales/app.json", content).unwrap();
+        assert_eq!(file.version(), 2);
+        assert_eq!(
+            file.keys(),
+            vec!["hello", "messages.title", "messages.body"]
+        );
+
+        assert!(file.remove("hello"));
+        assert!(file.remove("messages.title"));
+        assert_eq!(file.keys(), vec!["messages.body"]);
+        assert_eq!(
+            file.to_text().unwrap(),
+            indoc! {r#"
+            {
+              "_version": 2,
+              "messages": {
+                "body": {
+                  "en": "Body"
+                }
+              }
+            }
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let content = indoc! {r#"
+        hello: Hello
+        messages.title: Title
+        _version: 1
+        messages:
+          'body': "Body"
+        a: A
+        a.b: AB
+        "#};
+        let mut file = LocaleFile::parse("en.yml", content).unwrap();
+        file.normalize();
+        assert_eq!(
+            file.to_text().unwrap(),
+            indoc! {r#"
+            _version: 1
+            a: A
+            a.b: AB
+            hello: Hello
+            messages:
+              body: Body
+              title: Title
+            "#}
+        );
+
+        let content = indoc! {r#"
+        _version: 2
+        b.title:
+          zh: 标题
+          en: Title
+        a:
+          en: A
+        "#};
+        let mut file = LocaleFile::parse("app.yml", content).unwrap();
+        file.normalize();
+        assert_eq!(
+            file.to_text().unwrap(),
+            indoc! {r#"
+            _version: 2
+            a:
+              en: A
+            b:
+              title:
+                en: Title
+                zh: 标题
+            "#}
+        );
+
+        let content = indoc! {r#"
+        # Home page
+        messages.title: Title # Keep it short
+        # Greetings
+        hello: Hello
+        "#};
+        let mut file = LocaleFile::parse("en.yml", content).unwrap();
+        file.normalize();
+        assert_eq!(
+            file.to_text().unwrap(),
+            indoc! {r#"
+            # Home page
+            # Greetings
+            hello: Hello
+            messages:
+              title: Title # Keep it short
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_translations() {
+        let content = indoc! {r#"
+        hello: Hello
+        messages:
+          title: Title
+          count: 1
+        "#};
+        let file = LocaleFile::parse("en.yml", content).unwrap();
+        let trs = file.translations();
+        assert_eq!(trs["en"]["hello"], "Hello");
+        assert_eq!(trs["en"]["messages.title"], "Title");
+        assert_eq!(trs["en"]["messages.count"], "1");
+
+        let file = LocaleFile::from_translations("app.json", 2, &trs).unwrap();
+        assert_eq!(file.translations(), trs);
+        assert_eq!(
+            file.keys(),
+            vec!["hello", "messages.count", "messages.title"]
+        );
+
+        let file = LocaleFile::from_translations("en.toml", 1, &trs).unwrap();
+        assert_eq!(file.translations(), trs);
+        assert!(LocaleFile::from_translations("fr.toml", 1, &trs)
+            .unwrap()
+            .translations()["fr"]
+            .is_empty());
+    }
+
+    #[test]
+    fn test_yaml_anchors() {
+        let content = indoc! {r#"
+        _version: 2
+        product: &product
+          en: Acme
+          fr: Acme
+        legal: &legal
+          terms:
+            en: Terms
+            fr: Conditions
+          privacy:
+            en: Privacy
+            fr: Confidentialité
+        checkout:
+          <<: *legal
+          privacy:
+            en: Privacy policy
+          name: *product
+        "#};
+        let file = LocaleFile::parse("app.yml", content).unwrap();
+        let trs = file.translations();
+        assert_eq!(trs["en"]["checkout.terms"], "Terms");
+        assert_eq!(trs["fr"]["checkout.terms"], "Conditions");
+        assert_eq!(trs["en"]["checkout.privacy"], "Privacy policy");
+        assert_eq!(trs["en"]["checkout.name"], "Acme");
+        assert!(!trs["en"].keys().any(|key| key.contains("<<")));
+    }
+
+    #[test]
+    fn test_max_lengths() {
+        let content = indoc! {r#"
+        _version: 2
+        buttons:
+          save:
+            _max_length: 12
+            en: Save
+            de: Speichern
+        title:
+          en: Title
+        "#};
+        let file = LocaleFile::parse("app.yml", content).unwrap();
+        assert_eq!(
+            file.max_lengths(),
+            BTreeMap::from([("buttons.save".to_string(), 12)])
+        );
+        let trs = file.translations();
+        assert_eq!(trs.keys().collect::<Vec<_>>(), vec!["de", "en"]);
+        assert_eq!(trs["de"]["buttons.save"], "Speichern");
+    }
+
+    #[test]
+    fn test_set() {
+        let content = indoc! {r#"
+        hello: Hello
+        a: A
+        messages:
+          title: Title
+        "#};
+        let mut file = LocaleFile::parse("en.yml", content).unwrap();
+        file.set("hello", "en", "Hi");
+        file.set("messages.body", "en", "Body");
+        file.set("a.b", "en", "AB");
+        file.set("x.y.z", "en", "XYZ");
+        assert_eq!(
+            file.to_text().unwrap(),
+            indoc! {r#"
+            hello: Hi
+            a: A
+            messages:
+              title: Title
+              body: Body
+            a.b: AB
+            x:
+              y:
+                z: XYZ
+            "#}
+        );
+
+        let content = indoc! {r#"
+        _version: 2
+        messages:
+          title:
+            en: Title
+        "#};
+        let mut file = LocaleFile::parse("app.yml", content).unwrap();
+        file.set("messages.title", "fr", "Titre");
+        file.set("messages.body", "fr", "Corps");
+        let trs = file.translations();
+        assert_eq!(trs["en"]["messages.title"], "Title");
+        assert_eq!(trs["fr"]["messages.title"], "Titre");
+        assert_eq!(trs["fr"]["messages.body"], "Corps");
+    }
+
+    #[test]
+    fn test_apply_translations() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-apply-translations");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("en.yml"), "hello: Hello\n").unwrap();
+        std::fs::write(dir.join("app.yml"), "_version: 2\ntitle:\n  en: Title\n").unwrap();
+
+        let trs = Translations::from([
+            (
+                "en".to_string(),
+                std::collections::BTreeMap::from([
+                    ("title".to_string(), "New Title".to_string()),
+                    ("body".to_string(), "Body".to_string()),
+                ]),
+            ),
+            (
+                "fr".to_string(),
+                std::collections::BTreeMap::from([("hello".to_string(), "Bonjour".to_string())]),
+            ),
+        ]);
+        let changed = apply_translations(&dir, &trs, None).unwrap();
+        assert_eq!(changed, vec![dir.join("app.yml"), dir.join("en.yml")]);
+
+        let content = std::fs::read_to_string(dir.join("en.yml")).unwrap();
+        assert_eq!(content, "hello: Hello\nbody: Body\n");
+        let app = LocaleFile::load(dir.join("app.yml"))
+            .unwrap()
+            .translations();
+        assert_eq!(app["en"]["title"], "New Title");
+        assert_eq!(app["fr"]["hello"], "Bonjour");
+
+        let trs = Translations::from([
+            (
+                "en".to_string(),
+                std::collections::BTreeMap::from([("messages.new".to_string(), "New".to_string())]),
+            ),
+            (
+                "fr".to_string(),
+                std::collections::BTreeMap::from([("messages.new".to_string(), "New".to_string())]),
+            ),
+        ]);
+        apply_translations(&dir, &trs, Some("# TODO: translate")).unwrap();
+        let content = std::fs::read_to_string(dir.join("en.yml")).unwrap();
+        assert_eq!(
+            content,
+            "hello: Hello\nbody: Body\nmessages:\n  # TODO: translate\n  new: New\n"
+        );
+        let content = std::fs::read_to_string(dir.join("app.yml")).unwrap();
+        assert!(content.contains("messages:\n  new:\n    # TODO: translate\n    fr: New\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_key_lines() {
+        let content = indoc! {r#"
+        # comment
+        _version: 2
+        hello:
+          en: Hello
+          "zh-CN": 你好
+
+        messages:
+          title: { en: Title }
+          'a.b':
+            en: AB
+        "#};
+        let lines = key_lines(content, Format::Yaml);
+        assert_eq!(lines["hello"], 3);
+        assert_eq!(lines["hello.en"], 4);
+        assert_eq!(lines["hello.zh-CN"], 5);
+        assert_eq!(lines["messages.title"], 8);
+        assert_eq!(lines["messages.a.b.en"], 10);
+        assert_eq!(find_line(&lines, "messages.title.en"), Some(8));
+        assert_eq!(find_line(&lines, "unknown"), None);
+
+        let content = indoc! {r#"
+        {
+          "hello": "Hello",
+          "messages": {
+            "title": "Title, \"quoted\"",
+            "items": ["a", "b"],
+            "body": "Body"
+          }
+        }
+        "#};
+        let lines = key_lines(content, Format::Json);
+        assert_eq!(lines["hello"], 2);
+        assert_eq!(lines["messages.title"], 4);
+        assert_eq!(lines["messages.body"], 6);
+
+        let content = indoc! {r#"
+        hello = "Hello"
+        a.b = "AB"
+
+        [messages]
+        title = "Title"
+        "#};
+        let lines = key_lines(content, Format::Toml);
+        assert_eq!(lines["hello"], 1);
+        assert_eq!(lines["a.b"], 2);
+        assert_eq!(lines["messages.title"], 5);
+    }
+
+    #[test]
+    fn test_toml() {
+        let content = indoc! {r#"
+        hello = "Hello"
+
+        [messages]
+        title = "Title"
+        "#};
+        let mut file = LocaleFile::parse("en.toml", content).unwrap();
+        assert_eq!(file.keys(), vec!["hello", "messages.title"]);
+        assert!(file.remove("hello"));
+        assert_eq!(file.to_text().unwrap(), "[messages]\ntitle = \"Title\"\n");
+    }
+}
@@ -0,0 +1,287 @@
+//! Reuse the existing translations of identical or similar base locale texts, for
+//! `cargo i18n translate --from-memory`.
+use crate::checker::Translations;
+use crate::validator::edit_distance;
+use anyhow::{anyhow, Error};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// The comment above the texts of fuzzy matches, to review them.
+pub const FUZZY_COMMENT: &str = "# TODO: review fuzzy match";
+
+/// The minimum similarity of a fuzzy match, from 0 to 1.
+const FUZZY_THRESHOLD: f64 = 0.8;
+
+/// The translations of the base locale texts, by locale.
+#[derive(Debug, Default, Clone)]
+pub struct Memory {
+    /// locale => base text => translations, the most used first.
+    entries: HashMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+/// The texts filled from the memory, by locale and key.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Filled {
+    /// The translations of identical base locale texts.
+    pub exact: Translations,
+    /// The translations of similar base locale texts, to review.
+    pub fuzzy: Translations,
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a translation of a base locale text.
+    pub fn add(&mut self, locale: &str, source: &str, target: &str) {
+        if source.is_empty() || target.is_empty() {
+            return;
+        }
+        let targets = self
+            .entries
+            .entry(locale.to_string())
+            .or_default()
+            .entry(source.to_string())
+            .or_default();
+        targets.push(target.to_string());
+    }
+
+    /// Add the translations of the catalog, each text of the other locales for the base
+    /// locale text of the same key.
+    pub fn add_translations(&mut self, translations: &Translations, base_locale: &str) {
+        let Some(base_trs) = translations.get(base_locale) else {
+            return;
+        };
+        for (locale, trs) in translations {
+            if locale == base_locale {
+                continue;
+            }
+            for (key, text) in trs {
+                if let Some(base_text) = base_trs.get(key) {
+                    self.add(locale, base_text, text);
+                }
+            }
+        }
+    }
+
+    /// Add the translation units of a [TMX](https://en.wikipedia.org/wiki/Translation_Memory_eXchange)
+    /// file from the base locale to the `locales`, the languages like `fr-FR` match the locale `fr`.
+    pub fn add_tmx(&mut self, content: &str, base_locale: &str, locales: &[String]) {
+        let unit_re = Regex::new(r"(?s)<tu\b.*?</tu>").unwrap();
+        let variant_re = Regex::new(
+            r#"(?s)<tuv\b[^>]*?\b(?:xml:)?lang\s*=\s*"([^"]*)"[^>]*>.*?<seg>(.*?)</seg>"#,
+        )
+        .unwrap();
+
+        for unit in unit_re.find_iter(content) {
+            let variants = variant_re
+                .captures_iter(unit.as_str())
+                .map(|caps| (caps[1].to_string(), unescape_xml(&caps[2])))
+                .collect::<Vec<_>>();
+            let Some((_, source)) = variants
+                .iter()
+                .find(|(lang, _)| lang_matches(lang, base_locale))
+            else {
+                continue;
+            };
+
+            for locale in locales {
+                if locale == base_locale {
+                    continue;
+                }
+                if let Some((_, target)) =
+                    variants.iter().find(|(lang, _)| lang_matches(lang, locale))
+                {
+                    self.add(locale, source, target);
+                }
+            }
+        }
+    }
+
+    /// Load a TMX file, see [`Memory::add_tmx`].
+    pub fn load_tmx(
+        &mut self,
+        path: impl AsRef<Path>,
+        base_locale: &str,
+        locales: &[String],
+    ) -> Result<(), Error> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("Failed to read {}: {}", path.display(), err))?;
+        self.add_tmx(&content, base_locale, locales);
+        Ok(())
+    }
+
+    /// The translation of the base locale text in the locale, and whether it's a fuzzy match.
+    ///
+    /// An identical text uses its most used translation, otherwise the most similar text
+    /// with a similarity of at least 80% is used.
+    pub fn lookup(&self, locale: &str, text: &str) -> Option<(String, bool)> {
+        let entries = self.entries.get(locale)?;
+        if let Some(targets) = entries.get(text) {
+            return most_used(targets).map(|target| (target, false));
+        }
+
+        let (targets, similarity) = entries
+            .iter()
+            .map(|(source, targets)| (targets, similarity(text, source)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+        if similarity < FUZZY_THRESHOLD {
+            return None;
+        }
+        most_used(targets).map(|target| (target, true))
+    }
+}
+
+/// Fill the keys of the base locale missing in the other locales from the memory.
+pub fn fill_missing(
+    memory: &Memory,
+    translations: &Translations,
+    base_locale: &str,
+    locales: &[String],
+) -> Filled {
+    let mut filled = Filled::default();
+    let Some(base_trs) = translations.get(base_locale) else {
+        return filled;
+    };
+
+    for locale in locales {
+        if locale == base_locale {
+            continue;
+        }
+
+        let trs = translations.get(locale);
+        for (key, base_text) in base_trs {
+            if trs.is_some_and(|trs| trs.contains_key(key)) {
+                continue;
+            }
+            let Some((text, fuzzy)) = memory.lookup(locale, base_text) else {
+                continue;
+            };
+            let result = if fuzzy {
+                &mut filled.fuzzy
+            } else {
+                &mut filled.exact
+            };
+            result
+                .entry(locale.clone())
+                .or_default()
+                .insert(key.clone(), text);
+        }
+    }
+
+    filled
+}
+
+/// The most used text, the first one for a tie.
+fn most_used(texts: &[String]) -> Option<String> {
+    let mut counts = Vec::<(&String, usize)>::new();
+    for text in texts {
+        match counts.iter_mut().find(|(t, _)| *t == text) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((text, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(text, _)| text.clone())
+}
+
+/// The similarity of two texts from 0 to 1, by the edit distance of the characters.
+fn similarity(a: &str, b: &str) -> f64 {
+    let len = a.chars().count().max(b.chars().count());
+    if len == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(a, b) as f64 / len as f64
+}
+
+/// Whether a TMX language like `fr-FR` matches the locale, ignoring the case and
+/// the region missing in either.
+fn lang_matches(lang: &str, locale: &str) -> bool {
+    let lang = lang.replace('_', "-").to_lowercase();
+    let locale = locale.replace('_', "-").to_lowercase();
+    lang == locale
+        || lang.starts_with(&format!("{}-", locale))
+        || locale.starts_with(&format!("{}-", lang))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_fill_missing() {
+        let translations = Translations::from([
+            (
+                "en".to_string(),
+                BTreeMap::from([
+                    ("save".to_string(), "Save".to_string()),
+                    ("form.save".to_string(), "Save".to_string()),
+                    ("delete".to_string(), "Delete the file".to_string()),
+                    ("remove".to_string(), "Delete the files".to_string()),
+                    ("cancel".to_string(), "Cancel".to_string()),
+                    ("tmx".to_string(), "Sign in & continue".to_string()),
+                ]),
+            ),
+            (
+                "fr".to_string(),
+                BTreeMap::from([
+                    ("save".to_string(), "Enregistrer".to_string()),
+                    ("delete".to_string(), "Supprimer le fichier".to_string()),
+                ]),
+            ),
+        ]);
+
+        let mut memory = Memory::new();
+        memory.add_translations(&translations, "en");
+        memory.add_tmx(
+            indoc! {r#"
+            <tmx version="1.4"><body>
+              <tu>
+                <tuv xml:lang="en-US"><seg>Sign in &amp; continue</seg></tuv>
+                <tuv xml:lang="fr-FR"><seg>Se connecter et continuer</seg></tuv>
+              </tu>
+            </body></tmx>
+            "#},
+            "en",
+            &["fr".to_string()],
+        );
+
+        let filled = fill_missing(&memory, &translations, "en", &["fr".to_string()]);
+        assert_eq!(
+            filled.exact["fr"],
+            BTreeMap::from([
+                ("form.save".to_string(), "Enregistrer".to_string()),
+                ("tmx".to_string(), "Se connecter et continuer".to_string()),
+            ])
+        );
+        assert_eq!(
+            filled.fuzzy["fr"],
+            BTreeMap::from([("remove".to_string(), "Supprimer le fichier".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_most_used() {
+        let texts = ["a", "b", "b", "a", "c"].map(String::from);
+        assert_eq!(most_used(&texts).as_deref(), Some("a"));
+        assert_eq!(most_used(&[]), None);
+        assert!(lang_matches("fr-FR", "fr"));
+        assert!(lang_matches("zh_CN", "zh-cn"));
+        assert!(!lang_matches("fr", "fi"));
+    }
+}
@@ -0,0 +1,214 @@
+use crate::extractor::Results;
+use crate::locale_file::LocaleFile;
+use rust_i18n_support::{parse_interval, references};
+use std::collections::BTreeSet;
+
+/// The keys looked up by rust-i18n itself, not in the `t!` calls: the texts of
+/// `format_relative!` and the errors of the `validator` adapter, under its usual prefix.
+pub const BUILTIN_KEYS: &[&str] = &["relative_time.*", "validation.*"];
+
+/// The keys of the locale file that are not used in source, in file order.
+///
+/// The keys matching the `dynamic_keys` patterns, see [`matches_key`], or the [`BUILTIN_KEYS`]
+/// are considered used, so are the count intervals of the used keys, e.g. `inbox.0` and
+/// `inbox.2..` of `inbox`.
+pub fn unused(results: &Results, file: &LocaleFile, dynamic_keys: &[String]) -> Vec<String> {
+    file.keys()
+        .into_iter()
+        .filter(|key| !is_used(results, key))
+        .filter(|key| !dynamic_keys.iter().any(|pattern| matches_key(pattern, key)))
+        .filter(|key| !BUILTIN_KEYS.iter().any(|pattern| matches_key(pattern, key)))
+        .collect()
+}
+
+/// Remove the [`unused`] keys from the locale file.
+///
+/// Returns the removed keys, the file is not written.
+pub fn prune(results: &Results, file: &mut LocaleFile, dynamic_keys: &[String]) -> Vec<String> {
+    let unused = unused(results, file, dynamic_keys);
+
+    for key in &unused {
+        file.remove(key);
+    }
+
+    unused
+}
+
+/// The keys referenced by the texts of the locale files, e.g. `brand.name` of
+/// `%{@brand.name}`, they're used even if no source uses them.
+pub fn referenced_keys<'a>(files: impl IntoIterator<Item = &'a LocaleFile>) -> Vec<String> {
+    let mut keys = BTreeSet::new();
+    for file in files {
+        for texts in file.translations().values() {
+            for text in texts.values() {
+                keys.extend(references(text).into_iter().map(str::to_string));
+            }
+        }
+    }
+    keys.into_iter().collect()
+}
+
+/// Whether the key is used in source, or is a count interval of a used key.
+fn is_used(results: &Results, key: &str) -> bool {
+    results.contains_key(key)
+        || key.match_indices('.').any(|(i, _)| {
+            parse_interval(&key[i + 1..]).is_some() && results.contains_key(&key[..i])
+        })
+}
+
+/// Whether the key matches the pattern, where `*` matches any text, e.g. `errors.*`
+/// matches `errors.not_found` and `errors.http.404`.
+pub fn matches_key(pattern: &str, key: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == key;
+    };
+    let Some(key) = key.strip_prefix(prefix) else {
+        return false;
+    };
+    if !rest.contains('*') {
+        return key.ends_with(rest);
+    }
+
+    // Try the next part at each position
+    (0..=key.len())
+        .filter(|&i| key.is_char_boundary(i))
+        .any(|i| matches_key(rest, &key[i..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::Message;
+    use indoc::indoc;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_prune() {
+        let mut results = HashMap::new();
+        results.insert("hello".to_string(), Message::default());
+        results.insert("messages.title".to_string(), Message::default());
+
+        let content = indoc! {r#"
+        _version: 2
+        hello:
+          en: Hello
+        unused:
+          en: Unused
+        messages:
+          title:
+            en: Title
+          old:
+            en: Old
+        errors:
+          not_found:
+            en: Not found
+        "#};
+        let mut file = LocaleFile::parse("app.yml", content).unwrap();
+
+        let dynamic_keys = vec!["errors.*".to_string()];
+        assert_eq!(
+            unused(&results, &file, &dynamic_keys),
+            vec!["unused", "messages.old"]
+        );
+        assert_eq!(
+            prune(&results, &mut file, &dynamic_keys),
+            vec!["unused", "messages.old"]
+        );
+        assert_eq!(
+            file.to_text().unwrap(),
+            indoc! {r#"
+            _version: 2
+            hello:
+              en: Hello
+            messages:
+              title:
+                en: Title
+            errors:
+              not_found:
+                en: Not found
+            "#}
+        );
+        assert!(prune(&results, &mut file, &dynamic_keys).is_empty());
+    }
+
+    #[test]
+    fn test_prune_intervals() {
+        let mut results = HashMap::new();
+        results.insert("inbox".to_string(), Message::default());
+
+        let content = indoc! {r#"
+        _version: 2
+        inbox:
+          "0":
+            en: No messages
+          "2..":
+            en: "%{count} messages"
+        outbox:
+          "0":
+            en: Nothing sent
+        "#};
+        let file = LocaleFile::parse("app.yml", content).unwrap();
+
+        assert_eq!(unused(&results, &file, &[]), vec!["outbox.0"]);
+    }
+
+    #[test]
+    fn test_prune_builtin_keys() {
+        let results = HashMap::new();
+        let content = indoc! {r#"
+        _version: 2
+        relative_time:
+          minutes:
+            past:
+              one:
+                en: "%{count} minute ago"
+        validation:
+          length:
+            en: "%{field} is too short"
+        unused:
+          en: Unused
+        "#};
+        let mut file = LocaleFile::parse("app.yml", content).unwrap();
+
+        assert_eq!(prune(&results, &mut file, &[]), vec!["unused"]);
+    }
+
+    #[test]
+    fn test_referenced_keys() {
+        let results = HashMap::new();
+        let brand = indoc! {r#"
+        _version: 2
+        brand:
+          name:
+            en: Acme
+          unused:
+            en: Unused
+        "#};
+        let welcome = indoc! {r#"
+        _version: 2
+        welcome:
+          en: "Welcome to %{@brand.name}, %%{@brand.unused}"
+        "#};
+        let mut brand = LocaleFile::parse("brand.yml", brand).unwrap();
+        let welcome = LocaleFile::parse("welcome.yml", welcome).unwrap();
+
+        let referenced = referenced_keys([&brand, &welcome]);
+        assert_eq!(referenced, vec!["brand.name"]);
+        assert_eq!(
+            prune(&results, &mut brand, &referenced),
+            vec!["brand.unused"]
+        );
+    }
+
+    #[test]
+    fn test_matches_key() {
+        assert!(matches_key("errors.*", "errors.not_found"));
+        assert!(matches_key("errors.*", "errors.http.404"));
+        assert!(!matches_key("errors.*", "messages.errors.a"));
+        assert!(matches_key("*.title", "checkout.title"));
+        assert!(matches_key("a.*.b.*", "a.x.b.y"));
+        assert!(!matches_key("a.*.b", "a.x.c"));
+        assert!(matches_key("hello", "hello"));
+        assert!(!matches_key("hello", "hello.world"));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::locale_file::LocaleFile;
+use regex::{Captures, Regex};
+
+/// Rename a key in the locale file, keeping its texts of all locales.
+///
+/// Returns `true` if the key was found, the file is not written.
+pub fn rename_key(file: &mut LocaleFile, from: &str, to: &str) -> bool {
+    let texts = file
+        .translations()
+        .into_iter()
+        .filter_map(|(locale, mut texts)| texts.remove(from).map(|text| (locale, text)))
+        .collect::<Vec<_>>();
+    if texts.is_empty() {
+        return false;
+    }
+
+    file.remove(from);
+    for (locale, text) in &texts {
+        file.set(to, locale, text);
+    }
+    true
+}
+
+/// Rewrite the literal key of the `t!` calls in the Rust source.
+///
+/// Returns the new source and the lines changed, or `None` if the key is not used.
+pub fn rename_in_source(source: &str, from: &str, to: &str) -> Option<(String, Vec<usize>)> {
+    let re = Regex::new(&format!(
+        r#"(\bt!\s*[\(\[\{{]\s*)"{}""#,
+        regex::escape(&from.escape_default().to_string())
+    ))
+    .expect("Invalid regex");
+
+    let lines = re
+        .find_iter(source)
+        .map(|m| source[..m.start()].matches('\n').count() + 1)
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        return None;
+    }
+
+    let renamed = re.replace_all(source, |caps: &Captures| {
+        format!("{}\"{}\"", &caps[1], to.escape_default())
+    });
+    Some((renamed.into_owned(), lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_rename_key() {
+        let content = indoc! {r#"
+        _version: 2
+        hello:
+          en: Hello
+          zh-CN: 你好
+        "#};
+        let mut file = LocaleFile::parse("locales/app.yml", content).unwrap();
+        assert!(rename_key(&mut file, "hello", "messages.hello"));
+        assert!(!rename_key(&mut file, "hello", "messages.hi"));
+
+        let trs = file.translations();
+        assert_eq!(trs["en"]["messages.hello"], "Hello");
+        assert_eq!(trs["zh-CN"]["messages.hello"], "你好");
+        assert!(!trs["en"].contains_key("hello"));
+
+        let mut file = LocaleFile::parse("locales/en.yml", "a:\n  b: AB\n").unwrap();
+        assert!(rename_key(&mut file, "a.b", "c"));
+        assert_eq!(file.to_text().unwrap(), "c: AB\n");
+    }
+
+    #[test]
+    fn test_rename_in_source() {
+        let source = indoc! {r#"
+        fn main() {
+            t!("hello");
+            t!( "hello", name = "Jason");
+            t!("hello.world");
+            println!("hello");
+        }
+        "#};
+        let (renamed, lines) = rename_in_source(source, "hello", "messages.hello").unwrap();
+        assert_eq!(lines, vec![2, 3]);
+        assert_eq!(
+            renamed,
+            indoc! {r#"
+            fn main() {
+                t!("messages.hello");
+                t!( "messages.hello", name = "Jason");
+                t!("hello.world");
+                println!("hello");
+            }
+            "#}
+        );
+
+        assert_eq!(rename_in_source(source, "missing", "other"), None);
+    }
+}
@@ -0,0 +1,158 @@
+//! Report the findings in [SARIF](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html),
+//! for CI systems and code review bots to annotate the files.
+use crate::checker::Report;
+use crate::extractor::{DynamicKey, Location};
+use serde_json::{json, Value};
+use std::path::Path;
+
+pub const MISSING_TRANSLATION: &str = "missing-translation";
+pub const MISSING_FROM_BASE: &str = "missing-from-base";
+pub const CONFLICTING_TRANSLATION: &str = "conflicting-translation";
+pub const DYNAMIC_KEY: &str = "dynamic-key";
+
+static RULES: &[(&str, &str)] = &[
+    (
+        MISSING_TRANSLATION,
+        "A key used in source is missing in a locale.",
+    ),
+    (
+        MISSING_FROM_BASE,
+        "A key of the base locale is missing in another locale.",
+    ),
+    (
+        CONFLICTING_TRANSLATION,
+        "A key is defined in more than one locale file with different texts.",
+    ),
+    (
+        DYNAMIC_KEY,
+        "A `t!` key isn't a string literal, it can't be verified by extraction.",
+    ),
+];
+
+/// A problem to report, at the locations in source or locale files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule_id: &'static str,
+    pub message: String,
+    pub locations: Vec<Location>,
+}
+
+/// The findings of the missing translations in the report.
+pub fn findings(report: &Report) -> Vec<Finding> {
+    let missing = report.missing.iter().map(|missing| Finding {
+        rule_id: MISSING_TRANSLATION,
+        message: format!("[{}] missing \"{}\"", missing.locale, missing.key),
+        locations: missing.locations.clone(),
+    });
+    let missing_from_base = report.missing_from_base.iter().map(|missing| Finding {
+        rule_id: MISSING_FROM_BASE,
+        message: format!("[{}] missing \"{}\"", missing.locale, missing.key),
+        locations: missing.locations.clone(),
+    });
+    missing.chain(missing_from_base).collect()
+}
+
+/// The findings of the `t!` calls with dynamic keys.
+pub fn dynamic_key_findings(keys: &[DynamicKey]) -> Vec<Finding> {
+    keys.iter()
+        .map(|key| Finding {
+            rule_id: DYNAMIC_KEY,
+            message: format!("dynamic key `{}`", key.expr),
+            locations: vec![key.location.clone()],
+        })
+        .collect()
+}
+
+/// Build the SARIF log of the findings, the file paths are made relative to the `base_path`.
+pub fn to_sarif(findings: &[Finding], base_path: &Path) -> Value {
+    let rules = RULES
+        .iter()
+        .map(|(id, description)| json!({ "id": id, "shortDescription": { "text": description } }))
+        .collect::<Vec<_>>();
+
+    let results = findings
+        .iter()
+        .map(|finding| {
+            let locations = finding
+                .locations
+                .iter()
+                .map(|location| {
+                    let file = location.file.strip_prefix(base_path).unwrap_or(&location.file);
+                    json!({
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": file.display().to_string().replace('\\', "/") },
+                            "region": { "startLine": location.line.max(1) },
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "ruleId": finding.rule_id,
+                "level": "error",
+                "message": { "text": finding.message },
+                "locations": locations,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "cargo-i18n",
+                    "informationUri": "https://github.com/longbridge/rust-i18n",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::Missing;
+
+    #[test]
+    fn test_to_sarif() {
+        let report = Report {
+            missing: vec![Missing {
+                locale: "fr".into(),
+                key: "hello".into(),
+                locations: vec![Location {
+                    file: "./src/main.rs".into(),
+                    line: 4,
+                }],
+            }],
+            missing_from_base: vec![Missing {
+                locale: "fr".into(),
+                key: "title".into(),
+                locations: vec![],
+            }],
+        };
+
+        let mut findings = findings(&report);
+        findings.extend(dynamic_key_findings(&[DynamicKey {
+            location: Location {
+                file: "./src/lib.rs".into(),
+                line: 8,
+            },
+            expr: "key".into(),
+        }]));
+        let sarif = to_sarif(&findings, Path::new("./"));
+        let results = &sarif["runs"][0]["results"];
+        assert_eq!(results.as_array().unwrap().len(), 3);
+        assert_eq!(results[0]["ruleId"], MISSING_TRANSLATION);
+        assert_eq!(results[0]["message"]["text"], "[fr] missing \"hello\"");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/main.rs");
+        assert_eq!(location["region"]["startLine"], 4);
+        assert_eq!(results[1]["ruleId"], MISSING_FROM_BASE);
+        assert_eq!(results[1]["locations"], json!([]));
+        assert_eq!(results[2]["ruleId"], DYNAMIC_KEY);
+        assert_eq!(results[2]["message"]["text"], "dynamic key `key`");
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What was created by [`init`], the existing files are kept untouched.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Scaffold {
+    /// The files created or updated.
+    pub written: Vec<PathBuf>,
+    /// The files skipped because they were already set up.
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Set up a crate for rust-i18n: the locales directory with a starter `app.yml` in
+/// version 2 format, and the `[package.metadata.i18n]` config in `Cargo.toml`.
+///
+/// The first locale is used as the default locale.
+pub fn init(root: &Path, load_path: &str, locales: &[String]) -> Result<Scaffold, Error> {
+    let default_locale = locales
+        .first()
+        .ok_or_else(|| Error::msg("At least one locale is required"))?;
+    let mut scaffold = Scaffold::default();
+
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|e| Error::msg(format!("Read {} failed: {}", manifest_path.display(), e)))?;
+    if manifest.contains("metadata.i18n]") {
+        scaffold.skipped.push(manifest_path);
+    } else if !manifest.contains("[package]") {
+        return Err(Error::msg(format!(
+            "{} has no [package] section",
+            manifest_path.display()
+        )));
+    } else {
+        let separator = if manifest.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        fs::write(
+            &manifest_path,
+            format!(
+                "{}{}{}",
+                manifest,
+                separator,
+                config(load_path, default_locale, locales)
+            ),
+        )?;
+        scaffold.written.push(manifest_path);
+    }
+
+    let app_path = root.join(load_path).join("app.yml");
+    if app_path.exists() {
+        scaffold.skipped.push(app_path);
+    } else {
+        fs::create_dir_all(root.join(load_path))?;
+        fs::write(&app_path, starter_locale_file(locales))?;
+        scaffold.written.push(app_path);
+    }
+
+    Ok(scaffold)
+}
+
+/// The `i18n!` call to add into `lib.rs` or `main.rs`.
+pub fn snippet(load_path: &str, default_locale: &str) -> String {
+    format!(
+        "#[macro_use]\nextern crate rust_i18n;\n\ni18n!(\"{}\", fallback = \"{}\");\n",
+        load_path, default_locale
+    )
+}
+
+fn config(load_path: &str, default_locale: &str, locales: &[String]) -> String {
+    let available_locales = locales
+        .iter()
+        .map(|locale| format!("{:?}", locale))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "[package.metadata.i18n]\navailable-locales = [{}]\ndefault-locale = {:?}\nload-path = {:?}\n",
+        available_locales, default_locale, load_path
+    )
+}
+
+fn starter_locale_file(locales: &[String]) -> String {
+    let mut content = String::from("_version: 2\nhello:\n");
+    for locale in locales {
+        content.push_str(&format!("  {}: \"Hello, %{{name}}!\"\n", locale));
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_i18n_support::{load_locales, I18nConfig};
+
+    #[test]
+    fn test_init() {
+        let root = std::env::temp_dir().join(format!("rust-i18n-init-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+
+        let locales = vec!["en".to_string(), "zh-CN".to_string()];
+        let scaffold = init(&root, "locales", &locales).unwrap();
+        assert_eq!(scaffold.written.len(), 2);
+        assert!(scaffold.skipped.is_empty());
+
+        let cfg = I18nConfig::load(&root).unwrap();
+        assert_eq!(cfg.default_locale, "en");
+        assert_eq!(cfg.available_locales, locales);
+        assert_eq!(cfg.load_path, "locales");
+
+        let trs = load_locales(&root.join("locales").display().to_string(), |_| false);
+        assert_eq!(trs["zh-CN"]["hello"], "Hello, %{name}!");
+
+        let scaffold = init(&root, "locales", &locales).unwrap();
+        assert!(scaffold.written.is_empty());
+        assert_eq!(scaffold.skipped.len(), 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,267 @@
+//! Export and import the translations as CSV for translators working in spreadsheets.
+use crate::checker::Translations;
+use crate::extractor::Results;
+use anyhow::Error;
+use std::collections::BTreeSet;
+use std::io::{Read, Write};
+
+/// The header of the column with the source locations.
+pub const LOCATIONS_HEADER: &str = "locations";
+
+/// Write a CSV with one row per key, a column per locale and the source locations.
+///
+/// The keys are the keys used in source and the keys in the translations, sorted.
+pub fn export_csv<W: Write>(
+    writer: W,
+    results: &Results,
+    translations: &Translations,
+    locales: &[String],
+) -> Result<(), Error> {
+    let mut keys = results.keys().collect::<BTreeSet<_>>();
+    for trs in translations.values() {
+        keys.extend(trs.keys());
+    }
+
+    let mut writer = csv::Writer::from_writer(writer);
+    let mut header = vec!["key"];
+    header.extend(locales.iter().map(|l| l.as_str()));
+    header.push(LOCATIONS_HEADER);
+    writer.write_record(&header)?;
+
+    for key in keys {
+        let mut record = vec![key.clone()];
+        for locale in locales {
+            let text = translations
+                .get(locale)
+                .and_then(|trs| trs.get(key))
+                .cloned()
+                .unwrap_or_default();
+            record.push(text);
+        }
+
+        let locations = results
+            .get(key)
+            .map(|m| {
+                m.locations
+                    .iter()
+                    .map(|l| format!("{}:{}", l.file.display(), l.line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        record.push(locations);
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// An imported text different from the existing translation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub locale: String,
+    pub key: String,
+    pub current: String,
+    pub imported: String,
+}
+
+/// Read the translations from a CSV with a `key` column and a column per locale.
+///
+/// The `locations` column is ignored, so are the empty texts.
+pub fn import_csv<R: Read>(reader: R) -> Result<Translations, Error> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let key_index = headers
+        .iter()
+        .position(|h| h.trim() == "key")
+        .ok_or_else(|| anyhow::anyhow!("Missing `key` column in CSV header"))?;
+
+    let mut translations = Translations::new();
+    for record in reader.records() {
+        let record = record?;
+        let Some(key) = record.get(key_index).map(|k| k.trim()) else {
+            continue;
+        };
+        if key.is_empty() {
+            continue;
+        }
+
+        for (i, header) in headers.iter().enumerate() {
+            let locale = header.trim();
+            if i == key_index || locale == LOCATIONS_HEADER || locale.is_empty() {
+                continue;
+            }
+            let text = record.get(i).unwrap_or_default();
+            if text.is_empty() {
+                continue;
+            }
+            translations
+                .entry(locale.to_string())
+                .or_default()
+                .insert(key.to_string(), text.to_string());
+        }
+    }
+
+    Ok(translations)
+}
+
+/// Compare the imported translations with the existing ones.
+///
+/// Returns the changes to write, and the conflicts where an existing text is different.
+/// The conflicts are included in the changes only if `overwrite` is `true`.
+pub fn merge(
+    imported: &Translations,
+    existing: &Translations,
+    overwrite: bool,
+) -> (Translations, Vec<Conflict>) {
+    let mut changes = Translations::new();
+    let mut conflicts = vec![];
+
+    for (locale, texts) in imported {
+        for (key, text) in texts {
+            match existing.get(locale).and_then(|trs| trs.get(key)) {
+                Some(current) if current == text => continue,
+                Some(current) => {
+                    conflicts.push(Conflict {
+                        locale: locale.clone(),
+                        key: key.clone(),
+                        current: current.clone(),
+                        imported: text.clone(),
+                    });
+                    if !overwrite {
+                        continue;
+                    }
+                }
+                None => {}
+            }
+
+            changes
+                .entry(locale.clone())
+                .or_default()
+                .insert(key.clone(), text.clone());
+        }
+    }
+
+    (changes, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::{Location, Message};
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn test_export_csv() {
+        let mut results = HashMap::new();
+        results.insert(
+            "hello".to_string(),
+            Message {
+                key: "hello".into(),
+                locations: vec![
+                    Location {
+                        file: "src/main.rs".into(),
+                        line: 4,
+                    },
+                    Location {
+                        file: "src/lib.rs".into(),
+                        line: 10,
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        results.insert("new".to_string(), Message::default());
+
+        let translations = Translations::from([
+            (
+                "en".to_string(),
+                BTreeMap::from([
+                    ("hello".to_string(), "Hello, \"%{name}\"".to_string()),
+                    ("old".to_string(), "Old".to_string()),
+                ]),
+            ),
+            (
+                "fr".to_string(),
+                BTreeMap::from([("hello".to_string(), "Bonjour, %{name}".to_string())]),
+            ),
+        ]);
+
+        let mut output = vec![];
+        let locales = vec!["en".to_string(), "fr".to_string()];
+        export_csv(&mut output, &results, &translations, &locales).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "key,en,fr,locations\n\
+             hello,\"Hello, \"\"%{name}\"\"\",\"Bonjour, %{name}\",\"src/main.rs:4\nsrc/lib.rs:10\"\n\
+             new,,,\n\
+             old,Old,,\n"
+        );
+    }
+
+    #[test]
+    fn test_import_csv() {
+        let csv = "key,en,fr,locations\n\
+                   hello,Hello,\"Bonjour, %{name}\",src/main.rs:4\n\
+                   new,New,,\n\
+                   ,Ignored,,\n";
+        let imported = import_csv(csv.as_bytes()).unwrap();
+        assert_eq!(
+            imported,
+            Translations::from([
+                (
+                    "en".to_string(),
+                    BTreeMap::from([
+                        ("hello".to_string(), "Hello".to_string()),
+                        ("new".to_string(), "New".to_string()),
+                    ])
+                ),
+                (
+                    "fr".to_string(),
+                    BTreeMap::from([("hello".to_string(), "Bonjour, %{name}".to_string())])
+                ),
+            ])
+        );
+
+        assert!(import_csv("en,fr\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let imported = Translations::from([(
+            "fr".to_string(),
+            BTreeMap::from([
+                ("hello".to_string(), "Salut".to_string()),
+                ("same".to_string(), "Même".to_string()),
+                ("new".to_string(), "Nouveau".to_string()),
+            ]),
+        )]);
+        let existing = Translations::from([(
+            "fr".to_string(),
+            BTreeMap::from([
+                ("hello".to_string(), "Bonjour".to_string()),
+                ("same".to_string(), "Même".to_string()),
+            ]),
+        )]);
+
+        let (changes, conflicts) = merge(&imported, &existing, false);
+        assert_eq!(
+            changes["fr"],
+            BTreeMap::from([("new".to_string(), "Nouveau".to_string())])
+        );
+        assert_eq!(
+            conflicts,
+            vec![Conflict {
+                locale: "fr".into(),
+                key: "hello".into(),
+                current: "Bonjour".into(),
+                imported: "Salut".into(),
+            }]
+        );
+
+        let (changes, conflicts) = merge(&imported, &existing, true);
+        assert_eq!(changes["fr"].len(), 2);
+        assert_eq!(conflicts.len(), 1);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::checker::Translations;
+use crate::extractor::Results;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Translation coverage of a locale.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocaleStats {
+    pub locale: String,
+    pub total: usize,
+    pub translated: usize,
+    pub missing: usize,
+    /// Percentage of translated keys, from `0.0` to `100.0`.
+    pub percentage: f64,
+}
+
+/// Calculate the translation coverage of each locale.
+///
+/// The keys to translate are the keys used in source, plus the keys of the base locale.
+pub fn stats(
+    results: &Results,
+    translations: &Translations,
+    locales: &[String],
+    base_locale: &str,
+) -> Vec<LocaleStats> {
+    let mut keys = results.keys().collect::<BTreeSet<_>>();
+    if let Some(base_trs) = translations.get(base_locale) {
+        keys.extend(base_trs.keys());
+    }
+    let total = keys.len();
+
+    locales
+        .iter()
+        .map(|locale| {
+            let translated = translations
+                .get(locale)
+                .map(|trs| keys.iter().filter(|key| trs.contains_key(**key)).count())
+                .unwrap_or_default();
+            let percentage = if total == 0 {
+                100.0
+            } else {
+                translated as f64 * 100.0 / total as f64
+            };
+
+            LocaleStats {
+                locale: locale.clone(),
+                total,
+                translated,
+                missing: total - translated,
+                percentage,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::Message;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn test_stats() {
+        let mut results = HashMap::new();
+        results.insert("hello".to_string(), Message::default());
+        results.insert("world".to_string(), Message::default());
+
+        let mut translations = Translations::new();
+        translations.insert(
+            "en".into(),
+            BTreeMap::from([
+                ("hello".into(), "Hello".into()),
+                ("world".into(), "World".into()),
+                ("title".into(), "Title".into()),
+                ("unused".into(), "Unused".into()),
+            ]),
+        );
+        translations.insert(
+            "zh-CN".into(),
+            BTreeMap::from([("hello".into(), "你好".into())]),
+        );
+
+        let locales = vec!["en".into(), "zh-CN".into(), "fr".into()];
+        let stats = stats(&results, &translations, &locales, "en");
+
+        assert_eq!(stats[0].locale, "en");
+        assert_eq!(stats[0].total, 4);
+        assert_eq!(stats[0].translated, 4);
+        assert_eq!(stats[0].percentage, 100.0);
+        assert_eq!(stats[1].translated, 1);
+        assert_eq!(stats[1].missing, 3);
+        assert_eq!(stats[1].percentage, 25.0);
+        assert_eq!(stats[2].translated, 0);
+        assert_eq!(stats[2].percentage, 0.0);
+    }
+}
@@ -0,0 +1,106 @@
+//! Extract the translation calls in templates, e.g. Tera, Askama or MiniJinja.
+//!
+//! Only the tags `{{ ... }}` and `{% ... %}` are scanned, for calls and filters like:
+//!
+//! - `{{ t("hello") }}`, `{{ t!("hello", name = user.name) }}`, `{{ tr(key="hello") }}`
+//! - `{{ "hello" | t }}`, `{{ 'hello' | t(name=user.name) }}`
+//!
+//! The `maud!` and `html!` blocks in Rust files are already scanned as macro arguments.
+use crate::extractor::{add_message, Results};
+use regex::Regex;
+use rust_i18n_support::I18nConfig;
+use std::path::Path;
+use std::sync::LazyLock;
+
+static TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)\{\{.*?\}\}|\{%.*?%\}").unwrap());
+
+static CALL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b(?:t|tr)!?\(\s*(?:key\s*=\s*)?(?:"((?:[^"\\]|\\.)*)"|'((?:[^'\\]|\\.)*)')"#)
+        .unwrap()
+});
+
+static FILTER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:"((?:[^"\\]|\\.)*)"|'((?:[^'\\]|\\.)*)')\s*\|\s*(?:t|tr)\b"#).unwrap()
+});
+
+/// Extract the translation calls in the template source.
+pub fn extract(results: &mut Results, path: &Path, source: &str, cfg: &I18nConfig) {
+    for tag in TAG_RE.find_iter(source) {
+        for re in [&*CALL_RE, &*FILTER_RE] {
+            for caps in re.captures_iter(tag.as_str()) {
+                let Some(m) = caps.get(1).or_else(|| caps.get(2)) else {
+                    continue;
+                };
+                let offset = tag.start() + m.start();
+                let line = source[..offset].matches('\n').count() + 1;
+                add_message(results, &unescape(m.as_str()), path, line, cfg);
+            }
+        }
+    }
+}
+
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(c) => result.push(c),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_extract() {
+        let source = indoc! {r#"
+        <h1>{{ t("hello") }}</h1>
+        <p>{{ t!("welcome", name = user.name) }} t("not.in.tag")</p>
+        {% set title = tr(key="title") %}
+        <p>{{ "filter" | t }} {{ 'filter.args' | t(name=user.name) }}</p>
+        <p>{{ "not.translated" | upper }} {{ t('escaped \'quote\'') }}</p>
+        {{
+            t("multi.line")
+        }}
+        "#};
+
+        let mut results = Results::new();
+        extract(
+            &mut results,
+            Path::new("templates/index.html"),
+            source,
+            &I18nConfig::default(),
+        );
+
+        let mut messages: Vec<_> = results.values().collect();
+        messages.sort_by_key(|m| m.index);
+        let keys = messages
+            .iter()
+            .map(|m| (m.key.as_str(), m.locations[0].line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                ("hello", 1),
+                ("welcome", 2),
+                ("title", 3),
+                ("filter", 4),
+                ("filter.args", 4),
+                ("escaped 'quote'", 5),
+                ("multi.line", 7),
+            ]
+        );
+    }
+}
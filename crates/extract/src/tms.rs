@@ -0,0 +1,564 @@
+//! Round-trip the catalog with a translation management system (TMS), for `cargo i18n push`
+//! and `cargo i18n pull`.
+//!
+//! The texts are exchanged as flat JSON files of keys like `messages.title`. The providers
+//! are behind the `crowdin`, `lokalise`, `poeditor` and `weblate` features.
+#![cfg_attr(
+    not(all(
+        feature = "crowdin",
+        feature = "lokalise",
+        feature = "poeditor",
+        feature = "weblate"
+    )),
+    allow(dead_code, unused_imports)
+)]
+use crate::checker::Translations;
+use crate::translator::{env_var, post_json};
+use anyhow::{anyhow, Error};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// A translation management system, like Crowdin or Weblate.
+pub trait TmsProvider {
+    /// Upload the texts of a locale, `source` is true for the base locale texts, which
+    /// define the keys to translate.
+    fn push(
+        &self,
+        locale: &str,
+        texts: &BTreeMap<String, String>,
+        source: bool,
+    ) -> Result<(), Error>;
+
+    /// Download the texts of a locale, the untranslated keys may be missing or empty.
+    fn pull(&self, locale: &str) -> Result<BTreeMap<String, String>, Error>;
+}
+
+/// The pulled texts which are new or changed in the translations, empty texts are skipped.
+pub fn pulled_changes(
+    translations: &Translations,
+    locale: &str,
+    pulled: BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let current = translations.get(locale);
+    pulled
+        .into_iter()
+        .filter(|(key, text)| {
+            !text.is_empty() && current.and_then(|trs| trs.get(key)) != Some(text)
+        })
+        .collect()
+}
+
+/// Flatten a JSON file into texts by keys like `messages.title`.
+fn flatten_json(value: &serde_json::Value) -> BTreeMap<String, String> {
+    fn walk(prefix: &str, value: &serde_json::Value, texts: &mut BTreeMap<String, String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&key, value, texts);
+                }
+            }
+            serde_json::Value::String(text) => {
+                texts.insert(prefix.to_string(), text.clone());
+            }
+            serde_json::Value::Null => {}
+            value => {
+                texts.insert(prefix.to_string(), value.to_string());
+            }
+        }
+    }
+
+    let mut texts = BTreeMap::new();
+    walk("", value, &mut texts);
+    texts
+}
+
+fn status_error(url: &str, err: ureq::Error) -> Error {
+    match err {
+        ureq::Error::Status(code, response) => anyhow!(
+            "Request {} failed: {} {}",
+            url,
+            code,
+            response.into_string().unwrap_or_default()
+        ),
+        err => anyhow!("Request {} failed: {}", url, err),
+    }
+}
+
+/// [Crowdin](https://developer.crowdin.com/api/v2/), the texts are in one JSON file of the project.
+///
+/// Read from `CROWDIN_TOKEN`, `CROWDIN_PROJECT_ID`, and optionally `CROWDIN_FILE` (default
+/// `rust-i18n.json`) and `CROWDIN_BASE_URL` for Crowdin Enterprise.
+#[cfg(feature = "crowdin")]
+pub struct Crowdin {
+    token: String,
+    project_id: String,
+    file_name: String,
+    base_url: String,
+}
+
+#[cfg(feature = "crowdin")]
+impl Crowdin {
+    pub fn new(token: impl Into<String>, project_id: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            project_id: project_id.into(),
+            file_name: "rust-i18n.json".into(),
+            base_url: "https://api.crowdin.com/api/v2".into(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, Error> {
+        let mut provider = Self::new(env_var("CROWDIN_TOKEN")?, env_var("CROWDIN_PROJECT_ID")?);
+        if let Ok(file_name) = std::env::var("CROWDIN_FILE") {
+            provider.file_name = file_name;
+        }
+        if let Ok(base_url) = std::env::var("CROWDIN_BASE_URL") {
+            provider.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        Ok(provider)
+    }
+
+    fn auth(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+
+    fn project_url(&self, path: &str) -> String {
+        format!("{}/projects/{}{}", self.base_url, self.project_id, path)
+    }
+
+    /// The id of the project file, if uploaded.
+    fn file_id(&self) -> Result<Option<u64>, Error> {
+        let url = self.project_url("/files?limit=500");
+        let response: serde_json::Value = ureq::get(&url)
+            .set("Authorization", &self.auth())
+            .call()
+            .map_err(|err| status_error(&url, err))?
+            .into_json()?;
+        Ok(response["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|file| file["data"]["name"] == self.file_name.as_str())
+            .and_then(|file| file["data"]["id"].as_u64()))
+    }
+
+    /// Upload the texts into a storage, returns the storage id.
+    fn upload(&self, texts: &BTreeMap<String, String>) -> Result<u64, Error> {
+        let url = format!("{}/storages", self.base_url);
+        let response: serde_json::Value = ureq::post(&url)
+            .set("Authorization", &self.auth())
+            .set("Crowdin-API-FileName", &self.file_name)
+            .set("Content-Type", "application/json")
+            .send_string(&serde_json::to_string_pretty(texts)?)
+            .map_err(|err| status_error(&url, err))?
+            .into_json()?;
+        response["data"]["id"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid Crowdin response: {}", response))
+    }
+}
+
+#[cfg(feature = "crowdin")]
+impl TmsProvider for Crowdin {
+    fn push(
+        &self,
+        locale: &str,
+        texts: &BTreeMap<String, String>,
+        source: bool,
+    ) -> Result<(), Error> {
+        let storage_id = self.upload(texts)?;
+        let auth = self.auth();
+        match (self.file_id()?, source) {
+            (Some(file_id), true) => {
+                let url = self.project_url(&format!("/files/{}", file_id));
+                ureq::put(&url)
+                    .set("Authorization", &auth)
+                    .send_json(json!({ "storageId": storage_id }))
+                    .map_err(|err| status_error(&url, err))?;
+            }
+            (None, true) => {
+                let body = json!({ "storageId": storage_id, "name": self.file_name });
+                post_json(&self.project_url("/files"), Some(&auth), body)?;
+            }
+            (Some(file_id), false) => {
+                let url = self.project_url(&format!("/translations/{}", locale));
+                let body = json!({ "storageId": storage_id, "fileId": file_id });
+                post_json(&url, Some(&auth), body)?;
+            }
+            (None, false) => {
+                return Err(anyhow!(
+                    "{} is not in the Crowdin project, push the source texts first",
+                    self.file_name
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    fn pull(&self, locale: &str) -> Result<BTreeMap<String, String>, Error> {
+        let Some(file_id) = self.file_id()? else {
+            return Ok(BTreeMap::new());
+        };
+        let url = self.project_url(&format!("/translations/builds/files/{}", file_id));
+        let response = post_json(
+            &url,
+            Some(&self.auth()),
+            json!({ "targetLanguageId": locale, "skipUntranslatedStrings": true }),
+        )?;
+        let download_url = response["data"]["url"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Invalid Crowdin response: {}", response))?;
+        let content: serde_json::Value = ureq::get(download_url)
+            .call()
+            .map_err(|err| status_error(download_url, err))?
+            .into_json()?;
+        Ok(flatten_json(&content))
+    }
+}
+
+/// [Lokalise](https://developers.lokalise.com/reference/lokalise-rest-api), the keys of the
+/// project use the key names of the `web` platform.
+///
+/// Read from `LOKALISE_API_TOKEN` and `LOKALISE_PROJECT_ID`.
+#[cfg(feature = "lokalise")]
+pub struct Lokalise {
+    api_token: String,
+    project_id: String,
+}
+
+#[cfg(feature = "lokalise")]
+impl Lokalise {
+    /// The maximum number of keys in a page.
+    const PAGE_SIZE: usize = 5000;
+
+    pub fn new(api_token: impl Into<String>, project_id: impl Into<String>) -> Self {
+        Self {
+            api_token: api_token.into(),
+            project_id: project_id.into(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(Self::new(
+            env_var("LOKALISE_API_TOKEN")?,
+            env_var("LOKALISE_PROJECT_ID")?,
+        ))
+    }
+
+    fn project_url(&self, path: &str) -> String {
+        format!(
+            "https://api.lokalise.com/api2/projects/{}{}",
+            self.project_id, path
+        )
+    }
+}
+
+#[cfg(feature = "lokalise")]
+impl TmsProvider for Lokalise {
+    fn push(
+        &self,
+        locale: &str,
+        texts: &BTreeMap<String, String>,
+        _source: bool,
+    ) -> Result<(), Error> {
+        use base64::Engine;
+
+        let data =
+            base64::engine::general_purpose::STANDARD.encode(serde_json::to_string_pretty(texts)?);
+        let url = self.project_url("/files/upload");
+        ureq::post(&url)
+            .set("X-Api-Token", &self.api_token)
+            .send_json(json!({
+                "data": data,
+                "filename": format!("{}.json", locale),
+                "lang_iso": locale,
+                "replace_modified": true,
+            }))
+            .map_err(|err| status_error(&url, err))?;
+        Ok(())
+    }
+
+    fn pull(&self, locale: &str) -> Result<BTreeMap<String, String>, Error> {
+        let url = self.project_url("/keys");
+        let mut texts = BTreeMap::new();
+        for page in 1.. {
+            let response: serde_json::Value = ureq::get(&url)
+                .set("X-Api-Token", &self.api_token)
+                .query("include_translations", "1")
+                .query("limit", &Self::PAGE_SIZE.to_string())
+                .query("page", &page.to_string())
+                .call()
+                .map_err(|err| status_error(&url, err))?
+                .into_json()?;
+            let keys = response["keys"]
+                .as_array()
+                .ok_or_else(|| anyhow!("Invalid Lokalise response: {}", response))?;
+
+            for key in keys {
+                let name = &key["key_name"];
+                let Some(name) = name["web"].as_str().or(name.as_str()) else {
+                    continue;
+                };
+                let text = key["translations"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .find(|t| t["language_iso"] == locale)
+                    .and_then(|t| t["translation"].as_str());
+                if let Some(text) = text {
+                    texts.insert(name.to_string(), text.to_string());
+                }
+            }
+
+            if keys.len() < Self::PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(texts)
+    }
+}
+
+/// [POEditor](https://poeditor.com/docs/api), the terms of the project are the keys.
+///
+/// Read from `POEDITOR_API_TOKEN` and `POEDITOR_PROJECT_ID`.
+#[cfg(feature = "poeditor")]
+pub struct Poeditor {
+    api_token: String,
+    project_id: String,
+}
+
+#[cfg(feature = "poeditor")]
+impl Poeditor {
+    pub fn new(api_token: impl Into<String>, project_id: impl Into<String>) -> Self {
+        Self {
+            api_token: api_token.into(),
+            project_id: project_id.into(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(Self::new(
+            env_var("POEDITOR_API_TOKEN")?,
+            env_var("POEDITOR_PROJECT_ID")?,
+        ))
+    }
+
+    fn post(&self, path: &str, params: &[(&str, &str)]) -> Result<serde_json::Value, Error> {
+        let url = format!("https://api.poeditor.com/v2{}", path);
+        let mut form = vec![("api_token", self.api_token.as_str())];
+        form.push(("id", self.project_id.as_str()));
+        form.extend_from_slice(params);
+
+        let response: serde_json::Value = ureq::post(&url)
+            .send_form(&form)
+            .map_err(|err| status_error(&url, err))?
+            .into_json()?;
+        if response["response"]["status"] != "success" {
+            return Err(anyhow!("Request {} failed: {}", url, response["response"]));
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "poeditor")]
+impl TmsProvider for Poeditor {
+    fn push(
+        &self,
+        locale: &str,
+        texts: &BTreeMap<String, String>,
+        source: bool,
+    ) -> Result<(), Error> {
+        if source {
+            let terms = texts
+                .keys()
+                .map(|key| json!({ "term": key }))
+                .collect::<Vec<_>>();
+            self.post("/terms/add", &[("data", &serde_json::to_string(&terms)?)])?;
+        }
+
+        let translations = texts
+            .iter()
+            .map(|(key, text)| json!({ "term": key, "translation": { "content": text } }))
+            .collect::<Vec<_>>();
+        self.post(
+            "/languages/update",
+            &[
+                ("language", locale),
+                ("data", &serde_json::to_string(&translations)?),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn pull(&self, locale: &str) -> Result<BTreeMap<String, String>, Error> {
+        let response = self.post("/terms/list", &[("language", locale)])?;
+        Ok(response["result"]["terms"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|term| {
+                let key = term["term"].as_str()?;
+                let text = term["translation"]["content"].as_str()?;
+                Some((key.to_string(), text.to_string()))
+            })
+            .collect())
+    }
+}
+
+/// [Weblate](https://docs.weblate.org/en/latest/api.html), the component must use JSON files.
+///
+/// Read from `WEBLATE_URL` (e.g. `https://hosted.weblate.org`), `WEBLATE_TOKEN`,
+/// `WEBLATE_PROJECT` and `WEBLATE_COMPONENT`.
+#[cfg(feature = "weblate")]
+pub struct Weblate {
+    url: String,
+    token: String,
+    project: String,
+    component: String,
+}
+
+#[cfg(feature = "weblate")]
+impl Weblate {
+    pub fn new(
+        url: impl Into<String>,
+        token: impl Into<String>,
+        project: impl Into<String>,
+        component: impl Into<String>,
+    ) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+            project: project.into(),
+            component: component.into(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(Self::new(
+            env_var("WEBLATE_URL")?,
+            env_var("WEBLATE_TOKEN")?,
+            env_var("WEBLATE_PROJECT")?,
+            env_var("WEBLATE_COMPONENT")?,
+        ))
+    }
+
+    fn file_url(&self, locale: &str) -> String {
+        format!(
+            "{}/api/translations/{}/{}/{}/file/",
+            self.url, self.project, self.component, locale
+        )
+    }
+}
+
+#[cfg(feature = "weblate")]
+impl TmsProvider for Weblate {
+    fn push(
+        &self,
+        locale: &str,
+        texts: &BTreeMap<String, String>,
+        source: bool,
+    ) -> Result<(), Error> {
+        let method = if source { "replace" } else { "translate" };
+        let file = serde_json::to_string_pretty(texts)?;
+        let (content_type, body) = multipart(&[
+            ("method", None, method),
+            ("file", Some("texts.json"), &file),
+        ]);
+
+        let url = self.file_url(locale);
+        ureq::post(&url)
+            .set("Authorization", &format!("Token {}", self.token))
+            .set("Content-Type", &content_type)
+            .send_string(&body)
+            .map_err(|err| status_error(&url, err))?;
+        Ok(())
+    }
+
+    fn pull(&self, locale: &str) -> Result<BTreeMap<String, String>, Error> {
+        let url = self.file_url(locale);
+        let content: serde_json::Value = ureq::get(&url)
+            .set("Authorization", &format!("Token {}", self.token))
+            .call()
+            .map_err(|err| status_error(&url, err))?
+            .into_json()?;
+        Ok(flatten_json(&content))
+    }
+}
+
+/// Build a `multipart/form-data` body of the fields (name, file name, value), returns the
+/// content type and the body.
+#[cfg(feature = "weblate")]
+fn multipart(fields: &[(&str, Option<&str>, &str)]) -> (String, String) {
+    const BOUNDARY: &str = "rust-i18n-boundary-7MA4YWxkTrZu0gW";
+
+    let mut body = String::new();
+    for (name, file_name, value) in fields {
+        body.push_str(&format!("--{}\r\n", BOUNDARY));
+        match file_name {
+            Some(file_name) => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                 Content-Type: application/json\r\n",
+                name, file_name
+            )),
+            None => body.push_str(&format!(
+                "Content-Disposition: form-data; name=\"{}\"\r\n",
+                name
+            )),
+        }
+        body.push_str(&format!("\r\n{}\r\n", value));
+    }
+    body.push_str(&format!("--{}--\r\n", BOUNDARY));
+
+    (format!("multipart/form-data; boundary={}", BOUNDARY), body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pulled_changes() {
+        let translations = Translations::from([(
+            "fr".to_string(),
+            BTreeMap::from([
+                ("hello".to_string(), "Bonjour".to_string()),
+                ("title".to_string(), "Titre".to_string()),
+            ]),
+        )]);
+        let pulled = flatten_json(&json!({
+            "hello": "Bonjour",
+            "title": "Le titre",
+            "messages": { "new": "Nouveau", "empty": "", "none": null },
+        }));
+
+        assert_eq!(
+            pulled_changes(&translations, "fr", pulled),
+            BTreeMap::from([
+                ("messages.new".to_string(), "Nouveau".to_string()),
+                ("title".to_string(), "Le titre".to_string()),
+            ])
+        );
+    }
+
+    #[cfg(feature = "weblate")]
+    #[test]
+    fn test_multipart() {
+        let (content_type, body) =
+            multipart(&[("method", None, "replace"), ("file", Some("a.json"), "{}")]);
+        let boundary = content_type.split_once("boundary=").unwrap().1;
+        assert_eq!(
+            body,
+            format!(
+                "--{b}\r\nContent-Disposition: form-data; name=\"method\"\r\n\r\nreplace\r\n\
+                 --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.json\"\r\n\
+                 Content-Type: application/json\r\n\r\n{{}}\r\n--{b}--\r\n",
+                b = boundary
+            )
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Generate the config of external tools matching the locale files of the crate.
+use crate::generator::TODO_FILENAME;
+use crate::locale_file::{locale_files, LocaleFile};
+use rust_i18n_support::I18nConfig;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// The custom framework file of I18n Ally, in `.vscode`.
+pub const I18N_ALLY_FRAMEWORK_FILENAME: &str = "i18n-ally-custom-framework.yml";
+
+/// The config of the [I18n Ally](https://github.com/lokalise/i18n-ally) VS Code extension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct I18nAlly {
+    /// The content of `.vscode/i18n-ally-custom-framework.yml`.
+    pub framework: String,
+    /// The `i18n-ally.*` entries of `.vscode/settings.json`.
+    pub settings: Map<String, Value>,
+    /// What I18n Ally can't resolve like the runtime does.
+    pub warnings: Vec<String>,
+}
+
+/// Build the I18n Ally config from the config and the locale files of the crate.
+pub fn i18n_ally(cfg: &I18nConfig, locales_path: &Path) -> I18nAlly {
+    let mut warnings = vec![];
+    let mut extensions = BTreeSet::new();
+    let mut flat = false;
+    for path in locale_files(locales_path) {
+        if path.ends_with(TODO_FILENAME) {
+            continue;
+        }
+        let Ok(file) = LocaleFile::load(&path) else {
+            continue;
+        };
+
+        if file.version() == 2 {
+            warnings.push(format!(
+                "{} uses `_version: 2`, which I18n Ally can't read, run `cargo i18n migrate --to v1` to use it",
+                path.display()
+            ));
+        }
+        if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+            extensions.insert(ext.to_string());
+        }
+        flat |= has_dotted_keys(&file.data);
+    }
+    if cfg.minify_key {
+        warnings.push(
+            "`minify-key` is enabled, the keys in locale files are hashes I18n Ally can't match with the texts in source".into(),
+        );
+    }
+
+    let parsers = extensions
+        .iter()
+        .filter_map(|ext| match ext.as_str() {
+            "yml" | "yaml" => Some("yaml"),
+            "json" => Some("json"),
+            _ => None,
+        })
+        .collect::<BTreeSet<_>>();
+    if extensions.contains("toml") {
+        warnings.push("I18n Ally can't read TOML locale files".into());
+    }
+
+    let mut settings = Map::new();
+    let load_path = cfg.load_path.trim_start_matches("./").trim_end_matches('/');
+    settings.insert("i18n-ally.localesPaths".into(), json!([load_path]));
+    settings.insert(
+        "i18n-ally.pathMatcher".into(),
+        json!("**/(*.)?{locale}.{ext}"),
+    );
+    settings.insert("i18n-ally.enabledParsers".into(), json!(parsers));
+    settings.insert(
+        "i18n-ally.keystyle".into(),
+        json!(if flat { "flat" } else { "nested" }),
+    );
+    let base_locale = cfg.fallback.first().unwrap_or(&cfg.default_locale);
+    settings.insert("i18n-ally.sourceLanguage".into(), json!(base_locale));
+    settings.insert(
+        "i18n-ally.displayLanguage".into(),
+        json!(cfg.default_locale),
+    );
+    settings.insert("i18n-ally.enabledFrameworks".into(), json!(["custom"]));
+
+    I18nAlly {
+        framework: framework(),
+        settings,
+        warnings,
+    }
+}
+
+/// The custom framework matching the literal keys of `t!` and `tr!` calls.
+fn framework() -> String {
+    [
+        "languageIds:",
+        "  - rust",
+        "",
+        "usageMatchRegex:",
+        r#"  - "[^\\w\\d]tr?!\\([\\s\\n\\r]*['\"]({key})['\"]""#,
+        "",
+        "monopoly: true",
+        "",
+    ]
+    .join("\n")
+}
+
+/// Whether any mapping key contains `.`, which I18n Ally only reads as is with the flat key style.
+fn has_dotted_keys(value: &serde_yaml::Value) -> bool {
+    let Some(map) = value.as_mapping() else {
+        return false;
+    };
+    map.iter()
+        .any(|(k, v)| k.as_str().is_some_and(|k| k.contains('.')) || has_dotted_keys(v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_i18n_ally() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-i18n-ally");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("en.yml"), "messages:\n  \"a.b\": AB\n").unwrap();
+        std::fs::write(
+            dir.join("app.json"),
+            r#"{"_version": 2, "hi": {"en": "Hi"}}"#,
+        )
+        .unwrap();
+
+        let cfg = I18nConfig {
+            load_path: "./locales/".into(),
+            fallback: vec!["zh-CN".into()],
+            ..I18nConfig::default()
+        };
+        let config = i18n_ally(&cfg, &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            config.settings["i18n-ally.localesPaths"],
+            json!(["locales"])
+        );
+        assert_eq!(config.settings["i18n-ally.keystyle"], "flat");
+        assert_eq!(
+            config.settings["i18n-ally.enabledParsers"],
+            json!(["json", "yaml"])
+        );
+        assert_eq!(config.settings["i18n-ally.sourceLanguage"], "zh-CN");
+        assert_eq!(config.warnings.len(), 1);
+        assert!(config.warnings[0].contains("app.json"));
+        assert!(config.framework.contains("tr?!"));
+    }
+}
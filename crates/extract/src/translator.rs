@@ -0,0 +1,416 @@
+//! Fill the missing translations with a machine translation provider.
+use crate::checker::Translations;
+use anyhow::{anyhow, Error};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The file for machine translated texts, every entry is marked for review.
+pub const MACHINE_TRANSLATED_FILENAME: &str = "machine-translated.yml";
+
+const REVIEW_COMMENT: &str = "# TODO: review machine translation";
+
+/// Number of texts sent in one request.
+const BATCH_SIZE: usize = 50;
+
+/// A machine translation service.
+///
+/// The texts may contain placeholders like `%{name}`, they must be kept as is.
+pub trait TranslationProvider {
+    /// Translate the texts from the `from` locale to the `to` locale, in the same order.
+    fn translate(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>, Error>;
+}
+
+/// Translate the keys of the base locale missing in other locales.
+///
+/// Returns the new translations of each locale.
+pub fn translate_missing(
+    provider: &dyn TranslationProvider,
+    translations: &Translations,
+    base_locale: &str,
+    locales: &[String],
+) -> Result<Translations, Error> {
+    let mut result = Translations::new();
+    let Some(base_trs) = translations.get(base_locale) else {
+        return Ok(result);
+    };
+
+    for locale in locales {
+        if locale == base_locale {
+            continue;
+        }
+
+        let trs = translations.get(locale);
+        let missing = base_trs
+            .iter()
+            .filter(|(key, _)| !trs.is_some_and(|trs| trs.contains_key(*key)))
+            .collect::<Vec<_>>();
+
+        for chunk in missing.chunks(BATCH_SIZE) {
+            let texts = chunk.iter().map(|(_, v)| v.to_string()).collect::<Vec<_>>();
+            let translated = provider.translate(&texts, base_locale, locale)?;
+            if translated.len() != texts.len() {
+                return Err(anyhow!(
+                    "Expected {} translations, but got {}",
+                    texts.len(),
+                    translated.len()
+                ));
+            }
+
+            let entry = result.entry(locale.clone()).or_default();
+            for ((key, _), text) in chunk.iter().zip(translated) {
+                entry.insert(key.to_string(), text);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Write the machine translations into [`MACHINE_TRANSLATED_FILENAME`] in the locales path,
+/// merged with the existing entries, with a review comment above each key.
+pub fn write_machine_translations(
+    locales_path: impl AsRef<Path>,
+    translations: &Translations,
+) -> Result<(), Error> {
+    let path = locales_path.as_ref().join(MACHINE_TRANSLATED_FILENAME);
+
+    // key => locale => text
+    let mut entries = BTreeMap::<String, BTreeMap<String, String>>::new();
+    if path.exists() {
+        let content = std::fs::read_to_string(&path)?;
+        let data: BTreeMap<String, serde_yaml::Value> = serde_yaml::from_str(&content)?;
+        for (key, value) in data {
+            if let Ok(texts) = serde_yaml::from_value::<BTreeMap<String, String>>(value) {
+                entries.insert(key, texts);
+            }
+        }
+    }
+
+    for (locale, trs) in translations {
+        for (key, text) in trs {
+            entries
+                .entry(key.clone())
+                .or_default()
+                .insert(locale.clone(), text.clone());
+        }
+    }
+
+    let mut output = String::from("_version: 2\n");
+    for (key, texts) in entries {
+        let entry = BTreeMap::from([(key, texts)]);
+        output.push_str(REVIEW_COMMENT);
+        output.push('\n');
+        output.push_str(serde_yaml::to_string(&entry)?.trim_start_matches("---\n"));
+    }
+
+    std::fs::create_dir_all(locales_path.as_ref())?;
+    std::fs::write(&path, output)?;
+    Ok(())
+}
+
+/// Replace the placeholders like `%{name}` with `{open}name{close}`.
+fn placeholders_to_tags(text: &str, open: &str, close: &str) -> String {
+    let re = regex::Regex::new(r"%\{([^}]*)\}").unwrap();
+    re.replace_all(text, |caps: &regex::Captures| {
+        format!("{}{}{}", open, &caps[1], close)
+    })
+    .to_string()
+}
+
+/// Replace the `{open}name{close}` back to placeholders like `%{name}`.
+fn tags_to_placeholders(text: &str, open: &str, close: &str) -> String {
+    let re = regex::Regex::new(&format!(
+        "{}(.*?){}",
+        regex::escape(open),
+        regex::escape(close)
+    ))
+    .unwrap();
+    re.replace_all(text, "%{$1}").to_string()
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+pub(crate) fn post_json(
+    url: &str,
+    auth: Option<&str>,
+    body: serde_json::Value,
+) -> Result<serde_json::Value, Error> {
+    let mut request = ureq::post(url);
+    if let Some(auth) = auth {
+        request = request.set("Authorization", auth);
+    }
+    let response = request
+        .send_json(body)
+        .map_err(|err| anyhow!("Request {} failed: {}", url, err))?;
+    Ok(response.into_json()?)
+}
+
+pub(crate) fn env_var(name: &str) -> Result<String, Error> {
+    std::env::var(name).map_err(|_| anyhow!("Missing `{}` environment variable", name))
+}
+
+/// [DeepL](https://developers.deepl.com/docs/api-reference/translate), the API key is read from `DEEPL_API_KEY`.
+pub struct DeepL {
+    api_key: String,
+}
+
+impl DeepL {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(Self::new(env_var("DEEPL_API_KEY")?))
+    }
+
+    /// DeepL only accepts the language, except for a few variants.
+    fn target_lang(locale: &str) -> String {
+        let locale = locale.replace('_', "-").to_uppercase();
+        match locale.as_str() {
+            "EN-GB" | "EN-US" | "PT-BR" | "PT-PT" | "ZH-HANS" | "ZH-HANT" => locale,
+            "ZH-CN" | "ZH-SG" => "ZH-HANS".into(),
+            "ZH-TW" | "ZH-HK" | "ZH-MO" => "ZH-HANT".into(),
+            _ => locale.split('-').next().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl TranslationProvider for DeepL {
+    fn translate(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>, Error> {
+        let url = if self.api_key.ends_with(":fx") {
+            "https://api-free.deepl.com/v2/translate"
+        } else {
+            "https://api.deepl.com/v2/translate"
+        };
+
+        let texts = texts
+            .iter()
+            .map(|text| placeholders_to_tags(text, "<x>", "</x>"))
+            .collect::<Vec<_>>();
+        let source_lang = from.split(['-', '_']).next().unwrap_or_default();
+        let body = json!({
+            "text": texts,
+            "source_lang": source_lang.to_uppercase(),
+            "target_lang": Self::target_lang(to),
+            "tag_handling": "xml",
+            "ignore_tags": ["x"],
+        });
+
+        let auth = format!("DeepL-Auth-Key {}", self.api_key);
+        let response = post_json(url, Some(&auth), body)?;
+        response["translations"]
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid DeepL response: {}", response))?
+            .iter()
+            .map(|t| {
+                t["text"]
+                    .as_str()
+                    .map(|text| tags_to_placeholders(text, "<x>", "</x>"))
+                    .ok_or_else(|| anyhow!("Invalid DeepL response: {}", response))
+            })
+            .collect()
+    }
+}
+
+/// [Google Cloud Translation](https://cloud.google.com/translate/docs/reference/rest/v2/translate),
+/// the API key is read from `GOOGLE_TRANSLATE_API_KEY`.
+pub struct Google {
+    api_key: String,
+}
+
+impl Google {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(Self::new(env_var("GOOGLE_TRANSLATE_API_KEY")?))
+    }
+}
+
+impl TranslationProvider for Google {
+    fn translate(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>, Error> {
+        const OPEN: &str = "<span translate=\"no\">";
+        const CLOSE: &str = "</span>";
+
+        let url = format!(
+            "https://translation.googleapis.com/language/translate/v2?key={}",
+            self.api_key
+        );
+        let texts = texts
+            .iter()
+            .map(|text| placeholders_to_tags(text, OPEN, CLOSE))
+            .collect::<Vec<_>>();
+        let body = json!({
+            "q": texts,
+            "source": from.replace('_', "-"),
+            "target": to.replace('_', "-"),
+            "format": "html",
+        });
+
+        let response = post_json(&url, None, body)?;
+        response["data"]["translations"]
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid Google response: {}", response))?
+            .iter()
+            .map(|t| {
+                t["translatedText"]
+                    .as_str()
+                    .map(|text| unescape_html(&tags_to_placeholders(text, OPEN, CLOSE)))
+                    .ok_or_else(|| anyhow!("Invalid Google response: {}", response))
+            })
+            .collect()
+    }
+}
+
+/// [OpenAI](https://platform.openai.com/docs/api-reference/chat) chat completions,
+/// the API key is read from `OPENAI_API_KEY`.
+///
+/// `OPENAI_MODEL` (default `gpt-4o-mini`) and `OPENAI_BASE_URL` can be used to choose the model
+/// or an OpenAI compatible service.
+pub struct OpenAi {
+    api_key: String,
+    model: String,
+    base_url: String,
+}
+
+impl OpenAi {
+    pub fn new(api_key: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            model: model.into(),
+            base_url: "https://api.openai.com/v1".into(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, Error> {
+        let model = std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o-mini".into());
+        let mut provider = Self::new(env_var("OPENAI_API_KEY")?, model);
+        if let Ok(base_url) = std::env::var("OPENAI_BASE_URL") {
+            provider.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        Ok(provider)
+    }
+}
+
+impl TranslationProvider for OpenAi {
+    fn translate(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>, Error> {
+        let prompt = format!(
+            "Translate the texts in the JSON array from the `{}` locale to the `{}` locale. \
+             Keep placeholders like %{{name}} unchanged. \
+             Reply with a JSON object like {{\"translations\": [...]}} in the same order.",
+            from, to
+        );
+        let body = json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": prompt },
+                { "role": "user", "content": serde_json::to_string(texts)? },
+            ],
+            "response_format": { "type": "json_object" },
+        });
+
+        let url = format!("{}/chat/completions", self.base_url);
+        let auth = format!("Bearer {}", self.api_key);
+        let response = post_json(&url, Some(&auth), body)?;
+        let content = response["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Invalid OpenAI response: {}", response))?;
+
+        let result: serde_json::Value = serde_json::from_str(content)?;
+        serde_json::from_value(result["translations"].clone())
+            .map_err(|err| anyhow!("Invalid OpenAI response: {}, {}", content, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Upper;
+
+    impl TranslationProvider for Upper {
+        fn translate(&self, texts: &[String], _: &str, to: &str) -> Result<Vec<String>, Error> {
+            Ok(texts.iter().map(|t| format!("{}: {}", to, t)).collect())
+        }
+    }
+
+    #[test]
+    fn test_translate_missing() {
+        let mut translations = Translations::new();
+        translations.insert(
+            "en".into(),
+            BTreeMap::from([
+                ("hello".into(), "Hello".into()),
+                ("world".into(), "World".into()),
+            ]),
+        );
+        translations.insert(
+            "fr".into(),
+            BTreeMap::from([("hello".into(), "Bonjour".into())]),
+        );
+
+        let locales = vec!["en".into(), "fr".into(), "de".into()];
+        let result = translate_missing(&Upper, &translations, "en", &locales).unwrap();
+        assert_eq!(
+            result["fr"],
+            BTreeMap::from([("world".into(), "fr: World".into())])
+        );
+        assert_eq!(
+            result["de"],
+            BTreeMap::from([
+                ("hello".into(), "de: Hello".into()),
+                ("world".into(), "de: World".into())
+            ])
+        );
+        assert!(!result.contains_key("en"));
+    }
+
+    #[test]
+    fn test_write_machine_translations() {
+        let dir = std::env::temp_dir().join("rust-i18n-test-write-machine-translations");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let trs = Translations::from([(
+            "fr".into(),
+            BTreeMap::from([("hello".into(), "Bonjour".into())]),
+        )]);
+        write_machine_translations(&dir, &trs).unwrap();
+        let trs = Translations::from([(
+            "de".into(),
+            BTreeMap::from([("hello".into(), "Hallo".into())]),
+        )]);
+        write_machine_translations(&dir, &trs).unwrap();
+
+        let content = std::fs::read_to_string(dir.join(MACHINE_TRANSLATED_FILENAME)).unwrap();
+        assert_eq!(
+            content,
+            "_version: 2\n# TODO: review machine translation\nhello:\n  de: Hallo\n  fr: Bonjour\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let text = placeholders_to_tags("Hello, %{name}! %{count}", "<x>", "</x>");
+        assert_eq!(text, "Hello, <x>name</x>! <x>count</x>");
+        assert_eq!(
+            tags_to_placeholders(&text, "<x>", "</x>"),
+            "Hello, %{name}! %{count}"
+        );
+        assert_eq!(DeepL::target_lang("zh-CN"), "ZH-HANS");
+        assert_eq!(DeepL::target_lang("pt_BR"), "PT-BR");
+        assert_eq!(DeepL::target_lang("fr-FR"), "FR");
+    }
+}
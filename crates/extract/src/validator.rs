@@ -0,0 +1,267 @@
+use crate::checker::Translations;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A text using different placeholders from the base locale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub locale: String,
+    pub key: String,
+    /// Placeholders of the base locale missing in the text.
+    pub missing: Vec<String>,
+    /// Placeholders in the text but not in the base locale.
+    pub extra: Vec<String>,
+}
+
+impl Mismatch {
+    /// The missing placeholder most similar to an extra one, likely a typo.
+    pub fn suggestion(&self, extra: &str) -> Option<&str> {
+        self.missing
+            .iter()
+            .map(|missing| (edit_distance(extra, missing), missing))
+            .filter(|(distance, _)| *distance <= 2)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, missing)| missing.as_str())
+    }
+}
+
+/// A text longer than allowed for its key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooLong {
+    pub locale: String,
+    pub key: String,
+    /// The length of the text, in characters.
+    pub length: usize,
+    pub max_length: usize,
+    /// Whether the maximum length is the `_max_length` of the key, instead of a
+    /// percentage of the base locale text length.
+    pub explicit: bool,
+}
+
+/// Get the names of the placeholders like `%{name}` in the text, without the format hints
+/// like `%{size:bytes}`, and without the references to other keys like `%{@brand.name}`.
+///
+/// The escaped `%%{name}` is a literal text, not a placeholder.
+pub fn placeholders(text: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("%{") {
+        let escaped = rest[..start].ends_with('%');
+        rest = &rest[start + 2..];
+        if escaped {
+            continue;
+        }
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let name = rest[..end].split(':').next().unwrap_or_default();
+        if !name.starts_with('@') {
+            names.insert(name.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
+/// Check each text of the other locales uses the same placeholders as the base locale.
+pub fn validate(translations: &Translations, base_locale: &str) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+    let Some(base_trs) = translations.get(base_locale) else {
+        return mismatches;
+    };
+
+    for (locale, trs) in translations {
+        if locale == base_locale {
+            continue;
+        }
+
+        for (key, text) in trs {
+            let Some(base_text) = base_trs.get(key) else {
+                continue;
+            };
+
+            let expected = placeholders(base_text);
+            let actual = placeholders(text);
+            if expected == actual {
+                continue;
+            }
+
+            mismatches.push(Mismatch {
+                locale: locale.clone(),
+                key: key.clone(),
+                missing: expected.difference(&actual).cloned().collect(),
+                extra: actual.difference(&expected).cloned().collect(),
+            });
+        }
+    }
+
+    mismatches
+}
+
+/// Check the length of the texts, in characters, e.g. for the buttons which would truncate
+/// a longer German or Finnish text.
+///
+/// A text is at most the `max_lengths` of its key, for all locales, or otherwise at most
+/// `max_percent` percent of the base locale text length, for the other locales.
+pub fn check_lengths(
+    translations: &Translations,
+    base_locale: &str,
+    max_percent: Option<usize>,
+    max_lengths: &BTreeMap<String, usize>,
+) -> Vec<TooLong> {
+    let base_trs = translations.get(base_locale);
+
+    let mut too_long = vec![];
+    for (locale, trs) in translations {
+        for (key, text) in trs {
+            let (max_length, explicit) = match max_lengths.get(key) {
+                Some(max_length) => (*max_length, true),
+                None => {
+                    let base_text = base_trs.and_then(|trs| trs.get(key));
+                    match (max_percent, base_text) {
+                        (Some(percent), Some(base_text)) if locale != base_locale => {
+                            (base_text.chars().count() * percent / 100, false)
+                        }
+                        _ => continue,
+                    }
+                }
+            };
+
+            let length = text.chars().count();
+            if length > max_length {
+                too_long.push(TooLong {
+                    locale: locale.clone(),
+                    key: key.clone(),
+                    length,
+                    max_length,
+                    explicit,
+                });
+            }
+        }
+    }
+
+    too_long
+}
+
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr.push((prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1));
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_placeholders() {
+        let names = placeholders("Hello, %{name}! You have %{count} messages, %{name}.");
+        assert_eq!(names, BTreeSet::from(["count".into(), "name".into()]));
+        assert!(placeholders("100% sure, %{unclosed").is_empty());
+        let names = placeholders("%{size:bytes} of %{total:bytes}, %{size}");
+        assert_eq!(names, BTreeSet::from(["size".into(), "total".into()]));
+        let names = placeholders("Welcome to %{@brand.name}, %{name}");
+        assert_eq!(names, BTreeSet::from(["name".into()]));
+        let names = placeholders("Write %%{name} for the %{what}");
+        assert_eq!(names, BTreeSet::from(["what".into()]));
+    }
+
+    #[test]
+    fn test_validate() {
+        let translations = Translations::from([
+            (
+                "en".to_string(),
+                BTreeMap::from([
+                    ("hello".to_string(), "Hello, %{name}!".to_string()),
+                    ("count".to_string(), "%{count} items".to_string()),
+                    ("plain".to_string(), "Plain".to_string()),
+                ]),
+            ),
+            (
+                "fr".to_string(),
+                BTreeMap::from([
+                    ("hello".to_string(), "Bonjour, %{nmae} !".to_string()),
+                    ("count".to_string(), "%{count} éléments".to_string()),
+                    ("plain".to_string(), "Simple %{extra}".to_string()),
+                    ("only.fr".to_string(), "%{x}".to_string()),
+                ]),
+            ),
+        ]);
+
+        let mismatches = validate(&translations, "en");
+        assert_eq!(
+            mismatches,
+            vec![
+                Mismatch {
+                    locale: "fr".into(),
+                    key: "hello".into(),
+                    missing: vec!["name".into()],
+                    extra: vec!["nmae".into()],
+                },
+                Mismatch {
+                    locale: "fr".into(),
+                    key: "plain".into(),
+                    missing: vec![],
+                    extra: vec!["extra".into()],
+                },
+            ]
+        );
+        assert_eq!(mismatches[0].suggestion("nmae"), Some("name"));
+        assert_eq!(mismatches[1].suggestion("extra"), None);
+    }
+
+    #[test]
+    fn test_check_lengths() {
+        let translations = Translations::from([
+            (
+                "de".to_string(),
+                BTreeMap::from([
+                    ("save".to_string(), "Speichern".to_string()),
+                    ("title".to_string(), "Einstellungen".to_string()),
+                    ("ok".to_string(), "OK".to_string()),
+                ]),
+            ),
+            (
+                "en".to_string(),
+                BTreeMap::from([
+                    ("save".to_string(), "Save".to_string()),
+                    ("title".to_string(), "Preferences".to_string()),
+                    ("ok".to_string(), "Okay, got it".to_string()),
+                ]),
+            ),
+        ]);
+        let max_lengths = BTreeMap::from([("ok".to_string(), 10)]);
+
+        assert_eq!(
+            check_lengths(&translations, "en", Some(150), &max_lengths),
+            vec![
+                TooLong {
+                    locale: "de".into(),
+                    key: "save".into(),
+                    length: 9,
+                    max_length: 6,
+                    explicit: false,
+                },
+                TooLong {
+                    locale: "en".into(),
+                    key: "ok".into(),
+                    length: 12,
+                    max_length: 10,
+                    explicit: true,
+                },
+            ]
+        );
+        assert_eq!(
+            check_lengths(&translations, "en", None, &max_lengths).len(),
+            1
+        );
+    }
+}
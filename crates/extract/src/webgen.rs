@@ -0,0 +1,158 @@
+//! Generate the translations of the locale files for the web frontends, a JSON file for
+//! each locale and a TypeScript module with the types of the keys and the locales.
+use crate::checker::Translations;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The name of the TypeScript module.
+pub const TYPES_FILENAME: &str = "keys.ts";
+
+/// Generate the files of the translations, by file name: `{locale}.json` with the keys and
+/// the texts of each locale, and [`TYPES_FILENAME`] with the `Keys` and `Locale` unions.
+///
+/// Each key of `Keys` is documented with its text in the `base_locale`, if any.
+pub fn generate_web(translations: &Translations, base_locale: &str) -> BTreeMap<String, String> {
+    let mut files = translations
+        .iter()
+        .map(|(locale, texts)| {
+            let mut json = serde_json::to_string_pretty(texts).unwrap_or_default();
+            json.push('\n');
+            (format!("{}.json", locale), json)
+        })
+        .collect::<BTreeMap<_, _>>();
+    files.insert(
+        TYPES_FILENAME.to_string(),
+        generate_types(translations, base_locale),
+    );
+    files
+}
+
+fn generate_types(translations: &Translations, base_locale: &str) -> String {
+    let keys = translations
+        .values()
+        .flat_map(|texts| texts.keys())
+        .collect::<BTreeSet<_>>();
+    let base_texts = translations.get(base_locale);
+
+    let mut code = String::from(
+        "// This file is generated by `cargo i18n gen-web`, do not edit it by hand.\n\n",
+    );
+    code.push_str("/** The keys of the locale files. */\n");
+    code.push_str("export type Keys =\n");
+    if keys.is_empty() {
+        code.push_str("  never;\n");
+    }
+    for (i, key) in keys.iter().enumerate() {
+        if let Some(text) = base_texts.and_then(|texts| texts.get(*key)) {
+            code.push_str(&format!("  /** {} */\n", doc(text)));
+        }
+        let end = if i + 1 == keys.len() { ";" } else { "" };
+        code.push_str(&format!("  | {}{}\n", string(key), end));
+    }
+
+    let locales = translations
+        .keys()
+        .map(|locale| string(locale))
+        .collect::<Vec<_>>();
+    code.push_str("\n/** The locales of the locale files. */\n");
+    if locales.is_empty() {
+        code.push_str("export type Locale = never;\n");
+    } else {
+        code.push_str(&format!("export type Locale = {};\n", locales.join(" | ")));
+    }
+    code.push_str(&format!(
+        "\nexport const locales: readonly Locale[] = [{}];\n",
+        locales.join(", ")
+    ));
+    code.push_str("\n/** The texts of a locale, as in `{locale}.json`. */\n");
+    code.push_str("export type Messages = Partial<Record<Keys, string>>;\n");
+    code
+}
+
+/// A TypeScript string literal, the same as in JSON.
+fn string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// The text on one line of a doc comment.
+fn doc(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("*/", "*\\/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn translations() -> Translations {
+        Translations::from([
+            (
+                "en".to_string(),
+                BTreeMap::from([
+                    ("hello".to_string(), "Hello, %{name}!".to_string()),
+                    (
+                        "messages.title".to_string(),
+                        "Title\nSecond line */".to_string(),
+                    ),
+                ]),
+            ),
+            (
+                "zh-CN".to_string(),
+                BTreeMap::from([
+                    ("hello".to_string(), "你好，%{name}！".to_string()),
+                    ("messages.\"quoted\"".to_string(), "引号".to_string()),
+                ]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_generate_web() {
+        let files = generate_web(&translations(), "en");
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["en.json", "keys.ts", "zh-CN.json"]
+        );
+        assert_eq!(
+            files["zh-CN.json"],
+            indoc! {r#"
+            {
+              "hello": "你好，%{name}！",
+              "messages.\"quoted\"": "引号"
+            }
+            "#}
+        );
+        assert_eq!(
+            files["keys.ts"],
+            indoc! {r#"
+            // This file is generated by `cargo i18n gen-web`, do not edit it by hand.
+
+            /** The keys of the locale files. */
+            export type Keys =
+              /** Hello, %{name}! */
+              | "hello"
+              | "messages.\"quoted\""
+              /** Title Second line *\/ */
+              | "messages.title";
+
+            /** The locales of the locale files. */
+            export type Locale = "en" | "zh-CN";
+
+            export const locales: readonly Locale[] = ["en", "zh-CN"];
+
+            /** The texts of a locale, as in `{locale}.json`. */
+            export type Messages = Partial<Record<Keys, string>>;
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_generate_web_empty() {
+        let files = generate_web(&Translations::new(), "en");
+        assert!(files["keys.ts"].contains("export type Keys =\n  never;\n"));
+        assert!(files["keys.ts"].contains("export type Locale = never;\n"));
+    }
+}
@@ -1,23 +1,38 @@
 use quote::quote;
 use rust_i18n_support::{
-    is_debug, load_locales, I18nConfig, DEFAULT_MINIFY_KEY, DEFAULT_MINIFY_KEY_LEN,
-    DEFAULT_MINIFY_KEY_PREFIX, DEFAULT_MINIFY_KEY_THRESH,
+    is_debug, try_load_locales_with_conflicts, I18nConfig, DEFAULT_MINIFY_KEY,
+    DEFAULT_MINIFY_KEY_LEN, DEFAULT_MINIFY_KEY_PREFIX, DEFAULT_MINIFY_KEY_THRESH,
 };
-use std::collections::BTreeMap;
-use syn::{parse_macro_input, Expr, Ident, LitBool, LitStr, Token};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use syn::{parse_macro_input, DeriveInput, Expr, Ident, LitBool, LitStr, Token};
 
+mod localized_error;
 mod minify_key;
 mod tr;
 
 struct Args {
     locales_path: String,
-    default_locale: Option<String>,
     fallback: Option<Vec<String>>,
+    locales: Option<Vec<String>>,
     extend: Option<Expr>,
     minify_key: bool,
     minify_key_len: usize,
     minify_key_prefix: String,
     minify_key_thresh: usize,
+    codegen: Codegen,
+    inline: bool,
+    shared: bool,
+    negotiate: bool,
+}
+
+/// How the translations are embedded, the `codegen` option.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Codegen {
+    /// A `SimpleBackend` of `HashMap`s, built at the first access.
+    HashMap,
+    /// A `PhfBackend` of static perfect-hash maps.
+    Phf,
 }
 
 impl Args {
@@ -29,13 +44,25 @@ impl Args {
     }
 
     fn consume_fallback(&mut self, input: syn::parse::ParseStream) -> syn::parse::Result<()> {
+        self.fallback = Some(Self::parse_string_list(input, "fallback")?);
+        Ok(())
+    }
+
+    fn consume_locales(&mut self, input: syn::parse::ParseStream) -> syn::parse::Result<()> {
+        self.locales = Some(Self::parse_string_list(input, "locales")?);
+        Ok(())
+    }
+
+    /// Parse a string literal or an array of string literals.
+    fn parse_string_list(
+        input: syn::parse::ParseStream,
+        name: &str,
+    ) -> syn::parse::Result<Vec<String>> {
         if let Ok(val) = input.parse::<LitStr>() {
-            self.fallback = Some(vec![val.value()]);
-            return Ok(());
+            return Ok(vec![val.value()]);
         }
         let val = input.parse::<syn::ExprArray>()?;
-        let fallback = val
-            .elems
+        val.elems
             .into_iter()
             .map(|expr| {
                 if let syn::Expr::Lit(syn::ExprLit {
@@ -45,14 +72,12 @@ impl Args {
                 {
                     Ok(lit_str.value())
                 } else {
-                    Err(input.error(
-                        "`fallback` must be a string literal or an array of string literals",
-                    ))
+                    Err(input.error(format!(
+                        "`{name}` must be a string literal or an array of string literals"
+                    )))
                 }
             })
-            .collect::<syn::parse::Result<Vec<String>>>()?;
-        self.fallback = Some(fallback);
-        Ok(())
+            .collect::<syn::parse::Result<Vec<String>>>()
     }
 
     fn consume_minify_key(&mut self, input: syn::parse::ParseStream) -> syn::parse::Result<()> {
@@ -85,6 +110,45 @@ impl Args {
         Ok(())
     }
 
+    fn consume_inline(&mut self, input: syn::parse::ParseStream) -> syn::parse::Result<()> {
+        let lit_bool = input.parse::<LitBool>()?;
+        self.inline = lit_bool.value;
+        Ok(())
+    }
+
+    fn consume_shared(&mut self, input: syn::parse::ParseStream) -> syn::parse::Result<()> {
+        let lit_bool = input.parse::<LitBool>()?;
+        self.shared = lit_bool.value;
+        Ok(())
+    }
+
+    fn consume_negotiate(&mut self, input: syn::parse::ParseStream) -> syn::parse::Result<()> {
+        let lit_bool = input.parse::<LitBool>()?;
+        self.negotiate = lit_bool.value;
+        Ok(())
+    }
+
+    fn consume_codegen(&mut self, input: syn::parse::ParseStream) -> syn::parse::Result<()> {
+        let lit_str = input.parse::<LitStr>()?;
+        self.codegen = match lit_str.value().as_str() {
+            "hashmap" => Codegen::HashMap,
+            "phf" if cfg!(feature = "phf") => Codegen::Phf,
+            "phf" => {
+                return Err(syn::Error::new(
+                    lit_str.span(),
+                    "`codegen = \"phf\"` requires the `phf` feature of rust-i18n",
+                ))
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    lit_str.span(),
+                    "`codegen` must be \"hashmap\" or \"phf\"",
+                ))
+            }
+        };
+        Ok(())
+    }
+
     fn consume_options(&mut self, input: syn::parse::ParseStream) -> syn::parse::Result<()> {
         let ident = input.parse::<Ident>()?.to_string();
         input.parse::<Token![=]>()?;
@@ -94,6 +158,9 @@ impl Args {
             "fallback" => {
                 self.consume_fallback(input)?;
             }
+            "locales" => {
+                self.consume_locales(input)?;
+            }
             "backend" => {
                 let val = input.parse::<Expr>()?;
                 self.extend = Some(val);
@@ -110,6 +177,18 @@ impl Args {
             "minify_key_thresh" => {
                 self.consume_minify_key_thresh(input)?;
             }
+            "codegen" => {
+                self.consume_codegen(input)?;
+            }
+            "inline" => {
+                self.consume_inline(input)?;
+            }
+            "shared" => {
+                self.consume_shared(input)?;
+            }
+            "negotiate" => {
+                self.consume_negotiate(input)?;
+            }
             _ => {}
         }
 
@@ -130,7 +209,6 @@ impl Args {
                 .map_err(|_| input.error("Failed to load config from Cargo.toml for `metadata`"))?;
 
             self.locales_path = cfg.load_path;
-            self.default_locale = Some(cfg.default_locale.clone());
             if !cfg.fallback.is_empty() {
                 self.fallback = Some(cfg.fallback);
             }
@@ -179,13 +257,17 @@ impl syn::parse::Parse for Args {
 
         let mut result = Self {
             locales_path: String::from("locales"),
-            default_locale: None,
             fallback: None,
+            locales: None,
             extend: None,
             minify_key: DEFAULT_MINIFY_KEY,
             minify_key_len: DEFAULT_MINIFY_KEY_LEN,
             minify_key_prefix: DEFAULT_MINIFY_KEY_PREFIX.to_owned(),
             minify_key_thresh: DEFAULT_MINIFY_KEY_THRESH,
+            codegen: Codegen::HashMap,
+            inline: false,
+            shared: false,
+            negotiate: false,
         };
 
         result.load_metadata(input)?;
@@ -211,6 +293,8 @@ impl syn::parse::Parse for Args {
 /// # Attributes
 ///
 /// - `fallback` for set the fallback locale, if present [`t!`](macro.t.html) macro will use it as the fallback locale.
+/// - `locales` for embed only the given locales into the binary, default: all locales found in the path.
+///   * The `RUST_I18N_LOCALES` environment variable (comma separated, e.g. `en,de`) takes priority over it.
 /// - `backend` for set the backend, if present [`t!`](macro.t.html) macro will use it as the backend.
 /// - `metadata` to enable/disable loading of the [package.metadata.i18n] config from Cargo.toml, default: `true`.
 /// - `minify_key` for enable/disable minify key, default: [`DEFAULT_MINIFY_KEY`](constant.DEFAULT_MINIFY_KEY.html).
@@ -219,6 +303,15 @@ impl syn::parse::Parse for Args {
 /// - `minify_key_prefix` for set the minify key prefix, default: [`DEFAULT_MINIFY_KEY_PREFIX`](constant.DEFAULT_MINIFY_KEY_PREFIX.html).
 /// - `minify_key_thresh` for set the minify key threshold, default: [`DEFAULT_MINIFY_KEY_THRESH`](constant.DEFAULT_MINIFY_KEY_THRESH.html).
 ///   * If the length of the value is less than or equal to this value, the value will not be minified.
+/// - `codegen` for set how the translations are embedded, default: `"hashmap"`.
+///   * `"hashmap"` builds the `HashMap`s of a [`SimpleBackend`](struct.SimpleBackend.html) at the first access.
+///   * `"phf"` generates static perfect-hash maps of a `PhfBackend`, without startup cost and allocations, requires the `phf` feature.
+/// - `inline` for enable/disable inlining the texts of the literal keys into [`t!`](macro.t.html), default: `false`.
+///   * The texts are rendered without looking them up, unless they're overridden at runtime or the `backend` is set.
+/// - `shared` for enable/disable sharing the translations with the other crates of the binary, default: `false`.
+///   * The crates calling `i18n!` with `shared = true` on the same translations use a single backend, built once.
+/// - `negotiate` for enable/disable looking up the related locales of a missing locale, e.g. `zh-TW` -> `zh-Hant`, before the `fallback`, default: `false`.
+///   * The wildcard ranges, e.g. `zh-*-CN`, are always negotiated.
 ///
 /// ```no_run
 /// # use rust_i18n::i18n;
@@ -244,6 +337,18 @@ impl syn::parse::Parse for Args {
 /// # fn v6() {
 /// i18n!();
 /// # }
+/// # fn v7() {
+/// i18n!("locales", locales = ["en", "de"]);
+/// # }
+/// # fn v8() {
+/// i18n!("../locales", shared = true);
+/// # }
+/// ```
+///
+/// With the `phf` feature:
+///
+/// ```ignore
+/// i18n!("locales", codegen = "phf");
 /// ```
 #[proc_macro]
 pub fn i18n(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -254,8 +359,28 @@ pub fn i18n(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let current_dir = std::path::PathBuf::from(cargo_dir);
     let locales_path = current_dir.join(&args.locales_path);
 
-    let data = load_locales(&locales_path.display().to_string(), |_| false);
-    let code = generate_code(data, args);
+    let (mut data, conflicts) =
+        try_load_locales_with_conflicts(&locales_path.display().to_string(), |_| false, false)
+            .unwrap_or_else(|error| panic!("{}", error));
+    for conflict in &conflicts {
+        let files = conflict
+            .definitions
+            .iter()
+            .map(|(file, _)| file.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        eprintln!(
+            "warning: rust-i18n: \"{}\" of locale `{}` is defined with different texts in {}, using {}",
+            conflict.key,
+            conflict.locale,
+            files,
+            conflict.winner().0.display()
+        );
+    }
+    if let Some(locales) = selected_locales(args.locales.as_deref()) {
+        data.retain(|locale, _| locales.contains(locale));
+    }
+    let code = generate_code(data, args, &locales_path.display().to_string());
 
     if is_debug() {
         println!(
@@ -267,45 +392,169 @@ pub fn i18n(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     code.into()
 }
 
+/// The locales to embed, `RUST_I18N_LOCALES` takes priority over the `locales` option.
+fn selected_locales(locales: Option<&[String]>) -> Option<Vec<String>> {
+    if let Ok(env_locales) = std::env::var("RUST_I18N_LOCALES") {
+        let env_locales = env_locales
+            .split(',')
+            .map(|locale| locale.trim().to_string())
+            .filter(|locale| !locale.is_empty())
+            .collect::<Vec<_>>();
+        if !env_locales.is_empty() {
+            return Some(env_locales);
+        }
+    }
+
+    locales.map(|locales| locales.to_vec())
+}
+
+/// The texts of the translations in a single string, each identical text once, e.g. the brand
+/// names and the untranslated texts repeated in each locale, so the expansion is a string and
+/// the tables of the ranges of the texts, instead of a literal for each text.
+#[derive(Default)]
+struct Blob<'a> {
+    text: String,
+    ranges: HashMap<&'a str, [u32; 2]>,
+}
+
+impl<'a> Blob<'a> {
+    /// The range of a text in the blob, added if it's new.
+    fn range(&mut self, text: &'a str) -> [u32; 2] {
+        *self.ranges.entry(text).or_insert_with(|| {
+            let start = self.text.len();
+            self.text.push_str(text);
+            [start as u32, self.text.len() as u32]
+        })
+    }
+
+    /// The `&'static str` of a text, in `const` contexts.
+    #[cfg_attr(not(feature = "phf"), allow(dead_code))]
+    fn get(&mut self, text: &'a str) -> proc_macro2::TokenStream {
+        let [start, end] = self.range(text);
+        quote! { rust_i18n::_rust_i18n_blob_text(_RUST_I18N_BLOB, #start, #end) }
+    }
+
+    /// The `_RUST_I18N_BLOB` static.
+    fn to_static(&self) -> proc_macro2::TokenStream {
+        let text = &self.text;
+        quote! {
+            static _RUST_I18N_BLOB: &str = #text;
+        }
+    }
+}
+
+/// The offsets of the translations in the blob, for `_rust_i18n_blob_backend`: the range of
+/// the locale and the number of its texts, then the ranges of each key and its text, for each
+/// locale.
+fn blob_offsets<'a>(
+    translations: &'a BTreeMap<String, BTreeMap<String, String>>,
+    blob: &mut Blob<'a>,
+) -> Vec<u32> {
+    let mut offsets = vec![];
+    for (locale, translation) in translations {
+        offsets.extend(blob.range(locale));
+        offsets.push(translation.len() as u32);
+        for (key, text) in translation {
+            offsets.extend(blob.range(key));
+            offsets.extend(blob.range(text));
+        }
+    }
+    offsets
+}
+
+/// The `phf::Map` of the translations by key, by locale.
+#[cfg(feature = "phf")]
+fn phf_translations<'a>(
+    translations: &'a BTreeMap<String, BTreeMap<String, String>>,
+    blob: &mut Blob<'a>,
+) -> proc_macro2::TokenStream {
+    let locales = translations.keys().map(String::as_str).collect::<Vec<_>>();
+    let maps = translations
+        .values()
+        .map(|translation| {
+            let keys = translation.keys().map(String::as_str).collect::<Vec<_>>();
+            let values = translation.values().map(|value| blob.get(value));
+            phf_map(&keys, values.collect())
+        })
+        .collect();
+    phf_map(&locales, maps)
+}
+
+#[cfg(not(feature = "phf"))]
+fn phf_translations<'a>(
+    _translations: &'a BTreeMap<String, BTreeMap<String, String>>,
+    _blob: &mut Blob<'a>,
+) -> proc_macro2::TokenStream {
+    unreachable!("`codegen = \"phf\"` is rejected without the `phf` feature")
+}
+
+/// A `phf::Map` literal, like the one of `phf::phf_map!`.
+#[cfg(feature = "phf")]
+fn phf_map(keys: &[&str], values: Vec<proc_macro2::TokenStream>) -> proc_macro2::TokenStream {
+    let state = phf_generator::generate_hash(keys);
+    let key = state.key;
+    let disps = state.disps.iter().map(|(d1, d2)| quote! { (#d1, #d2) });
+    let entries = state.map.iter().map(|&index| {
+        let (key, value) = (keys[index], &values[index]);
+        quote! { (#key, #value) }
+    });
+    quote! {
+        rust_i18n::__phf::Map {
+            key: #key,
+            disps: &[#(#disps),*],
+            entries: &[#(#entries),*],
+        }
+    }
+}
+
 fn generate_code(
     translations: BTreeMap<String, BTreeMap<String, String>>,
     args: Args,
+    locales_path: &str,
 ) -> proc_macro2::TokenStream {
-    let all_translations = translations.iter().map(|(locale, translation)| {
-        let translation_length = translation.len();
-        let translation = translation.iter().map(
-            |(k, v)| quote! { ::std::borrow::Cow::Borrowed(#k), ::std::borrow::Cow::Borrowed(#v) },
-        );
+    // The texts are inlined into `t!` unless another backend, or the tracing of the lookups, may
+    // change them.
+    let inline_args = if args.inline && args.extend.is_none() && !cfg!(feature = "tracing") {
+        let locales = translations.keys();
+        quote! { , _locales_path = #locales_path, _locales = [#(#locales),*] }
+    } else {
+        quote! {}
+    };
+
+    let mut blob = Blob::default();
+    let all_translations = if args.codegen == Codegen::Phf {
+        let translations = phf_translations(&translations, &mut blob);
+        // Unused without texts
+        let blob = (!blob.text.is_empty()).then(|| blob.to_static());
         quote! {
-            ::std::borrow::Cow::Borrowed(#locale),
-            {
-                let mut map = std::collections::HashMap::with_capacity(#translation_length);
-                #(
-                    map.insert(#translation);
-                )*
-                map
-            }
+            #blob
+            static _RUST_I18N_TRANSLATIONS: rust_i18n::PhfTranslations = #translations;
+            let backend = rust_i18n::PhfBackend::new(&_RUST_I18N_TRANSLATIONS);
+        }
+    } else {
+        let offsets = blob_offsets(&translations, &mut blob);
+        let offsets_len = offsets.len();
+        let blob = blob.to_static();
+        quote! {
+            #blob
+            static _RUST_I18N_OFFSETS: [u32; #offsets_len] = [#(#offsets),*];
+            let backend = rust_i18n::_rust_i18n_blob_backend(_RUST_I18N_BLOB, &_RUST_I18N_OFFSETS);
         }
-    });
-    let all_translations = quote! {
-        let mut backend  = rust_i18n::SimpleBackend::new();
-
-        #(
-            backend.add_translations(#all_translations);
-        )*
     };
 
-    let default_locale = if let Some(default_locale) = args.default_locale {
+    // The crates share the backend of the same translations, by their path and their hash
+    let all_translations = if args.shared {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        translations.hash(&mut hasher);
+        let key = format!("{}#{:016x}", locales_path, hasher.finish());
         quote! {
-            use std::ops::Deref;
-            if #default_locale != rust_i18n::locale().deref() {
-                rust_i18n::set_locale(rust_i18n::locale().deref());
-            } else {
-                rust_i18n::set_locale(#default_locale);
-            }
+            let backend = rust_i18n::_rust_i18n_shared_backend(#key, || {
+                #all_translations
+                Box::new(backend)
+            });
         }
     } else {
-        quote! {}
+        all_translations
     };
 
     let fallback = if let Some(fallback) = args.fallback {
@@ -326,6 +575,47 @@ fn generate_code(
         quote! {}
     };
 
+    // Look up the overrides, the extension, then the backend, with `translate` or `template`
+    let backend_lookup = |method: proc_macro2::TokenStream,
+                          into_owned: proc_macro2::TokenStream| {
+        let lookup = quote! {
+            rust_i18n::_rust_i18n_overrides()
+                .#method(locale, key)
+                .map(#into_owned)
+                .or_else(|| _RUST_I18N_EXTENSION.get().and_then(|backend| backend.#method(locale, key)))
+                .or_else(|| _RUST_I18N_BACKEND.#method(locale, key))
+        };
+        if cfg!(feature = "tracing") {
+            quote! { rust_i18n::tracing::_lookup(locale, key, || #lookup) }
+        } else {
+            lookup
+        }
+    };
+    let backend_translate = backend_lookup(
+        quote! { translate },
+        quote! { |value| value.into_owned().into() },
+    );
+    let backend_template = backend_lookup(
+        quote! { template },
+        quote! { rust_i18n::MessageTemplate::into_owned },
+    );
+    let trace_fallback = |fallback: proc_macro2::TokenStream| {
+        if cfg!(feature = "tracing") {
+            quote! { rust_i18n::tracing::_fallback(locale, key, #fallback); }
+        } else {
+            quote! {}
+        }
+    };
+    let trace_territory_fallback = trace_fallback(quote! { fallback_locale });
+    let trace_negotiated_fallback = trace_fallback(quote! { negotiated });
+    let trace_configured_fallback = trace_fallback(quote! { fallback_locale });
+    let trace_missing = if cfg!(feature = "tracing") {
+        quote! { rust_i18n::tracing::_missing(locale, key, key, None); }
+    } else {
+        quote! {}
+    };
+
+    let negotiate = args.negotiate;
     let minify_key = args.minify_key;
     let minify_key_len = args.minify_key_len;
     let minify_key_prefix = args.minify_key_prefix;
@@ -341,7 +631,6 @@ fn generate_code(
         static _RUST_I18N_BACKEND: std::sync::LazyLock<Box<dyn rust_i18n::Backend>> = std::sync::LazyLock::new(|| {
             #all_translations
             #extend_code
-            #default_locale
 
             Box::new(backend)
         });
@@ -360,24 +649,37 @@ fn generate_code(
         pub fn _rust_i18n_extend(
             backend: &'static dyn rust_i18n::Backend,
             namespace: &'static str,
-        ) {
+        ) -> bool {
             let extension = rust_i18n::NamespacedBackend::new(backend, namespace);
-            assert!(
-                _RUST_I18N_EXTENSION.set(extension).is_ok(),
-                "rust-i18n backend for {} has already been extended",
-                namespace
-            );
+            _RUST_I18N_EXTENSION.set(extension).is_ok()
         }
 
         #[inline]
         fn _rust_i18n_backend_translate<'r>(locale: &str, key: &str) -> Option<std::borrow::Cow<'r, str>> {
-            _RUST_I18N_EXTENSION
-                .get()
-                .and_then(|backend| backend.translate(locale, key))
-                .or_else(|| _RUST_I18N_BACKEND.translate(locale, key))
+            #backend_translate
+        }
+
+        #[inline]
+        fn _rust_i18n_backend_template<'r>(locale: &str, key: &str) -> Option<rust_i18n::MessageTemplate<'r>> {
+            #backend_template
+        }
+
+        /// Whether the texts inlined into `t!` are the ones looked up, without runtime overrides
+        #[inline]
+        #[doc(hidden)]
+        #[allow(missing_docs)]
+        pub fn _rust_i18n_inlined() -> bool {
+            _RUST_I18N_EXTENSION.get().is_none() && rust_i18n::_rust_i18n_overrides().is_empty()
         }
 
+        // Track `RUST_I18N_LOCALES`, so changing it rebuilds the crate.
+        const _: Option<&str> = option_env!("RUST_I18N_LOCALES");
+
         static _RUST_I18N_FALLBACK_LOCALE: Option<&[&'static str]> = #fallback;
+        static _RUST_I18N_NEGOTIATE: bool = #negotiate;
+        /// The locales of the backend, to negotiate the missing locales with
+        static _RUST_I18N_LOCALES: std::sync::LazyLock<Vec<std::borrow::Cow<'static, str>>> =
+            std::sync::LazyLock::new(|| _RUST_I18N_BACKEND.available_locales());
         static _RUST_I18N_MINIFY_KEY: bool = #minify_key;
         static _RUST_I18N_MINIFY_KEY_LEN: usize = #minify_key_len;
         static _RUST_I18N_MINIFY_KEY_PREFIX: &str = #minify_key_prefix;
@@ -401,6 +703,7 @@ fn generate_code(
         #[doc(hidden)]
         pub fn _rust_i18n_translate<'r>(locale: &str, key: &'r str) -> std::borrow::Cow<'r, str> {
             _rust_i18n_try_translate(locale, key).unwrap_or_else(|| {
+                #trace_missing
                 if locale.is_empty() {
                     key.into()
                 } else {
@@ -409,23 +712,76 @@ fn generate_code(
             })
         }
 
-        /// Try to get I18n text by locale and key
+        /// Try to get I18n text by locale and key, with the references like `%{@brand.name}` resolved
         #[inline]
         #[doc(hidden)]
         #[allow(missing_docs)]
         pub fn _rust_i18n_try_translate<'r>(locale: &str, key: impl AsRef<str>) -> Option<std::borrow::Cow<'r, str>> {
-            _rust_i18n_backend_translate(locale, key.as_ref())
+            _rust_i18n_lookup(locale, key.as_ref(), _rust_i18n_backend_translate).map(|text| {
+                rust_i18n::resolve_references(text, |key| {
+                    _rust_i18n_lookup(locale, key, _rust_i18n_backend_translate)
+                })
+            })
+        }
+
+        /// Try to get the parsed I18n template by locale and key, with the references like `%{@brand.name}` resolved
+        #[inline]
+        #[doc(hidden)]
+        #[allow(missing_docs)]
+        pub fn _rust_i18n_try_template<'r>(locale: &str, key: impl AsRef<str>) -> Option<rust_i18n::MessageTemplate<'r>> {
+            _rust_i18n_lookup(locale, key.as_ref(), _rust_i18n_backend_template).map(|template| {
+                rust_i18n::resolve_template_references(template, |key| {
+                    _rust_i18n_lookup(locale, key, _rust_i18n_backend_translate)
+                })
+            })
+        }
+
+        /// Look up a key in the locale, then in the fallback locales, with `backend_lookup`
+        #[inline]
+        fn _rust_i18n_lookup<T>(
+            locale: &str,
+            key: &str,
+            backend_lookup: impl Fn(&str, &str) -> Option<T>,
+        ) -> Option<T> {
+            backend_lookup(locale, key)
                 .or_else(|| {
+                    // Wildcard ranges like `zh-*-CN` are resolved by the negotiation below.
+                    if locale.contains('*') {
+                        return None;
+                    }
+
                     let mut current_locale = locale;
                     while let Some(fallback_locale) = _rust_i18n_lookup_fallback(current_locale) {
-                        if let Some(value) = _rust_i18n_backend_translate(fallback_locale, key.as_ref()) {
+                        if let Some(value) = backend_lookup(fallback_locale, key) {
+                            #trace_territory_fallback
                             return Some(value);
                         }
                         current_locale = fallback_locale;
                     }
 
+                    None
+                })
+                .or_else(|| {
+                    // Match related locales, e.g. `zh-TW` -> `zh-Hant` with `negotiate`, or the
+                    // wildcard ranges, e.g. `zh-*-CN` -> `zh-Hans-CN`
+                    if !_RUST_I18N_NEGOTIATE && !locale.contains('*') {
+                        return None;
+                    }
+                    rust_i18n::negotiate(&[locale], &_RUST_I18N_LOCALES)
+                        .filter(|negotiated| *negotiated != locale)
+                        .and_then(|negotiated| {
+                            let value = backend_lookup(negotiated, key)?;
+                            #trace_negotiated_fallback
+                            Some(value)
+                        })
+                })
+                .or_else(|| {
                     _RUST_I18N_FALLBACK_LOCALE.and_then(|fallback| {
-                        fallback.iter().find_map(|locale| _rust_i18n_backend_translate(locale, key.as_ref()))
+                        fallback.iter().find_map(|fallback_locale| {
+                            let value = backend_lookup(fallback_locale, key)?;
+                            #trace_configured_fallback
+                            Some(value)
+                        })
                     })
                 })
         }
@@ -435,11 +791,16 @@ fn generate_code(
         #[allow(missing_docs)]
         pub fn _rust_i18n_available_locales() -> Vec<std::borrow::Cow<'static, str>> {
             let mut locales = _RUST_I18N_BACKEND.available_locales();
-            if let Some(extension) = _RUST_I18N_EXTENSION.get() {
-                for locale in extension.available_locales() {
-                    if !locales.contains(&locale) {
-                        locales.push(locale.into_owned().into());
-                    }
+            let extension_locales = _RUST_I18N_EXTENSION
+                .get()
+                .map(|extension| extension.available_locales())
+                .unwrap_or_default();
+            for locale in extension_locales
+                .into_iter()
+                .chain(rust_i18n::_rust_i18n_overrides().available_locales())
+            {
+                if !locales.contains(&locale) {
+                    locales.push(locale.into_owned().into());
                 }
             }
             locales.sort();
@@ -450,7 +811,7 @@ fn generate_code(
         #[allow(unused_macros)]
         macro_rules! __rust_i18n_t {
             ($($all_tokens:tt)*) => {
-                rust_i18n::_tr!($($all_tokens)*, _minify_key = #minify_key, _minify_key_len = #minify_key_len, _minify_key_prefix = #minify_key_prefix, _minify_key_thresh = #minify_key_thresh)
+                rust_i18n::_tr!($($all_tokens)*, _minify_key = #minify_key, _minify_key_len = #minify_key_len, _minify_key_prefix = #minify_key_prefix, _minify_key_thresh = #minify_key_thresh #inline_args)
             }
         }
 
@@ -466,7 +827,16 @@ fn generate_code(
             }
         }
 
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! __rust_i18n_t_lazy {
+            ($($all_tokens:tt)*) => {
+                rust_i18n::_tr!($($all_tokens)*, _lazy = true, _minify_key = #minify_key, _minify_key_len = #minify_key_len, _minify_key_prefix = #minify_key_prefix, _minify_key_thresh = #minify_key_thresh)
+            }
+        }
+
         pub(crate) use __rust_i18n_t as _rust_i18n_t;
+        pub(crate) use __rust_i18n_t_lazy as _rust_i18n_t_lazy;
         pub(crate) use __rust_i18n_tkv as _rust_i18n_tkv;
     }
 }
@@ -488,3 +858,81 @@ pub fn _minify_key(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn _tr(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     parse_macro_input!(input as tr::Tr).into()
 }
+
+/// Derive `rust_i18n::LocalizedError` for an error enum, translating each variant by a key
+/// with its fields as the arguments.
+///
+/// - `#[i18n(prefix = "errors")]` on the enum, the prefix of the keys, the enum name in
+///   snake case by default, e.g. `app_error`.
+/// - `#[i18n(key = "errors.not_found")]` on a variant, the key of the variant,
+///   `<prefix>.<variant>` in snake case by default, e.g. `errors.not_found`.
+/// - `#[i18n(skip)]` on a field, not an argument, the fields must be `Display` without it,
+///   the tuple fields are named by their indexes, e.g. `%{0}`.
+#[proc_macro_derive(LocalizedError, attributes(i18n))]
+pub fn derive_localized_error(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    localized_error::LocalizedError::new(parse_macro_input!(input as DeriveInput)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blob_offsets, Blob};
+    use std::collections::BTreeMap;
+
+    fn translations(
+        data: &[(&str, &[(&str, &str)])],
+    ) -> BTreeMap<String, BTreeMap<String, String>> {
+        data.iter()
+            .map(|(locale, trs)| {
+                let trs = trs
+                    .iter()
+                    .map(|(key, text)| (key.to_string(), text.to_string()))
+                    .collect();
+                (locale.to_string(), trs)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_blob_deduplicates_texts() {
+        let translations = translations(&[
+            ("en", &[("hello", "Hello"), ("hi", "Hello")]),
+            ("fr", &[("hello", "Bonjour"), ("hi", "Hello")]),
+        ]);
+        let mut blob = Blob::default();
+        let offsets = blob_offsets(&translations, &mut blob);
+
+        assert_eq!(blob.text, "enhelloHellohifrBonjour");
+        assert_eq!(
+            offsets,
+            vec![
+                0, 2, 2, 2, 7, 7, 12, 12, 14, 7, 12, // en
+                14, 16, 2, 2, 7, 16, 23, 12, 14, 7, 12, // fr
+            ]
+        );
+    }
+
+    #[test]
+    fn test_blob_ranges_in_bytes() {
+        let translations = translations(&[("zh-CN", &[("hello", "你好"), ("bye", "再见")])]);
+        let mut blob = Blob::default();
+        let offsets = blob_offsets(&translations, &mut blob);
+
+        let text = |i: usize| &blob.text[offsets[i] as usize..offsets[i + 1] as usize];
+        assert_eq!(offsets[2], 2);
+        assert_eq!((text(0), text(3), text(5)), ("zh-CN", "bye", "再见"));
+        assert_eq!((text(7), text(9)), ("hello", "你好"));
+        assert_eq!(blob.text.len(), "zh-CNbye再见hello你好".len());
+    }
+
+    #[test]
+    fn test_blob_empty() {
+        let translations = BTreeMap::new();
+        let mut blob = Blob::default();
+        assert!(blob_offsets(&translations, &mut blob).is_empty());
+        assert!(blob.text.is_empty());
+        assert_eq!(
+            blob.to_static().to_string(),
+            quote::quote! { static _RUST_I18N_BLOB: &str = ""; }.to_string()
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// The `#[derive(LocalizedError)]` of an enum.
+pub struct LocalizedError {
+    input: DeriveInput,
+}
+
+impl LocalizedError {
+    pub fn new(input: DeriveInput) -> Self {
+        Self { input }
+    }
+
+    fn expand(&self) -> syn::Result<proc_macro2::TokenStream> {
+        let Data::Enum(data) = &self.input.data else {
+            return Err(syn::Error::new_spanned(
+                &self.input.ident,
+                "LocalizedError can only be derived for enums",
+            ));
+        };
+
+        let mut prefix = None;
+        for attr in self
+            .input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("i18n"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("prefix") {
+                    prefix = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `prefix = \"...\"`"))
+                }
+            })?;
+        }
+        let prefix = prefix.unwrap_or_else(|| to_snake_case(&self.input.ident.to_string()));
+
+        let mut arms = vec![];
+        for variant in &data.variants {
+            let mut key = None;
+            for attr in variant
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("i18n"))
+            {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("key") {
+                        key = Some(meta.value()?.parse::<LitStr>()?.value());
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `key = \"...\"`"))
+                    }
+                })?;
+            }
+            let key = key.unwrap_or_else(|| {
+                format!("{}.{}", prefix, to_snake_case(&variant.ident.to_string()))
+            });
+
+            let mut bindings = vec![];
+            let mut patterns = vec![];
+            let mut values = vec![];
+            for (index, field) in variant.fields.iter().enumerate() {
+                let mut skip = false;
+                for attr in field
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("i18n"))
+                {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("skip") {
+                            skip = true;
+                            Ok(())
+                        } else {
+                            Err(meta.error("expected `skip`"))
+                        }
+                    })?;
+                }
+                let (binding, pattern) = match &field.ident {
+                    Some(ident) => (ident.clone(), ident.to_string()),
+                    None => (format_ident!("_{}", index), index.to_string()),
+                };
+                if !skip {
+                    patterns.push(pattern);
+                    values.push(binding.clone());
+                }
+                bindings.push(binding);
+            }
+
+            let ident = &variant.ident;
+            let fields = match &variant.fields {
+                Fields::Named(_) => quote! { { #(#bindings,)* .. } },
+                Fields::Unnamed(_) => quote! { ( #(#bindings,)* ) },
+                Fields::Unit => quote! {},
+            };
+            arms.push(quote! {
+                #[allow(unused_variables)]
+                Self::#ident #fields => {
+                    let text = crate::_rust_i18n_translate(locale, #key);
+                    rust_i18n::replace_patterns_with_locale(
+                        locale,
+                        &text,
+                        &[#(#patterns),*],
+                        &[#(format!("{}", #values)),*],
+                    )
+                }
+            });
+        }
+
+        let ident = &self.input.ident;
+        let (impl_generics, ty_generics, where_clause) = self.input.generics.split_for_impl();
+        let body = if arms.is_empty() {
+            quote! { match *self {} }
+        } else {
+            quote! { match self { #(#arms)* } }
+        };
+        Ok(quote! {
+            impl #impl_generics rust_i18n::LocalizedError for #ident #ty_generics #where_clause {
+                fn localized_message(&self, locale: &str) -> String {
+                    #body
+                }
+            }
+        })
+    }
+}
+
+impl From<LocalizedError> for proc_macro::TokenStream {
+    fn from(val: LocalizedError) -> Self {
+        val.expand()
+            .unwrap_or_else(|err| err.to_compile_error())
+            .into()
+    }
+}
+
+/// `NotFound` => `not_found`
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut chars = name.chars().peekable();
+    let mut prev_lower = false;
+    while let Some(c) = chars.next() {
+        if c.is_uppercase() {
+            let next_lower = chars.peek().is_some_and(|next| next.is_lowercase());
+            if !snake.is_empty() && (prev_lower || next_lower) && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+            prev_lower = false;
+        } else {
+            snake.push(c);
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        }
+    }
+    snake
+}
@@ -1,10 +1,16 @@
 use quote::{quote, ToTokens};
 use rust_i18n_support::{
-    MinifyKey, DEFAULT_MINIFY_KEY_LEN, DEFAULT_MINIFY_KEY_PREFIX, DEFAULT_MINIFY_KEY_THRESH,
+    is_rtl, try_load_locales, MessageTemplate, MinifyKey, Segment, DEFAULT_MINIFY_KEY_LEN,
+    DEFAULT_MINIFY_KEY_PREFIX, DEFAULT_MINIFY_KEY_THRESH,
 };
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use syn::{parse::discouraged::Speculative, token::Brace, Expr, Ident, LitStr, Token};
 
 #[derive(Clone, Debug, Default)]
+#[allow(clippy::large_enum_variant)]
 pub enum Value {
     #[default]
     Empty,
@@ -258,15 +264,65 @@ impl syn::parse::Parse for Messsage {
     }
 }
 
+type Translations = BTreeMap<String, BTreeMap<String, String>>;
+
+/// The files of a locales path with their modified times, the translations loaded from them
+/// are stale when they change.
+type Fingerprint = Vec<(PathBuf, Option<SystemTime>)>;
+
+/// The translations by locales path for the inlined texts, loaded again when the files change,
+/// since the proc-macro servers, e.g. rust-analyzer, live across the edits.
+static TRANSLATIONS: Mutex<BTreeMap<String, (Fingerprint, Arc<Translations>)>> =
+    Mutex::new(BTreeMap::new());
+
+fn fingerprint(path: &Path, files: &mut Fingerprint) {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            fingerprint(&entry.path(), files);
+        } else {
+            files.push((entry.path(), metadata.modified().ok()));
+        }
+    }
+}
+
+fn load_translations(locales_path: &str) -> Arc<Translations> {
+    let mut files = vec![];
+    fingerprint(Path::new(locales_path), &mut files);
+    files.sort();
+
+    let mut translations = TRANSLATIONS.lock().unwrap_or_else(|e| e.into_inner());
+    match translations.get(locales_path) {
+        Some((cached, trs)) if *cached == files => trs.clone(),
+        _ => {
+            // The errors are reported by `i18n!`, nothing is inlined without the translations.
+            let trs =
+                Arc::new(try_load_locales(locales_path, |_| false, false).unwrap_or_default());
+            translations.insert(locales_path.to_owned(), (files, trs.clone()));
+            trs
+        }
+    }
+}
+
 /// A type representing the `tr!` proc macro.
 pub(crate) struct Tr {
     pub msg: Messsage,
     pub args: Arguments,
     pub locale: Option<Value>,
+    pub lazy: bool,
     pub minify_key: bool,
     pub minify_key_len: usize,
     pub minify_key_prefix: String,
     pub minify_key_thresh: usize,
+    /// The path of the translations of `i18n!`, given when the texts can be inlined.
+    pub locales_path: Option<String>,
+    /// The locales embedded by `i18n!`.
+    pub locales: Vec<String>,
 }
 
 impl Tr {
@@ -275,10 +331,13 @@ impl Tr {
             msg: Messsage::default(),
             args: Arguments::default(),
             locale: None,
+            lazy: false,
             minify_key: false,
             minify_key_len: DEFAULT_MINIFY_KEY_LEN,
             minify_key_prefix: DEFAULT_MINIFY_KEY_PREFIX.into(),
             minify_key_thresh: DEFAULT_MINIFY_KEY_THRESH,
+            locales_path: None,
+            locales: Vec::new(),
         }
     }
 
@@ -303,6 +362,18 @@ impl Tr {
         ))
     }
 
+    fn parse_lazy(value: &Value) -> syn::parse::Result<bool> {
+        if let Value::Expr(Expr::Lit(expr_lit)) = value {
+            if let syn::Lit::Bool(lit_bool) = &expr_lit.lit {
+                return Ok(lit_bool.value);
+            }
+        }
+        Err(syn::Error::new_spanned(
+            value,
+            "`_lazy` Expected a boolean literal",
+        ))
+    }
+
     fn parse_minify_key_len(value: &Value) -> syn::parse::Result<usize> {
         if let Value::Expr(Expr::Lit(expr_lit)) = value {
             if let syn::Lit::Int(lit_int) = &expr_lit.lit {
@@ -339,12 +410,50 @@ impl Tr {
         ))
     }
 
+    fn parse_locales_path(value: &Value) -> syn::parse::Result<String> {
+        if let Value::Expr(Expr::Lit(expr_lit)) = value {
+            if let syn::Lit::Str(lit_str) = &expr_lit.lit {
+                return Ok(lit_str.value());
+            }
+        }
+        Err(syn::Error::new_spanned(
+            value,
+            "`_locales_path` Expected a string literal",
+        ))
+    }
+
+    fn parse_locales(value: &Value) -> syn::parse::Result<Vec<String>> {
+        if let Value::Expr(Expr::Array(expr_array)) = value {
+            return expr_array
+                .elems
+                .iter()
+                .map(|elem| match elem {
+                    Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit_str),
+                        ..
+                    }) => Ok(lit_str.value()),
+                    _ => Err(syn::Error::new_spanned(
+                        elem,
+                        "`_locales` Expected a string literal",
+                    )),
+                })
+                .collect();
+        }
+        Err(syn::Error::new_spanned(
+            value,
+            "`_locales` Expected an array of string literals",
+        ))
+    }
+
     fn filter_arguments(&mut self) -> syn::parse::Result<()> {
         for arg in self.args.iter() {
             match arg.name.as_str() {
                 "locale" => {
                     self.locale = Some(arg.value.clone());
                 }
+                "_lazy" => {
+                    self.lazy = Self::parse_lazy(&arg.value)?;
+                }
                 "_minify_key" => {
                     self.minify_key = Self::parse_minify_key(&arg.value)?;
                 }
@@ -357,6 +466,12 @@ impl Tr {
                 "_minify_key_thresh" => {
                     self.minify_key_thresh = Self::parse_minify_key_thresh(&arg.value)?;
                 }
+                "_locales_path" => {
+                    self.locales_path = Some(Self::parse_locales_path(&arg.value)?);
+                }
+                "_locales" => {
+                    self.locales = Self::parse_locales(&arg.value)?;
+                }
                 _ => {}
             }
         }
@@ -364,10 +479,13 @@ impl Tr {
         self.args.as_mut().retain(|v| {
             ![
                 "locale",
+                "_lazy",
                 "_minify_key",
                 "_minify_key_len",
                 "_minify_key_prefix",
                 "_minify_key_thresh",
+                "_locales_path",
+                "_locales",
             ]
             .contains(&v.name.as_str())
         });
@@ -387,7 +505,106 @@ impl Tr {
         quote! {}
     }
 
+    #[cfg(feature = "tracing")]
+    fn trace_missing() -> proc_macro2::TokenStream {
+        quote! {
+            let msg_key = std::string::String::from(std::convert::AsRef::<str>::as_ref(&msg_key));
+            let msg_val = rust_i18n::CowStr::from(msg_val);
+            rust_i18n::tracing::_missing(locale, &msg_key, msg_val.as_str(), Some((file!(), line!())));
+        }
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    fn trace_missing() -> proc_macro2::TokenStream {
+        quote! {}
+    }
+
+    /// The `match` arms of the locales having the text of a literal key, rendered with the
+    /// arguments at expansion time, e.g. `"en" => Some(format!("Hello, {}!", values[0]))`.
+    ///
+    /// The texts with references are looked up instead, so are the ones of the right-to-left
+    /// locales with arguments, isolated depending on the runtime settings.
+    fn inline_arms(&self) -> Vec<proc_macro2::TokenStream> {
+        let (Some(locales_path), Some(msg_val)) = (&self.locales_path, self.msg.val.to_string())
+        else {
+            return Vec::new();
+        };
+        let msg_key = if self.minify_key {
+            MinifyKey::minify_key(
+                msg_val.as_str(),
+                self.minify_key_len,
+                self.minify_key_prefix.as_str(),
+                self.minify_key_thresh,
+            )
+            .into_owned()
+        } else {
+            msg_val
+        };
+        let translations = load_translations(locales_path);
+        self.locales
+            .iter()
+            .filter_map(|locale| {
+                let text = translations.get(locale)?.get(&msg_key)?;
+                if text.contains("%{@") {
+                    return None;
+                }
+                if self.args.is_empty() {
+                    let text = text.replace("%%{", "%{");
+                    return Some(quote! { #locale => Some(#text) });
+                }
+                if is_rtl(locale) {
+                    return None;
+                }
+                let (format, indexes) = self.inline_format(text)?;
+                Some(quote! { #locale => Some(format!(#format, #(values[#indexes]),*)) })
+            })
+            .collect()
+    }
+
+    /// The `format!` string of a text and the indexes of the arguments of its placeholders,
+    /// `None` when an argument has a format hint, e.g. `%{size:bytes}`, formatted at runtime.
+    fn inline_format(&self, text: &str) -> Option<(String, Vec<usize>)> {
+        let keys = self.args.keys();
+        let escape = |text: &str| text.replace('{', "{{").replace('}', "}}");
+        let mut format = String::with_capacity(text.len());
+        let mut indexes = Vec::new();
+        for segment in MessageTemplate::parse(text).segments() {
+            match segment {
+                Segment::Text(text) => format.push_str(&escape(text)),
+                Segment::Arg {
+                    name,
+                    hint,
+                    pattern,
+                } => match keys.iter().position(|key| key == name) {
+                    Some(_) if hint.is_some() => return None,
+                    Some(index) => {
+                        format.push_str("{}");
+                        indexes.push(index);
+                    }
+                    None => format.push_str(&escape(pattern)),
+                },
+            }
+        }
+        Some((format, indexes))
+    }
+
     fn into_token_stream(self) -> proc_macro2::TokenStream {
+        // The texts known at expansion time skip the lookup, unless they're overridden.
+        let inline_arms = if self.lazy {
+            Vec::new()
+        } else {
+            self.inline_arms()
+        };
+        let inlined = quote! {
+            if crate::_rust_i18n_inlined() {
+                match locale {
+                    #(#inline_arms,)*
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        };
         let (msg_key, msg_val) = if self.minify_key && self.msg.val.is_expr_lit_str() {
             let msg_val = self.msg.val.to_string().unwrap();
             let msg_key = MinifyKey::minify_key(
@@ -411,11 +628,17 @@ impl Tr {
             let msg_key = quote! { &msg_val };
             (msg_key, msg_val)
         };
+        // The locale of `t_lazy!` is resolved when displayed, unless it's given.
+        let lazy_locale = self.locale.as_ref().map_or_else(
+            || quote! { None },
+            |locale| quote! { Some({ let locale: &str = #locale; std::string::String::from(locale) }) },
+        );
         let locale = self.locale.map_or_else(
             || quote! { &rust_i18n::locale() },
             |locale| quote! { #locale },
         );
         let keys: Vec<_> = self.args.keys().iter().map(|v| quote! { #v }).collect();
+        // The values formatted into strings, kept by `t_lazy!`.
         let values: Vec<_> = self
             .args
             .as_ref()
@@ -429,17 +652,86 @@ impl Tr {
                 quote! { format!(#sepecifiers, #value) }
             })
             .collect();
+        // The values borrowed and written into the text as they are, only formatted into
+        // strings with the format specifiers, e.g. `count = 5 : {:08}`.
+        let display_values: Vec<_> = self
+            .args
+            .as_ref()
+            .iter()
+            .zip(&values)
+            .map(|(v, formatted)| match v.specifiers {
+                Some(_) => formatted.clone(),
+                None => {
+                    let value = &v.value;
+                    quote! { &(#value) }
+                }
+            })
+            .collect();
+        let display_indexes = (0..display_values.len()).map(syn::Index::from);
+        let values_len = display_values.len();
+        // Select the text by the count intervals of the key, e.g. `2..`, when `count` is given.
+        let count_index = self.args.keys().iter().position(|key| key == "count");
+        let select_interval = match count_index {
+            Some(index) if !self.minify_key => quote! {
+                .or_else(|| {
+                    let count = values[#index].to_string().trim().parse::<u64>().ok()?;
+                    rust_i18n::select_interval(
+                        std::convert::AsRef::<str>::as_ref(&msg_key),
+                        count,
+                        |key| crate::_rust_i18n_try_translate(locale, key),
+                    )
+                    .map(rust_i18n::MessageTemplate::parse)
+                })
+            },
+            _ => quote! {},
+        };
+        let inline_text = match (inline_arms.is_empty(), self.args.is_empty()) {
+            (true, _) => quote! {},
+            (false, true) => quote! {
+                let inlined = #inlined;
+                if let Some(text) = inlined {
+                    std::borrow::Cow::<str>::Borrowed(text).into()
+                } else
+            },
+            (false, false) => quote! {
+                let inlined = #inlined;
+                if let Some(replaced) = inlined {
+                    std::borrow::Cow::from(replaced)
+                } else
+            },
+        };
         let logging = Self::log_missing();
-        if self.args.is_empty() {
+        let tracing = Self::trace_missing();
+        if self.lazy {
+            let select_interval = !self.minify_key;
             quote! {
                 {
                     let msg_val = #msg_val;
                     let msg_key = #msg_key;
-                    if let Some(translated) = crate::_rust_i18n_try_translate(#locale, &msg_key) {
-                        translated.into()
+                    rust_i18n::LazyTranslation::_new(
+                        std::convert::AsRef::<str>::as_ref(&msg_key).into(),
+                        rust_i18n::CowStr::from(msg_val).as_str().into(),
+                        #lazy_locale,
+                        vec![#(#keys),*],
+                        vec![#(#values),*],
+                        #select_interval,
+                        |locale, key| crate::_rust_i18n_try_translate(locale, key),
+                    )
+                }
+            }
+        } else if self.args.is_empty() {
+            quote! {
+                {
+                    let msg_val = #msg_val;
+                    let msg_key = #msg_key;
+                    let locale: &str = #locale;
+                    #inline_text
+                    if let Some(translated) = crate::_rust_i18n_try_translate(locale, &msg_key) {
+                        rust_i18n::_unescape_patterns(translated).into()
                     } else {
                         #logging
-                        rust_i18n::CowStr::from(msg_val).into_inner()
+                        #tracing
+                        rust_i18n::_unescape_patterns(rust_i18n::CowStr::from(msg_val).into_inner())
                     }
                 }
             }
@@ -449,14 +741,19 @@ impl Tr {
                     let msg_val = #msg_val;
                     let msg_key = #msg_key;
                     let keys = &[#(#keys),*];
-                    let values = &[#(#values),*];
+                    let values = (#(#display_values,)*);
+                    let values: &[&dyn std::fmt::Display; #values_len] = &[#(&values.#display_indexes),*];
+                    let locale: &str = #locale;
                     {
-                    if let Some(translated) = crate::_rust_i18n_try_translate(#locale, &msg_key) {
-                        let replaced = rust_i18n::replace_patterns(&translated, keys, values);
+                    #inline_text
+                    if let Some(template) = crate::_rust_i18n_try_template(locale, &msg_key)#select_interval {
+                        let replaced = rust_i18n::render_template(locale, &template, keys, values);
                         std::borrow::Cow::from(replaced)
                     } else {
                         #logging
-                        let replaced = rust_i18n::replace_patterns(rust_i18n::CowStr::from(msg_val).as_str(), keys, values);
+                        #tracing
+                        let template = rust_i18n::MessageTemplate::parse(rust_i18n::CowStr::from(msg_val).into_inner());
+                        let replaced = rust_i18n::render_template(locale, &template, keys, values);
                         std::borrow::Cow::from(replaced)
                     }
                 }
@@ -505,3 +802,24 @@ impl From<Tr> for proc_macro2::TokenStream {
         args.into_token_stream()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::load_translations;
+
+    #[test]
+    fn test_load_translations_again_when_changed() {
+        let dir = std::env::temp_dir().join(format!("rust-i18n-tr-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("en.yml"), "hello: Hello").unwrap();
+        let path = dir.display().to_string();
+
+        assert_eq!(load_translations(&path)["en"]["hello"], "Hello");
+        assert!(load_translations(&path).get("fr").is_none());
+
+        std::fs::write(dir.join("fr.yml"), "hello: Bonjour").unwrap();
+        assert_eq!(load_translations(&path)["fr"]["hello"], "Bonjour");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
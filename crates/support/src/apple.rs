@@ -0,0 +1,591 @@
+//! Read and write Apple `.strings` and `.stringsdict` files, e.g. shared with an iOS or macOS
+//! app.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The CLDR plural categories, the subkeys of a plural key, e.g. `apples.one`.
+pub const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// The locale of an Apple localization file from its `.lproj` directory, e.g. `zh-Hans` of
+/// `zh-Hans.lproj/Localizable.strings`.
+pub fn lproj_locale(path: &Path) -> Option<&str> {
+    path.parent()?
+        .file_name()?
+        .to_str()?
+        .strip_suffix(".lproj")
+        .filter(|locale| !locale.is_empty())
+}
+
+/// Parse the `"key" = "value";` pairs of a `.strings` file, in order.
+///
+/// The file must be UTF-8, the default of Xcode since the string catalogs.
+///
+/// ```
+/// # use rust_i18n_support::parse_strings;
+/// let pairs = parse_strings("/* Checkout */\n\"checkout.title\" = \"Caf\\U00E9\";").unwrap();
+/// assert_eq!(pairs, vec![("checkout.title".to_string(), "Café".to_string())]);
+/// ```
+pub fn parse_strings(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut parser = StringsParser {
+        content: content.trim_start_matches('\u{feff}'),
+        pos: 0,
+    };
+    let mut pairs = vec![];
+    while parser.skip_comments()? {
+        let key = parser.string()?;
+        let value = if parser.consume('=')? {
+            parser.string()?
+        } else {
+            // `"key";` is the translation of the key with itself
+            key.clone()
+        };
+        if !parser.consume(';')? {
+            return Err(parser.error("expected `;`"));
+        }
+        pairs.push((key, value));
+    }
+    Ok(pairs)
+}
+
+/// Write the pairs as a `.strings` file.
+///
+/// ```
+/// # use rust_i18n_support::write_strings;
+/// let content = write_strings([("checkout.title", "\"Pay\" now")]);
+/// assert_eq!(content, "\"checkout.title\" = \"\\\"Pay\\\" now\";\n");
+/// ```
+pub fn write_strings<K: AsRef<str>, V: AsRef<str>>(
+    pairs: impl IntoIterator<Item = (K, V)>,
+) -> String {
+    let mut output = String::new();
+    for (key, value) in pairs {
+        output.push_str(&format!(
+            "\"{}\" = \"{}\";\n",
+            escape_strings(key.as_ref()),
+            escape_strings(value.as_ref())
+        ));
+    }
+    output
+}
+
+struct StringsParser<'a> {
+    content: &'a str,
+    pos: usize,
+}
+
+impl<'a> StringsParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.content[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.content[..self.pos].lines().count().max(1);
+        format!("Invalid strings format at line {}, {}", line, message)
+    }
+
+    /// Skip the whitespace and the comments, returns whether anything is left.
+    fn skip_comments(&mut self) -> Result<bool, String> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                let end = comment
+                    .find("*/")
+                    .ok_or_else(|| self.error("unterminated comment"))?;
+                self.pos += end + 4;
+            } else {
+                return Ok(!trimmed.is_empty());
+            }
+        }
+    }
+
+    fn consume(&mut self, c: char) -> Result<bool, String> {
+        self.skip_comments()?;
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// A quoted string, or an unquoted word like in the old-style property lists.
+    fn string(&mut self) -> Result<String, String> {
+        self.skip_comments()?;
+        let rest = self.rest();
+        let Some(quoted) = rest.strip_prefix('"') else {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || "_.-$:/".contains(c)))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                return Err(self.error("expected a string"));
+            }
+            self.pos += len;
+            return Ok(rest[..len].to_string());
+        };
+
+        let mut result = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 2;
+                    return Ok(result);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => result.push('\n'),
+                    Some('t') => result.push('\t'),
+                    Some('r') => result.push('\r'),
+                    Some('0') => result.push('\0'),
+                    Some('U' | 'u') => {
+                        let hex = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+                        let unit = u16::from_str_radix(&hex, 16)
+                            .map_err(|_| self.error("invalid unicode escape"))?;
+                        let mut units = vec![unit];
+                        // A surrogate pair is written as two escapes
+                        if (0xd800..0xdc00).contains(&unit) {
+                            let low = chars
+                                .as_str()
+                                .strip_prefix("\\U")
+                                .or_else(|| chars.as_str().strip_prefix("\\u"));
+                            if let Some(low) = low.and_then(|low| low.get(..4)) {
+                                if let Ok(low) = u16::from_str_radix(low, 16) {
+                                    units.push(low);
+                                    chars.by_ref().take(6).for_each(drop);
+                                }
+                            }
+                        }
+                        result.extend(char::decode_utf16(units).map(|c| c.unwrap_or('\u{fffd}')));
+                    }
+                    Some(c) => result.push(c),
+                    None => break,
+                },
+                c => result.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
+
+fn escape_strings(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
+
+/// A value of a property list, only the dictionaries and the strings are kept.
+#[derive(Debug)]
+enum Plist {
+    Dict(Vec<(String, Plist)>),
+    String(String),
+    Other,
+}
+
+impl Plist {
+    fn get(&self, key: &str) -> Option<&Plist> {
+        match self {
+            Self::Dict(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the plural keys of a `.stringsdict` file, into the `key.{category}` subkeys,
+/// e.g. `apples.one` and `apples.other`.
+///
+/// The number of the plural rule, e.g. `%d`, is replaced with `%{count}`. The keys with several
+/// plural variables are split by the first one, the others take their `other` form with
+/// their number replaced by `%{name}`, e.g. `%{folders}`.
+///
+/// ```
+/// # use rust_i18n_support::parse_stringsdict;
+/// let content = r#"<plist version="1.0"><dict>
+///   <key>apples</key>
+///   <dict>
+///     <key>NSStringLocalizedFormatKey</key><string>%#@count@</string>
+///     <key>count</key>
+///     <dict>
+///       <key>NSStringFormatSpecTypeKey</key><string>NSStringPluralRuleType</string>
+///       <key>NSStringFormatValueTypeKey</key><string>d</string>
+///       <key>one</key><string>%d apple</string>
+///       <key>other</key><string>%d apples</string>
+///     </dict>
+///   </dict>
+/// </dict></plist>"#;
+/// let pairs = parse_stringsdict(content).unwrap();
+/// assert_eq!(pairs[0], ("apples.one".to_string(), "%{count} apple".to_string()));
+/// assert_eq!(pairs[1], ("apples.other".to_string(), "%{count} apples".to_string()));
+/// ```
+pub fn parse_stringsdict(content: &str) -> Result<Vec<(String, String)>, String> {
+    let Plist::Dict(entries) = parse_plist(content)? else {
+        return Err("Invalid stringsdict format, expected a dict".into());
+    };
+
+    let mut pairs = vec![];
+    for (key, entry) in entries {
+        let Some(format) = entry
+            .get("NSStringLocalizedFormatKey")
+            .and_then(Plist::as_str)
+        else {
+            continue;
+        };
+
+        // The `%#@name@` variables of the format, with their plural rule
+        let variables = format
+            .split("%#@")
+            .skip(1)
+            .filter_map(|s| s.split_once('@'))
+            .filter_map(|(name, _)| Some((name, entry.get(name)?)))
+            .filter(|(_, rule)| {
+                rule.get("NSStringFormatSpecTypeKey")
+                    .and_then(Plist::as_str)
+                    == Some("NSStringPluralRuleType")
+            })
+            .collect::<Vec<_>>();
+        let Some((first, first_rule)) = variables.first() else {
+            continue;
+        };
+
+        let expand = |name: &str, rule: &Plist, category: &str| {
+            let text = rule
+                .get(category)
+                .and_then(Plist::as_str)
+                .unwrap_or_default();
+            let value_type = rule
+                .get("NSStringFormatValueTypeKey")
+                .and_then(Plist::as_str)
+                .unwrap_or("d");
+            let placeholder = if name == *first {
+                "%{count}".to_string()
+            } else {
+                format!("%{{{}}}", name)
+            };
+            text.replace(&format!("%{}", value_type), &placeholder)
+        };
+
+        for category in PLURAL_CATEGORIES {
+            if first_rule.get(category).is_none() {
+                continue;
+            }
+            let mut text = format.to_string();
+            for (name, rule) in &variables {
+                let category = if name == first { category } else { "other" };
+                text = text.replace(&format!("%#@{}@", name), &expand(name, rule, category));
+            }
+            pairs.push((format!("{}.{}", key, category), text));
+        }
+    }
+    Ok(pairs)
+}
+
+/// Write the plural keys as a `.stringsdict` file, the texts by category by key, e.g. `one`
+/// and `other` of `apples`. `%{count}` is written as `%d`.
+pub fn write_stringsdict(plurals: &BTreeMap<String, BTreeMap<String, String>>) -> String {
+    let mut output = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n<dict>\n",
+    );
+    for (key, texts) in plurals {
+        output.push_str(&format!(
+            "  <key>{}</key>\n  <dict>\n    <key>NSStringLocalizedFormatKey</key>\n    <string>%#@count@</string>\n    <key>count</key>\n    <dict>\n      <key>NSStringFormatSpecTypeKey</key>\n      <string>NSStringPluralRuleType</string>\n      <key>NSStringFormatValueTypeKey</key>\n      <string>d</string>\n",
+            escape_xml(key)
+        ));
+        for category in PLURAL_CATEGORIES {
+            if let Some(text) = texts.get(category) {
+                output.push_str(&format!(
+                    "      <key>{}</key>\n      <string>{}</string>\n",
+                    category,
+                    escape_xml(&text.replace("%{count}", "%d"))
+                ));
+            }
+        }
+        output.push_str("    </dict>\n  </dict>\n");
+    }
+    output.push_str("</dict>\n</plist>\n");
+    output
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn unescape_xml(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match c {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The tags and the texts of an XML document, without the declarations and the comments.
+fn xml_tokens(content: &str) -> Result<Vec<XmlToken<'_>>, String> {
+    let mut tokens = vec![];
+    let mut rest = content;
+    while let Some(start) = rest.find('<') {
+        if !rest[..start].trim().is_empty() {
+            tokens.push(XmlToken::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else {
+            rest.find('>').map(|end| end + 1)
+        }
+        .ok_or_else(|| "Invalid stringsdict format, unterminated tag".to_string())?;
+
+        let tag = &rest[1..end - 1];
+        if !tag.starts_with(['?', '!']) {
+            if let Some(tag) = tag.strip_prefix('/') {
+                tokens.push(XmlToken::Close(tag.trim()));
+            } else if let Some(tag) = tag.strip_suffix('/') {
+                tokens.push(XmlToken::Empty(tag_name(tag)));
+            } else {
+                tokens.push(XmlToken::Open(tag_name(tag)));
+            }
+        }
+        rest = &rest[end..];
+    }
+    Ok(tokens)
+}
+
+fn tag_name(tag: &str) -> &str {
+    tag.split_whitespace().next().unwrap_or_default()
+}
+
+enum XmlToken<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Empty(&'a str),
+    Text(&'a str),
+}
+
+fn parse_plist(content: &str) -> Result<Plist, String> {
+    let tokens = xml_tokens(content)?;
+    let mut tokens = tokens
+        .iter()
+        .skip_while(|token| !matches!(token, XmlToken::Open("plist")))
+        .skip(1)
+        .peekable();
+    parse_plist_value(&mut tokens)
+}
+
+fn parse_plist_value<'a>(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = &'a XmlToken<'a>>>,
+) -> Result<Plist, String> {
+    let invalid = |message: &str| format!("Invalid stringsdict format, {}", message);
+    match tokens.next() {
+        Some(XmlToken::Empty("dict")) => Ok(Plist::Dict(vec![])),
+        Some(XmlToken::Empty("string")) => Ok(Plist::String(String::new())),
+        Some(XmlToken::Empty(_)) => Ok(Plist::Other),
+        Some(XmlToken::Open("dict")) => {
+            let mut entries = vec![];
+            loop {
+                match tokens.next() {
+                    Some(XmlToken::Close("dict")) => return Ok(Plist::Dict(entries)),
+                    Some(XmlToken::Open("key")) => {
+                        let key = match tokens.next() {
+                            Some(XmlToken::Text(text)) => {
+                                tokens.next();
+                                unescape_xml(text)
+                            }
+                            _ => String::new(),
+                        };
+                        entries.push((key, parse_plist_value(tokens)?));
+                    }
+                    Some(XmlToken::Empty("key")) => {
+                        entries.push((String::new(), parse_plist_value(tokens)?));
+                    }
+                    _ => return Err(invalid("expected a key in dict")),
+                }
+            }
+        }
+        Some(XmlToken::Open(tag)) => {
+            let mut text = String::new();
+            let mut depth = 0;
+            loop {
+                match tokens.next() {
+                    Some(XmlToken::Text(t)) if depth == 0 => text.push_str(&unescape_xml(t)),
+                    Some(XmlToken::Open(_)) => depth += 1,
+                    Some(XmlToken::Close(_)) if depth > 0 => depth -= 1,
+                    Some(XmlToken::Close(_)) => break,
+                    Some(_) => {}
+                    None => return Err(invalid("unterminated tag")),
+                }
+            }
+            Ok(if *tag == "string" {
+                Plist::String(text)
+            } else {
+                Plist::Other
+            })
+        }
+        _ => Err(invalid("expected a value")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_strings() {
+        let content = r#"
+/* A comment */
+"hello" = "Hello, %{name}!";
+// Another comment
+"escaped \"key\"" = "Line 1\nLine 2\t\\ \U4F60\U597D \UD83D\UDE00";
+"same";
+unquoted = "Unquoted";
+"unicode" /* inline */ = "你好";
+"#;
+        assert_eq!(
+            parse_strings(content).unwrap(),
+            vec![
+                ("hello".into(), "Hello, %{name}!".into()),
+                (
+                    "escaped \"key\"".into(),
+                    "Line 1\nLine 2\t\\ 你好 😀".into()
+                ),
+                ("same".into(), "same".into()),
+                ("unquoted".into(), "Unquoted".into()),
+                ("unicode".into(), "你好".into()),
+            ]
+        );
+
+        assert!(parse_strings("\"hello\" = \"Hello\"").is_err());
+        assert!(parse_strings("\"hello\" = \"Hello;").is_err());
+        assert!(parse_strings("/* hello").is_err());
+
+        let pairs = vec![("escaped \"key\"", "Line 1\nLine 2\t\\ 你好")];
+        let parsed = parse_strings(&write_strings(pairs.clone())).unwrap();
+        assert_eq!(parsed, vec![(pairs[0].0.into(), pairs[0].1.into())]);
+    }
+
+    #[test]
+    fn test_parse_stringsdict() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <!-- A comment -->
+    <key>inbox</key>
+    <dict>
+        <key>NSStringLocalizedFormatKey</key>
+        <string>%#@messages@ in %#@folders@</string>
+        <key>messages</key>
+        <dict>
+            <key>NSStringFormatSpecTypeKey</key>
+            <string>NSStringPluralRuleType</string>
+            <key>NSStringFormatValueTypeKey</key>
+            <string>ld</string>
+            <key>zero</key>
+            <string>No messages</string>
+            <key>one</key>
+            <string>%ld message &amp; more</string>
+            <key>other</key>
+            <string>%ld messages</string>
+        </dict>
+        <key>folders</key>
+        <dict>
+            <key>NSStringFormatSpecTypeKey</key>
+            <string>NSStringPluralRuleType</string>
+            <key>NSStringFormatValueTypeKey</key>
+            <string>d</string>
+            <key>one</key>
+            <string>%d folder</string>
+            <key>other</key>
+            <string>%d folders</string>
+        </dict>
+    </dict>
+    <key>version</key>
+    <integer>1</integer>
+</dict>
+</plist>"#;
+        assert_eq!(
+            parse_stringsdict(content).unwrap(),
+            vec![
+                (
+                    "inbox.zero".into(),
+                    "No messages in %{folders} folders".into()
+                ),
+                (
+                    "inbox.one".into(),
+                    "%{count} message & more in %{folders} folders".into()
+                ),
+                (
+                    "inbox.other".into(),
+                    "%{count} messages in %{folders} folders".into()
+                ),
+            ]
+        );
+
+        let plurals = BTreeMap::from([(
+            "apples".to_string(),
+            BTreeMap::from([
+                ("one".to_string(), "%{count} apple".to_string()),
+                ("other".to_string(), "%{count} apples & <pears>".to_string()),
+            ]),
+        )]);
+        assert_eq!(
+            parse_stringsdict(&write_stringsdict(&plurals)).unwrap(),
+            vec![
+                ("apples.one".into(), "%{count} apple".into()),
+                ("apples.other".into(), "%{count} apples & <pears>".into()),
+            ]
+        );
+
+        assert!(parse_stringsdict("<plist><dict><key>a</key>").is_err());
+    }
+
+    #[test]
+    fn test_lproj_locale() {
+        assert_eq!(
+            lproj_locale(Path::new("locales/zh-Hans.lproj/Localizable.strings")),
+            Some("zh-Hans")
+        );
+        assert_eq!(lproj_locale(Path::new("locales/en.strings")), None);
+    }
+}
@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use crate::{try_load_locales_from_files, Backend, BackendExt, SimpleBackend};
+
+/// A backend loading the locale files packed in an archive, a `.tar`, `.tar.gz`, `.tar.zst`
+/// or `.zip` of the `locales` directory, so the translations ship as a single asset.
+///
+/// The format is detected from the content, the files are loaded like in the `locales`
+/// directory.
+///
+/// ```ignore
+/// rust_i18n::i18n!(
+///     backend = rust_i18n::ArchiveBackend::from_bytes(include_bytes!("../locales.tar.zst")).unwrap()
+/// );
+/// ```
+pub struct ArchiveBackend(SimpleBackend);
+
+impl ArchiveBackend {
+    /// Load the translations from the bytes of an archive.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut backend = SimpleBackend::new();
+        for (locale, trs) in try_load_locales_from_files(read_archive(bytes)?)? {
+            backend.add_translations(
+                locale.into(),
+                trs.into_iter().map(|(k, v)| (k.into(), v.into())).collect(),
+            );
+        }
+        Ok(Self(backend))
+    }
+
+    /// Load the translations from an archive file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|err| format!("Failed to open file '{}': {}", path.display(), err))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl Backend for ArchiveBackend {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        self.0.available_locales()
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        self.0.translate(locale, key)
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        self.0.messages_for_locale(locale)
+    }
+}
+
+impl BackendExt for ArchiveBackend {}
+
+/// Read the files of a `.tar`, `.tar.gz`, `.tar.zst` or `.zip` archive.
+pub(crate) fn read_archive(bytes: &[u8]) -> Result<Vec<(PathBuf, String)>, String> {
+    let invalid = |err: &dyn std::fmt::Display| format!("Invalid archive, {}", err);
+    match bytes {
+        [0x1f, 0x8b, ..] => read_tar(flate2::read::GzDecoder::new(bytes)),
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => {
+            read_tar(ruzstd::decoding::StreamingDecoder::new(bytes).map_err(|err| invalid(&err))?)
+        }
+        [b'P', b'K', 0x03, 0x04, ..] => {
+            let mut archive =
+                zip::ZipArchive::new(Cursor::new(bytes)).map_err(|err| invalid(&err))?;
+            let mut files = vec![];
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i).map_err(|err| invalid(&err))?;
+                let Some(path) = file.enclosed_name().filter(|_| file.is_file()) else {
+                    continue;
+                };
+                let mut content = String::new();
+                file.read_to_string(&mut content)
+                    .map_err(|err| format!("Read file `{}` failed, {}", path.display(), err))?;
+                files.push((path, content));
+            }
+            Ok(files)
+        }
+        _ => read_tar(bytes),
+    }
+}
+
+fn read_tar(reader: impl Read) -> Result<Vec<(PathBuf, String)>, String> {
+    let invalid = |err: std::io::Error| format!("Invalid archive, {}", err);
+    let mut archive = tar::Archive::new(reader);
+    let mut files = vec![];
+    for entry in archive.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(invalid)?.into_owned();
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|err| format!("Read file `{}` failed, {}", path.display(), err))?;
+        files.push((path, content));
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    pub(crate) fn tar(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(vec![]);
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    pub(crate) fn tar_gz(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(&tar(files)).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(vec![]));
+        writer
+            .add_directory("locales/", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        for (path, content) in files {
+            writer
+                .start_file(*path, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// A zstd frame of raw blocks, without compression.
+    fn zstd(data: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x28, 0xb5, 0x2f, 0xfd];
+        // Single segment, 4 bytes content size
+        frame.push(0b1010_0000);
+        frame.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        let mut chunks = data.chunks(128 * 1024).peekable();
+        while let Some(chunk) = chunks.next() {
+            let last = chunks.peek().is_none() as u32;
+            let header = last | ((chunk.len() as u32) << 3);
+            frame.extend_from_slice(&header.to_le_bytes()[..3]);
+            frame.extend_from_slice(chunk);
+        }
+        frame
+    }
+
+    #[test]
+    fn test_archive_backend() {
+        let files = [
+            ("locales/en.yml", "hello: Hello"),
+            (
+                "locales/app.yml",
+                "_version: 2\nbye:\n  en: Bye\n  fr: Au revoir",
+            ),
+        ];
+        for archive in [tar(&files), tar_gz(&files), zstd(&tar(&files)), zip(&files)] {
+            let backend = ArchiveBackend::from_bytes(&archive).unwrap();
+            assert_eq!(backend.available_locales(), vec!["en", "fr"]);
+            assert_eq!(backend.translate("en", "hello").as_deref(), Some("Hello"));
+            assert_eq!(backend.translate("fr", "bye").as_deref(), Some("Au revoir"));
+        }
+
+        assert!(ArchiveBackend::from_bytes(b"not an archive").is_err());
+        assert!(ArchiveBackend::open("not-found.tar").is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+
+use arc_swap::ArcSwap;
+
+use crate::{Backend, BackendExt};
+
+/// I18n backend trait for the translation sources which are looked up asynchronously,
+/// e.g. over the network or from a database.
+///
+/// `t!` is synchronous, use it through a [`SnapshotBackend`].
+pub trait AsyncBackend: Send + Sync + 'static {
+    /// Return the available locales
+    fn available_locales(&self) -> impl Future<Output = Vec<String>> + Send;
+    /// Get the translation for the given locale and key
+    fn translate(&self, locale: &str, key: &str) -> impl Future<Output = Option<String>> + Send;
+    /// Get all translations for the given locale, `None` if they can't be listed
+    fn messages_for_locale(
+        &self,
+        locale: &str,
+    ) -> impl Future<Output = Option<Vec<(String, String)>>> + Send;
+}
+
+type Translations = HashMap<String, HashMap<String, String>>;
+
+/// A synchronous [`Backend`] serving a snapshot of the translations of an [`AsyncBackend`].
+///
+/// The snapshot is loaded with [`SnapshotBackend::load`] and updated with
+/// [`SnapshotBackend::refresh`], e.g. periodically from a background task. The keys missing
+/// from the snapshot are remembered and looked up one by one on the next refresh, for the
+/// backends which can't list their messages.
+///
+/// Cloning is cheap and clones share the snapshot.
+///
+/// ```ignore
+/// let backend = SnapshotBackend::load(DatabaseBackend::new(pool)).await;
+/// tokio::spawn({
+///     let backend = backend.clone();
+///     async move {
+///         loop {
+///             tokio::time::sleep(Duration::from_secs(60)).await;
+///             backend.refresh().await;
+///         }
+///     }
+/// });
+/// ```
+pub struct SnapshotBackend<B> {
+    backend: Arc<B>,
+    translations: Arc<ArcSwap<Translations>>,
+    missing: Arc<Mutex<HashSet<(String, String)>>>,
+}
+
+impl<B> Clone for SnapshotBackend<B> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            translations: self.translations.clone(),
+            missing: self.missing.clone(),
+        }
+    }
+}
+
+impl<B: AsyncBackend> SnapshotBackend<B> {
+    /// Load the snapshot of the backend.
+    pub async fn load(backend: B) -> Self {
+        let snapshot = Self {
+            backend: Arc::new(backend),
+            translations: Default::default(),
+            missing: Default::default(),
+        };
+        snapshot.refresh().await;
+        snapshot
+    }
+
+    /// Load the snapshot of the backend, blocking the current thread.
+    ///
+    /// The futures relying on an async runtime, e.g. the clients built on tokio, must be
+    /// loaded with [`SnapshotBackend::load`] inside the runtime instead.
+    pub fn load_blocking(backend: B) -> Self {
+        block_on(Self::load(backend))
+    }
+
+    /// The wrapped backend.
+    pub fn inner(&self) -> &B {
+        &self.backend
+    }
+
+    /// Load the translations again, along with the keys missed since the last refresh.
+    pub async fn refresh(&self) {
+        let mut translations = Translations::new();
+        for locale in self.backend.available_locales().await {
+            if let Some(messages) = self.backend.messages_for_locale(&locale).await {
+                translations.insert(locale, messages.into_iter().collect());
+            }
+        }
+
+        // The keys of the backends which can't list their messages are kept from the
+        // previous snapshot, and refreshed when they are missed
+        for (locale, messages) in self.translations.load().iter() {
+            if !translations.contains_key(locale) {
+                translations.insert(locale.clone(), messages.clone());
+            }
+        }
+
+        let missing = std::mem::take(&mut *self.lock_missing());
+        for (locale, key) in missing {
+            if let Some(value) = self.backend.translate(&locale, &key).await {
+                translations.entry(locale).or_default().insert(key, value);
+            }
+        }
+
+        self.translations.store(Arc::new(translations));
+    }
+
+    fn lock_missing(&self) -> std::sync::MutexGuard<'_, HashSet<(String, String)>> {
+        self.missing
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<B: AsyncBackend> Backend for SnapshotBackend<B> {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let mut locales = self
+            .translations
+            .load()
+            .keys()
+            .map(|locale| Cow::Owned(locale.clone()))
+            .collect::<Vec<_>>();
+        locales.sort();
+        locales
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        let value = self
+            .translations
+            .load()
+            .get(locale)
+            .and_then(|messages| messages.get(key).cloned());
+        if value.is_none() {
+            self.lock_missing()
+                .insert((locale.to_string(), key.to_string()));
+        }
+        value.map(Cow::Owned)
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        self.translations.load().get(locale).map(|messages| {
+            messages
+                .iter()
+                .map(|(k, v)| (Cow::Owned(k.clone()), Cow::Owned(v.clone())))
+                .collect()
+        })
+    }
+}
+
+impl<B: AsyncBackend> BackendExt for SnapshotBackend<B> {}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run the future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RemoteBackend {
+        list: bool,
+    }
+
+    impl AsyncBackend for RemoteBackend {
+        async fn available_locales(&self) -> Vec<String> {
+            vec!["en".into()]
+        }
+
+        async fn translate(&self, locale: &str, key: &str) -> Option<String> {
+            (key != "missing").then(|| format!("{locale}.{key} by key"))
+        }
+
+        async fn messages_for_locale(&self, _: &str) -> Option<Vec<(String, String)>> {
+            self.list.then(|| vec![("hello".into(), "Hello".into())])
+        }
+    }
+
+    #[test]
+    fn test_snapshot_backend() {
+        let backend = SnapshotBackend::load_blocking(RemoteBackend { list: true });
+        assert_eq!(backend.available_locales(), vec!["en"]);
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("Hello"));
+        assert_eq!(backend.translate("en", "other"), None);
+
+        block_on(backend.refresh());
+        assert_eq!(
+            backend.translate("en", "other").as_deref(),
+            Some("en.other by key")
+        );
+    }
+
+    #[test]
+    fn test_snapshot_backend_by_key() {
+        let backend = SnapshotBackend::load_blocking(RemoteBackend { list: false });
+        assert!(backend.available_locales().is_empty());
+        assert_eq!(backend.translate("fr", "hello"), None);
+        assert_eq!(backend.translate("fr", "missing"), None);
+
+        let handle = backend.clone();
+        block_on(handle.refresh());
+        assert_eq!(
+            backend.translate("fr", "hello").as_deref(),
+            Some("fr.hello by key")
+        );
+        assert_eq!(backend.translate("fr", "missing"), None);
+
+        // The keys looked up one by one are kept
+        block_on(handle.refresh());
+        assert_eq!(
+            backend.translate("fr", "hello").as_deref(),
+            Some("fr.hello by key")
+        );
+    }
+}
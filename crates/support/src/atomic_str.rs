@@ -1,15 +1,32 @@
-use std::fmt;
-use std::ops::Deref;
+use alloc::string::String;
+use core::fmt;
+use core::ops::Deref;
 
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
 use arc_swap::{ArcSwapAny, Guard};
+#[cfg(feature = "std")]
 use triomphe::Arc;
 
 /// A thread-safe atomically reference-counting string.
+#[cfg(feature = "std")]
 pub struct AtomicStr(ArcSwapAny<Arc<String>>);
 
+/// A thread-safe atomically reference-counting string.
+///
+/// Without `std`, the string is guarded by a `spin::RwLock`.
+#[cfg(not(feature = "std"))]
+pub struct AtomicStr(spin::RwLock<Arc<String>>);
+
 /// A thread-safe view the string that was stored when `AtomicStr::as_str()` was called.
+#[cfg(feature = "std")]
 struct GuardedStr(Guard<Arc<String>>);
 
+/// A thread-safe view the string that was stored when `AtomicStr::as_str()` was called.
+#[cfg(not(feature = "std"))]
+struct GuardedStr(Arc<String>);
+
 impl Deref for GuardedStr {
     type Target = str;
 
@@ -22,18 +39,29 @@ impl AtomicStr {
     /// Create a new `AtomicStr` with the given value.
     pub fn new(value: &str) -> Self {
         let arced = Arc::new(value.into());
-        Self(ArcSwapAny::new(arced))
+        #[cfg(feature = "std")]
+        return Self(ArcSwapAny::new(arced));
+        #[cfg(not(feature = "std"))]
+        return Self(spin::RwLock::new(arced));
     }
 
     /// Get the string slice.
     pub fn as_str(&self) -> impl Deref<Target = str> {
-        GuardedStr(self.0.load())
+        #[cfg(feature = "std")]
+        return GuardedStr(self.0.load());
+        #[cfg(not(feature = "std"))]
+        return GuardedStr(self.0.read().clone());
     }
 
     /// Replaces the value at self with src.
     pub fn replace(&self, src: impl Into<String>) {
         let arced = Arc::new(src.into());
+        #[cfg(feature = "std")]
         self.0.store(arced);
+        #[cfg(not(feature = "std"))]
+        {
+            *self.0.write() = arced;
+        }
     }
 }
 
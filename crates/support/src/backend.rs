@@ -1,24 +1,44 @@
-use std::borrow::Cow;
-use std::collections::HashMap;
-
-/// A view of another backend restricted to a single namespace.
-pub struct NamespacedBackend {
-    backend: &'static dyn Backend,
-    namespace: &'static str,
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::{HashMap, MessageTemplate};
+
+/// A view of another backend restricted to the keys below a prefix, which is stripped from
+/// the keys, see [`BackendExt::scoped`].
+///
+/// Useful to hand a plugin or a library the translations meant for it only.
+pub struct ScopedBackend<B> {
+    backend: B,
+    /// The prefix with the trailing `.`
+    prefix: String,
 }
 
-impl NamespacedBackend {
-    /// Create a backend that exposes only keys below `namespace`.
-    pub fn new(backend: &'static dyn Backend, namespace: &'static str) -> Self {
-        Self { backend, namespace }
+/// A view of a `'static` backend restricted to a single namespace, used by `extend!`.
+pub type NamespacedBackend = ScopedBackend<&'static dyn Backend>;
+
+impl<B: Backend> ScopedBackend<B> {
+    /// Create a backend that exposes only keys below `prefix`.
+    pub fn new(backend: B, prefix: &str) -> Self {
+        Self {
+            backend,
+            prefix: format!("{prefix}."),
+        }
+    }
+
+    /// The prefix of the keys, e.g. `admin`.
+    pub fn prefix(&self) -> &str {
+        self.prefix.trim_end_matches('.')
     }
 
-    fn namespaced_key(&self, key: &str) -> String {
-        format!("{}.{key}", self.namespace)
+    fn scoped_key(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
     }
 }
 
-impl Backend for NamespacedBackend {
+impl<B: Backend> Backend for ScopedBackend<B> {
     fn available_locales(&self) -> Vec<Cow<'_, str>> {
         self.backend
             .available_locales()
@@ -28,17 +48,20 @@ impl Backend for NamespacedBackend {
     }
 
     fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
-        self.backend.translate(locale, &self.namespaced_key(key))
+        self.backend.translate(locale, &self.scoped_key(key))
+    }
+
+    fn template(&self, locale: &str, key: &str) -> Option<MessageTemplate<'_>> {
+        self.backend.template(locale, &self.scoped_key(key))
     }
 
     fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
-        let prefix = format!("{}.", self.namespace);
         let messages = self
             .backend
             .messages_for_locale(locale)?
             .into_iter()
             .filter_map(|(key, value)| {
-                key.strip_prefix(&prefix)
+                key.strip_prefix(&self.prefix)
                     .map(|key| (Cow::Owned(key.to_string()), value))
             })
             .collect::<Vec<_>>();
@@ -47,6 +70,48 @@ impl Backend for NamespacedBackend {
     }
 }
 
+impl<B: Backend> BackendExt for ScopedBackend<B> {}
+
+impl<B: Backend + ?Sized> Backend for &'static B {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        (**self).available_locales()
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        (**self).translate(locale, key)
+    }
+
+    fn template(&self, locale: &str, key: &str) -> Option<MessageTemplate<'_>> {
+        (**self).template(locale, key)
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        (**self).messages_for_locale(locale)
+    }
+}
+
+impl<B: Backend + ?Sized> BackendExt for &'static B {}
+
+impl<B: Backend + ?Sized> Backend for Arc<B> {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        (**self).available_locales()
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        (**self).translate(locale, key)
+    }
+
+    fn template(&self, locale: &str, key: &str) -> Option<MessageTemplate<'_>> {
+        (**self).template(locale, key)
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        (**self).messages_for_locale(locale)
+    }
+}
+
+impl<B: Backend + ?Sized> BackendExt for Arc<B> {}
+
 /// I18n backend trait
 pub trait Backend: Send + Sync + 'static {
     /// Return the available locales
@@ -55,6 +120,11 @@ pub trait Backend: Send + Sync + 'static {
     fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>>;
     /// Get all translations for the given locale
     fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>>;
+    /// Get the translation for the given locale and key with its placeholders parsed, the
+    /// backends storing the parsed templates return them without parsing the text again
+    fn template(&self, locale: &str, key: &str) -> Option<MessageTemplate<'_>> {
+        self.translate(locale, key).map(MessageTemplate::parse)
+    }
 }
 
 pub trait BackendExt: Backend {
@@ -65,6 +135,37 @@ pub trait BackendExt: Backend {
     {
         CombinedBackend(self, other)
     }
+
+    /// Restrict the backend to the keys below `prefix`, which is stripped from the keys.
+    ///
+    /// Wrap the backend in an `Arc` or take a `&'static` reference to keep using it.
+    ///
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use std::sync::Arc;
+    /// # use rust_i18n_support::{Backend, BackendExt, SimpleBackend};
+    /// let mut backend = SimpleBackend::new();
+    /// backend.add_translations("en".into(), HashMap::from([("admin.title".into(), "Admin".into())]));
+    /// let backend = Arc::new(backend);
+    ///
+    /// let admin = backend.clone().scoped("admin");
+    /// assert_eq!(admin.translate("en", "title").as_deref(), Some("Admin"));
+    /// assert_eq!(admin.translate("en", "admin.title"), None);
+    /// ```
+    fn scoped(self, prefix: &str) -> ScopedBackend<Self>
+    where
+        Self: Sized,
+    {
+        ScopedBackend::new(self, prefix)
+    }
+}
+
+/// A backend whose translations can be changed at runtime, see [`OverrideBackend`](crate::OverrideBackend).
+pub trait BackendMut: Backend {
+    /// Set the translation for the given locale and key, replacing the existing one
+    fn set_translation(&self, locale: &str, key: &str, value: &str);
+    /// Remove the translation for the given locale and key, returns whether it was present
+    fn remove_translation(&self, locale: &str, key: &str) -> bool;
 }
 
 pub struct CombinedBackend<A, B>(A, B);
@@ -91,6 +192,13 @@ where
             .or_else(|| self.0.translate(locale, key))
     }
 
+    #[inline]
+    fn template(&self, locale: &str, key: &str) -> Option<MessageTemplate<'_>> {
+        self.1
+            .template(locale, key)
+            .or_else(|| self.0.template(locale, key))
+    }
+
     fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
         match (
             self.1.messages_for_locale(locale),
@@ -113,8 +221,8 @@ where
 
 /// Simple KeyValue storage backend
 pub struct SimpleBackend {
-    /// All translations key is flatten key, like `en.hello.world`
-    translations: HashMap<Cow<'static, str>, HashMap<Cow<'static, str>, Cow<'static, str>>>,
+    /// All translations key is flatten key, like `en.hello.world`, parsed when they're added
+    translations: HashMap<Cow<'static, str>, HashMap<Cow<'static, str>, MessageTemplate<'static>>>,
 }
 
 impl
@@ -133,9 +241,11 @@ impl
     >(
         iter: I,
     ) -> Self {
-        Self {
-            translations: iter.into_iter().collect(),
+        let mut backend = Self::new();
+        for (locale, data) in iter {
+            backend.add_translations(locale, data);
         }
+        backend
     }
 }
 
@@ -163,30 +273,47 @@ impl SimpleBackend {
         locale: Cow<'static, str>,
         data: HashMap<Cow<'static, str>, Cow<'static, str>>,
     ) {
-        let trs = self.translations.entry(locale.into()).or_default();
-        trs.extend(data);
+        let trs = self.translations.entry(locale).or_default();
+        trs.extend(
+            data.into_iter()
+                .map(|(key, value)| (key, MessageTemplate::parse(value))),
+        );
     }
 }
 
 impl Backend for SimpleBackend {
     fn available_locales(&self) -> Vec<Cow<'_, str>> {
-        let mut locales = self.translations.keys().cloned().collect::<Vec<_>>();
+        let mut locales = self
+            .translations
+            .keys()
+            .map(|locale| Cow::Borrowed(locale.as_ref()))
+            .collect::<Vec<_>>();
         locales.sort();
         locales
     }
 
     fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
         if let Some(trs) = self.translations.get(locale) {
-            return trs.get(key).cloned();
+            // Borrowed even when added as `Cow::Owned`, so the lookups don't allocate
+            return trs.get(key).map(|value| Cow::Borrowed(value.text()));
         }
 
         None
     }
 
     fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        self.translations.get(locale).map(|trs| {
+            trs.iter()
+                .map(|(k, v)| (Cow::Borrowed(k.as_ref()), Cow::Borrowed(v.text())))
+                .collect()
+        })
+    }
+
+    fn template(&self, locale: &str, key: &str) -> Option<MessageTemplate<'_>> {
         self.translations
-            .get(locale)
-            .map(|trs| trs.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .get(locale)?
+            .get(key)
+            .map(MessageTemplate::borrowed)
     }
 }
 
@@ -202,6 +329,7 @@ impl Default for SimpleBackend {
 mod tests {
     use std::borrow::Cow;
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     use super::SimpleBackend;
     use super::{Backend, BackendExt, NamespacedBackend};
@@ -230,6 +358,27 @@ mod tests {
         assert_eq!(backend.available_locales(), vec!["en", "zh-CN"]);
     }
 
+    #[test]
+    fn test_simple_backend_borrowed() {
+        let mut backend = SimpleBackend::new();
+        let mut data = HashMap::new();
+        data.insert(Cow::Owned("hello".into()), Cow::Owned("Hello".into()));
+        backend.add_translations(Cow::Owned("en".into()), data);
+
+        assert!(matches!(
+            backend.translate("en", "hello"),
+            Some(Cow::Borrowed("Hello"))
+        ));
+        assert!(matches!(
+            backend.available_locales()[..],
+            [Cow::Borrowed("en")]
+        ));
+        assert!(matches!(
+            backend.messages_for_locale("en").unwrap()[..],
+            [(Cow::Borrowed("hello"), Cow::Borrowed("Hello"))]
+        ));
+    }
+
     #[test]
     fn test_combined_backend() {
         let mut backend = SimpleBackend::new();
@@ -285,4 +434,35 @@ mod tests {
             )])
         );
     }
+
+    #[test]
+    fn test_scoped_backend() {
+        let mut backend = SimpleBackend::new();
+        backend.add_translations(
+            "en".into(),
+            HashMap::from([
+                ("admin.title".into(), "Admin".into()),
+                ("admin.users.title".into(), "Users".into()),
+                ("administrator".into(), "Administrator".into()),
+            ]),
+        );
+        backend.add_translations(
+            "fr".into(),
+            HashMap::from([("title".into(), "Titre".into())]),
+        );
+
+        let admin = Arc::new(backend).scoped("admin");
+        assert_eq!(admin.prefix(), "admin");
+        assert_eq!(admin.translate("en", "title").as_deref(), Some("Admin"));
+        assert_eq!(admin.translate("en", "trator"), None);
+        assert_eq!(admin.translate("fr", "title"), None);
+        assert_eq!(admin.available_locales(), vec!["en"]);
+
+        let users = admin.scoped("users");
+        assert_eq!(users.translate("en", "title").as_deref(), Some("Users"));
+        assert_eq!(
+            users.messages_for_locale("en"),
+            Some(vec![(Cow::from("title"), Cow::from("Users"))])
+        );
+    }
 }
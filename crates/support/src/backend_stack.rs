@@ -0,0 +1,249 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use arc_swap::ArcSwap;
+
+use crate::{Backend, BackendExt, HashMap};
+
+#[derive(Clone)]
+struct Layer {
+    name: String,
+    backend: Arc<dyn Backend>,
+}
+
+type Layers = Arc<Vec<Layer>>;
+
+/// A stack of named backends, the translations of an upper layer override the lower layers,
+/// like [`BackendExt::extend`] without nesting `CombinedBackend` types.
+///
+/// The layers can be added, replaced and removed at runtime, e.g. to load the overrides of
+/// a tenant. Cloning is cheap and clones share the same layers, so keep a clone around to
+/// change the layers after passing it to `i18n!(backend = ...)`.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use rust_i18n_support::{Backend, BackendStack, SimpleBackend};
+/// # fn backend(text: &'static str) -> SimpleBackend {
+/// #     let mut backend = SimpleBackend::new();
+/// #     backend.add_translations("en".into(), HashMap::from([("hello".into(), text.into())]));
+/// #     backend
+/// # }
+/// let stack = BackendStack::new()
+///     .with("defaults", backend("Hello"))
+///     .with("database", backend("Hi"));
+/// assert_eq!(stack.translate("en", "hello").as_deref(), Some("Hi"));
+///
+/// stack.push("tenant", backend("Howdy"));
+/// assert_eq!(stack.translate("en", "hello").as_deref(), Some("Howdy"));
+/// assert_eq!(stack.layers(), vec!["defaults", "database", "tenant"]);
+/// ```
+#[derive(Clone, Default)]
+pub struct BackendStack {
+    #[cfg(feature = "std")]
+    layers: Arc<ArcSwap<Vec<Layer>>>,
+    #[cfg(not(feature = "std"))]
+    layers: Arc<spin::RwLock<Layers>>,
+}
+
+impl BackendStack {
+    /// Create an empty stack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a layer on top of the stack, see [`BackendStack::push`].
+    pub fn with(self, name: impl Into<String>, backend: impl Backend) -> Self {
+        self.push(name, backend);
+        self
+    }
+
+    /// Add a layer on top of the stack, overriding all the other layers.
+    ///
+    /// A layer with the same name is replaced in place, keeping its precedence.
+    pub fn push(&self, name: impl Into<String>, backend: impl Backend) {
+        let layer = Layer {
+            name: name.into(),
+            backend: Arc::new(backend),
+        };
+        self.update(
+            |layers| match layers.iter().position(|l| l.name == layer.name) {
+                Some(index) => layers[index] = layer.clone(),
+                None => layers.push(layer.clone()),
+            },
+        );
+    }
+
+    /// Add a layer right below the layer named `above`, or at the bottom of the stack if
+    /// there is no such layer. A layer with the same name is removed first.
+    pub fn insert_below(&self, above: &str, name: impl Into<String>, backend: impl Backend) {
+        let layer = Layer {
+            name: name.into(),
+            backend: Arc::new(backend),
+        };
+        self.update(|layers| {
+            layers.retain(|l| l.name != layer.name);
+            let index = layers.iter().position(|l| l.name == above).unwrap_or(0);
+            layers.insert(index, layer.clone());
+        });
+    }
+
+    /// Remove the layer with the name, returns whether it was found.
+    pub fn remove(&self, name: &str) -> bool {
+        let mut found = false;
+        self.update(|layers| {
+            let len = layers.len();
+            layers.retain(|l| l.name != name);
+            found = layers.len() != len;
+        });
+        found
+    }
+
+    /// The names of the layers, from the bottom to the top.
+    pub fn layers(&self) -> Vec<String> {
+        self.load().iter().map(|l| l.name.clone()).collect()
+    }
+
+    fn load(&self) -> Layers {
+        #[cfg(feature = "std")]
+        return self.layers.load_full();
+        #[cfg(not(feature = "std"))]
+        return self.layers.read().clone();
+    }
+
+    fn update(&self, mut f: impl FnMut(&mut Vec<Layer>)) {
+        #[cfg(feature = "std")]
+        self.layers.rcu(|layers| {
+            let mut layers = Vec::clone(layers);
+            f(&mut layers);
+            layers
+        });
+        #[cfg(not(feature = "std"))]
+        {
+            let mut guard = self.layers.write();
+            let mut layers = Vec::clone(&guard);
+            f(&mut layers);
+            *guard = Arc::new(layers);
+        }
+    }
+}
+
+impl Backend for BackendStack {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let mut locales = Vec::<Cow<'_, str>>::new();
+        for layer in self.load().iter() {
+            for locale in layer.backend.available_locales() {
+                if !locales.contains(&locale) {
+                    locales.push(Cow::Owned(locale.into_owned()));
+                }
+            }
+        }
+        locales
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        self.load().iter().rev().find_map(|layer| {
+            layer
+                .backend
+                .translate(locale, key)
+                .map(|text| Cow::Owned(text.into_owned()))
+        })
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        let mut messages = HashMap::<String, String>::new();
+        let mut found = false;
+        for layer in self.load().iter().rev() {
+            let Some(layer_messages) = layer.backend.messages_for_locale(locale) else {
+                continue;
+            };
+            found = true;
+            for (key, text) in layer_messages {
+                messages
+                    .entry(key.to_string())
+                    .or_insert_with(|| text.into_owned());
+            }
+        }
+
+        found.then(|| {
+            messages
+                .into_iter()
+                .map(|(key, text)| (Cow::Owned(key), Cow::Owned(text)))
+                .collect()
+        })
+    }
+}
+
+impl BackendExt for BackendStack {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleBackend;
+
+    fn backend(locale: &'static str, texts: &[(&'static str, &'static str)]) -> SimpleBackend {
+        let mut backend = SimpleBackend::new();
+        backend.add_translations(
+            locale.into(),
+            texts
+                .iter()
+                .map(|(k, v)| (Cow::from(*k), Cow::from(*v)))
+                .collect(),
+        );
+        backend
+    }
+
+    #[test]
+    fn test_backend_stack() {
+        let stack = BackendStack::new()
+            .with(
+                "embedded",
+                backend("en", &[("hello", "Hello"), ("bye", "Bye")]),
+            )
+            .with("file", backend("fr", &[("hello", "Bonjour")]));
+        let handle = stack.clone();
+
+        handle.push("tenant", backend("en", &[("hello", "Howdy")]));
+        handle.insert_below(
+            "tenant",
+            "database",
+            backend("en", &[("hello", "Hi"), ("bye", "Ciao")]),
+        );
+        assert_eq!(
+            stack.layers(),
+            vec!["embedded", "file", "database", "tenant"]
+        );
+        assert_eq!(stack.translate("en", "hello").as_deref(), Some("Howdy"));
+        assert_eq!(stack.translate("en", "bye").as_deref(), Some("Ciao"));
+        assert_eq!(stack.translate("fr", "hello").as_deref(), Some("Bonjour"));
+        assert_eq!(stack.available_locales(), vec!["en", "fr"]);
+
+        let mut messages = stack.messages_for_locale("en").unwrap();
+        messages.sort();
+        assert_eq!(
+            messages,
+            vec![
+                (Cow::from("bye"), Cow::from("Ciao")),
+                (Cow::from("hello"), Cow::from("Howdy")),
+            ]
+        );
+        assert_eq!(stack.messages_for_locale("de"), None);
+
+        // Replacing a layer keeps its place
+        handle.push("database", backend("en", &[("bye", "See you")]));
+        assert_eq!(
+            stack.layers(),
+            vec!["embedded", "file", "database", "tenant"]
+        );
+        assert_eq!(stack.translate("en", "bye").as_deref(), Some("See you"));
+
+        assert!(handle.remove("tenant"));
+        assert!(!handle.remove("tenant"));
+        assert_eq!(stack.translate("en", "hello").as_deref(), Some("Hello"));
+
+        handle.insert_below("missing", "bottom", backend("en", &[("new", "New")]));
+        assert_eq!(stack.layers()[0], "bottom");
+    }
+}
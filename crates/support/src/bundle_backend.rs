@@ -0,0 +1,338 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{Backend, BackendExt};
+
+/// The magic bytes of a bundle.
+const MAGIC: &[u8; 4] = b"I18N";
+/// The version of the bundle format.
+const VERSION: u32 = 1;
+/// The size of the magic bytes, the version and the number of locales.
+const HEADER_LEN: usize = 12;
+/// The size of a locale, its name and the range of its messages.
+const LOCALE_LEN: usize = 16;
+/// The size of a message, its key and its text.
+const MESSAGE_LEN: usize = 16;
+
+/// Serialize the translations by key, by locale, into a bundle loaded by [`BundleBackend`].
+///
+/// The bundle is a table of the locales and a table of the messages sorted by key, referring
+/// to the strings, each identical string is written once. All the numbers are little-endian
+/// `u32`:
+///
+/// ```text
+/// "I18N" version locale_count
+/// [name_offset name_len messages_start messages_len] * locale_count
+/// [key_offset key_len text_offset text_len] * message_count
+/// strings
+/// ```
+pub fn write_bundle<'a>(translations: &'a BTreeMap<String, BTreeMap<String, String>>) -> Vec<u8> {
+    let message_count = translations.values().map(BTreeMap::len).sum::<usize>();
+    let strings_start = HEADER_LEN + translations.len() * LOCALE_LEN + message_count * MESSAGE_LEN;
+
+    let mut strings = Vec::new();
+    let mut offsets = BTreeMap::<&str, u32>::new();
+    let mut string = |value: &'a str| -> [u32; 2] {
+        let offset = *offsets.entry(value).or_insert_with(|| {
+            let offset = strings_start + strings.len();
+            strings.extend_from_slice(value.as_bytes());
+            offset as u32
+        });
+        [offset, value.len() as u32]
+    };
+
+    let mut locales = Vec::new();
+    let mut messages = Vec::new();
+    for (locale, trs) in translations {
+        let [name_offset, name_len] = string(locale);
+        let messages_start = (messages.len() / MESSAGE_LEN) as u32;
+        for (key, text) in trs {
+            for number in string(key).into_iter().chain(string(text)) {
+                messages.extend_from_slice(&number.to_le_bytes());
+            }
+        }
+        for number in [name_offset, name_len, messages_start, trs.len() as u32] {
+            locales.extend_from_slice(&number.to_le_bytes());
+        }
+    }
+
+    let mut bundle = Vec::with_capacity(strings_start + strings.len());
+    bundle.extend_from_slice(MAGIC);
+    bundle.extend_from_slice(&VERSION.to_le_bytes());
+    bundle.extend_from_slice(&(translations.len() as u32).to_le_bytes());
+    bundle.extend_from_slice(&locales);
+    bundle.extend_from_slice(&messages);
+    bundle.extend_from_slice(&strings);
+    bundle
+}
+
+/// A backend reading the translations in place from a bundle written by [`write_bundle`],
+/// e.g. with `cargo i18n bundle`.
+///
+/// The bundle is checked once when it's loaded, then the lookups are binary searches in its
+/// bytes, nothing is parsed or copied. Embed it in the binary instead of the locale files,
+/// so `i18n!` doesn't generate the code of all the translations, or load it at runtime, from
+/// a file or a memory map of it.
+///
+/// ```ignore
+/// rust_i18n::i18n!(
+///     backend = rust_i18n::BundleBackend::new(include_bytes!("../locales.bin").as_slice()).unwrap()
+/// );
+/// ```
+pub struct BundleBackend {
+    bytes: Box<dyn AsRef<[u8]> + Send + Sync>,
+}
+
+impl BundleBackend {
+    /// Load a bundle from its bytes, e.g. an `include_bytes!`, a `Vec<u8>` or a memory map.
+    pub fn new(bytes: impl AsRef<[u8]> + Send + Sync + 'static) -> Result<Self, String> {
+        validate(bytes.as_ref())?;
+        Ok(Self {
+            bytes: Box::new(bytes),
+        })
+    }
+
+    /// Load a bundle from a file.
+    #[cfg(feature = "std")]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|err| format!("Failed to open file '{}': {}", path.display(), err))?;
+        Self::new(bytes)
+    }
+
+    fn bundle(&self) -> Bundle<'_> {
+        Bundle((*self.bytes).as_ref())
+    }
+}
+
+impl Backend for BundleBackend {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let bundle = self.bundle();
+        (0..bundle.locale_count())
+            .map(|index| Cow::Borrowed(bundle.locale(index).0))
+            .collect()
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        let bundle = self.bundle();
+        let messages = bundle.messages(locale)?;
+        let index = binary_search(messages.len(), |index| {
+            bundle.message(messages.start + index).0.cmp(key)
+        })?;
+        Some(Cow::Borrowed(bundle.message(messages.start + index).1))
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        let bundle = self.bundle();
+        let messages = bundle.messages(locale)?;
+        Some(
+            messages
+                .map(|index| {
+                    let (key, text) = bundle.message(index);
+                    (Cow::Borrowed(key), Cow::Borrowed(text))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl BackendExt for BundleBackend {}
+
+/// The bytes of a checked bundle.
+#[derive(Clone, Copy)]
+struct Bundle<'a>(&'a [u8]);
+
+impl<'a> Bundle<'a> {
+    fn number(&self, offset: usize) -> usize {
+        read_number(self.0, offset).unwrap_or_default()
+    }
+
+    fn string(&self, offset: usize) -> &'a str {
+        let start = self.number(offset);
+        let end = start + self.number(offset + 4);
+        // SAFETY: the strings are checked to be UTF-8 when the bundle is loaded.
+        unsafe { core::str::from_utf8_unchecked(&self.0[start..end]) }
+    }
+
+    fn locale_count(&self) -> usize {
+        self.number(8)
+    }
+
+    /// The name of a locale and the range of its messages.
+    fn locale(&self, index: usize) -> (&'a str, core::ops::Range<usize>) {
+        let offset = HEADER_LEN + index * LOCALE_LEN;
+        let start = self.number(offset + 8);
+        (self.string(offset), start..start + self.number(offset + 12))
+    }
+
+    fn messages(&self, locale: &str) -> Option<core::ops::Range<usize>> {
+        let index = binary_search(self.locale_count(), |index| {
+            self.locale(index).0.cmp(locale)
+        })?;
+        Some(self.locale(index).1)
+    }
+
+    /// The key and the text of a message.
+    fn message(&self, index: usize) -> (&'a str, &'a str) {
+        let offset = HEADER_LEN + self.locale_count() * LOCALE_LEN + index * MESSAGE_LEN;
+        (self.string(offset), self.string(offset + 8))
+    }
+}
+
+fn read_number(bytes: &[u8], offset: usize) -> Option<usize> {
+    let number = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(number.try_into().ok()?) as usize)
+}
+
+fn binary_search(len: usize, cmp: impl Fn(usize) -> Ordering) -> Option<usize> {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match cmp(mid) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => return Some(mid),
+        }
+    }
+    None
+}
+
+/// Check the tables and the strings of a bundle, so it's read without checks.
+fn validate(bytes: &[u8]) -> Result<(), String> {
+    let invalid = |reason: &str| format!("Invalid bundle, {}", reason);
+    if bytes.get(..4) != Some(MAGIC.as_slice()) {
+        return Err(invalid("not a rust-i18n bundle"));
+    }
+    let version = read_number(bytes, 4).ok_or_else(|| invalid("truncated header"))?;
+    if version != VERSION as usize {
+        return Err(invalid(&format!("unsupported version {}", version)));
+    }
+    let locale_count = read_number(bytes, 8).ok_or_else(|| invalid("truncated header"))?;
+
+    let number = |offset: usize| read_number(bytes, offset).ok_or_else(|| invalid("truncated"));
+    let string = |offset: usize| -> Result<&str, String> {
+        let start = number(offset)?;
+        let end = start
+            .checked_add(number(offset + 4)?)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| invalid("string out of bounds"))?;
+        core::str::from_utf8(&bytes[start..end]).map_err(|err| invalid(&err.to_string()))
+    };
+
+    // The tables of the locales and of their messages are in bounds
+    let table_end = |start: usize, count: usize, len: usize| {
+        count
+            .checked_mul(len)
+            .and_then(|size| start.checked_add(size))
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| invalid("table out of bounds"))
+    };
+    let messages_start = table_end(HEADER_LEN, locale_count, LOCALE_LEN)?;
+    let mut prev_locale = None;
+    for index in 0..locale_count {
+        let offset = HEADER_LEN + index * LOCALE_LEN;
+        let locale = string(offset)?;
+        if prev_locale.is_some_and(|prev| prev >= locale) {
+            return Err(invalid("locales not sorted"));
+        }
+        prev_locale = Some(locale);
+
+        let start = number(offset + 8)?;
+        let end = start
+            .checked_add(number(offset + 12)?)
+            .ok_or_else(|| invalid("table out of bounds"))?;
+        table_end(messages_start, end, MESSAGE_LEN)?;
+        let mut prev_key = None;
+        for index in start..end {
+            let offset = messages_start + index * MESSAGE_LEN;
+            let key = string(offset)?;
+            if prev_key.is_some_and(|prev| prev >= key) {
+                return Err(invalid("keys not sorted"));
+            }
+            prev_key = Some(key);
+            string(offset + 8)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translations() -> BTreeMap<String, BTreeMap<String, String>> {
+        [
+            (
+                "en",
+                &[("hello", "Hello"), ("bye", "Bye"), ("hi", "Hello")][..],
+            ),
+            ("fr", &[("hello", "Bonjour"), ("bye", "Au revoir")][..]),
+            ("zh-CN", &[][..]),
+        ]
+        .into_iter()
+        .map(|(locale, trs)| {
+            let trs = trs
+                .iter()
+                .map(|(key, text)| (key.to_string(), text.to_string()))
+                .collect();
+            (locale.to_string(), trs)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_bundle_backend() {
+        let bundle = write_bundle(&translations());
+        let backend = BundleBackend::new(bundle).unwrap();
+        assert_eq!(backend.available_locales(), vec!["en", "fr", "zh-CN"]);
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("Hello"));
+        assert_eq!(backend.translate("en", "hi").as_deref(), Some("Hello"));
+        assert_eq!(backend.translate("fr", "bye").as_deref(), Some("Au revoir"));
+        assert_eq!(backend.translate("fr", "hi"), None);
+        assert_eq!(backend.translate("zh-CN", "hello"), None);
+        assert_eq!(backend.translate("de", "hello"), None);
+        assert_eq!(
+            backend.messages_for_locale("fr"),
+            Some(vec![
+                (Cow::from("bye"), Cow::from("Au revoir")),
+                (Cow::from("hello"), Cow::from("Bonjour")),
+            ])
+        );
+        assert_eq!(backend.messages_for_locale("zh-CN"), Some(vec![]));
+        assert_eq!(backend.messages_for_locale("de"), None);
+    }
+
+    #[test]
+    fn test_write_bundle() {
+        let bundle = write_bundle(&translations());
+        // "Hello" is written once
+        let strings = &bundle[HEADER_LEN + 3 * LOCALE_LEN + 5 * MESSAGE_LEN..];
+        assert_eq!(strings, b"enbyeByehelloHellohifrAu revoirBonjourzh-CN");
+
+        let backend = BundleBackend::new(write_bundle(&BTreeMap::new())).unwrap();
+        assert!(backend.available_locales().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_bundle() {
+        let bundle = write_bundle(&translations());
+        assert!(BundleBackend::new(b"not a bundle".as_slice()).is_err());
+        assert!(BundleBackend::new(bundle[..bundle.len() - 1].to_vec()).is_err());
+        assert!(BundleBackend::new(bundle[..HEADER_LEN + 4].to_vec()).is_err());
+
+        let mut unsupported = bundle.clone();
+        unsupported[4] = 2;
+        assert!(BundleBackend::new(unsupported).is_err());
+
+        let mut not_utf8 = bundle.clone();
+        let last = not_utf8.len() - 1;
+        not_utf8[last] = 0xff;
+        assert!(BundleBackend::new(not_utf8).is_err());
+    }
+}
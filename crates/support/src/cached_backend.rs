@@ -0,0 +1,369 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::{Backend, BackendExt};
+
+struct Slot<T> {
+    value: T,
+    fetched_at: Instant,
+    refreshing: AtomicBool,
+}
+
+impl<T> Slot<T> {
+    fn new(value: T) -> Arc<Self> {
+        Arc::new(Self {
+            value,
+            fetched_at: Instant::now(),
+            refreshing: AtomicBool::new(false),
+        })
+    }
+}
+
+type Slots<K, T> = RwLock<HashMap<K, Arc<Slot<T>>>>;
+
+struct Inner<B> {
+    backend: B,
+    ttl: Duration,
+    stale: Duration,
+    max_locales: usize,
+    max_keys: usize,
+    available_locales: Slots<(), Vec<String>>,
+    /// The messages of a locale, `None` if the backend can't list them.
+    locales: Slots<String, Option<HashMap<String, String>>>,
+    /// The keys looked up one by one, for the locales the backend can't list.
+    keys: Slots<(String, String), Option<String>>,
+}
+
+/// A backend caching the translations of a slow backend, e.g. loaded over HTTP or from a
+/// database, so `t!` doesn't wait for a round-trip on every lookup.
+///
+/// The messages of a locale are fetched at once with [`Backend::messages_for_locale`] and
+/// kept for the TTL, the backends which can't list their messages are cached key by key.
+/// Within the [stale-while-revalidate](CachedBackend::stale_while_revalidate) window after
+/// the TTL, the cached translations are still served while they are refreshed in the
+/// background.
+///
+/// Cloning is cheap and clones share the cache.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use rust_i18n_support::{CachedBackend, SimpleBackend};
+/// # let remote = SimpleBackend::new();
+/// let backend = CachedBackend::new(remote, Duration::from_secs(60))
+///     .stale_while_revalidate(Duration::from_secs(600))
+///     .max_locales(20);
+/// ```
+pub struct CachedBackend<B> {
+    inner: Arc<Inner<B>>,
+}
+
+impl<B> Clone for CachedBackend<B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+enum Freshness {
+    Fresh,
+    Stale,
+    Expired,
+}
+
+impl<B: Backend> CachedBackend<B> {
+    /// Cache the translations of the backend for the `ttl`.
+    pub fn new(backend: B, ttl: Duration) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                backend,
+                ttl,
+                stale: Duration::ZERO,
+                max_locales: usize::MAX,
+                max_keys: usize::MAX,
+                available_locales: Default::default(),
+                locales: Default::default(),
+                keys: Default::default(),
+            }),
+        }
+    }
+
+    /// Serve the expired translations for up to `stale` after the TTL while they are
+    /// refreshed in a background thread, defaults to zero.
+    pub fn stale_while_revalidate(self, stale: Duration) -> Self {
+        self.configure(|inner| inner.stale = stale)
+    }
+
+    /// Keep at most `max` locales, dropping the oldest ones, defaults to no limit.
+    pub fn max_locales(self, max: usize) -> Self {
+        self.configure(|inner| inner.max_locales = max)
+    }
+
+    /// Keep at most `max` keys of the locales cached key by key, dropping the oldest ones,
+    /// defaults to no limit.
+    pub fn max_keys(self, max: usize) -> Self {
+        self.configure(|inner| inner.max_keys = max)
+    }
+
+    /// The wrapped backend.
+    pub fn inner(&self) -> &B {
+        &self.inner.backend
+    }
+
+    /// Drop the cached translations of the locale, they are fetched again on the next lookup.
+    pub fn invalidate(&self, locale: &str) {
+        write(&self.inner.locales).remove(locale);
+        write(&self.inner.keys).retain(|(l, _), _| l != locale);
+    }
+
+    /// Drop all the cached translations.
+    pub fn clear(&self) {
+        write(&self.inner.available_locales).clear();
+        write(&self.inner.locales).clear();
+        write(&self.inner.keys).clear();
+    }
+
+    fn configure(mut self, f: impl FnOnce(&mut Inner<B>)) -> Self {
+        f(Arc::get_mut(&mut self.inner).expect("configure CachedBackend before cloning it"));
+        self
+    }
+
+    fn freshness<T>(&self, slot: &Slot<T>) -> Freshness {
+        let age = slot.fetched_at.elapsed();
+        if age < self.inner.ttl {
+            Freshness::Fresh
+        } else if age < self.inner.ttl + self.inner.stale {
+            Freshness::Stale
+        } else {
+            Freshness::Expired
+        }
+    }
+
+    /// Get the cached value of the key, fetching it when missing or expired.
+    fn get<K, T>(
+        &self,
+        slots: fn(&Inner<B>) -> &Slots<K, T>,
+        key: &K,
+        max: usize,
+        fetch: fn(&B, &K) -> T,
+    ) -> Arc<Slot<T>>
+    where
+        K: Eq + Hash + Clone + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        let cached = read(slots(&self.inner)).get(key).cloned();
+        if let Some(slot) = cached {
+            match self.freshness(&slot) {
+                Freshness::Fresh => return slot,
+                Freshness::Stale => {
+                    if !slot.refreshing.swap(true, Ordering::AcqRel) {
+                        let inner = self.inner.clone();
+                        let key = key.clone();
+                        std::thread::spawn(move || {
+                            let slot = Slot::new(fetch(&inner.backend, &key));
+                            insert(slots(&inner), key, slot, max);
+                        });
+                    }
+                    return slot;
+                }
+                Freshness::Expired => {}
+            }
+        }
+
+        let slot = Slot::new(fetch(&self.inner.backend, key));
+        insert(slots(&self.inner), key.clone(), slot.clone(), max);
+        slot
+    }
+
+    fn locale_messages(&self, locale: &str) -> Arc<Slot<Option<HashMap<String, String>>>> {
+        self.get(
+            |inner| &inner.locales,
+            &locale.to_string(),
+            self.inner.max_locales,
+            |backend, locale| {
+                backend.messages_for_locale(locale).map(|messages| {
+                    messages
+                        .into_iter()
+                        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                        .collect()
+                })
+            },
+        )
+    }
+}
+
+impl<B: Backend> Backend for CachedBackend<B> {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let slot = self.get(
+            |inner| &inner.available_locales,
+            &(),
+            1,
+            |backend, _| {
+                backend
+                    .available_locales()
+                    .into_iter()
+                    .map(Cow::into_owned)
+                    .collect()
+            },
+        );
+        slot.value.iter().cloned().map(Cow::Owned).collect()
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        if let Some(messages) = &self.locale_messages(locale).value {
+            return messages.get(key).cloned().map(Cow::Owned);
+        }
+
+        let slot = self.get(
+            |inner| &inner.keys,
+            &(locale.to_string(), key.to_string()),
+            self.inner.max_keys,
+            |backend, (locale, key)| backend.translate(locale, key).map(Cow::into_owned),
+        );
+        slot.value.clone().map(Cow::Owned)
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        let slot = self.locale_messages(locale);
+        let messages = slot.value.as_ref()?;
+        Some(
+            messages
+                .iter()
+                .map(|(k, v)| (Cow::Owned(k.clone()), Cow::Owned(v.clone())))
+                .collect(),
+        )
+    }
+}
+
+impl<B: Backend> BackendExt for CachedBackend<B> {}
+
+fn read<K, T>(slots: &Slots<K, T>) -> std::sync::RwLockReadGuard<'_, HashMap<K, Arc<Slot<T>>>> {
+    slots
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn write<K, T>(slots: &Slots<K, T>) -> std::sync::RwLockWriteGuard<'_, HashMap<K, Arc<Slot<T>>>> {
+    slots
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn insert<K: Eq + Hash + Clone, T>(slots: &Slots<K, T>, key: K, slot: Arc<Slot<T>>, max: usize) {
+    let mut slots = write(slots);
+    slots.insert(key.clone(), slot);
+    while slots.len() > max.max(1) {
+        let oldest = slots
+            .iter()
+            .filter(|(k, _)| **k != key)
+            .min_by_key(|(_, slot)| slot.fetched_at)
+            .map(|(k, _)| k.clone());
+        match oldest {
+            Some(oldest) => slots.remove(&oldest),
+            None => break,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    /// A backend returning the number of fetches as the translation.
+    #[derive(Default)]
+    struct CountingBackend {
+        fetches: AtomicUsize,
+        list: bool,
+    }
+
+    impl CountingBackend {
+        fn fetches(&self) -> usize {
+            self.fetches.load(Ordering::SeqCst)
+        }
+
+        fn fetch(&self) -> String {
+            (self.fetches.fetch_add(1, Ordering::SeqCst) + 1).to_string()
+        }
+    }
+
+    impl Backend for CountingBackend {
+        fn available_locales(&self) -> Vec<Cow<'_, str>> {
+            vec!["en".into()]
+        }
+
+        fn translate(&self, _: &str, _: &str) -> Option<Cow<'_, str>> {
+            Some(self.fetch().into())
+        }
+
+        fn messages_for_locale(&self, _: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+            self.list
+                .then(|| vec![("hello".into(), self.fetch().into())])
+        }
+    }
+
+    #[test]
+    fn test_cached_backend() {
+        let backend = CachedBackend::new(
+            CountingBackend {
+                list: true,
+                ..Default::default()
+            },
+            Duration::from_secs(60),
+        );
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("1"));
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("1"));
+        assert_eq!(backend.translate("en", "missing"), None);
+        assert_eq!(backend.inner().fetches(), 1);
+
+        backend.invalidate("en");
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_cached_backend_by_key() {
+        let backend =
+            CachedBackend::new(CountingBackend::default(), Duration::from_secs(60)).max_keys(2);
+        assert_eq!(backend.translate("en", "a").as_deref(), Some("1"));
+        assert_eq!(backend.translate("en", "a").as_deref(), Some("1"));
+        assert_eq!(backend.translate("en", "b").as_deref(), Some("2"));
+        assert_eq!(backend.translate("en", "c").as_deref(), Some("3"));
+        assert_eq!(backend.messages_for_locale("en"), None);
+
+        // `a` is the oldest key, dropped for `c`
+        assert_eq!(backend.translate("en", "b").as_deref(), Some("2"));
+        assert_eq!(backend.translate("en", "a").as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn test_cached_backend_expiry() {
+        let backend = CachedBackend::new(CountingBackend::default(), Duration::ZERO);
+        assert_eq!(backend.translate("en", "a").as_deref(), Some("1"));
+        assert_eq!(backend.translate("en", "a").as_deref(), Some("2"));
+
+        let backend = CachedBackend::new(CountingBackend::default(), Duration::ZERO)
+            .stale_while_revalidate(Duration::from_secs(60));
+        assert_eq!(backend.translate("en", "a").as_deref(), Some("1"));
+        // The stale translation is served while it is refreshed
+        assert_eq!(backend.translate("en", "a").as_deref(), Some("1"));
+
+        let started = Instant::now();
+        while backend.inner().fetches() < 2 && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        while read(&backend.inner.keys)[&("en".into(), "a".into())]
+            .value
+            .as_deref()
+            != Some("2")
+            && started.elapsed() < Duration::from_secs(5)
+        {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(backend.inner().fetches(), 2);
+    }
+}
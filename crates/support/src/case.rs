@@ -0,0 +1,136 @@
+use alloc::string::String;
+
+/// The Greek letters with an accent, and the letters they are uppercased to.
+///
+/// The accents are dropped in uppercase, e.g. `Αθήνα` is `ΑΘΗΝΑ`.
+static GREEK_ACCENTS: &[(char, char)] = &[
+    ('ά', 'Α'),
+    ('έ', 'Ε'),
+    ('ή', 'Η'),
+    ('ί', 'Ι'),
+    ('ό', 'Ο'),
+    ('ύ', 'Υ'),
+    ('ώ', 'Ω'),
+    ('ΐ', 'Ϊ'),
+    ('ΰ', 'Ϋ'),
+    ('Ά', 'Α'),
+    ('Έ', 'Ε'),
+    ('Ή', 'Η'),
+    ('Ί', 'Ι'),
+    ('Ό', 'Ο'),
+    ('Ύ', 'Υ'),
+    ('Ώ', 'Ω'),
+];
+
+fn language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or_default()
+}
+
+/// Convert a text to uppercase with the rules of a locale.
+///
+/// Unlike [`str::to_uppercase`], the Turkish and Azerbaijani `i` is `İ`, and the Greek accents
+/// are dropped.
+///
+/// ```
+/// # use rust_i18n_support::to_upper_with_locale;
+/// assert_eq!(to_upper_with_locale("tr", "istanbul"), "İSTANBUL");
+/// assert_eq!(to_upper_with_locale("en", "istanbul"), "ISTANBUL");
+/// assert_eq!(to_upper_with_locale("el", "Αθήνα"), "ΑΘΗΝΑ");
+/// ```
+pub fn to_upper_with_locale(locale: &str, s: &str) -> String {
+    match language(locale) {
+        "tr" | "az" => s
+            .chars()
+            .fold(String::with_capacity(s.len()), |mut upper, c| {
+                match c {
+                    'i' => upper.push('İ'),
+                    c => upper.extend(c.to_uppercase()),
+                }
+                upper
+            }),
+        "el" => s
+            .chars()
+            .fold(String::with_capacity(s.len()), |mut upper, c| {
+                match GREEK_ACCENTS.iter().find(|(accented, _)| *accented == c) {
+                    Some((_, plain)) => upper.push(*plain),
+                    None => upper.extend(c.to_uppercase()),
+                }
+                upper
+            }),
+        _ => s.to_uppercase(),
+    }
+}
+
+/// Convert a text to lowercase with the rules of a locale.
+///
+/// Unlike [`str::to_lowercase`], the Turkish and Azerbaijani `I` is `ı`. The Greek final
+/// sigma is `ς`, as in [`str::to_lowercase`].
+///
+/// ```
+/// # use rust_i18n_support::to_lower_with_locale;
+/// assert_eq!(to_lower_with_locale("tr", "DİYARBAKIR"), "diyarbakır");
+/// assert_eq!(to_lower_with_locale("en", "DIYARBAKIR"), "diyarbakir");
+/// assert_eq!(to_lower_with_locale("el", "ΟΔΟΣ"), "οδο\u{3c2}");
+/// ```
+pub fn to_lower_with_locale(locale: &str, s: &str) -> String {
+    match language(locale) {
+        "tr" | "az" => {
+            let s = s.replace('I', "ı").replace('İ', "i");
+            s.to_lowercase()
+        }
+        _ => s.to_lowercase(),
+    }
+}
+
+/// Convert the first letter of a text to uppercase with the rules of a locale, the rest is
+/// unchanged.
+///
+/// The Dutch `ij` is capitalized together, e.g. `IJsland`, and the Greek accent is kept,
+/// e.g. `Ώρα`.
+///
+/// ```
+/// # use rust_i18n_support::capitalize_with_locale;
+/// assert_eq!(capitalize_with_locale("en", "hello world"), "Hello world");
+/// assert_eq!(capitalize_with_locale("tr", "izmir"), "İzmir");
+/// assert_eq!(capitalize_with_locale("nl", "ijsland"), "IJsland");
+/// ```
+pub fn capitalize_with_locale(locale: &str, s: &str) -> String {
+    let first_len = match (language(locale), s.get(..2)) {
+        ("nl", Some("ij" | "iJ")) => 2,
+        _ => s.chars().next().map_or(0, char::len_utf8),
+    };
+    let mut capitalized = match language(locale) {
+        "el" => s[..first_len].to_uppercase(),
+        _ => to_upper_with_locale(locale, &s[..first_len]),
+    };
+    capitalized.push_str(&s[first_len..]);
+    capitalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_conversion() {
+        assert_eq!(to_upper_with_locale("tr-TR", "ıi"), "Iİ");
+        assert_eq!(to_upper_with_locale("az", "iş"), "İŞ");
+        assert_eq!(to_upper_with_locale("de", "straße"), "STRASSE");
+        assert_eq!(to_upper_with_locale("el-GR", "καλημέρα"), "ΚΑΛΗΜΕΡΑ");
+        assert_eq!(to_upper_with_locale("en", "καλημέρα"), "ΚΑΛΗΜΈΡΑ");
+
+        assert_eq!(to_lower_with_locale("tr", "Iİ"), "ıi");
+        assert_eq!(to_lower_with_locale("en", "I"), "i");
+        assert_eq!(
+            to_lower_with_locale("el", "ΣΟΦΟΣ ΣΟΦΙΑ"),
+            "σοφο\u{3c2} σοφια"
+        );
+
+        assert_eq!(capitalize_with_locale("en", ""), "");
+        assert_eq!(capitalize_with_locale("en", "élan"), "Élan");
+        assert_eq!(capitalize_with_locale("tr", "ılık"), "Ilık");
+        assert_eq!(capitalize_with_locale("nl", "ijs"), "IJs");
+        assert_eq!(capitalize_with_locale("en", "ijs"), "Ijs");
+        assert_eq!(capitalize_with_locale("el", "ώρα"), "Ώρα");
+    }
+}
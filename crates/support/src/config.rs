@@ -29,6 +29,20 @@ pub struct I18nConfig {
     pub minify_key_prefix: String,
     #[serde(default = "minify_key_thresh")]
     pub minify_key_thresh: usize,
+    /// Glob patterns of the template files to extract, relative to the crate root.
+    #[serde(default = "templates")]
+    pub templates: Vec<String>,
+    /// Glob patterns of the files to skip when extracting, relative to the crate root.
+    #[serde(default = "ignore")]
+    pub ignore: Vec<String>,
+    /// Patterns of the keys built at runtime, e.g. `errors.*`, which are never reported
+    /// or removed as unused.
+    #[serde(default = "dynamic_keys")]
+    pub dynamic_keys: Vec<String>,
+    /// The maximum length of a translation, in percent of the base locale text length,
+    /// e.g. `150`, checked by `cargo i18n validate`.
+    #[serde(default = "max_length_percent")]
+    pub max_length_percent: Option<usize>,
 }
 
 impl Default for I18nConfig {
@@ -42,6 +56,10 @@ impl Default for I18nConfig {
             minify_key_len: crate::DEFAULT_MINIFY_KEY_LEN,
             minify_key_prefix: crate::DEFAULT_MINIFY_KEY_PREFIX.to_string(),
             minify_key_thresh: crate::DEFAULT_MINIFY_KEY_THRESH,
+            templates: vec!["templates/**/*".to_string()],
+            ignore: vec![],
+            dynamic_keys: vec![],
+            max_length_percent: None,
         }
     }
 }
@@ -127,6 +145,22 @@ fn minify_key_thresh() -> usize {
     I18nConfig::default().minify_key_thresh
 }
 
+fn templates() -> Vec<String> {
+    I18nConfig::default().templates
+}
+
+fn ignore() -> Vec<String> {
+    I18nConfig::default().ignore
+}
+
+fn dynamic_keys() -> Vec<String> {
+    I18nConfig::default().dynamic_keys
+}
+
+fn max_length_percent() -> Option<usize> {
+    I18nConfig::default().max_length_percent
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct MainConfig {
@@ -145,6 +179,10 @@ fn test_parse() {
         minify-key-len = 12
         minify-key-prefix = "T_"
         minify-key-thresh = 16
+        templates = ["views/**/*.html"]
+        ignore = ["target/**", "generated/**"]
+        dynamic-keys = ["errors.*"]
+        max-length-percent = 150
     "#;
 
     let cfg = I18nConfig::parse(contents).unwrap();
@@ -156,6 +194,10 @@ fn test_parse() {
     assert_eq!(cfg.minify_key_len, 12);
     assert_eq!(cfg.minify_key_prefix, "T_");
     assert_eq!(cfg.minify_key_thresh, 16);
+    assert_eq!(cfg.templates, vec!["views/**/*.html"]);
+    assert_eq!(cfg.ignore, vec!["target/**", "generated/**"]);
+    assert_eq!(cfg.dynamic_keys, vec!["errors.*"]);
+    assert_eq!(cfg.max_length_percent, Some(150));
 
     let contents = r#"
         [i18n]
@@ -172,6 +214,10 @@ fn test_parse() {
     assert_eq!(cfg.default_locale, "en");
     assert_eq!(cfg.available_locales, vec!["en"]);
     assert_eq!(cfg.load_path, "./locales");
+    assert_eq!(cfg.templates, vec!["templates/**/*"]);
+    assert!(cfg.ignore.is_empty());
+    assert!(cfg.dynamic_keys.is_empty());
+    assert_eq!(cfg.max_length_percent, None);
 }
 
 #[test]
@@ -0,0 +1,331 @@
+use core::fmt;
+
+use crate::{locale_info::native_name, negotiate};
+
+type Names = &'static [(&'static str, &'static str)];
+
+/// Names of languages in other languages, a subset of the CLDR data.
+///
+/// Each table has the same languages, the display languages are matched like
+/// [`negotiate`](crate::negotiate), e.g. `zh-TW` uses the `zh-Hant` names.
+static DISPLAY_NAMES: &[(&str, Names)] = &[
+    (
+        "en",
+        &[
+            ("ar", "Arabic"),
+            ("de", "German"),
+            ("en", "English"),
+            ("es", "Spanish"),
+            ("fr", "French"),
+            ("hi", "Hindi"),
+            ("id", "Indonesian"),
+            ("it", "Italian"),
+            ("ja", "Japanese"),
+            ("ko", "Korean"),
+            ("nl", "Dutch"),
+            ("pl", "Polish"),
+            ("pt", "Portuguese"),
+            ("pt-BR", "Brazilian Portuguese"),
+            ("pt-PT", "European Portuguese"),
+            ("ru", "Russian"),
+            ("sv", "Swedish"),
+            ("th", "Thai"),
+            ("tr", "Turkish"),
+            ("uk", "Ukrainian"),
+            ("vi", "Vietnamese"),
+            ("zh", "Chinese"),
+            ("zh-Hans", "Simplified Chinese"),
+            ("zh-Hant", "Traditional Chinese"),
+        ],
+    ),
+    (
+        "de",
+        &[
+            ("ar", "Arabisch"),
+            ("de", "Deutsch"),
+            ("en", "Englisch"),
+            ("es", "Spanisch"),
+            ("fr", "Französisch"),
+            ("hi", "Hindi"),
+            ("id", "Indonesisch"),
+            ("it", "Italienisch"),
+            ("ja", "Japanisch"),
+            ("ko", "Koreanisch"),
+            ("nl", "Niederländisch"),
+            ("pl", "Polnisch"),
+            ("pt", "Portugiesisch"),
+            ("pt-BR", "Brasilianisches Portugiesisch"),
+            ("pt-PT", "Europäisches Portugiesisch"),
+            ("ru", "Russisch"),
+            ("sv", "Schwedisch"),
+            ("th", "Thailändisch"),
+            ("tr", "Türkisch"),
+            ("uk", "Ukrainisch"),
+            ("vi", "Vietnamesisch"),
+            ("zh", "Chinesisch"),
+            ("zh-Hans", "Chinesisch (vereinfacht)"),
+            ("zh-Hant", "Chinesisch (traditionell)"),
+        ],
+    ),
+    (
+        "es",
+        &[
+            ("ar", "árabe"),
+            ("de", "alemán"),
+            ("en", "inglés"),
+            ("es", "español"),
+            ("fr", "francés"),
+            ("hi", "hindi"),
+            ("id", "indonesio"),
+            ("it", "italiano"),
+            ("ja", "japonés"),
+            ("ko", "coreano"),
+            ("nl", "neerlandés"),
+            ("pl", "polaco"),
+            ("pt", "portugués"),
+            ("pt-BR", "portugués de Brasil"),
+            ("pt-PT", "portugués de Portugal"),
+            ("ru", "ruso"),
+            ("sv", "sueco"),
+            ("th", "tailandés"),
+            ("tr", "turco"),
+            ("uk", "ucraniano"),
+            ("vi", "vietnamita"),
+            ("zh", "chino"),
+            ("zh-Hans", "chino simplificado"),
+            ("zh-Hant", "chino tradicional"),
+        ],
+    ),
+    (
+        "fr",
+        &[
+            ("ar", "arabe"),
+            ("de", "allemand"),
+            ("en", "anglais"),
+            ("es", "espagnol"),
+            ("fr", "français"),
+            ("hi", "hindi"),
+            ("id", "indonésien"),
+            ("it", "italien"),
+            ("ja", "japonais"),
+            ("ko", "coréen"),
+            ("nl", "néerlandais"),
+            ("pl", "polonais"),
+            ("pt", "portugais"),
+            ("pt-BR", "portugais brésilien"),
+            ("pt-PT", "portugais européen"),
+            ("ru", "russe"),
+            ("sv", "suédois"),
+            ("th", "thaï"),
+            ("tr", "turc"),
+            ("uk", "ukrainien"),
+            ("vi", "vietnamien"),
+            ("zh", "chinois"),
+            ("zh-Hans", "chinois simplifié"),
+            ("zh-Hant", "chinois traditionnel"),
+        ],
+    ),
+    (
+        "ja",
+        &[
+            ("ar", "アラビア語"),
+            ("de", "ドイツ語"),
+            ("en", "英語"),
+            ("es", "スペイン語"),
+            ("fr", "フランス語"),
+            ("hi", "ヒンディー語"),
+            ("id", "インドネシア語"),
+            ("it", "イタリア語"),
+            ("ja", "日本語"),
+            ("ko", "韓国語"),
+            ("nl", "オランダ語"),
+            ("pl", "ポーランド語"),
+            ("pt", "ポルトガル語"),
+            ("pt-BR", "ポルトガル語 (ブラジル)"),
+            ("pt-PT", "ポルトガル語 (ポルトガル)"),
+            ("ru", "ロシア語"),
+            ("sv", "スウェーデン語"),
+            ("th", "タイ語"),
+            ("tr", "トルコ語"),
+            ("uk", "ウクライナ語"),
+            ("vi", "ベトナム語"),
+            ("zh", "中国語"),
+            ("zh-Hans", "簡体中国語"),
+            ("zh-Hant", "繁体中国語"),
+        ],
+    ),
+    (
+        "zh-Hans",
+        &[
+            ("ar", "阿拉伯语"),
+            ("de", "德语"),
+            ("en", "英语"),
+            ("es", "西班牙语"),
+            ("fr", "法语"),
+            ("hi", "印地语"),
+            ("id", "印度尼西亚语"),
+            ("it", "意大利语"),
+            ("ja", "日语"),
+            ("ko", "韩语"),
+            ("nl", "荷兰语"),
+            ("pl", "波兰语"),
+            ("pt", "葡萄牙语"),
+            ("pt-BR", "巴西葡萄牙语"),
+            ("pt-PT", "欧洲葡萄牙语"),
+            ("ru", "俄语"),
+            ("sv", "瑞典语"),
+            ("th", "泰语"),
+            ("tr", "土耳其语"),
+            ("uk", "乌克兰语"),
+            ("vi", "越南语"),
+            ("zh", "中文"),
+            ("zh-Hans", "简体中文"),
+            ("zh-Hant", "繁体中文"),
+        ],
+    ),
+    (
+        "zh-Hant",
+        &[
+            ("ar", "阿拉伯文"),
+            ("de", "德文"),
+            ("en", "英文"),
+            ("es", "西班牙文"),
+            ("fr", "法文"),
+            ("hi", "印地文"),
+            ("id", "印尼文"),
+            ("it", "義大利文"),
+            ("ja", "日文"),
+            ("ko", "韓文"),
+            ("nl", "荷蘭文"),
+            ("pl", "波蘭文"),
+            ("pt", "葡萄牙文"),
+            ("pt-BR", "巴西葡萄牙文"),
+            ("pt-PT", "歐洲葡萄牙文"),
+            ("ru", "俄文"),
+            ("sv", "瑞典文"),
+            ("th", "泰文"),
+            ("tr", "土耳其文"),
+            ("uk", "烏克蘭文"),
+            ("vi", "越南文"),
+            ("zh", "中文"),
+            ("zh-Hans", "簡體中文"),
+            ("zh-Hant", "繁體中文"),
+        ],
+    ),
+];
+
+/// The display name of a locale, see [`locale_display_name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleDisplayName<'a> {
+    locale: &'a str,
+}
+
+/// Get the display name of a locale, for building language pickers.
+///
+/// It displays as the name of the locale in its own language, or the locale itself when
+/// it is unknown, and [`LocaleDisplayName::in_locale`] gets the name in another language.
+///
+/// ```
+/// # use rust_i18n_support::locale_display_name;
+/// assert_eq!(locale_display_name("de"), "Deutsch");
+/// assert_eq!(locale_display_name("de").in_locale("en"), Some("German"));
+/// assert_eq!(locale_display_name("zh-TW").in_locale("fr"), Some("chinois traditionnel"));
+/// assert_eq!(locale_display_name("tlh").to_string(), "tlh");
+/// ```
+pub fn locale_display_name(locale: &str) -> LocaleDisplayName<'_> {
+    LocaleDisplayName { locale }
+}
+
+impl<'a> LocaleDisplayName<'a> {
+    /// The locale, e.g. `de`.
+    pub fn locale(&self) -> &'a str {
+        self.locale
+    }
+
+    /// The name of the locale in its own language, e.g. `Deutsch` for `de`.
+    pub fn native(&self) -> Option<&'static str> {
+        native_name(self.locale)
+    }
+
+    /// The name of the locale in another language, e.g. `German` for `de` in `en`.
+    ///
+    /// Returns `None` when the name is unknown.
+    pub fn in_locale(&self, display_locale: &str) -> Option<&'static str> {
+        let display_languages: alloc::vec::Vec<&str> =
+            DISPLAY_NAMES.iter().map(|(tag, _)| *tag).collect();
+        let Some(display_language) = negotiate(&[display_locale], &display_languages) else {
+            // The name in the locale itself
+            let language = |locale: &str| locale.split(['-', '_']).next().map(str::to_lowercase);
+            return self
+                .native()
+                .filter(|_| language(self.locale) == language(display_locale));
+        };
+
+        let (_, names) = DISPLAY_NAMES
+            .iter()
+            .find(|(tag, _)| *tag == display_language)?;
+        let tags: alloc::vec::Vec<&str> = names.iter().map(|(tag, _)| *tag).collect();
+        let tag = negotiate(&[self.locale], &tags)?;
+        names
+            .iter()
+            .find(|(name_tag, _)| *name_tag == tag)
+            .map(|(_, name)| *name)
+    }
+}
+
+impl fmt::Display for LocaleDisplayName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.native().unwrap_or(self.locale))
+    }
+}
+
+impl PartialEq<str> for LocaleDisplayName<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.native().unwrap_or(self.locale) == other
+    }
+}
+
+impl PartialEq<&str> for LocaleDisplayName<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_locale_display_name() {
+        assert_eq!(locale_display_name("de-AT"), "Deutsch");
+        assert_eq!(locale_display_name("ja").native(), Some("日本語"));
+        assert_eq!(locale_display_name("tlh").native(), None);
+        assert_eq!(locale_display_name("zh-CN").to_string(), "简体中文");
+
+        let name = locale_display_name("pt-BR");
+        assert_eq!(name.in_locale("en"), Some("Brazilian Portuguese"));
+        assert_eq!(
+            name.in_locale("de-CH"),
+            Some("Brasilianisches Portugiesisch")
+        );
+        assert_eq!(
+            locale_display_name("pt-AO").in_locale("en"),
+            Some("Portuguese")
+        );
+
+        assert_eq!(
+            locale_display_name("zh-CN").in_locale("en"),
+            Some("Simplified Chinese")
+        );
+        assert_eq!(locale_display_name("zh").in_locale("en"), Some("Chinese"));
+        assert_eq!(locale_display_name("en").in_locale("zh-TW"), Some("英文"));
+        assert_eq!(locale_display_name("en").in_locale("zh"), Some("英语"));
+        assert_eq!(locale_display_name("ko").in_locale("ja"), Some("韓国語"));
+
+        // No names in Korean, except its own name
+        assert_eq!(locale_display_name("ko-KR").in_locale("ko"), Some("한국어"));
+        assert_eq!(locale_display_name("en").in_locale("ko"), None);
+        assert_eq!(locale_display_name("tlh").in_locale("en"), None);
+    }
+}
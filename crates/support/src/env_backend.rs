@@ -0,0 +1,160 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::{Backend, BackendExt, Locale};
+
+/// The prefix of the variables read by [`EnvBackend::new`].
+pub const ENV_PREFIX: &str = "RUST_I18N__";
+
+/// A backend reading translations from environment variables, for emergency copy fixes in
+/// containerized deployments without a rebuild.
+///
+/// A variable like `RUST_I18N__EN__CHECKOUT_TITLE` is the translation of the `en` locale,
+/// `RUST_I18N__ZH_CN__...` of `zh-CN`. The key is compared with the non-alphanumeric
+/// characters replaced by `_` and ignoring case, so `CHECKOUT_TITLE` is the translation of
+/// `checkout.title`, and `checkout_title` too.
+///
+/// The variables are read once, when the backend is created. The keys can't be listed, so
+/// [`Backend::messages_for_locale`] returns `None`.
+///
+/// ```
+/// # use rust_i18n_support::{Backend, EnvBackend};
+/// let backend = EnvBackend::from_vars([("RUST_I18N__EN__CHECKOUT_TITLE", "Pay now")]);
+/// assert_eq!(backend.translate("en", "checkout.title").as_deref(), Some("Pay now"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EnvBackend {
+    /// The locale code and the translations by normalized key, by normalized locale.
+    locales: HashMap<String, (String, HashMap<String, String>)>,
+}
+
+impl EnvBackend {
+    /// Read the variables prefixed with `RUST_I18N__`.
+    pub fn new() -> Self {
+        Self::with_prefix(ENV_PREFIX)
+    }
+
+    /// Read the variables prefixed with `prefix`, e.g. `MY_APP_I18N__`.
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self::from_vars_with_prefix(std::env::vars(), prefix)
+    }
+
+    /// Read the translations from the given variables prefixed with `RUST_I18N__`.
+    pub fn from_vars<K: AsRef<str>, V: Into<String>>(
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        Self::from_vars_with_prefix(vars, ENV_PREFIX)
+    }
+
+    fn from_vars_with_prefix<K: AsRef<str>, V: Into<String>>(
+        vars: impl IntoIterator<Item = (K, V)>,
+        prefix: &str,
+    ) -> Self {
+        let mut backend = Self::default();
+        for (name, value) in vars {
+            let Some((locale, key)) = name
+                .as_ref()
+                .strip_prefix(prefix)
+                .and_then(|name| name.split_once("__"))
+            else {
+                continue;
+            };
+            let (locale, key) = (normalize(locale), normalize(key));
+            if locale.is_empty() || key.is_empty() {
+                continue;
+            }
+
+            let code = Locale::parse(&locale)
+                .map(|locale| locale.to_string())
+                .unwrap_or_else(|_| locale.to_lowercase());
+            backend
+                .locales
+                .entry(locale)
+                .or_insert_with(|| (code, HashMap::new()))
+                .1
+                .insert(key, value.into());
+        }
+        backend
+    }
+}
+
+/// Uppercase with the runs of non-alphanumeric characters replaced by a single `_`.
+fn normalize(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_uppercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+impl Backend for EnvBackend {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let mut locales = self
+            .locales
+            .values()
+            .map(|(code, _)| Cow::Borrowed(code.as_str()))
+            .collect::<Vec<_>>();
+        locales.sort();
+        locales
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        let (_, trs) = self.locales.get(&normalize(locale))?;
+        trs.get(&normalize(key))
+            .map(|value| Cow::Borrowed(value.as_str()))
+    }
+
+    fn messages_for_locale(&self, _locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        None
+    }
+}
+
+impl BackendExt for EnvBackend {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleBackend;
+
+    #[test]
+    fn test_env_backend() {
+        let backend = EnvBackend::from_vars([
+            ("RUST_I18N__EN__CHECKOUT_TITLE", "Pay now"),
+            ("RUST_I18N__ZH_CN__CHECKOUT__TITLE", "立即支付"),
+            ("RUST_I18N__EN", "No key"),
+            ("RUST_I18N_DEBUG", "1"),
+            ("PATH", "/usr/bin"),
+        ]);
+        assert_eq!(backend.available_locales(), vec!["en", "zh-CN"]);
+        assert_eq!(
+            backend.translate("en", "checkout.title").as_deref(),
+            Some("Pay now")
+        );
+        assert_eq!(
+            backend.translate("en", "checkout_title").as_deref(),
+            Some("Pay now")
+        );
+        assert_eq!(
+            backend.translate("zh-CN", "checkout.title").as_deref(),
+            Some("立即支付")
+        );
+        assert_eq!(backend.translate("fr", "checkout.title"), None);
+        assert_eq!(backend.messages_for_locale("en"), None);
+
+        // Layered last, the variables override the other translations
+        let mut base = SimpleBackend::new();
+        base.add_translations(
+            "en".into(),
+            HashMap::from([
+                ("checkout.title".into(), "Checkout".into()),
+                ("hello".into(), "Hello".into()),
+            ]),
+        );
+        let combined = base.extend(backend);
+        assert_eq!(
+            combined.translate("en", "checkout.title").as_deref(),
+            Some("Pay now")
+        );
+        assert_eq!(combined.translate("en", "hello").as_deref(), Some("Hello"));
+    }
+}
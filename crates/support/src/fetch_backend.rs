@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::Backend;
+
+type Messages = HashMap<String, HashMap<String, String>>;
+
+/// A backend for `wasm32` front-ends that loads translations at runtime.
+///
+/// Translations are JSON documents, either preloaded with [`FetchBackend::from_slice`]
+/// or downloaded by the browser with [`FetchBackend::fetch`].
+/// A bundle maps each locale to its (optionally nested) messages:
+///
+/// ```json
+/// {
+///   "en": { "hello": "Hello", "messages": { "welcome": "Welcome, %{name}" } },
+///   "zh-CN": { "hello": "你好" }
+/// }
+/// ```
+///
+/// Cloning is cheap and clones share the same translations, so keep a clone
+/// around to load more locales after passing it to `i18n!(backend = ...)`.
+#[derive(Clone, Default)]
+pub struct FetchBackend {
+    translations: Arc<RwLock<Messages>>,
+}
+
+impl FetchBackend {
+    /// Create an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a backend from a preloaded JSON bundle, e.g. `include_bytes!("locales.json")`.
+    pub fn from_slice(bundle: &[u8]) -> Result<Self, String> {
+        let backend = Self::new();
+        backend.add_bundle(bundle)?;
+        Ok(backend)
+    }
+
+    /// Add all locales of a JSON bundle, merging with the existing translations.
+    pub fn add_bundle(&self, bundle: &[u8]) -> Result<(), String> {
+        let value = serde_json::from_slice::<serde_json::Value>(bundle)
+            .map_err(|err| format!("Invalid JSON format, {}", err))?;
+        let serde_json::Value::Object(locales) = value else {
+            return Err("Invalid bundle, expected an object of locales".into());
+        };
+
+        for (locale, messages) in locales {
+            self.add_messages(&locale, &messages);
+        }
+
+        Ok(())
+    }
+
+    /// Add the JSON messages of a single locale, merging with the existing translations.
+    pub fn add_locale(&self, locale: &str, messages: &[u8]) -> Result<(), String> {
+        let value = serde_json::from_slice::<serde_json::Value>(messages)
+            .map_err(|err| format!("Invalid JSON format, {}", err))?;
+        self.add_messages(locale, &value);
+        Ok(())
+    }
+
+    fn add_messages(&self, locale: &str, messages: &serde_json::Value) {
+        let flatten = crate::flatten_keys("", messages);
+        let mut translations = self.translations.write().unwrap();
+        translations
+            .entry(locale.to_string())
+            .or_default()
+            .extend(flatten);
+    }
+
+    /// Download a JSON bundle with the browser `fetch` API and add all of its locales.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn fetch(&self, url: &str) -> Result<(), String> {
+        let text = fetch_text(url).await?;
+        self.add_bundle(text.as_bytes())
+    }
+
+    /// Download the JSON messages of a single locale with the browser `fetch` API.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn fetch_locale(&self, locale: &str, url: &str) -> Result<(), String> {
+        let text = fetch_text(url).await?;
+        self.add_locale(locale, text.as_bytes())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_text(url: &str) -> Result<String, String> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let window = web_sys::window().ok_or("No `window` available")?;
+    let response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .map_err(|err| format!("Fetch '{url}' failed: {err:?}"))?;
+    let response: web_sys::Response = response
+        .dyn_into()
+        .map_err(|_| format!("Fetch '{url}' returned an invalid response"))?;
+    if !response.ok() {
+        return Err(format!("Fetch '{url}' failed: HTTP {}", response.status()));
+    }
+
+    let text = response
+        .text()
+        .map_err(|err| format!("Read '{url}' failed: {err:?}"))?;
+    let text = JsFuture::from(text)
+        .await
+        .map_err(|err| format!("Read '{url}' failed: {err:?}"))?;
+    text.as_string()
+        .ok_or_else(|| format!("Read '{url}' failed: body is not a string"))
+}
+
+impl Backend for FetchBackend {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let translations = self.translations.read().unwrap();
+        let mut locales = translations
+            .keys()
+            .map(|locale| Cow::Owned(locale.clone()))
+            .collect::<Vec<_>>();
+        locales.sort();
+        locales
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        let translations = self.translations.read().unwrap();
+        translations
+            .get(locale)?
+            .get(key)
+            .map(|value| Cow::Owned(value.clone()))
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        let translations = self.translations.read().unwrap();
+        translations.get(locale).map(|trs| {
+            trs.iter()
+                .map(|(k, v)| (Cow::Owned(k.clone()), Cow::Owned(v.clone())))
+                .collect()
+        })
+    }
+}
+
+impl crate::BackendExt for FetchBackend {}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::FetchBackend;
+    use crate::Backend;
+
+    #[test]
+    fn test_fetch_backend_from_slice() {
+        let bundle = r#"{
+            "en": { "hello": "Hello", "messages": { "welcome": "Welcome" } },
+            "zh-CN": { "hello": "你好" }
+        }"#;
+        let backend = FetchBackend::from_slice(bundle.as_bytes()).unwrap();
+
+        assert_eq!(backend.available_locales(), vec!["en", "zh-CN"]);
+        assert_eq!(backend.translate("en", "hello"), Some(Cow::from("Hello")));
+        assert_eq!(
+            backend.translate("en", "messages.welcome"),
+            Some(Cow::from("Welcome"))
+        );
+        assert_eq!(backend.translate("zh-CN", "hello"), Some(Cow::from("你好")));
+        assert_eq!(backend.translate("zh-CN", "messages.welcome"), None);
+
+        assert!(FetchBackend::from_slice(b"[]").is_err());
+    }
+
+    #[test]
+    fn test_fetch_backend_shared_clone() {
+        let backend = FetchBackend::new();
+        let handle = backend.clone();
+        handle.add_locale("fr", br#"{"hello": "Bonjour"}"#).unwrap();
+
+        assert_eq!(backend.translate("fr", "hello"), Some(Cow::from("Bonjour")));
+    }
+}
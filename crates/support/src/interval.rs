@@ -0,0 +1,133 @@
+use alloc::{borrow::Cow, format};
+
+/// The key listing the count intervals of a key, e.g. `messages._intervals` is `0,1,2..`.
+///
+/// It is added by the loader for the keys with an interval, e.g. `2..`, in their subkeys.
+pub const INTERVALS_KEY: &str = "_intervals";
+
+/// Parse a count interval, returns the inclusive bounds.
+///
+/// The intervals are a count, e.g. `1`, or an inclusive range, e.g. `2..4`, `2..` or `..4`.
+///
+/// ```
+/// # use rust_i18n_support::parse_interval;
+/// assert_eq!(parse_interval("1"), Some((1, Some(1))));
+/// assert_eq!(parse_interval("2..4"), Some((2, Some(4))));
+/// assert_eq!(parse_interval("2.."), Some((2, None)));
+/// assert_eq!(parse_interval("..4"), Some((0, Some(4))));
+/// assert_eq!(parse_interval("other"), None);
+/// ```
+pub fn parse_interval(interval: &str) -> Option<(u64, Option<u64>)> {
+    let number = |s: &str| {
+        if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse::<u64>().ok()
+        } else {
+            None
+        }
+    };
+
+    match interval.split_once("..") {
+        Some(("", "")) => None,
+        Some((start, end)) => {
+            let start = if start.is_empty() { 0 } else { number(start)? };
+            let end = if end.is_empty() {
+                None
+            } else {
+                Some(number(end)?)
+            };
+            if end.is_some_and(|end| end < start) {
+                return None;
+            }
+            Some((start, end))
+        }
+        None => number(interval).map(|n| (n, Some(n))),
+    }
+}
+
+/// Returns `true` if the interval is a range, e.g. `2..`.
+#[cfg(any(feature = "codegen", feature = "wasm"))]
+pub(crate) fn is_interval_range(interval: &str) -> bool {
+    interval.contains("..") && parse_interval(interval).is_some()
+}
+
+/// Select the text of a count by the intervals of a key, e.g. `messages.0`, `messages.1` and
+/// `messages.2..`, `translate` gets the text of a key.
+///
+/// The narrowest interval containing the count is used, e.g. `1` before `1..`.
+///
+/// ```
+/// # use rust_i18n_support::select_interval;
+/// # use std::borrow::Cow;
+/// let translate = |key: &str| {
+///     let text = match key {
+///         "messages._intervals" => "0,1,2..",
+///         "messages.0" => "no messages",
+///         "messages.1" => "one message",
+///         "messages.2.." => "%{count} messages",
+///         _ => return None,
+///     };
+///     Some(Cow::Borrowed(text))
+/// };
+/// assert_eq!(select_interval("messages", 0, translate).as_deref(), Some("no messages"));
+/// assert_eq!(select_interval("messages", 5, translate).as_deref(), Some("%{count} messages"));
+/// assert_eq!(select_interval("other", 5, translate), None);
+/// ```
+pub fn select_interval<'a>(
+    key: &str,
+    count: u64,
+    translate: impl Fn(&str) -> Option<Cow<'a, str>>,
+) -> Option<Cow<'a, str>> {
+    let intervals = translate(&format!("{}.{}", key, INTERVALS_KEY))?;
+    let interval = intervals
+        .split(',')
+        .filter_map(|interval| {
+            let (start, end) = parse_interval(interval)?;
+            let contains = count >= start && end.is_none_or(|end| count <= end);
+            contains.then(|| (end.map_or(u64::MAX, |end| end - start), interval))
+        })
+        .min_by_key(|(span, _)| *span)
+        .map(|(_, interval)| interval)?;
+    translate(&format!("{}.{}", key, interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("0"), Some((0, Some(0))));
+        assert_eq!(parse_interval("10..20"), Some((10, Some(20))));
+        assert_eq!(parse_interval("3..3"), Some((3, Some(3))));
+        assert_eq!(parse_interval("4..2"), None);
+        assert_eq!(parse_interval(".."), None);
+        assert_eq!(parse_interval("1...3"), None);
+        assert_eq!(parse_interval("-1"), None);
+        assert_eq!(parse_interval("+1"), None);
+        assert_eq!(parse_interval(""), None);
+    }
+
+    #[test]
+    fn test_select_interval() {
+        let translate = |key: &str| {
+            let text = match key {
+                "inbox._intervals" => "0,1..,1,5..9",
+                "inbox.0" => "empty",
+                "inbox.1" => "one",
+                "inbox.1.." => "many",
+                "inbox.5..9" => "several",
+                _ => return None,
+            };
+            Some(Cow::Borrowed(text))
+        };
+        let select = |count| select_interval("inbox", count, translate);
+        assert_eq!(select(0).as_deref(), Some("empty"));
+        assert_eq!(select(1).as_deref(), Some("one"));
+        assert_eq!(select(2).as_deref(), Some("many"));
+        assert_eq!(select(7).as_deref(), Some("several"));
+        assert_eq!(select(10).as_deref(), Some("many"));
+
+        let translate = |key: &str| (key == "inbox._intervals").then_some(Cow::Borrowed("2..4"));
+        assert_eq!(select_interval("inbox", 1, translate), None);
+    }
+}
@@ -1,20 +1,132 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("rust-i18n-support requires either the `std` or the `alloc` feature");
+
+#[cfg(feature = "std")]
+mod async_backend;
+#[cfg(any(feature = "std", feature = "spin"))]
 mod atomic_str;
 mod backend;
+#[cfg(any(feature = "std", feature = "spin"))]
+mod backend_stack;
+#[cfg(feature = "bundle")]
+mod bundle_backend;
+#[cfg(feature = "std")]
+mod cached_backend;
+mod case;
 mod cow_str;
+mod display_name;
+#[cfg(feature = "std")]
+mod env_backend;
+mod interval;
+mod locale;
+mod locale_info;
+mod locale_match;
+mod message_template;
 mod minify_key;
+#[cfg(any(feature = "std", feature = "spin"))]
+mod override_backend;
+#[cfg(feature = "phf")]
+mod phf_backend;
+mod plural;
+#[cfg(any(feature = "std", feature = "spin"))]
+mod recording_backend;
+mod reference;
+mod relative_time;
+#[cfg(feature = "std")]
+mod system_locale;
+mod units;
+#[cfg(feature = "std")]
+pub use async_backend::{AsyncBackend, SnapshotBackend};
+#[cfg(any(feature = "std", feature = "spin"))]
 pub use atomic_str::AtomicStr;
-pub use backend::{Backend, BackendExt, CombinedBackend, NamespacedBackend, SimpleBackend};
+pub use backend::{
+    Backend, BackendExt, BackendMut, CombinedBackend, NamespacedBackend, ScopedBackend,
+    SimpleBackend,
+};
+#[cfg(any(feature = "std", feature = "spin"))]
+pub use backend_stack::BackendStack;
+#[cfg(feature = "bundle")]
+pub use bundle_backend::{write_bundle, BundleBackend};
+#[cfg(feature = "std")]
+pub use cached_backend::CachedBackend;
+pub use case::{capitalize_with_locale, to_lower_with_locale, to_upper_with_locale};
 pub use cow_str::CowStr;
+pub use display_name::{locale_display_name, LocaleDisplayName};
+#[cfg(feature = "std")]
+pub use env_backend::{EnvBackend, ENV_PREFIX};
+pub use interval::{parse_interval, select_interval, INTERVALS_KEY};
+pub use locale::{Locale, ParseLocaleError};
+pub use locale_info::{is_rtl, text_direction, LocaleInfo, TextDirection};
+pub use locale_match::{
+    filter_locales, lookup_locale, matches_range, negotiate, parse_accept_language,
+};
+pub use message_template::{MessageTemplate, Segment};
 pub use minify_key::{
     minify_key, MinifyKey, DEFAULT_MINIFY_KEY, DEFAULT_MINIFY_KEY_LEN, DEFAULT_MINIFY_KEY_PREFIX,
     DEFAULT_MINIFY_KEY_THRESH,
 };
+#[cfg(any(feature = "std", feature = "spin"))]
+pub use override_backend::OverrideBackend;
+#[cfg(feature = "phf")]
+pub use phf_backend::{PhfBackend, PhfTranslations};
+pub use plural::plural_category;
+#[cfg(any(feature = "std", feature = "spin"))]
+pub use recording_backend::{MissingKey, RecordingBackend};
+pub use reference::{references, resolve_references, resolve_template_references};
+pub use relative_time::RelativeTime;
+#[cfg(feature = "std")]
+pub use system_locale::system_locales;
+pub use units::{
+    format_binary_bytes, format_bytes, format_distance, format_percent, format_unit,
+    MeasurementSystem,
+};
 
 #[cfg(feature = "codegen")]
 mod config;
 #[cfg(feature = "codegen")]
 pub use config::I18nConfig;
+#[cfg(feature = "codegen")]
+mod apple;
+#[cfg(feature = "codegen")]
+pub use apple::{
+    lproj_locale, parse_strings, parse_stringsdict, write_strings, write_stringsdict,
+    PLURAL_CATEGORIES,
+};
+#[cfg(feature = "codegen")]
+mod properties;
+#[cfg(feature = "codegen")]
+pub use properties::{parse_properties, properties_locale, write_properties};
+
+#[cfg(feature = "archive")]
+mod archive_backend;
+#[cfg(feature = "archive")]
+pub use archive_backend::ArchiveBackend;
+
+#[cfg(feature = "ota")]
+mod ota_backend;
+#[cfg(feature = "ota")]
+pub use ota_backend::OtaBackend;
+
+#[cfg(feature = "wasm")]
+mod fetch_backend;
+#[cfg(feature = "wasm")]
+pub use fetch_backend::FetchBackend;
 
+#[cfg(feature = "phf")]
+#[doc(hidden)]
+pub use phf as __phf;
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::HashMap;
+
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::HashMap;
+
+#[cfg(feature = "std")]
 pub fn is_debug() -> bool {
     std::env::var("RUST_I18N_DEBUG").unwrap_or_else(|_| "0".to_string()) == "1"
 }
@@ -26,14 +138,17 @@ use std::fs::File;
 #[cfg(feature = "codegen")]
 use std::io::prelude::*;
 #[cfg(feature = "codegen")]
-use std::{collections::BTreeMap, path::Path};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
-#[cfg(feature = "codegen")]
-type Locale = String;
 #[cfg(feature = "codegen")]
 type Value = serde_json::Value;
 #[cfg(feature = "codegen")]
-type Translations = BTreeMap<Locale, Value>;
+type Translations = BTreeMap<String, Value>;
+#[cfg(feature = "codegen")]
+type Locales = BTreeMap<String, BTreeMap<String, String>>;
 
 #[cfg(feature = "codegen")]
 fn merge_value(a: &mut Value, b: &Value) {
@@ -66,8 +181,62 @@ pub fn try_load_locales<F: Fn(&str) -> bool>(
     ignore_if: F,
     report_file_lookup_errors: bool,
 ) -> Result<BTreeMap<String, BTreeMap<String, String>>, String> {
-    let mut result: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    try_load_locales_with_conflicts(locales_path, ignore_if, report_file_lookup_errors)
+        .map(|(locales, _)| locales)
+}
+
+/// A key defined in more than one locale file with different texts.
+#[cfg(feature = "codegen")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub locale: String,
+    pub key: String,
+    /// The file and text of each definition in the order of loading.
+    pub definitions: Vec<(PathBuf, String)>,
+}
+
+#[cfg(feature = "codegen")]
+impl Conflict {
+    /// The definition used after merging, which is the last one loaded.
+    pub fn winner(&self) -> &(PathBuf, String) {
+        self.definitions
+            .last()
+            .expect("Conflict without definitions")
+    }
+}
+
+/// The key of the files included by a locale file, e.g. `_include: checkout.yml`.
+#[cfg(feature = "codegen")]
+const INCLUDE_KEY: &str = "_include";
+
+/// The extensions of the locale files.
+#[cfg(feature = "codegen")]
+const EXTENSIONS: &[&str] = &[
+    "yml",
+    "yaml",
+    "json",
+    #[cfg(feature = "json5")]
+    "json5",
+    #[cfg(feature = "json5")]
+    "jsonc",
+    "toml",
+    "properties",
+    "strings",
+    "stringsdict",
+    "csv",
+];
+
+/// Load the locales like [`try_load_locales`], also returning the keys defined
+/// in more than one file with different texts.
+#[cfg(feature = "codegen")]
+pub fn try_load_locales_with_conflicts<F: Fn(&str) -> bool>(
+    locales_path: &str,
+    ignore_if: F,
+    report_file_lookup_errors: bool,
+) -> Result<(Locales, Vec<Conflict>), String> {
+    let mut result: Locales = BTreeMap::new();
     let mut translations = BTreeMap::new();
+    let mut definitions: BTreeMap<(String, String), Vec<(PathBuf, String)>> = BTreeMap::new();
 
     let locales_path = match Path::new(locales_path).normalize() {
         Ok(p) => p,
@@ -78,7 +247,7 @@ pub fn try_load_locales<F: Fn(&str) -> bool>(
             return if report_file_lookup_errors {
                 Err(format!("Path '{locales_path}' cannot be normalized: '{e}'"))
             } else {
-                Ok(result)
+                Ok((result, vec![]))
             };
         }
     };
@@ -91,12 +260,12 @@ pub fn try_load_locales<F: Fn(&str) -> bool>(
             return if report_file_lookup_errors {
                 Err("Could not convert path.".to_string())
             } else {
-                Ok(result)
+                Ok((result, vec![]))
             };
         }
     };
 
-    let path_pattern = format!("{locales_path}/**/*.{{yml,yaml,json,toml}}");
+    let path_pattern = format!("{locales_path}/**/*.{{{}}}", EXTENSIONS.join(","));
 
     if is_debug() {
         println!("cargo:i18n-locale={}", &path_pattern);
@@ -110,10 +279,11 @@ pub fn try_load_locales<F: Fn(&str) -> bool>(
         return if report_file_lookup_errors {
             Err(format!("Path '{locales_path}' not found."))
         } else {
-            Ok(result)
+            Ok((result, vec![]))
         };
     }
 
+    let mut entries = vec![];
     for entry in globwalk::glob(&path_pattern)
         .map_err(|error| format!("Failed to read glob pattern: {error}"))?
     {
@@ -125,15 +295,10 @@ pub fn try_load_locales<F: Fn(&str) -> bool>(
         if ignore_if(&entry.display().to_string()) {
             continue;
         }
+        entries.push(entry);
+    }
 
-        let locale = entry
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .and_then(|s| s.split('.').last())
-            .unwrap();
-
-        let ext = entry.extension().and_then(|s| s.to_str()).unwrap();
-
+    let files = par_map(entries, |entry| {
         let file = File::open(&entry)
             .map_err(|error| format!("Failed to open file '{entry:?}': {error}"))?;
         let mut reader = std::io::BufReader::new(file);
@@ -142,50 +307,398 @@ pub fn try_load_locales<F: Fn(&str) -> bool>(
         reader
             .read_to_string(&mut content)
             .map_err(|error| format!("Read file '{entry:?}' failed: {error}."))?;
+        Ok((entry, content))
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, String>>()?;
 
-        let trs = parse_file(&content, ext, locale).map_err(|error| {
-            format!("Parse file `{}` failed, reason: {}", entry.display(), error)
-        })?;
-
-        trs.into_iter().for_each(|(k, new_value)| {
-            translations
-                .entry(k)
-                .and_modify(|old_value| merge_value(old_value, &new_value))
-                .or_insert(new_value);
-        });
+    let parsed = parse_files(files, |path| {
+        if is_debug() {
+            println!("cargo:i18n-load={}", path.display());
+        }
+        std::fs::read_to_string(path).ok()
+    })?;
+    for (entry, trs) in parsed {
+        for (locale, value) in &trs {
+            for (key, text) in flatten_keys("", value) {
+                definitions
+                    .entry((locale.clone(), key))
+                    .or_default()
+                    .push((entry.clone(), text));
+            }
+        }
+        merge_translations(&mut translations, trs);
     }
 
     translations.iter().for_each(|(locale, trs)| {
         result.insert(locale.to_string(), flatten_keys("", trs));
     });
 
-    Ok(result)
+    let conflicts = definitions
+        .into_iter()
+        .filter(|(_, defs)| defs.iter().any(|(_, text)| *text != defs[0].1))
+        .map(|((locale, key), definitions)| Conflict {
+            locale,
+            key,
+            definitions,
+        })
+        .collect();
+
+    Ok((result, conflicts))
+}
+
+/// Load the locales from the contents of locale files, e.g. read from an archive.
+///
+/// The files are named like in a locales directory, e.g. `en.yml` or `app/app.fr.json`,
+/// the files of other types are skipped.
+#[cfg(feature = "codegen")]
+pub fn try_load_locales_from_files<P: AsRef<Path>, C: AsRef<str>>(
+    files: impl IntoIterator<Item = (P, C)>,
+) -> Result<Locales, String> {
+    let files = files
+        .into_iter()
+        .filter(|(path, _)| {
+            let ext = path.as_ref().extension().and_then(|s| s.to_str());
+            ext.is_some_and(|ext| EXTENSIONS.contains(&ext))
+        })
+        .map(|(path, content)| (path.as_ref().to_path_buf(), content.as_ref().to_string()))
+        .collect();
+
+    let mut translations = Translations::new();
+    for (_, trs) in parse_files(files, |_| None)? {
+        merge_translations(&mut translations, trs);
+    }
+
+    Ok(translations
+        .iter()
+        .map(|(locale, trs)| (locale.to_string(), flatten_keys("", trs)))
+        .collect())
 }
 
+/// The locale of a file, from the `.lproj` directory of the Apple files, e.g.
+/// `en.lproj/Localizable.strings`, or the last part of the name, e.g. `app.en.yml`.
 #[cfg(feature = "codegen")]
-fn parse_file(content: &str, ext: &str, locale: &str) -> Result<Translations, String> {
-    let result = match ext {
-        "yml" | "yaml" => serde_saphyr::from_str::<serde_json::Value>(content)
+fn file_locale(path: &Path) -> &str {
+    lproj_locale(path)
+        .or_else(|| path.file_stem()?.to_str()?.split('.').next_back())
+        .unwrap_or_default()
+}
+
+/// Parse a locale file, also returning the paths of its `_include`.
+#[cfg(feature = "codegen")]
+fn parse_file_with_includes(
+    content: &str,
+    ext: &str,
+    locale: &str,
+) -> Result<(Translations, Vec<String>), String> {
+    let documents = match ext {
+        // A YAML stream may have several documents, separated by `---`
+        "yml" | "yaml" => serde_saphyr::from_multiple::<Value>(content)
             .map_err(|err| format!("Invalid YAML format, {}", err)),
-        "json" => serde_json::from_str::<serde_json::Value>(content)
+        "json" => serde_json::from_str::<Value>(content)
+            .map(|v| vec![v])
             .map_err(|err| format!("Invalid JSON format, {}", err)),
-        "toml" => toml::from_str::<serde_json::Value>(content)
+        "toml" => toml::from_str::<toml::Value>(content)
+            .map(|v| vec![toml_to_json(v)])
             .map_err(|err| format!("Invalid TOML format, {}", err)),
+        #[cfg(feature = "json5")]
+        "json5" | "jsonc" => json5::from_str::<Value>(content)
+            .map(|v| vec![v])
+            .map_err(|err| format!("Invalid JSON5 format, {}", err)),
+        // The keys are flat, e.g. `checkout.title`, and the locale may be named like Java,
+        // e.g. `messages_fr`
+        "properties" => {
+            let messages = parse_properties(content)
+                .into_iter()
+                .map(|(key, value)| (key, serde_json::Value::String(value)))
+                .collect();
+            return Ok((
+                Translations::from([(properties_locale(locale), Value::Object(messages))]),
+                vec![],
+            ));
+        }
+        "csv" => return parse_csv(content).map(|trs| (trs, vec![])),
+        // The plural keys of `.stringsdict` are split into `key.{category}`
+        "strings" | "stringsdict" => {
+            let pairs = if ext == "strings" {
+                parse_strings(content)?
+            } else {
+                parse_stringsdict(content)?
+            };
+            let messages = pairs
+                .into_iter()
+                .map(|(key, value)| (key, serde_json::Value::String(value)))
+                .collect();
+            return Ok((
+                Translations::from([(locale.to_string(), Value::Object(messages))]),
+                vec![],
+            ));
+        }
         _ => Err("Invalid file extension".into()),
-    };
+    }?;
 
-    match result {
-        Ok(v) => match get_version(&v) {
-            2 => {
-                if let Some(trs) = parse_file_v2("", &v) {
-                    return Ok(trs);
+    let mut trs = Translations::new();
+    let mut includes = vec![];
+    for mut data in documents {
+        if data.is_null() {
+            continue;
+        }
+        let doc_includes = take_includes(&mut data)?;
+        let doc_trs = match get_version(&data) {
+            2 => match parse_file_v2("", &data) {
+                Some(trs) => trs,
+                // A file with only the `_include` of other files
+                None if !doc_includes.is_empty() => Translations::new(),
+                None => {
+                    return Err("Invalid locale file format, please check the version field".into())
                 }
+            },
+            _ => parse_file_v1(locale, &data),
+        };
+        merge_translations(&mut trs, doc_trs);
+        includes.extend(doc_includes);
+    }
+    Ok((trs, includes))
+}
 
-                Err("Invalid locale file format, please check the version field".into())
+/// Remove the `_include` of a document, a path or a list of paths.
+#[cfg(feature = "codegen")]
+fn take_includes(data: &mut Value) -> Result<Vec<String>, String> {
+    let invalid = || {
+        format!(
+            "Invalid `{}`, expected a path or a list of paths",
+            INCLUDE_KEY
+        )
+    };
+    match data.as_object_mut().and_then(|o| o.remove(INCLUDE_KEY)) {
+        None => Ok(vec![]),
+        Some(Value::String(path)) => Ok(vec![path]),
+        Some(Value::Array(paths)) => paths
+            .into_iter()
+            .map(|path| match path {
+                Value::String(path) => Ok(path),
+                _ => Err(invalid()),
+            })
+            .collect(),
+        Some(_) => Err(invalid()),
+    }
+}
+
+#[cfg(feature = "codegen")]
+fn merge_translations(trs: &mut Translations, other: Translations) {
+    for (locale, new_value) in other {
+        trs.entry(locale)
+            .and_modify(|old_value| merge_value(old_value, &new_value))
+            .or_insert(new_value);
+    }
+}
+
+/// Map the items on the threads of the machine, keeping their order.
+///
+/// The files are read and parsed concurrently, since the projects with hundreds of files
+/// would otherwise pay for them one by one on every build.
+#[cfg(feature = "codegen")]
+fn par_map<T: Send, U: Send>(items: Vec<T>, f: impl Fn(T) -> U + Sync) -> Vec<U> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if threads == 1 || items.len() < 2 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(threads);
+    let mut chunks = vec![];
+    let mut items = items.into_iter().peekable();
+    while items.peek().is_some() {
+        chunks.push(items.by_ref().take(chunk_size).collect::<Vec<_>>());
+    }
+
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || chunk.into_iter().map(f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// Parse the locale files with the files of their `_include`, relative to them. The included
+/// files are loaded into the files including them, not on their own, and read with `read`
+/// when they aren't in `files`.
+#[cfg(feature = "codegen")]
+fn parse_files(
+    files: Vec<(PathBuf, String)>,
+    read: impl Fn(&Path) -> Option<String> + Sync,
+) -> Result<Vec<(PathBuf, Translations)>, String> {
+    let contents = files
+        .iter()
+        .map(|(path, content)| (normalize_path(path), content.as_str()))
+        .collect::<BTreeMap<_, _>>();
+    let results = par_map(files.iter().collect(), |(path, content)| {
+        let mut included = std::collections::BTreeSet::new();
+        let mut loader = IncludeLoader {
+            contents: &contents,
+            read: &read,
+            included: &mut included,
+            stack: vec![],
+        };
+        let trs = loader.parse(path, content, file_locale(path))?;
+        Ok::<_, String>((path.clone(), trs, included))
+    });
+
+    let mut included = std::collections::BTreeSet::new();
+    let mut parsed = vec![];
+    for result in results {
+        let (path, trs, file_included) = result?;
+        included.extend(file_included);
+        parsed.push((path, trs));
+    }
+
+    Ok(parsed
+        .into_iter()
+        .filter(|(path, _)| !included.contains(&normalize_path(path)))
+        .collect())
+}
+
+#[cfg(feature = "codegen")]
+struct IncludeLoader<'a, R> {
+    contents: &'a BTreeMap<PathBuf, &'a str>,
+    read: &'a R,
+    included: &'a mut std::collections::BTreeSet<PathBuf>,
+    /// The files being parsed, to detect the include cycles.
+    stack: Vec<PathBuf>,
+}
+
+#[cfg(feature = "codegen")]
+impl<R: Fn(&Path) -> Option<String>> IncludeLoader<'_, R> {
+    /// Parse a file in the locale of the file including it, its own keys override the
+    /// included ones.
+    fn parse(&mut self, path: &Path, content: &str, locale: &str) -> Result<Translations, String> {
+        let ext = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        // Other spreadsheets, e.g. an export left in the locales directory, aren't translations
+        if ext == "csv" && !csv_has_key_column(content) {
+            eprintln!(
+                "warning: rust-i18n: `{}` is skipped, it has no `key` column",
+                path.display()
+            );
+            return Ok(Translations::new());
+        }
+        let (own, includes) = parse_file_with_includes(content, ext, locale).map_err(|error| {
+            format!("Parse file `{}` failed, reason: {}", path.display(), error)
+        })?;
+
+        let mut trs = Translations::new();
+        self.stack.push(normalize_path(path));
+        for include in includes {
+            let include_path =
+                normalize_path(&path.parent().unwrap_or(Path::new("")).join(&include));
+            if self.stack.contains(&include_path) {
+                return Err(format!(
+                    "Include cycle of `{}` in `{}`",
+                    include,
+                    path.display()
+                ));
             }
-            _ => Ok(parse_file_v1(locale, &v)),
-        },
-        Err(e) => Err(e),
+            let content = match self.contents.get(&include_path) {
+                Some(content) => content.to_string(),
+                None => (self.read)(&include_path).ok_or_else(|| {
+                    format!(
+                        "Included file `{}` of `{}` not found",
+                        include,
+                        path.display()
+                    )
+                })?,
+            };
+            merge_translations(&mut trs, self.parse(&include_path, &content, locale)?);
+            self.included.insert(include_path);
+        }
+        self.stack.pop();
+
+        merge_translations(&mut trs, own);
+        Ok(trs)
+    }
+}
+
+/// Resolve the `.` and `..` of a path, without reading the file system.
+#[cfg(feature = "codegen")]
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir if result.file_name().is_some() => {
+                result.pop();
+            }
+            component => result.push(component),
+        }
+    }
+    result
+}
+
+#[cfg(feature = "codegen")]
+fn csv_has_key_column(content: &str) -> bool {
+    // The invalid files are reported by `parse_csv`
+    match csv::Reader::from_reader(content.as_bytes()).headers() {
+        Ok(headers) => headers.iter().any(|header| header.trim() == "key"),
+        Err(_) => true,
+    }
+}
+
+/// Parse a CSV with a `key` column and a column per locale, like the spreadsheet exported by
+/// `cargo i18n export`, the `locations` column and the empty texts are ignored.
+#[cfg(feature = "codegen")]
+fn parse_csv(content: &str) -> Result<Translations, String> {
+    let invalid = |err: csv::Error| format!("Invalid CSV format, {}", err);
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers().map_err(invalid)?.clone();
+    let key_index = headers
+        .iter()
+        .position(|header| header.trim() == "key")
+        .ok_or("Missing `key` column in CSV header")?;
+
+    let mut trs = BTreeMap::<String, serde_json::Map<String, Value>>::new();
+    for record in reader.records() {
+        let record = record.map_err(invalid)?;
+        let key = record.get(key_index).unwrap_or_default().trim();
+        if key.is_empty() {
+            continue;
+        }
+        for (i, locale) in headers.iter().map(str::trim).enumerate() {
+            let text = record.get(i).unwrap_or_default();
+            if i == key_index || locale == "locations" || locale.is_empty() || text.is_empty() {
+                continue;
+            }
+            trs.entry(locale.to_string())
+                .or_default()
+                .insert(key.to_string(), text.into());
+        }
+    }
+    Ok(trs
+        .into_iter()
+        .map(|(locale, messages)| (locale, Value::Object(messages)))
+        .collect())
+}
+
+/// Convert a TOML value, with the dates as strings like in YAML, e.g. `1979-05-27`.
+#[cfg(feature = "codegen")]
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(s) => s.into(),
+        toml::Value::Integer(i) => i.into(),
+        toml::Value::Float(f) => f.into(),
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(d) => d.to_string().into(),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => table
+            .into_iter()
+            .map(|(k, v)| (k, toml_to_json(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
     }
 }
 
@@ -202,7 +715,7 @@ fn parse_file_v2(key_prefix: &str, data: &serde_json::Value) -> Option<Translati
         for (key, value) in messages {
             if let serde_json::Value::Object(sub_messages) = value {
                 for (locale, text) in sub_messages {
-                    if text.is_string() {
+                    if !text.is_object() && !text.is_null() {
                         let key = format_keys(&[key_prefix, key]);
                         let sub_trs = BTreeMap::from([(key, text.clone())]);
                         let sub_value = serde_json::to_value(&sub_trs).unwrap();
@@ -244,7 +757,7 @@ fn get_version(data: &serde_json::Value) -> usize {
     1
 }
 
-#[cfg(feature = "codegen")]
+#[cfg(any(feature = "codegen", feature = "wasm"))]
 fn format_keys(keys: &[&str]) -> String {
     keys.iter()
         .filter(|k| !k.is_empty())
@@ -253,9 +766,43 @@ fn format_keys(keys: &[&str]) -> String {
         .join(".")
 }
 
-#[cfg(feature = "codegen")]
-fn flatten_keys(prefix: &str, trs: &Value) -> BTreeMap<String, String> {
-    let mut v = BTreeMap::<String, String>::new();
+#[cfg(any(feature = "codegen", feature = "wasm"))]
+fn flatten_keys(
+    prefix: &str,
+    trs: &serde_json::Value,
+) -> std::collections::BTreeMap<String, String> {
+    let mut v = flatten_value(prefix, trs);
+
+    // List the count intervals of the keys, e.g. `inbox.0`, `inbox.1` and `inbox.2..`,
+    // as `inbox._intervals` for `select_interval`
+    let mut intervals = std::collections::BTreeMap::<&str, (Vec<&str>, bool)>::new();
+    for key in v.keys() {
+        let split = key.match_indices('.').find_map(|(i, _)| {
+            let interval = &key[i + 1..];
+            parse_interval(interval).map(|_| (&key[..i], interval))
+        });
+        if let Some((prefix, interval)) = split {
+            let (list, has_range) = intervals.entry(prefix).or_default();
+            list.push(interval);
+            *has_range |= interval::is_interval_range(interval);
+        }
+    }
+    let intervals = intervals
+        .into_iter()
+        .filter(|(_, (_, has_range))| *has_range)
+        .map(|(prefix, (list, _))| (format!("{}.{}", prefix, INTERVALS_KEY), list.join(",")))
+        .collect::<Vec<_>>();
+    v.extend(intervals);
+
+    v
+}
+
+#[cfg(any(feature = "codegen", feature = "wasm"))]
+fn flatten_value(
+    prefix: &str,
+    trs: &serde_json::Value,
+) -> std::collections::BTreeMap<String, String> {
+    let mut v = std::collections::BTreeMap::<String, String>::new();
     let prefix = prefix.to_string();
 
     match &trs {
@@ -269,7 +816,7 @@ fn flatten_keys(prefix: &str, trs: &Value) -> BTreeMap<String, String> {
                 } else {
                     format!("{}.{}", prefix, k)
                 };
-                v.extend(flatten_keys(key.as_str(), vv));
+                v.extend(flatten_value(key.as_str(), vv));
             }
         }
         serde_json::Value::Null => {
@@ -281,8 +828,14 @@ fn flatten_keys(prefix: &str, trs: &Value) -> BTreeMap<String, String> {
         serde_json::Value::Number(s) => {
             v.insert(prefix, format!("{}", s));
         }
-        serde_json::Value::Array(_) => {
-            v.insert(prefix, "".into());
+        // The items are indexed, e.g. `days.0`
+        serde_json::Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                v.extend(flatten_value(
+                    &format_keys(&[&prefix, &i.to_string()]),
+                    item,
+                ));
+            }
         }
     }
 
@@ -291,7 +844,14 @@ fn flatten_keys(prefix: &str, trs: &Value) -> BTreeMap<String, String> {
 
 #[cfg(all(test, feature = "codegen"))]
 mod tests {
-    use super::{merge_value, parse_file};
+    use super::{
+        merge_value, par_map, parse_file_with_includes, try_load_locales_from_files,
+        try_load_locales_with_conflicts, Translations,
+    };
+
+    fn parse_file(content: &str, ext: &str, locale: &str) -> Result<Translations, String> {
+        parse_file_with_includes(content, ext, locale).map(|(trs, _)| trs)
+    }
 
     #[test]
     fn test_merge_value() {
@@ -353,6 +913,26 @@ mod tests {
         assert_eq!(trs["en"]["bar"], "Bar");
     }
 
+    #[cfg(feature = "json5")]
+    #[test]
+    fn test_parse_file_in_json5() {
+        let content = r#"{
+            // A comment
+            foo: "Foo",
+            "bar": {
+                /* Nested */
+                "baz": 'Baz',
+            },
+        }"#;
+        for ext in ["json5", "jsonc"] {
+            let trs = parse_file(content, ext, "en").expect("Should ok");
+            assert_eq!(trs["en"]["foo"], "Foo");
+            assert_eq!(trs["en"]["bar"]["baz"], "Baz");
+        }
+
+        parse_file("{ foo: }", "json5", "en").expect_err("Should error");
+    }
+
     #[test]
     fn test_get_version() {
         let json = serde_saphyr::from_str::<serde_json::Value>("_version: 2").unwrap();
@@ -405,4 +985,419 @@ mod tests {
         assert_eq!(trs["zh-CN"]["welcome.sub"], "欢迎 1");
         assert_eq!(trs["jp"]["welcome.sub"], "ようこそ 1");
     }
+
+    #[test]
+    fn test_parse_file_in_yaml_with_anchors() {
+        let content = r#"
+        product: &product Acme
+        legal: &legal
+          terms: Terms of %{product}
+          privacy: Privacy
+        checkout:
+          <<: *legal
+          privacy: Privacy policy
+          name: *product
+        signup:
+          <<: [*legal]
+        "#;
+        let trs = parse_file(content, "yml", "en").expect("Should ok");
+        assert_eq!(trs["en"]["checkout"]["terms"], "Terms of %{product}");
+        assert_eq!(trs["en"]["checkout"]["privacy"], "Privacy policy");
+        assert_eq!(trs["en"]["checkout"]["name"], "Acme");
+        assert_eq!(trs["en"]["signup"]["privacy"], "Privacy");
+        assert!(trs["en"]["checkout"].get("<<").is_none());
+
+        let content = r#"
+        _version: 2
+        legal: &legal
+          en: Terms
+          fr: Conditions
+        checkout:
+          terms: *legal
+          title:
+            <<: *legal
+            fr: Conditions générales
+        "#;
+        let trs = parse_file(content, "yml", "filename").expect("Should ok");
+        assert_eq!(trs["fr"]["checkout.terms"], "Conditions");
+        assert_eq!(trs["en"]["checkout.title"], "Terms");
+        assert_eq!(trs["fr"]["checkout.title"], "Conditions générales");
+    }
+
+    #[test]
+    fn test_parse_file_parity() {
+        let files = [
+            (
+                "en.yml",
+                r#"
+                hello: Hello
+                released: 1979-05-27
+                nested:
+                  count: 3
+                  enabled: true
+                  deep:
+                    deeper:
+                      key: Deep
+                days:
+                  - Monday
+                  - Tuesday
+                "#,
+            ),
+            (
+                "en.json",
+                r#"{
+                    "hello": "Hello",
+                    "released": "1979-05-27",
+                    "nested": {
+                        "count": 3,
+                        "enabled": true,
+                        "deep": { "deeper": { "key": "Deep" } }
+                    },
+                    "days": ["Monday", "Tuesday"]
+                }"#,
+            ),
+            (
+                "en.toml",
+                r#"
+                hello = "Hello"
+                released = 1979-05-27
+                days = ["Monday", "Tuesday"]
+
+                [nested]
+                count = 3
+                enabled = true
+
+                [nested.deep.deeper]
+                key = "Deep"
+                "#,
+            ),
+            (
+                "app.yml",
+                r#"
+                _version: 2
+                hello:
+                  en: Hello
+                released:
+                  en: 1979-05-27
+                nested:
+                  count:
+                    en: 3
+                  enabled:
+                    en: true
+                  deep:
+                    deeper:
+                      key:
+                        en: Deep
+                days:
+                  en: [Monday, Tuesday]
+                "#,
+            ),
+            (
+                "app.json",
+                r#"{
+                    "_version": 2,
+                    "hello": { "en": "Hello" },
+                    "released": { "en": "1979-05-27" },
+                    "nested": {
+                        "count": { "en": 3 },
+                        "enabled": { "en": true },
+                        "deep": { "deeper": { "key": { "en": "Deep" } } }
+                    },
+                    "days": { "en": ["Monday", "Tuesday"] }
+                }"#,
+            ),
+            (
+                "app.toml",
+                r#"
+                _version = 2
+
+                [hello]
+                en = "Hello"
+
+                [released]
+                en = 1979-05-27
+
+                [nested]
+                count.en = 3
+                enabled.en = true
+
+                [nested.deep.deeper.key]
+                en = "Deep"
+
+                [days]
+                en = ["Monday", "Tuesday"]
+                "#,
+            ),
+        ];
+
+        let expected = std::collections::BTreeMap::from([
+            ("days.0".to_string(), "Monday".to_string()),
+            ("days.1".to_string(), "Tuesday".to_string()),
+            ("hello".to_string(), "Hello".to_string()),
+            ("nested.count".to_string(), "3".to_string()),
+            ("nested.deep.deeper.key".to_string(), "Deep".to_string()),
+            ("nested.enabled".to_string(), "true".to_string()),
+            ("released".to_string(), "1979-05-27".to_string()),
+        ]);
+        for (path, content) in files {
+            let content = content
+                .lines()
+                .map(|line| line.strip_prefix("                ").unwrap_or(line))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let locales = try_load_locales_from_files([(path, content)]).expect("Should ok");
+            assert_eq!(locales["en"], expected, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_load_locales_with_conflicts() {
+        let dir = std::env::temp_dir().join(format!("rust-i18n-conflicts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("en.yml"), "hello: Hello\nsame: Same").unwrap();
+        std::fs::write(
+            dir.join("app.yml"),
+            "_version: 2\nhello:\n  en: Hi\n  fr: Bonjour\nsame:\n  en: Same",
+        )
+        .unwrap();
+
+        let (locales, conflicts) =
+            try_load_locales_with_conflicts(&dir.display().to_string(), |_| false, true)
+                .expect("Should ok");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(
+            (conflict.locale.as_str(), conflict.key.as_str()),
+            ("en", "hello")
+        );
+        assert_eq!(conflict.definitions.len(), 2);
+        assert_eq!(locales["en"]["hello"], conflict.winner().1);
+    }
+
+    #[test]
+    fn test_parse_file_in_yaml_with_documents() {
+        let content = "hello: Hello\n---\n_version: 2\nbye:\n  en: Bye\n  fr: Au revoir\n---\n";
+        let trs = parse_file(content, "yml", "en").expect("Should ok");
+        assert_eq!(trs["en"]["hello"], "Hello");
+        assert_eq!(trs["en"]["bye"], "Bye");
+        assert_eq!(trs["fr"]["bye"], "Au revoir");
+
+        let (_, includes) = parse_file_with_includes(
+            "_include: [a.yml, b.yml]\n---\n_include: c.yml",
+            "yml",
+            "en",
+        )
+        .expect("Should ok");
+        assert_eq!(includes, vec!["a.yml", "b.yml", "c.yml"]);
+        parse_file_with_includes("_include: 1", "yml", "en").expect_err("Should error");
+    }
+
+    #[test]
+    fn test_load_locales_with_includes() {
+        let locales = try_load_locales_from_files([
+            (
+                "locales/en.yml",
+                "_include: en/checkout.yml\nhello: Hello",
+            ),
+            (
+                "locales/en/checkout.yml",
+                "hello: Overridden\ncheckout:\n  title: Checkout",
+            ),
+            (
+                "locales/app.yml",
+                "_version: 2\n_include: [shared/legal.yml]",
+            ),
+            (
+                "locales/shared/legal.yml",
+                "_version: 2\n_include: ./../shared/privacy.yml\nterms:\n  en: Terms\n  fr: Conditions",
+            ),
+            (
+                "locales/shared/privacy.yml",
+                "_version: 2\nprivacy:\n  en: Privacy",
+            ),
+        ])
+        .expect("Should ok");
+        assert_eq!(locales.keys().collect::<Vec<_>>(), vec!["en", "fr"]);
+        assert_eq!(locales["en"]["hello"], "Hello");
+        assert_eq!(locales["en"]["checkout.title"], "Checkout");
+        assert_eq!(locales["en"]["terms"], "Terms");
+        assert_eq!(locales["fr"]["terms"], "Conditions");
+        assert_eq!(locales["en"]["privacy"], "Privacy");
+
+        try_load_locales_from_files([("en.yml", "_include: missing.yml")])
+            .expect_err("Should error");
+        try_load_locales_from_files([
+            ("en.yml", "_include: fr.yml"),
+            ("fr.yml", "_include: en.yml"),
+        ])
+        .expect_err("Should error");
+
+        // The included files outside the locales path are read from the disk
+        let dir = std::env::temp_dir().join(format!("rust-i18n-includes-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("locales")).unwrap();
+        std::fs::write(dir.join("shared.yml"), "_version: 2\nbye:\n  en: Bye").unwrap();
+        std::fs::write(
+            dir.join("locales/app.yml"),
+            "_version: 2\n_include: ../shared.yml",
+        )
+        .unwrap();
+        let locales =
+            super::try_load_locales(&dir.join("locales").display().to_string(), |_| false, true)
+                .expect("Should ok");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(locales["en"]["bye"], "Bye");
+    }
+
+    #[test]
+    fn test_load_locales_with_intervals() {
+        let locales = try_load_locales_from_files([
+            (
+                "en.yml",
+                "inbox:\n  title: Inbox\n  \"0\": No messages\n  \"1\": One message\n  \"2..\": \"%{count} messages\"\ndays: [Monday, Tuesday]",
+            ),
+            (
+                "app.yml",
+                "_version: 2\ncart:\n  \"0\":\n    en: Empty\n    fr: Vide\n  \"1..9\":\n    en: A few items\n    fr: Quelques articles",
+            ),
+        ])
+        .expect("Should ok");
+        assert_eq!(locales["en"]["inbox._intervals"], "0,1,2..");
+        assert_eq!(locales["en"]["inbox.2.."], "%{count} messages");
+        assert_eq!(locales["en"]["cart._intervals"], "0,1..9");
+        assert_eq!(locales["fr"]["cart._intervals"], "0,1..9");
+        assert_eq!(locales["fr"]["cart.1..9"], "Quelques articles");
+        // The arrays have no ranges
+        assert!(!locales["en"].contains_key("days._intervals"));
+    }
+
+    #[test]
+    fn test_par_map() {
+        let items = (0..100).collect::<Vec<_>>();
+        assert_eq!(
+            par_map(items, |i| i * 2),
+            (0..100).map(|i| i * 2).collect::<Vec<_>>()
+        );
+        assert_eq!(par_map(vec![1], |i| i + 1), vec![2]);
+        assert!(par_map(Vec::<i32>::new(), |i| i).is_empty());
+    }
+
+    #[test]
+    fn test_load_locales_from_many_files() {
+        // The later files win, whatever thread parsed them
+        let files = (0..64)
+            .map(|i| {
+                (
+                    format!("locales/{i:02}.en.yml"),
+                    format!("hello: Hello {i}\nkey{i}: {i}"),
+                )
+            })
+            .collect::<Vec<_>>();
+        let locales = try_load_locales_from_files(files).expect("Should ok");
+
+        assert_eq!(locales["en"]["hello"], "Hello 63");
+        assert_eq!(locales["en"]["key0"], "0");
+        assert_eq!(locales["en"]["key63"], "63");
+    }
+
+    #[test]
+    fn test_load_locales_from_many_files_with_error() {
+        let mut files = (0..64)
+            .map(|i| (format!("locales/{i:02}.en.yml"), format!("key{i}: {i}")))
+            .collect::<Vec<_>>();
+        files[41].1 = "key41: [".to_string();
+
+        let error = try_load_locales_from_files(files).expect_err("Should error");
+        assert!(error.contains("locales/41.en.yml"), "{}", error);
+    }
+
+    #[test]
+    fn test_load_locales_with_conflicts_in_many_files() {
+        let dir = std::env::temp_dir().join(format!("rust-i18n-many-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 0..48 {
+            std::fs::write(
+                dir.join(format!("{i:02}.en.yml")),
+                format!("hello: Hello {i}\nkey{i}: {i}"),
+            )
+            .unwrap();
+        }
+
+        let load = || {
+            try_load_locales_with_conflicts(&dir.display().to_string(), |_| false, true)
+                .expect("Should ok")
+        };
+        let (locales, conflicts) = load();
+        for _ in 0..8 {
+            let (other_locales, other_conflicts) = load();
+            assert_eq!(other_locales, locales);
+            assert_eq!(other_conflicts.len(), 1);
+            assert_eq!(other_conflicts[0].definitions, conflicts[0].definitions);
+        }
+        std::fs::write(dir.join("07.en.yml"), "hello: [").unwrap();
+        let error = try_load_locales_with_conflicts(&dir.display().to_string(), |_| false, true)
+            .expect_err("Should error");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(error.contains("07.en.yml"), "{}", error);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].key, "hello");
+        assert_eq!(conflicts[0].definitions.len(), 48);
+        assert_eq!(locales["en"]["hello"], conflicts[0].winner().1);
+        assert_eq!(locales["en"].len(), 49);
+    }
+
+    #[test]
+    fn test_load_locales_from_files() {
+        let locales = try_load_locales_from_files([
+            ("locales/en.yml", "hello: Hello\nnested:\n  key: Nested"),
+            ("locales/app.fr.json", r#"{"hello": "Bonjour"}"#),
+            ("locales/app.yml", "_version: 2\nbye:\n  en: Bye"),
+            (
+                "locales/messages_de.properties",
+                "checkout.title = Kasse \\u00fcber",
+            ),
+            (
+                "locales/zh-CN.lproj/Localizable.strings",
+                "\"checkout.title\" = \"结账\";",
+            ),
+            (
+                "locales/zh-CN.lproj/Localizable.stringsdict",
+                "<plist><dict><key>apples</key><dict>\
+                 <key>NSStringLocalizedFormatKey</key><string>%#@n@</string>\
+                 <key>n</key><dict>\
+                 <key>NSStringFormatSpecTypeKey</key><string>NSStringPluralRuleType</string>\
+                 <key>other</key><string>%d 个苹果</string>\
+                 </dict></dict></dict></plist>",
+            ),
+            (
+                "locales/all.csv",
+                "key,en,es,locations\nbye,,Adiós,src/main.rs:1\nwelcome,\"Welcome, %{name}\",,\n",
+            ),
+            ("README.md", "# Locales"),
+        ])
+        .expect("Should ok");
+
+        assert_eq!(locales["en"]["hello"], "Hello");
+        assert_eq!(locales["en"]["nested.key"], "Nested");
+        assert_eq!(locales["en"]["bye"], "Bye");
+        assert_eq!(locales["fr"]["hello"], "Bonjour");
+        assert_eq!(locales["de"]["checkout.title"], "Kasse über");
+        assert_eq!(locales["zh-CN"]["checkout.title"], "结账");
+        assert_eq!(locales["zh-CN"]["apples.other"], "%{count} 个苹果");
+        assert_eq!(locales["en"]["welcome"], "Welcome, %{name}");
+        assert_eq!(locales["es"]["bye"], "Adiós");
+        assert!(!locales["en"].contains_key("locations"));
+
+        try_load_locales_from_files([("en.yml", "hello: [")]).expect_err("Should error");
+
+        // Not a catalog, skipped
+        let locales = try_load_locales_from_files([
+            ("locales/en.yml", "hello: Hello"),
+            ("locales/export.csv", "name,total\nsales,42\n"),
+        ])
+        .expect("Should ok");
+        assert_eq!(locales.keys().collect::<Vec<_>>(), vec!["en"]);
+    }
 }
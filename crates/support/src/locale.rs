@@ -0,0 +1,240 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+/// A validated and normalized [BCP 47](https://www.rfc-editor.org/info/bcp47) language tag.
+///
+/// Parsing accepts `_` as separator and any letter case, and normalizes the tag
+/// to the canonical form, so `en_us`, `EN-us` and `en-US` are the same locale.
+///
+/// - Language is lowercase: `EN` -> `en`.
+/// - Script is titlecase: `hant` -> `Hant`.
+/// - Region is uppercase: `us` -> `US`.
+/// - Variants, extensions and private use subtags are lowercase.
+///
+/// ```
+/// # use rust_i18n_support::Locale;
+/// let locale: Locale = "zh_hant_tw".parse().unwrap();
+/// assert_eq!(locale, "zh-Hant-TW");
+/// assert_eq!(locale.language(), "zh");
+/// assert_eq!(locale.script(), Some("Hant"));
+/// assert_eq!(locale.region(), Some("TW"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Locale(String);
+
+/// An error returned when parsing an invalid [`Locale`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLocaleError {
+    tag: String,
+}
+
+impl fmt::Display for ParseLocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid locale `{}`", self.tag)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseLocaleError {}
+
+impl Locale {
+    /// Parse and normalize a language tag.
+    pub fn parse(tag: &str) -> Result<Self, ParseLocaleError> {
+        let error = || ParseLocaleError { tag: tag.into() };
+
+        let subtags = tag.trim().split(['-', '_']).collect::<Vec<_>>();
+        let (language, rest) = subtags.split_first().ok_or_else(error)?;
+        if !(2..=8).contains(&language.len()) || !is_alpha(language) {
+            return Err(error());
+        }
+
+        let mut normalized = language.to_ascii_lowercase();
+        // Only the subtags before the first singleton (extension or private use) have
+        // positional meaning, the rest is lowercased as is.
+        let mut in_extension = false;
+        for (i, subtag) in rest.iter().enumerate() {
+            if subtag.is_empty()
+                || subtag.len() > 8
+                || !subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+            {
+                return Err(error());
+            }
+
+            normalized.push('-');
+            if subtag.len() == 1 {
+                in_extension = true;
+            }
+
+            if in_extension {
+                normalized.push_str(&subtag.to_ascii_lowercase());
+            } else if i == 0 && subtag.len() == 4 && is_alpha(subtag) {
+                // Script
+                normalized.push_str(&subtag[..1].to_ascii_uppercase());
+                normalized.push_str(&subtag[1..].to_ascii_lowercase());
+            } else if (subtag.len() == 2 && is_alpha(subtag))
+                || (subtag.len() == 3 && subtag.bytes().all(|b| b.is_ascii_digit()))
+            {
+                // Region
+                normalized.push_str(&subtag.to_ascii_uppercase());
+            } else {
+                normalized.push_str(&subtag.to_ascii_lowercase());
+            }
+        }
+
+        if ends_with_singleton(&normalized) {
+            return Err(error());
+        }
+
+        Ok(Self(normalized))
+    }
+
+    /// Get the normalized tag.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Get the language subtag, e.g. `zh` in `zh-Hant-TW`.
+    pub fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+
+    /// Get the script subtag, e.g. `Hant` in `zh-Hant-TW`.
+    pub fn script(&self) -> Option<&str> {
+        self.positional_subtags()
+            .find(|s| s.len() == 4 && s.as_bytes()[0].is_ascii_uppercase())
+    }
+
+    /// Get the region subtag, e.g. `TW` in `zh-Hant-TW`.
+    pub fn region(&self) -> Option<&str> {
+        self.positional_subtags().find(|s| {
+            (s.len() == 2 && s.bytes().all(|b| b.is_ascii_uppercase()))
+                || (s.len() == 3 && s.bytes().all(|b| b.is_ascii_digit()))
+        })
+    }
+
+    fn positional_subtags(&self) -> impl Iterator<Item = &str> {
+        self.0.split('-').skip(1).take_while(|s| s.len() > 1)
+    }
+
+    /// Consume the locale, returning the normalized tag.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+fn is_alpha(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn ends_with_singleton(tag: &str) -> bool {
+    tag.rsplit('-').next().is_some_and(|s| s.len() == 1)
+}
+
+impl FromStr for Locale {
+    type Err = ParseLocaleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Locale {
+    type Error = ParseLocaleError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = ParseLocaleError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> Self {
+        locale.0
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Deref for Locale {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Locale {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Locale {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Locale {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Locale;
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(Locale::parse("en").unwrap(), "en");
+        assert_eq!(Locale::parse("EN").unwrap(), "en");
+        assert_eq!(Locale::parse("en-us").unwrap(), "en-US");
+        assert_eq!(Locale::parse("en_US").unwrap(), "en-US");
+        assert_eq!(Locale::parse(" zh_hant_tw ").unwrap(), "zh-Hant-TW");
+        assert_eq!(Locale::parse("es-419").unwrap(), "es-419");
+        assert_eq!(Locale::parse("de-CH-1996").unwrap(), "de-CH-1996");
+        assert_eq!(
+            Locale::parse("zh-Hant-CN-x-Private1-PRIVATE2").unwrap(),
+            "zh-Hant-CN-x-private1-private2"
+        );
+        assert_eq!(Locale::parse("en-u-ca-GB").unwrap(), "en-u-ca-gb");
+
+        assert!(Locale::parse("").is_err());
+        assert!(Locale::parse("e").is_err());
+        assert!(Locale::parse("en-").is_err());
+        assert!(Locale::parse("en--US").is_err());
+        assert!(Locale::parse("12").is_err());
+        assert!(Locale::parse("en-x").is_err());
+        assert!(Locale::parse("en-toolongsubtag").is_err());
+        assert!(Locale::parse("en US").is_err());
+    }
+
+    #[test]
+    fn test_locale_subtags() {
+        let locale: Locale = "zh-hant-tw".parse().unwrap();
+        assert_eq!(locale.language(), "zh");
+        assert_eq!(locale.script(), Some("Hant"));
+        assert_eq!(locale.region(), Some("TW"));
+        assert_eq!(locale.to_string(), "zh-Hant-TW");
+
+        let locale: Locale = "en-x-us".parse().unwrap();
+        assert_eq!(locale.language(), "en");
+        assert_eq!(locale.script(), None);
+        assert_eq!(locale.region(), None);
+    }
+}
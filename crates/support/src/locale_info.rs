@@ -0,0 +1,210 @@
+use alloc::string::{String, ToString};
+
+use crate::Locale;
+
+/// The direction of text in a locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// Left to right, e.g. English.
+    Ltr,
+    /// Right to left, e.g. Arabic, Hebrew.
+    Rtl,
+}
+
+/// Languages written right to left by default.
+static RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku", "ps", "sd", "syr", "ug", "ur", "yi",
+];
+
+/// Scripts written right to left.
+static RTL_SCRIPTS: &[&str] = &["Adlm", "Arab", "Hebr", "Nkoo", "Rohg", "Syrc", "Thaa"];
+
+/// Native names of languages, a subset of the CLDR data.
+///
+/// More specific tags must come before the language, e.g. `zh-Hant` before `zh`.
+static NATIVE_NAMES: &[(&str, &str)] = &[
+    ("ar", "العربية"),
+    ("bg", "Български"),
+    ("bn", "বাংলা"),
+    ("ca", "Català"),
+    ("cs", "Čeština"),
+    ("da", "Dansk"),
+    ("de", "Deutsch"),
+    ("el", "Ελληνικά"),
+    ("en", "English"),
+    ("es", "Español"),
+    ("et", "Eesti"),
+    ("fa", "فارسی"),
+    ("fi", "Suomi"),
+    ("fil", "Filipino"),
+    ("fr", "Français"),
+    ("he", "עברית"),
+    ("hi", "हिन्दी"),
+    ("hr", "Hrvatski"),
+    ("hu", "Magyar"),
+    ("id", "Indonesia"),
+    ("it", "Italiano"),
+    ("ja", "日本語"),
+    ("ko", "한국어"),
+    ("lt", "Lietuvių"),
+    ("lv", "Latviešu"),
+    ("ms", "Melayu"),
+    ("nb", "Norsk bokmål"),
+    ("nl", "Nederlands"),
+    ("no", "Norsk"),
+    ("pl", "Polski"),
+    ("pt-BR", "Português (Brasil)"),
+    ("pt-PT", "Português (Portugal)"),
+    ("pt", "Português"),
+    ("ro", "Română"),
+    ("ru", "Русский"),
+    ("sk", "Slovenčina"),
+    ("sl", "Slovenščina"),
+    ("sr", "Српски"),
+    ("sv", "Svenska"),
+    ("sw", "Kiswahili"),
+    ("ta", "தமிழ்"),
+    ("th", "ไทย"),
+    ("tr", "Türkçe"),
+    ("uk", "Українська"),
+    ("ur", "اردو"),
+    ("vi", "Tiếng Việt"),
+    ("zh-Hant", "繁體中文"),
+    ("zh-TW", "繁體中文"),
+    ("zh-HK", "繁體中文（香港）"),
+    ("zh-MO", "繁體中文（澳門）"),
+    ("zh-Hans", "简体中文"),
+    ("zh-CN", "简体中文"),
+    ("zh-SG", "简体中文（新加坡）"),
+    ("zh", "中文"),
+];
+
+/// Metadata of a locale, useful for building language pickers.
+///
+/// ```
+/// # use rust_i18n_support::{LocaleInfo, TextDirection};
+/// let info = LocaleInfo::new("zh-Hant-TW");
+/// assert_eq!(info.code, "zh-Hant-TW");
+/// assert_eq!(info.language, "zh");
+/// assert_eq!(info.script.as_deref(), Some("Hant"));
+/// assert_eq!(info.region.as_deref(), Some("TW"));
+/// assert_eq!(info.native_name, Some("繁體中文"));
+/// assert_eq!(info.direction, TextDirection::Ltr);
+///
+/// assert_eq!(LocaleInfo::new("ar").direction, TextDirection::Rtl);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocaleInfo {
+    /// The locale as used in the translations, e.g. `zh-CN`.
+    pub code: String,
+    /// The language subtag, e.g. `zh`.
+    pub language: String,
+    /// The script subtag, e.g. `Hans`.
+    pub script: Option<String>,
+    /// The region subtag, e.g. `CN`.
+    pub region: Option<String>,
+    /// The name of the locale in its own language, e.g. `简体中文`.
+    pub native_name: Option<&'static str>,
+    /// The text direction.
+    pub direction: TextDirection,
+}
+
+impl LocaleInfo {
+    /// Build the metadata of a locale.
+    pub fn new(code: &str) -> Self {
+        let (language, script, region) = match Locale::parse(code) {
+            Ok(locale) => (
+                locale.language().to_string(),
+                locale.script().map(|s| s.to_string()),
+                locale.region().map(|s| s.to_string()),
+            ),
+            Err(_) => (code.to_string(), None, None),
+        };
+
+        Self {
+            code: code.to_string(),
+            native_name: native_name(code),
+            direction: text_direction(code),
+            language,
+            script,
+            region,
+        }
+    }
+
+    /// Returns `true` if the locale is written right to left.
+    pub fn is_rtl(&self) -> bool {
+        self.direction == TextDirection::Rtl
+    }
+}
+
+/// Get the text direction of a locale.
+pub fn text_direction(locale: &str) -> TextDirection {
+    let mut subtags = locale.split(['-', '_']);
+    let language = subtags.next().unwrap_or_default();
+    let script = subtags.next().filter(|s| s.len() == 4);
+
+    let is_rtl = match script {
+        Some(script) => RTL_SCRIPTS.iter().any(|s| s.eq_ignore_ascii_case(script)),
+        None => RTL_LANGUAGES
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(language)),
+    };
+
+    if is_rtl {
+        TextDirection::Rtl
+    } else {
+        TextDirection::Ltr
+    }
+}
+
+/// Returns `true` if the locale is written right to left, e.g. `ar` or `he-IL`.
+pub fn is_rtl(locale: &str) -> bool {
+    text_direction(locale) == TextDirection::Rtl
+}
+
+/// Get the name of a locale in its own language, e.g. `Deutsch` for `de`.
+pub(crate) fn native_name(locale: &str) -> Option<&'static str> {
+    let locale = Locale::parse(locale).ok()?;
+    NATIVE_NAMES
+        .iter()
+        .find(|(tag, _)| crate::lookup_locale(&locale, &[tag]).is_some())
+        .map(|(_, name)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_info() {
+        let info = LocaleInfo::new("en");
+        assert_eq!(info.language, "en");
+        assert_eq!(info.script, None);
+        assert_eq!(info.region, None);
+        assert_eq!(info.native_name, Some("English"));
+        assert!(!info.is_rtl());
+
+        let info = LocaleInfo::new("pt-BR");
+        assert_eq!(info.region.as_deref(), Some("BR"));
+        assert_eq!(info.native_name, Some("Português (Brasil)"));
+
+        assert_eq!(LocaleInfo::new("de-AT").native_name, Some("Deutsch"));
+        assert_eq!(LocaleInfo::new("zh-CN").native_name, Some("简体中文"));
+        assert_eq!(LocaleInfo::new("zh").native_name, Some("中文"));
+        assert_eq!(LocaleInfo::new("tlh").native_name, None);
+        assert_eq!(LocaleInfo::new("???").language, "???");
+    }
+
+    #[test]
+    fn test_text_direction() {
+        assert_eq!(text_direction("en"), TextDirection::Ltr);
+        assert_eq!(text_direction("ar"), TextDirection::Rtl);
+        assert_eq!(text_direction("he-IL"), TextDirection::Rtl);
+        assert_eq!(text_direction("fa_IR"), TextDirection::Rtl);
+        assert_eq!(text_direction("pa-Arab"), TextDirection::Rtl);
+        assert_eq!(text_direction("pa"), TextDirection::Ltr);
+        assert_eq!(text_direction("az-Latn"), TextDirection::Ltr);
+        assert!(is_rtl("ar-EG"));
+        assert!(!is_rtl("en"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Locale matching with [RFC 4647](https://datatracker.ietf.org/doc/html/rfc4647) language ranges.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Likely scripts of regions and likely regions of scripts, for languages written in
+/// more than one script, so `zh-TW` can match `zh-Hant` and the other way around.
+///
+/// A small subset of the CLDR likely subtags data.
+static LIKELY_SUBTAGS: &[(&str, &str, &str)] = &[
+    // (language, script, region)
+    ("zh", "Hans", "CN"),
+    ("zh", "Hans", "SG"),
+    ("zh", "Hant", "TW"),
+    ("zh", "Hant", "HK"),
+    ("zh", "Hant", "MO"),
+    ("sr", "Cyrl", "RS"),
+    ("sr", "Latn", "ME"),
+    ("pa", "Guru", "IN"),
+    ("pa", "Arab", "PK"),
+    ("uz", "Latn", "UZ"),
+    ("uz", "Arab", "AF"),
+];
+
+/// Check if a language tag matches an extended language range.
+///
+/// The range can contain `*` wildcards which match any sequence of subtags,
+/// e.g. `zh-*-CN` matches `zh-CN`, `zh-Hans-CN` but not `zh-TW`, and `*` matches everything.
+/// Comparison is case-insensitive.
+///
+/// https://datatracker.ietf.org/doc/html/rfc4647#section-3.3.2
+///
+/// ```
+/// # use rust_i18n_support::matches_range;
+/// assert!(matches_range("zh-*-CN", "zh-Hans-CN"));
+/// assert!(matches_range("de-DE", "de-Latn-DE-1996"));
+/// assert!(!matches_range("de-DE", "de-x-DE"));
+/// ```
+pub fn matches_range(range: &str, tag: &str) -> bool {
+    let mut range = range.split(['-', '_']);
+    let mut tag = tag.split(['-', '_']).peekable();
+
+    match (range.next(), tag.next()) {
+        (Some("*"), Some(_)) => {}
+        (Some(r), Some(t)) if r.eq_ignore_ascii_case(t) => {}
+        _ => return false,
+    }
+
+    for subtag in range {
+        if subtag == "*" {
+            continue;
+        }
+
+        loop {
+            match tag.peek() {
+                None => return false,
+                Some(t) if t.eq_ignore_ascii_case(subtag) => {
+                    tag.next();
+                    break;
+                }
+                Some(t) if t.len() == 1 => return false,
+                Some(_) => {
+                    tag.next();
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// Filter the locales matching an extended language range, keeping the order.
+pub fn filter_locales<'a, S: AsRef<str>>(range: &str, locales: &'a [S]) -> Vec<&'a str> {
+    locales
+        .iter()
+        .map(|locale| locale.as_ref())
+        .filter(|locale| matches_range(range, locale))
+        .collect()
+}
+
+/// Find the best locale for a language range by progressively truncating it.
+///
+/// For example: `"zh-Hant-CN-x-private1"` -> `"zh-Hant-CN"` -> `"zh-Hant"` -> `"zh"`.
+///
+/// https://datatracker.ietf.org/doc/html/rfc4647#section-3.4
+pub fn lookup_locale<'a, S: AsRef<str>>(range: &str, locales: &'a [S]) -> Option<&'a str> {
+    let mut range = range;
+    loop {
+        if let Some(locale) = find_locale(range, locales) {
+            return Some(locale);
+        }
+
+        let n = range.rfind(['-', '_'])?;
+        range = &range[..n];
+        // Remove a trailing singleton, e.g. `-x`.
+        if range.len() > 2 && range.as_bytes()[range.len() - 2] == b'-' {
+            range = &range[..range.len() - 2];
+        }
+    }
+}
+
+/// Parse an `Accept-Language` header into the language ranges, by descending quality.
+///
+/// The ranges with a zero quality and the `*` range are dropped, the ranges with the same
+/// quality keep their order.
+///
+/// ```
+/// # use rust_i18n_support::parse_accept_language;
+/// let ranges = parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5");
+/// assert_eq!(ranges, ["fr-CH", "fr", "en", "de"]);
+/// assert_eq!(parse_accept_language("en;q=0.5, zh-TW, ja;q=0"), ["zh-TW", "en"]);
+/// ```
+pub fn parse_accept_language(header: &str) -> Vec<&str> {
+    let mut ranges = header
+        .split(',')
+        .filter_map(|item| {
+            let mut params = item.split(';');
+            let range = params.next()?.trim();
+            let quality = params
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!range.is_empty() && range != "*" && quality > 0.0).then_some((range, quality))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+/// Find the best available locale for the requested language ranges, in priority order.
+///
+/// For each requested range the match is, in order:
+///
+/// 1. The same locale, ignoring case and `_` vs `-`.
+/// 2. The first locale matching the range as an extended range, e.g. `zh-*-CN`.
+/// 3. A locale found by truncating the range, e.g. `en-US` -> `en`.
+/// 4. A locale with the same language and likely script, e.g. `zh-TW` <-> `zh-Hant`.
+/// 5. The first locale of the same language, e.g. `de` -> `de-AT`.
+///
+/// ```
+/// # use rust_i18n_support::negotiate;
+/// let available = ["en", "zh-Hans", "zh-Hant"];
+/// assert_eq!(negotiate(&["zh-TW", "en"], &available), Some("zh-Hant"));
+/// assert_eq!(negotiate(&["fr", "en-GB"], &available), Some("en"));
+/// assert_eq!(negotiate(&["*-Hant"], &available), Some("zh-Hant"));
+/// assert_eq!(negotiate(&["fr"], &available), None);
+/// ```
+pub fn negotiate<'a, R: AsRef<str>, S: AsRef<str>>(
+    requested: &[R],
+    available: &'a [S],
+) -> Option<&'a str> {
+    requested
+        .iter()
+        .find_map(|range| negotiate_one(range.as_ref(), available))
+}
+
+fn negotiate_one<'a, S: AsRef<str>>(range: &str, available: &'a [S]) -> Option<&'a str> {
+    let range = range.trim();
+    if range.is_empty() {
+        return None;
+    }
+
+    if let Some(locale) = find_locale(range, available) {
+        return Some(locale);
+    }
+
+    if range.contains('*') {
+        return filter_locales(range, available).first().copied();
+    }
+
+    if let Some(locale) = lookup_locale(range, available) {
+        if locale.contains(['-', '_']) || !range.contains(['-', '_']) {
+            return Some(locale);
+        }
+    }
+
+    if let Some(locale) = likely_locale(range, available) {
+        return Some(locale);
+    }
+
+    lookup_locale(range, available).or_else(|| {
+        let language = range.split(['-', '_']).next()?;
+        filter_locales(language, available).first().copied()
+    })
+}
+
+/// Match by the likely script of a region or the likely regions of a script.
+fn likely_locale<'a, S: AsRef<str>>(range: &str, available: &'a [S]) -> Option<&'a str> {
+    let mut subtags = range.split(['-', '_']);
+    let language = subtags.next()?;
+    let rest = subtags.collect::<Vec<_>>();
+
+    let mut candidates: Vec<String> = Vec::new();
+    for (lang, script, region) in LIKELY_SUBTAGS {
+        if !lang.eq_ignore_ascii_case(language) {
+            continue;
+        }
+
+        if rest.iter().any(|s| s.eq_ignore_ascii_case(region)) {
+            candidates.push(format!("{language}-{script}"));
+        }
+        if rest.iter().any(|s| s.eq_ignore_ascii_case(script)) {
+            candidates.push(format!("{language}-{region}"));
+            candidates.push(format!("{language}-{script}-{region}"));
+        }
+    }
+
+    candidates
+        .iter()
+        .find_map(|candidate| find_locale(candidate, available))
+        .or_else(|| {
+            candidates
+                .iter()
+                .find_map(|candidate| filter_locales(candidate, available).first().copied())
+        })
+}
+
+fn find_locale<'a, S: AsRef<str>>(tag: &str, locales: &'a [S]) -> Option<&'a str> {
+    locales
+        .iter()
+        .map(|locale| locale.as_ref())
+        .find(|locale| same_tag(locale, tag))
+}
+
+fn same_tag(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes().zip(b.bytes()).all(|(a, b)| {
+            let a = if a == b'_' { b'-' } else { a };
+            let b = if b == b'_' { b'-' } else { b };
+            a.eq_ignore_ascii_case(&b)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(parse_accept_language("zh-CN"), ["zh-CN"]);
+        assert_eq!(
+            parse_accept_language("de;q=0.7,en-US;q=0.9 , fr"),
+            ["fr", "en-US", "de"]
+        );
+        assert_eq!(parse_accept_language("en; q=0.3, ja;q=0.3"), ["en", "ja"]);
+        assert_eq!(parse_accept_language("en;q=bad, ja"), ["ja"]);
+        assert!(parse_accept_language("").is_empty());
+        assert!(parse_accept_language("*").is_empty());
+    }
+
+    #[test]
+    fn test_matches_range() {
+        assert!(matches_range("*", "en"));
+        assert!(matches_range("*-CH", "de-CH"));
+        assert!(matches_range("*-CH", "fr-Latn-CH"));
+        assert!(matches_range("de-*-DE", "de-DE"));
+        assert!(matches_range("de-*-DE", "de-de"));
+        assert!(matches_range("de-*-DE", "de-Latn-DE"));
+        assert!(matches_range("de-*-DE", "de-Latf-DE"));
+        assert!(matches_range("de-*-DE", "de-DE-x-goethe"));
+        assert!(matches_range("de-*-DE", "de-Latn-DE-1996"));
+        assert!(matches_range("de-*-DE", "de-Deva-DE"));
+        assert!(matches_range("zh_*_CN", "zh-Hans-CN"));
+
+        assert!(!matches_range("de-*-DE", "de"));
+        assert!(!matches_range("de-*-DE", "de-x-DE"));
+        assert!(!matches_range("de-*-DE", "de-Deva"));
+        assert!(!matches_range("zh-*-CN", "zh-TW"));
+        assert!(!matches_range("en", "fr"));
+    }
+
+    #[test]
+    fn test_lookup_locale() {
+        let available = ["en", "zh", "zh-Hant"];
+        assert_eq!(
+            lookup_locale("zh-Hant-CN-x-private1-private2", &available),
+            Some("zh-Hant")
+        );
+        assert_eq!(lookup_locale("zh-CN", &available), Some("zh"));
+        assert_eq!(lookup_locale("en-x-foo", &available), Some("en"));
+        assert_eq!(lookup_locale("fr-FR", &available), None);
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let available = ["en", "en-GB", "zh-CN", "zh-Hant", "de-AT"];
+        assert_eq!(negotiate(&["en-gb"], &available), Some("en-GB"));
+        assert_eq!(negotiate(&["en_GB"], &available), Some("en-GB"));
+        assert_eq!(negotiate(&["en-US"], &available), Some("en"));
+        assert_eq!(negotiate(&["zh-TW"], &available), Some("zh-Hant"));
+        assert_eq!(negotiate(&["zh-HK"], &available), Some("zh-Hant"));
+        assert_eq!(negotiate(&["zh-Hans"], &available), Some("zh-CN"));
+        assert_eq!(negotiate(&["zh-SG"], &available), Some("zh-CN"));
+        assert_eq!(negotiate(&["*-GB"], &available), Some("en-GB"));
+        assert_eq!(negotiate(&["de"], &available), Some("de-AT"));
+        assert_eq!(negotiate(&["fr", "de-DE"], &available), Some("de-AT"));
+        assert_eq!(negotiate(&["fr", "ja"], &available), None);
+        assert_eq!(negotiate::<&str, &str>(&[], &available), None);
+
+        let available = ["zh-TW", "zh-CN"];
+        assert_eq!(negotiate(&["zh-Hant"], &available), Some("zh-TW"));
+        assert_eq!(negotiate(&["zh"], &available), Some("zh-TW"));
+    }
+}
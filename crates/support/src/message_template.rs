@@ -0,0 +1,272 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+/// A part of a [`MessageTemplate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// A text, with the escaped `%%{` unescaped.
+    Text(&'a str),
+    /// A placeholder, e.g. `%{size:bytes}`.
+    Arg {
+        /// The name of the argument, e.g. `size`.
+        name: &'a str,
+        /// The format hint, e.g. `bytes`.
+        hint: Option<&'a str>,
+        /// The placeholder itself, e.g. `%{size:bytes}`, kept when the argument is missing.
+        pattern: &'a str,
+    },
+}
+
+/// The byte ranges of a segment in the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Span {
+    Text(usize, usize),
+    Arg {
+        start: usize,
+        colon: Option<usize>,
+        end: usize,
+    },
+}
+
+/// A text with its `%{var}` placeholders parsed, so the arguments are joined without scanning
+/// the text again.
+///
+/// The backends store the parsed templates of their translations, see
+/// [`Backend::template`](crate::Backend::template).
+///
+/// ```
+/// # use rust_i18n_support::{MessageTemplate, Segment};
+/// let template = MessageTemplate::parse("%{count} files, %{size:bytes}, 100%%{x}");
+/// assert_eq!(
+///     template.segments().collect::<Vec<_>>(),
+///     [
+///         Segment::Arg { name: "count", hint: None, pattern: "%{count}" },
+///         Segment::Text(" files, "),
+///         Segment::Arg { name: "size", hint: Some("bytes"), pattern: "%{size:bytes}" },
+///         Segment::Text(", 100"),
+///         Segment::Text("%{x}"),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageTemplate<'a> {
+    text: Cow<'a, str>,
+    /// Empty when the text has no placeholders and no escapes.
+    spans: Cow<'a, [Span]>,
+}
+
+impl<'a> MessageTemplate<'a> {
+    /// Parse the placeholders of a text.
+    pub fn parse(text: impl Into<Cow<'a, str>>) -> Self {
+        let text = text.into();
+        let spans = parse_spans(&text).into();
+        Self { text, spans }
+    }
+
+    /// The text of the template, as it was parsed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Take the text of the template.
+    pub fn into_text(self) -> Cow<'a, str> {
+        self.text
+    }
+
+    /// Returns `true` if the text has placeholders or escaped `%%{`, so it's rendered by
+    /// joining its segments instead of being the text as is.
+    pub fn has_segments(&self) -> bool {
+        !self.spans.is_empty()
+    }
+
+    /// The segments of the text, in order.
+    pub fn segments(&self) -> impl Iterator<Item = Segment<'_>> + '_ {
+        let whole = (!self.has_segments() && !self.text.is_empty())
+            .then_some(Span::Text(0, self.text.len()));
+        self.spans
+            .iter()
+            .copied()
+            .chain(whole)
+            .map(|span| match span {
+                Span::Text(start, end) => Segment::Text(&self.text[start..end]),
+                Span::Arg { start, colon, end } => Segment::Arg {
+                    name: &self.text[start + 2..colon.unwrap_or(end)],
+                    hint: colon.map(|colon| &self.text[colon + 1..end]),
+                    pattern: &self.text[start..=end],
+                },
+            })
+    }
+
+    /// A template borrowing this one, without parsing the text again.
+    pub fn borrowed(&self) -> MessageTemplate<'_> {
+        MessageTemplate {
+            text: Cow::Borrowed(&self.text),
+            spans: Cow::Borrowed(&self.spans),
+        }
+    }
+
+    /// A template owning its text.
+    pub fn into_owned(self) -> MessageTemplate<'static> {
+        MessageTemplate {
+            text: Cow::Owned(self.text.into_owned()),
+            spans: Cow::Owned(self.spans.into_owned()),
+        }
+    }
+}
+
+/// The spans of the placeholders and the texts between them, none for a plain text.
+fn parse_spans(text: &str) -> Vec<Span> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut prev_end = 0;
+    let push_text = |spans: &mut Vec<Span>, start: usize, end: usize| {
+        if start < end {
+            spans.push(Span::Text(start, end));
+        }
+    };
+    let mut found = false;
+
+    // 1: after `%`, 2: in `%{`, 3: after `%%`
+    let mut stage = 0;
+    let mut pattern_start = 0;
+    let mut colon = None;
+    for (i, &b) in bytes.iter().enumerate() {
+        match (stage, b) {
+            (1, b'%') => {
+                stage = 3;
+            }
+            (3, b'{') => {
+                // Drop the first `%` of `%%{`
+                stage = 0;
+                push_text(&mut spans, prev_end, i - 2);
+                prev_end = i - 1;
+                found = true;
+            }
+            (1, b'{') => {
+                stage = 2;
+                pattern_start = i - 1;
+                colon = None;
+            }
+            (2, b':') if colon.is_none() => {
+                colon = Some(i);
+            }
+            (2, b'}') => {
+                stage = 0;
+                push_text(&mut spans, prev_end, pattern_start);
+                spans.push(Span::Arg {
+                    start: pattern_start,
+                    colon,
+                    end: i,
+                });
+                prev_end = i + 1;
+                found = true;
+            }
+            (_, b'%') => {
+                stage = 1;
+            }
+            (1 | 3, _) => {
+                stage = 0;
+            }
+            _ => {}
+        }
+    }
+    if found {
+        push_text(&mut spans, prev_end, bytes.len());
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments<'a>(template: &'a MessageTemplate<'_>) -> Vec<Segment<'a>> {
+        template.segments().collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let template = MessageTemplate::parse("Hello, %{name}!");
+        assert!(template.has_segments());
+        assert_eq!(
+            segments(&template),
+            [
+                Segment::Text("Hello, "),
+                Segment::Arg {
+                    name: "name",
+                    hint: None,
+                    pattern: "%{name}"
+                },
+                Segment::Text("!"),
+            ]
+        );
+
+        let template = MessageTemplate::parse("Hello");
+        assert!(!template.has_segments());
+        assert_eq!(segments(&template), [Segment::Text("Hello")]);
+        assert_eq!(segments(&MessageTemplate::parse("")), []);
+
+        let template = MessageTemplate::parse("%{a}%{b:percent}% %{ 100%");
+        assert_eq!(
+            segments(&template),
+            [
+                Segment::Arg {
+                    name: "a",
+                    hint: None,
+                    pattern: "%{a}"
+                },
+                Segment::Arg {
+                    name: "b",
+                    hint: Some("percent"),
+                    pattern: "%{b:percent}"
+                },
+                Segment::Text("% %{ 100%"),
+            ]
+        );
+
+        // A `%` in a placeholder starts another one, e.g. `%{a%{b}`
+        assert_eq!(
+            segments(&MessageTemplate::parse("%{a%{b}")),
+            [
+                Segment::Text("%{a"),
+                Segment::Arg {
+                    name: "b",
+                    hint: None,
+                    pattern: "%{b}"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_escaped() {
+        let template = MessageTemplate::parse("Use %%{name} for %{name}");
+        assert_eq!(
+            segments(&template),
+            [
+                Segment::Text("Use "),
+                Segment::Text("%{name} for "),
+                Segment::Arg {
+                    name: "name",
+                    hint: None,
+                    pattern: "%{name}"
+                },
+            ]
+        );
+        assert_eq!(template.text(), "Use %%{name} for %{name}");
+        assert_eq!(
+            segments(&MessageTemplate::parse("%%{x}")),
+            [Segment::Text("%{x}")]
+        );
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let template = MessageTemplate::parse(alloc::string::String::from("%{a} b"));
+        let borrowed = template.borrowed();
+        assert!(matches!(borrowed.text, Cow::Borrowed(_)));
+        assert!(matches!(borrowed.spans, Cow::Borrowed(_)));
+        assert_eq!(segments(&borrowed), segments(&template));
+        assert_eq!(template.clone().into_owned(), template);
+    }
+}
@@ -1,6 +1,7 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
 use siphasher::sip128::SipHasher13;
-use std::borrow::Cow;
-use std::sync::LazyLock;
 
 /// The default value of `minify_key` feature.
 pub const DEFAULT_MINIFY_KEY: bool = false;
@@ -14,12 +15,10 @@ pub const DEFAULT_MINIFY_KEY_PREFIX: &str = "";
 /// The minimum length of the value to be generated the translation key
 pub const DEFAULT_MINIFY_KEY_THRESH: usize = 127;
 
-// The hasher for generate the literal translation key
-static TR_KEY_HASHER: LazyLock<SipHasher13> = LazyLock::new(SipHasher13::new);
-
 /// Calculate a 128-bit siphash of a value.
 pub fn hash128<T: AsRef<[u8]> + ?Sized>(value: &T) -> u128 {
-    TR_KEY_HASHER.hash(value.as_ref()).as_u128()
+    // The hasher for generate the literal translation key
+    SipHasher13::new().hash(value.as_ref()).as_u128()
 }
 
 /// Generate a translation key from a value.
@@ -0,0 +1,258 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+use crate::archive_backend::read_archive;
+use crate::{try_load_locales_from_files, Backend, BackendExt};
+
+type Translations = HashMap<String, HashMap<String, String>>;
+
+const BUNDLE_FILENAME: &str = "bundle";
+const SIGNATURE_FILENAME: &str = "bundle.sig";
+const ETAG_FILENAME: &str = "bundle.etag";
+
+/// A backend updating the translations over the air, for the apps shipping copy updates
+/// between releases.
+///
+/// The translations are downloaded from `url` as a bundle, an archive of the `locales`
+/// directory like the ones of [`ArchiveBackend`](crate::ArchiveBackend). The bundle is signed with Ed25519, the 64 bytes of its
+/// signature are downloaded from `{url}.sig` and verified with the public key of the app.
+///
+/// The verified bundle is kept in the cache directory and loaded again on the next start,
+/// so the app has the last translations offline. [`OtaBackend::update`] swaps the
+/// translations in place, the lookups in progress keep the previous ones.
+///
+/// Cloning is cheap and clones share the translations.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use rust_i18n_support::OtaBackend;
+/// # const PUBLIC_KEY: [u8; 32] = [0; 32];
+/// let backend = OtaBackend::new(
+///     "https://cdn.example.com/my-app/locales.tar.gz",
+///     PUBLIC_KEY,
+///     "/var/cache/my-app/locales",
+/// );
+/// backend.spawn_updates(Duration::from_secs(3600));
+/// ```
+pub struct OtaBackend {
+    url: Arc<str>,
+    public_key: [u8; 32],
+    cache_dir: Arc<Path>,
+    translations: Arc<ArcSwap<Translations>>,
+}
+
+impl Clone for OtaBackend {
+    fn clone(&self) -> Self {
+        Self {
+            url: self.url.clone(),
+            public_key: self.public_key,
+            cache_dir: self.cache_dir.clone(),
+            translations: self.translations.clone(),
+        }
+    }
+}
+
+impl OtaBackend {
+    /// Create the backend, loading the bundle cached by a previous update if it is valid.
+    ///
+    /// It doesn't download anything, call [`OtaBackend::update`] for that.
+    pub fn new(url: &str, public_key: [u8; 32], cache_dir: impl Into<PathBuf>) -> Self {
+        let backend = Self {
+            url: url.into(),
+            public_key,
+            cache_dir: cache_dir.into().into(),
+            translations: Default::default(),
+        };
+
+        let cached = std::fs::read(backend.cache_dir.join(BUNDLE_FILENAME)).and_then(|bundle| {
+            Ok((
+                bundle,
+                std::fs::read(backend.cache_dir.join(SIGNATURE_FILENAME))?,
+            ))
+        });
+        if let Ok((bundle, signature)) = cached {
+            // An invalid cache is replaced by the next update
+            _ = backend.load(&bundle, &signature);
+        }
+        backend
+    }
+
+    /// Download the bundle and swap in its translations, returns whether they were updated.
+    ///
+    /// The bundle is downloaded again only when its `ETag` changed.
+    pub fn update(&self) -> Result<bool, String> {
+        let etag_path = self.cache_dir.join(ETAG_FILENAME);
+        let mut request = ureq::get(&self.url);
+        if let Ok(etag) = std::fs::read_to_string(&etag_path) {
+            if !self.translations.load().is_empty() {
+                request = request.set("If-None-Match", etag.trim());
+            }
+        }
+
+        let response = request
+            .call()
+            .map_err(|err| format!("Failed to download `{}`, {}", self.url, err))?;
+        if response.status() == 304 {
+            return Ok(false);
+        }
+        let etag = response.header("ETag").map(str::to_string);
+        let bundle = read_body(response, &self.url)?;
+
+        let signature_url = format!("{}.sig", self.url);
+        let signature = ureq::get(&signature_url)
+            .call()
+            .map_err(|err| format!("Failed to download `{}`, {}", signature_url, err))
+            .and_then(|response| read_body(response, &signature_url))?;
+
+        let changed = self.load(&bundle, &signature)?;
+        if changed {
+            std::fs::create_dir_all(&self.cache_dir)
+                .and_then(|_| std::fs::write(self.cache_dir.join(BUNDLE_FILENAME), &bundle))
+                .and_then(|_| std::fs::write(self.cache_dir.join(SIGNATURE_FILENAME), &signature))
+                .map_err(|err| {
+                    format!(
+                        "Failed to cache the bundle in `{}`, {}",
+                        self.cache_dir.display(),
+                        err
+                    )
+                })?;
+        }
+        match etag {
+            Some(etag) => _ = std::fs::write(etag_path, etag),
+            None => _ = std::fs::remove_file(etag_path),
+        }
+        Ok(changed)
+    }
+
+    /// Call [`OtaBackend::update`] now and then every `interval` in a background thread,
+    /// the errors are printed to stderr.
+    pub fn spawn_updates(&self, interval: Duration) -> JoinHandle<()> {
+        let backend = self.clone();
+        std::thread::spawn(move || loop {
+            if let Err(err) = backend.update() {
+                eprintln!("rust-i18n: {}", err);
+            }
+            std::thread::sleep(interval);
+        })
+    }
+
+    /// Verify the signature of the bundle and swap in its translations, returns whether
+    /// they changed.
+    pub fn load(&self, bundle: &[u8], signature: &[u8]) -> Result<bool, String> {
+        UnparsedPublicKey::new(&ED25519, &self.public_key)
+            .verify(bundle, signature)
+            .map_err(|_| "Invalid signature of the translation bundle".to_string())?;
+
+        let translations = parse_bundle(bundle)?;
+        if **self.translations.load() == translations {
+            return Ok(false);
+        }
+        self.translations.store(Arc::new(translations));
+        Ok(true)
+    }
+}
+
+fn read_body(response: ureq::Response, url: &str) -> Result<Vec<u8>, String> {
+    let mut body = vec![];
+    response
+        .into_reader()
+        .read_to_end(&mut body)
+        .map_err(|err| format!("Failed to download `{}`, {}", url, err))?;
+    Ok(body)
+}
+
+fn parse_bundle(bundle: &[u8]) -> Result<Translations, String> {
+    Ok(try_load_locales_from_files(read_archive(bundle)?)?
+        .into_iter()
+        .map(|(locale, trs)| (locale, trs.into_iter().collect()))
+        .collect())
+}
+
+impl Backend for OtaBackend {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let mut locales = self
+            .translations
+            .load()
+            .keys()
+            .map(|locale| Cow::Owned(locale.clone()))
+            .collect::<Vec<_>>();
+        locales.sort();
+        locales
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        self.translations
+            .load()
+            .get(locale)?
+            .get(key)
+            .map(|value| Cow::Owned(value.clone()))
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        self.translations.load().get(locale).map(|messages| {
+            messages
+                .iter()
+                .map(|(k, v)| (Cow::Owned(k.clone()), Cow::Owned(v.clone())))
+                .collect()
+        })
+    }
+}
+
+impl BackendExt for OtaBackend {}
+
+#[cfg(test)]
+mod tests {
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    use super::*;
+    use crate::archive_backend::tests::tar_gz as bundle;
+
+    #[test]
+    fn test_ota_backend() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key: [u8; 32] = key_pair.public_key().as_ref().try_into().unwrap();
+        let cache_dir = std::env::temp_dir().join(format!("rust-i18n-ota-{}", std::process::id()));
+
+        let backend = OtaBackend::new("http://localhost/locales.tar.gz", public_key, &cache_dir);
+        assert!(backend.available_locales().is_empty());
+
+        let data = bundle(&[
+            ("locales/en.yml", "hello: Hello"),
+            (
+                "locales/app.yml",
+                "_version: 2\nbye:\n  en: Bye\n  fr: Au revoir",
+            ),
+        ]);
+        let signature = key_pair.sign(&data);
+        assert!(backend.load(&data, signature.as_ref()).unwrap());
+        assert!(!backend.load(&data, signature.as_ref()).unwrap());
+        assert_eq!(backend.available_locales(), vec!["en", "fr"]);
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("Hello"));
+        assert_eq!(backend.translate("fr", "bye").as_deref(), Some("Au revoir"));
+
+        // A tampered bundle is rejected, the translations are kept
+        let other = bundle(&[("en.yml", "hello: Hacked")]);
+        backend
+            .load(&other, signature.as_ref())
+            .expect_err("Should error");
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("Hello"));
+
+        // The cached bundle is loaded on start
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        std::fs::write(cache_dir.join(BUNDLE_FILENAME), &data).unwrap();
+        std::fs::write(cache_dir.join(SIGNATURE_FILENAME), signature.as_ref()).unwrap();
+        let backend = OtaBackend::new("http://localhost/locales.tar.gz", public_key, &cache_dir);
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("Hello"));
+    }
+}
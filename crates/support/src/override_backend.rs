@@ -0,0 +1,161 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use arc_swap::ArcSwap;
+
+use crate::{Backend, BackendExt, BackendMut, HashMap};
+
+type Translations = HashMap<String, HashMap<String, String>>;
+
+/// A backend holding translations set at runtime, e.g. copy edits from an admin panel.
+///
+/// Lookups are lock-free, each change copies the translations, so it suits occasional edits
+/// rather than loading whole catalogs.
+///
+/// ```
+/// # use rust_i18n_support::{Backend, BackendMut, OverrideBackend};
+/// let overrides = OverrideBackend::new();
+/// overrides.set_translation("en", "hello", "Howdy");
+/// assert_eq!(overrides.translate("en", "hello").as_deref(), Some("Howdy"));
+///
+/// assert!(overrides.remove_translation("en", "hello"));
+/// assert_eq!(overrides.translate("en", "hello"), None);
+/// ```
+#[derive(Default)]
+pub struct OverrideBackend {
+    #[cfg(feature = "std")]
+    translations: ArcSwap<Translations>,
+    #[cfg(not(feature = "std"))]
+    translations: spin::RwLock<Arc<Translations>>,
+}
+
+impl OverrideBackend {
+    /// Create an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove all the translations.
+    pub fn clear(&self) {
+        self.update(|translations| translations.clear());
+    }
+
+    /// Returns `true` if there are no translations.
+    pub fn is_empty(&self) -> bool {
+        #[cfg(feature = "std")]
+        return self.translations.load().is_empty();
+        #[cfg(not(feature = "std"))]
+        return self.translations.read().is_empty();
+    }
+
+    fn load(&self) -> Arc<Translations> {
+        #[cfg(feature = "std")]
+        return self.translations.load_full();
+        #[cfg(not(feature = "std"))]
+        return self.translations.read().clone();
+    }
+
+    fn update(&self, mut f: impl FnMut(&mut Translations)) {
+        #[cfg(feature = "std")]
+        self.translations.rcu(|translations| {
+            let mut translations = Translations::clone(translations);
+            f(&mut translations);
+            translations
+        });
+        #[cfg(not(feature = "std"))]
+        {
+            let mut guard = self.translations.write();
+            let mut translations = Translations::clone(&guard);
+            f(&mut translations);
+            *guard = Arc::new(translations);
+        }
+    }
+}
+
+impl BackendMut for OverrideBackend {
+    fn set_translation(&self, locale: &str, key: &str, value: &str) {
+        self.update(|translations| {
+            translations
+                .entry(locale.to_string())
+                .or_default()
+                .insert(key.to_string(), value.to_string());
+        });
+    }
+
+    fn remove_translation(&self, locale: &str, key: &str) -> bool {
+        let mut removed = false;
+        self.update(|translations| {
+            if let Some(trs) = translations.get_mut(locale) {
+                removed = trs.remove(key).is_some();
+                if trs.is_empty() {
+                    translations.remove(locale);
+                }
+            }
+        });
+        removed
+    }
+}
+
+impl Backend for OverrideBackend {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let mut locales = self
+            .load()
+            .keys()
+            .map(|locale| Cow::Owned(locale.clone()))
+            .collect::<Vec<_>>();
+        locales.sort();
+        locales
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        self.load()
+            .get(locale)?
+            .get(key)
+            .map(|value| Cow::Owned(value.clone()))
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        self.load().get(locale).map(|trs| {
+            trs.iter()
+                .map(|(k, v)| (Cow::Owned(k.clone()), Cow::Owned(v.clone())))
+                .collect()
+        })
+    }
+}
+
+impl BackendExt for OverrideBackend {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_backend() {
+        let overrides = OverrideBackend::new();
+        assert!(overrides.available_locales().is_empty());
+        assert!(overrides.is_empty());
+
+        overrides.set_translation("en", "hello", "Hello");
+        overrides.set_translation("en", "hello", "Howdy");
+        overrides.set_translation("fr", "hello", "Salut");
+        assert_eq!(overrides.available_locales(), vec!["en", "fr"]);
+        assert_eq!(overrides.translate("en", "hello").as_deref(), Some("Howdy"));
+        assert_eq!(
+            overrides.messages_for_locale("fr"),
+            Some(vec![(Cow::from("hello"), Cow::from("Salut"))])
+        );
+
+        assert!(overrides.remove_translation("fr", "hello"));
+        assert!(!overrides.remove_translation("fr", "hello"));
+        assert_eq!(overrides.available_locales(), vec!["en"]);
+
+        assert!(!overrides.is_empty());
+
+        overrides.clear();
+        assert_eq!(overrides.translate("en", "hello"), None);
+        assert!(overrides.is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::{Backend, BackendExt};
+
+/// The translations by key, by locale, in perfect-hash maps built at compile time.
+pub type PhfTranslations = phf::Map<&'static str, phf::Map<&'static str, &'static str>>;
+
+/// A backend of static [`phf`] maps, generated by `i18n!("locales", codegen = "phf")`.
+///
+/// Unlike [`SimpleBackend`](crate::SimpleBackend), nothing is built at the first access and
+/// nothing is allocated, the translations are borrowed from the binary.
+#[derive(Debug, Clone, Copy)]
+pub struct PhfBackend {
+    translations: &'static PhfTranslations,
+}
+
+impl PhfBackend {
+    /// Create a backend of the given translations.
+    pub const fn new(translations: &'static PhfTranslations) -> Self {
+        Self { translations }
+    }
+}
+
+impl Backend for PhfBackend {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        let mut locales = self
+            .translations
+            .keys()
+            .map(|locale| Cow::Borrowed(*locale))
+            .collect::<Vec<_>>();
+        locales.sort();
+        locales
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        self.translations
+            .get(locale)?
+            .get(key)
+            .map(|value| Cow::Borrowed(*value))
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        self.translations.get(locale).map(|trs| {
+            trs.entries()
+                .map(|(key, value)| (Cow::Borrowed(*key), Cow::Borrowed(*value)))
+                .collect()
+        })
+    }
+}
+
+impl BackendExt for PhfBackend {}
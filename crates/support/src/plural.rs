@@ -0,0 +1,131 @@
+/// Get the CLDR plural category of a count in a locale, e.g. `one` or `other`.
+///
+/// Only the rules of the integers are needed, as the counts are integers. The languages
+/// without rules, e.g. English, use `one` for `1` and `other` otherwise.
+///
+/// ```
+/// # use rust_i18n_support::plural_category;
+/// assert_eq!(plural_category("en", 1), "one");
+/// assert_eq!(plural_category("en", 2), "other");
+/// assert_eq!(plural_category("fr", 0), "one");
+/// assert_eq!(plural_category("ru-RU", 22), "few");
+/// assert_eq!(plural_category("zh-CN", 1), "other");
+/// ```
+pub fn plural_category(locale: &str, count: u64) -> &'static str {
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    let (n10, n100) = (count % 10, count % 100);
+
+    match language {
+        "ja" | "ko" | "zh" | "th" | "vi" | "id" | "ms" | "lo" | "my" | "km" | "yue" => "other",
+        "fr" | "pt" | "hi" | "bn" | "fa" | "gu" | "kn" | "zu" | "am" if count <= 1 => "one",
+        "fr" | "pt" | "hi" | "bn" | "fa" | "gu" | "kn" | "zu" | "am" => "other",
+        "ru" | "uk" | "be" => match (n10, n100) {
+            (1, n100) if n100 != 11 => "one",
+            (2..=4, n100) if !(12..=14).contains(&n100) => "few",
+            _ => "many",
+        },
+        "hr" | "sr" | "bs" => match (n10, n100) {
+            (1, n100) if n100 != 11 => "one",
+            (2..=4, n100) if !(12..=14).contains(&n100) => "few",
+            _ => "other",
+        },
+        "pl" => match (count, n10, n100) {
+            (1, _, _) => "one",
+            (_, 2..=4, n100) if !(12..=14).contains(&n100) => "few",
+            _ => "many",
+        },
+        "cs" | "sk" => match count {
+            1 => "one",
+            2..=4 => "few",
+            _ => "other",
+        },
+        "lt" => match (n10, n100) {
+            (_, 11..=19) => "other",
+            (1, _) => "one",
+            (2..=9, _) => "few",
+            _ => "other",
+        },
+        "lv" => match (n10, n100) {
+            (0, _) | (_, 11..=19) => "zero",
+            (1, _) => "one",
+            _ => "other",
+        },
+        "ro" => match (count, n100) {
+            (1, _) => "one",
+            (0, _) | (_, 1..=19) => "few",
+            _ => "other",
+        },
+        "sl" => match n100 {
+            1 => "one",
+            2 => "two",
+            3 | 4 => "few",
+            _ => "other",
+        },
+        "he" | "iw" => match count {
+            1 => "one",
+            2 => "two",
+            _ => "other",
+        },
+        "ar" => match (count, n100) {
+            (0, _) => "zero",
+            (1, _) => "one",
+            (2, _) => "two",
+            (_, 3..=10) => "few",
+            (_, 11..=99) => "many",
+            _ => "other",
+        },
+        _ if count == 1 => "one",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plural_category;
+
+    #[test]
+    fn test_plural_category() {
+        let categories = |locale: &str, counts: &[u64]| {
+            counts
+                .iter()
+                .map(|&count| plural_category(locale, count))
+                .collect::<alloc::vec::Vec<_>>()
+        };
+
+        assert_eq!(categories("en", &[0, 1, 2]), ["other", "one", "other"]);
+        assert_eq!(categories("de-AT", &[1, 21]), ["one", "other"]);
+        assert_eq!(categories("ja", &[0, 1]), ["other", "other"]);
+        assert_eq!(categories("pt_BR", &[0, 1, 2]), ["one", "one", "other"]);
+        assert_eq!(
+            categories("ru", &[1, 2, 5, 11, 12, 21, 22, 25, 111]),
+            ["one", "few", "many", "many", "many", "one", "few", "many", "many"]
+        );
+        assert_eq!(categories("hr", &[1, 3, 5]), ["one", "few", "other"]);
+        assert_eq!(
+            categories("pl", &[1, 2, 5, 21, 22]),
+            ["one", "few", "many", "many", "few"]
+        );
+        assert_eq!(categories("cs", &[1, 3, 5]), ["one", "few", "other"]);
+        assert_eq!(
+            categories("lt", &[1, 2, 10, 11, 21]),
+            ["one", "few", "other", "other", "one"]
+        );
+        assert_eq!(
+            categories("lv", &[0, 1, 11, 21]),
+            ["zero", "one", "zero", "one"]
+        );
+        assert_eq!(
+            categories("ro", &[0, 1, 2, 20, 101]),
+            ["few", "one", "few", "other", "few"]
+        );
+        assert_eq!(
+            categories("sl", &[1, 2, 3, 5, 101]),
+            ["one", "two", "few", "other", "one"]
+        );
+        assert_eq!(categories("he", &[1, 2, 3]), ["one", "two", "other"]);
+        assert_eq!(
+            categories("ar", &[0, 1, 2, 3, 11, 100]),
+            ["zero", "one", "two", "few", "many", "other"]
+        );
+    }
+}
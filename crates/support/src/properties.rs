@@ -0,0 +1,255 @@
+//! Read and write Java `.properties` files, e.g. shared with JVM services.
+
+/// Parse the `key=value` pairs of a `.properties` file, in order.
+///
+/// The keys are flat, e.g. `checkout.title`. The comments (`#` and `!`), the line
+/// continuations and the escapes, including `\uXXXX`, are handled like `java.util.Properties`,
+/// except the file is read as UTF-8.
+///
+/// ```
+/// # use rust_i18n_support::parse_properties;
+/// let pairs = parse_properties("# Checkout\ncheckout.title = Caf\\u00e9\ncheckout.pay: Pay \\\n    now");
+/// assert_eq!(pairs[0], ("checkout.title".to_string(), "Café".to_string()));
+/// assert_eq!(pairs[1], ("checkout.pay".to_string(), "Pay now".to_string()));
+/// ```
+pub fn parse_properties(content: &str) -> Vec<(String, String)> {
+    let mut pairs = vec![];
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+
+        // Join the continued lines, a line ending with an odd number of `\`
+        let mut logical = line.to_string();
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some(next) => logical.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+
+        let (key, value) = split_key_value(&logical);
+        pairs.push((unescape(key), unescape(value)));
+    }
+    pairs
+}
+
+/// Write the `key=value` pairs as a `.properties` file, the non-ASCII characters are escaped
+/// as `\uXXXX`, so the file is read by any Java version.
+///
+/// ```
+/// # use rust_i18n_support::write_properties;
+/// let content = write_properties([("checkout.title", "Café")]);
+/// assert_eq!(content, "checkout.title=Caf\\u00e9\n");
+/// ```
+pub fn write_properties<K: AsRef<str>, V: AsRef<str>>(
+    pairs: impl IntoIterator<Item = (K, V)>,
+) -> String {
+    let mut output = String::new();
+    for (key, value) in pairs {
+        output.push_str(&escape(key.as_ref(), true));
+        output.push('=');
+        output.push_str(&escape(value.as_ref(), false));
+        output.push('\n');
+    }
+    output
+}
+
+/// The locale of a `.properties` file from its name without extension, e.g. `fr` of
+/// `fr` and `app.fr`, or `zh-CN` of the Java resource bundle `messages_zh_CN`.
+///
+/// ```
+/// # use rust_i18n_support::properties_locale;
+/// assert_eq!(properties_locale("messages_zh_CN"), "zh-CN");
+/// assert_eq!(properties_locale("app.fr"), "fr");
+/// ```
+pub fn properties_locale(stem: &str) -> String {
+    let name = stem.rsplit('.').next().unwrap_or(stem);
+    let locale = match name.split_once('_') {
+        // A bundle name, e.g. `messages_fr`, unless it starts with the language, e.g. `zh_CN`
+        Some((bundle, locale))
+            if !(2..=3).contains(&bundle.len())
+                || !bundle.chars().all(|c| c.is_ascii_lowercase()) =>
+        {
+            locale
+        }
+        _ => name,
+    };
+    locale.replace('_', "-")
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+/// Split at the first unescaped `=`, `:` or whitespace, skipping the whitespace around it.
+fn split_key_value(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '=' | ':' => return (&line[..i], line[i + 1..].trim_start()),
+            c if c.is_whitespace() => {
+                let rest = line[i..].trim_start();
+                let rest = rest
+                    .strip_prefix(['=', ':'])
+                    .map_or(rest, |rest| rest.trim_start());
+                return (&line[..i], rest);
+            }
+            _ => {}
+        }
+    }
+    (line, "")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('f') => result.push('\u{c}'),
+            Some('u') => {
+                let hex = chars.by_ref().take(4).collect::<String>();
+                let Ok(unit) = u16::from_str_radix(&hex, 16) else {
+                    result.push_str("\\u");
+                    result.push_str(&hex);
+                    continue;
+                };
+                // A surrogate pair is written as two escapes, e.g. `😀`
+                let units = if (0xd800..0xdc00).contains(&unit) && chars.as_str().starts_with("\\u")
+                {
+                    let low = u16::from_str_radix(chars.as_str().get(2..6).unwrap_or_default(), 16);
+                    match low {
+                        Ok(low) if (0xdc00..0xe000).contains(&low) => {
+                            chars.by_ref().take(6).for_each(drop);
+                            vec![unit, low]
+                        }
+                        _ => vec![unit],
+                    }
+                } else {
+                    vec![unit]
+                };
+                result.extend(char::decode_utf16(units).map(|c| c.unwrap_or('\u{fffd}')));
+            }
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+    result
+}
+
+fn escape(text: &str, is_key: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\t' => result.push_str("\\t"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\u{c}' => result.push_str("\\f"),
+            '=' | ':' | '#' | '!' if is_key || i == 0 => {
+                result.push('\\');
+                result.push(c);
+            }
+            ' ' if is_key || i == 0 => result.push_str("\\ "),
+            c if c.is_ascii() && !c.is_ascii_control() => result.push(c),
+            c => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    result.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_properties() {
+        let content = r#"
+# A comment
+! Another comment
+hello = Hello, %{name}!
+colon:Colon
+space Space separated
+    indented=Indented
+empty=
+multiline = First \
+            second \
+            third
+escaped\ key\=x = \ leading space\ttab\nnewline\\
+unicode = 你好 😀
+trailing\\
+next = Next
+"#;
+        assert_eq!(
+            parse_properties(content),
+            vec![
+                ("hello".into(), "Hello, %{name}!".into()),
+                ("colon".into(), "Colon".into()),
+                ("space".into(), "Space separated".into()),
+                ("indented".into(), "Indented".into()),
+                ("empty".into(), "".into()),
+                ("multiline".into(), "First second third".into()),
+                (
+                    "escaped key=x".into(),
+                    " leading space\ttab\nnewline\\".into()
+                ),
+                ("unicode".into(), "你好 😀".into()),
+                ("trailing\\".into(), "".into()),
+                ("next".into(), "Next".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_properties_locale() {
+        assert_eq!(properties_locale("en"), "en");
+        assert_eq!(properties_locale("zh_CN"), "zh-CN");
+        assert_eq!(properties_locale("messages_fr"), "fr");
+        assert_eq!(properties_locale("messages_zh_Hant_TW"), "zh-Hant-TW");
+        assert_eq!(properties_locale("view.pt_BR"), "pt-BR");
+        assert_eq!(properties_locale("errors_de"), "de");
+    }
+
+    #[test]
+    fn test_write_properties() {
+        let pairs = vec![
+            ("hello", "Hello, %{name}!"),
+            ("escaped key=x", " leading space\ttab\nnewline\\"),
+            ("unicode", "你好 😀"),
+            ("ratio", "a=b: #1"),
+        ];
+        let content = write_properties(pairs.clone());
+        assert_eq!(
+            content,
+            "hello=Hello, %{name}!\n\
+             escaped\\ key\\=x=\\ leading space\\ttab\\nnewline\\\\\n\
+             unicode=\\u4f60\\u597d \\ud83d\\ude00\n\
+             ratio=a=b: #1\n"
+        );
+
+        let parsed = parse_properties(&content);
+        for ((key, value), (parsed_key, parsed_value)) in pairs.iter().zip(parsed) {
+            assert_eq!((*key, *value), (parsed_key.as_str(), parsed_value.as_str()));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::{Backend, BackendExt, HashMap};
+
+/// A translation missing from a [`RecordingBackend`], with the number of lookups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingKey {
+    /// The locale looked up
+    pub locale: String,
+    /// The key looked up
+    pub key: String,
+    /// The number of lookups
+    pub count: usize,
+}
+
+/// A backend recording the lookups missing from the wrapped backend, so a service can report
+/// the untranslated keys actually hit by its users.
+///
+/// Every locale tried by `t!` is recorded, e.g. a `zh-CN` miss falling back to `zh` records
+/// `zh-CN` even when `zh` has the translation.
+///
+/// Cloning is cheap and clones share the records, so keep a clone around to drain them after
+/// passing it to `i18n!(backend = ...)`.
+///
+/// ```
+/// # use rust_i18n_support::{Backend, RecordingBackend, SimpleBackend};
+/// let backend = RecordingBackend::new(SimpleBackend::new());
+/// backend.translate("fr", "hello");
+/// backend.translate("fr", "hello");
+///
+/// let missing = backend.drain_missing();
+/// assert_eq!((missing[0].key.as_str(), missing[0].count), ("hello", 2));
+/// assert!(backend.drain_missing().is_empty());
+/// ```
+pub struct RecordingBackend<B> {
+    backend: Arc<B>,
+    #[cfg(feature = "std")]
+    missing: Arc<std::sync::Mutex<HashMap<(String, String), usize>>>,
+    #[cfg(not(feature = "std"))]
+    missing: Arc<spin::RwLock<HashMap<(String, String), usize>>>,
+}
+
+impl<B> Clone for RecordingBackend<B> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            missing: self.missing.clone(),
+        }
+    }
+}
+
+impl<B: Backend> RecordingBackend<B> {
+    /// Wrap the backend.
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            missing: Default::default(),
+        }
+    }
+
+    /// The wrapped backend.
+    pub fn inner(&self) -> &B {
+        &self.backend
+    }
+
+    /// Take the missing translations recorded so far, the most looked up first.
+    pub fn drain_missing(&self) -> Vec<MissingKey> {
+        let mut missing = self
+            .with_missing(core::mem::take)
+            .into_iter()
+            .map(|((locale, key), count)| MissingKey { locale, key, count })
+            .collect::<Vec<_>>();
+        missing.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| (&a.locale, &a.key).cmp(&(&b.locale, &b.key)))
+        });
+        missing
+    }
+
+    fn with_missing<T>(&self, f: impl FnOnce(&mut HashMap<(String, String), usize>) -> T) -> T {
+        #[cfg(feature = "std")]
+        let mut missing = self
+            .missing
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        #[cfg(not(feature = "std"))]
+        let mut missing = self.missing.write();
+        f(&mut missing)
+    }
+}
+
+impl<B: Backend> Backend for RecordingBackend<B> {
+    fn available_locales(&self) -> Vec<Cow<'_, str>> {
+        self.backend.available_locales()
+    }
+
+    fn translate(&self, locale: &str, key: &str) -> Option<Cow<'_, str>> {
+        let value = self.backend.translate(locale, key);
+        if value.is_none() {
+            self.with_missing(|missing| {
+                *missing
+                    .entry((locale.to_string(), key.to_string()))
+                    .or_default() += 1;
+            });
+        }
+        value
+    }
+
+    fn messages_for_locale(&self, locale: &str) -> Option<Vec<(Cow<'_, str>, Cow<'_, str>)>> {
+        self.backend.messages_for_locale(locale)
+    }
+}
+
+impl<B: Backend> BackendExt for RecordingBackend<B> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleBackend;
+
+    #[test]
+    fn test_recording_backend() {
+        let mut inner = SimpleBackend::new();
+        inner.add_translations(
+            "en".into(),
+            HashMap::from([("hello".into(), "Hello".into())]),
+        );
+        let backend = RecordingBackend::new(inner);
+        let handle = backend.clone();
+
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("Hello"));
+        backend.translate("en", "bye");
+        backend.translate("fr", "hello");
+        backend.translate("fr", "hello");
+
+        assert_eq!(
+            handle.drain_missing(),
+            vec![
+                MissingKey {
+                    locale: "fr".into(),
+                    key: "hello".into(),
+                    count: 2,
+                },
+                MissingKey {
+                    locale: "en".into(),
+                    key: "bye".into(),
+                    count: 1,
+                },
+            ]
+        );
+        assert!(backend.drain_missing().is_empty());
+    }
+}
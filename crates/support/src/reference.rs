@@ -0,0 +1,143 @@
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+
+use crate::MessageTemplate;
+
+/// The pattern of a reference to another key, e.g. `%{@brand.name}`.
+const REFERENCE_START: &str = "%{@";
+
+/// Resolve the references to other keys in a text, e.g. `%{@brand.name}`, `translate` gets
+/// the text of a key.
+///
+/// The escaped `%%{@key}` is not a reference. The references in the referenced texts are resolved too. A reference to a missing key, or
+/// to a key referencing itself back, is kept as is.
+///
+/// ```
+/// # use rust_i18n_support::resolve_references;
+/// # use std::borrow::Cow;
+/// let translate = |key: &str| {
+///     let text = match key {
+///         "brand.name" => "Acme %{@brand.suffix}",
+///         "brand.suffix" => "Cloud",
+///         "loop" => "%{@loop}",
+///         _ => return None,
+///     };
+///     Some(Cow::Borrowed(text))
+/// };
+/// let text = resolve_references("Welcome to %{@brand.name}, %{name}!".into(), translate);
+/// assert_eq!(text, "Welcome to Acme Cloud, %{name}!");
+/// assert_eq!(resolve_references("%{@loop}".into(), translate), "%{@loop}");
+/// assert_eq!(resolve_references("%{@missing}".into(), translate), "%{@missing}");
+/// ```
+pub fn resolve_references<'a>(
+    text: Cow<'a, str>,
+    translate: impl Fn(&str) -> Option<Cow<'a, str>>,
+) -> Cow<'a, str> {
+    if !text.contains(REFERENCE_START) {
+        return text;
+    }
+
+    let mut output = String::with_capacity(text.len());
+    resolve(&text, &translate, &mut vec![], &mut output);
+    Cow::Owned(output)
+}
+
+/// The keys referenced by a text, e.g. `brand.name` of `%{@brand.name}`, the escaped `%%{@key}`
+/// is not a reference.
+///
+/// ```
+/// # use rust_i18n_support::references;
+/// assert_eq!(references("%{@brand.name} %%{@escaped} %{@b}"), vec!["brand.name", "b"]);
+/// ```
+pub fn references(text: &str) -> Vec<&str> {
+    let mut keys = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find(REFERENCE_START) {
+        let reference = &rest[start + REFERENCE_START.len()..];
+        let escaped = rest[..start].ends_with('%');
+        let Some(end) = reference.find('}') else {
+            break;
+        };
+        if !escaped {
+            keys.push(&reference[..end]);
+        }
+        rest = &reference[end + 1..];
+    }
+    keys
+}
+
+/// Resolve the references of a template like [`resolve_references`], it's parsed again only
+/// when it has references.
+pub fn resolve_template_references<'a>(
+    template: MessageTemplate<'a>,
+    translate: impl Fn(&str) -> Option<Cow<'a, str>>,
+) -> MessageTemplate<'a> {
+    if !template.text().contains(REFERENCE_START) {
+        return template;
+    }
+
+    MessageTemplate::parse(resolve_references(template.into_text(), translate))
+}
+
+fn resolve<'a>(
+    text: &str,
+    translate: &impl Fn(&str) -> Option<Cow<'a, str>>,
+    stack: &mut Vec<String>,
+    output: &mut String,
+) {
+    let mut rest = text;
+    while let Some(start) = rest.find(REFERENCE_START) {
+        if rest[..start].ends_with('%') {
+            // An escaped `%%{@`
+            output.push_str(&rest[..start + REFERENCE_START.len()]);
+            rest = &rest[start + REFERENCE_START.len()..];
+            continue;
+        }
+        output.push_str(&rest[..start]);
+        let reference = &rest[start..];
+        let Some(end) = reference.find('}') else {
+            rest = reference;
+            break;
+        };
+        let key = &reference[REFERENCE_START.len()..end];
+        let is_cycle = stack.iter().any(|k| k == key);
+        match (!is_cycle).then(|| translate(key)).flatten() {
+            Some(referenced) => {
+                stack.push(key.into());
+                resolve(&referenced, translate, stack, output);
+                stack.pop();
+            }
+            None => output.push_str(&reference[..=end]),
+        }
+        rest = &reference[end + 1..];
+    }
+    output.push_str(rest);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_references() {
+        let translate = |key: &str| {
+            let text = match key {
+                "a" => "A(%{@b})",
+                "b" => "B(%{@c}, %{@c})",
+                "c" => "C",
+                "x" => "X(%{@y})",
+                "y" => "Y(%{@x})",
+                _ => return None,
+            };
+            Some(Cow::Borrowed(text))
+        };
+        let resolve = |text: &'static str| resolve_references(text.into(), translate);
+
+        assert!(matches!(resolve("plain %{name}"), Cow::Borrowed(_)));
+        assert_eq!(resolve("%{@a}!"), "A(B(C, C))!");
+        assert_eq!(resolve("%{@x}"), "X(Y(%{@x}))");
+        assert_eq!(resolve("%{@c}%{@c}"), "CC");
+        assert_eq!(resolve("%{@}"), "%{@}");
+        assert_eq!(resolve("unclosed %{@a"), "unclosed %{@a");
+        assert_eq!(resolve("%%{@c} is %{@c}"), "%%{@c} is C");
+    }
+}
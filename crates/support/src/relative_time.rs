@@ -0,0 +1,188 @@
+use alloc::{borrow::Cow, format, string::String, string::ToString};
+use core::time::Duration;
+
+use crate::plural_category;
+
+/// The units of a [`RelativeTime`], from the smallest.
+static UNITS: &[(&str, u64)] = &[
+    ("seconds", 1),
+    ("minutes", 60),
+    ("hours", 60 * 60),
+    ("days", 24 * 60 * 60),
+    ("weeks", 7 * 24 * 60 * 60),
+    ("months", 30 * 24 * 60 * 60),
+    ("years", 365 * 24 * 60 * 60),
+];
+
+/// A time relative to now, formatted like `3 minutes ago` or `in 2 days`.
+///
+/// The texts are the `relative_time.{unit}.{past,future}.{category}` keys of the locale
+/// files, e.g. `relative_time.minutes.past.one`, with the number as `%{count}`, and the
+/// `relative_time.now` key when it is less than a second away. The missing texts are the
+/// English ones.
+///
+/// ```
+/// # use rust_i18n_support::RelativeTime;
+/// # use std::{borrow::Cow, time::Duration};
+/// let time = RelativeTime::from(Duration::from_secs(180));
+/// assert_eq!(time.format("en", |_| None), "3 minutes ago");
+///
+/// let time = RelativeTime::from_seconds(2 * 24 * 60 * 60);
+/// assert_eq!(time.format("en", |_| None), "in 2 days");
+///
+/// let time = RelativeTime::from_seconds(-1);
+/// let translate = |key: &str| (key == "relative_time.seconds.past.other").then(|| Cow::from("%{count} 秒前"));
+/// assert_eq!(time.format("zh-CN", translate), "1 秒前");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RelativeTime {
+    seconds: i64,
+}
+
+impl RelativeTime {
+    /// The time `seconds` from now, in the past when negative.
+    pub fn from_seconds(seconds: i64) -> Self {
+        Self { seconds }
+    }
+
+    /// The seconds from now, in the past when negative.
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Get the largest unit and the whole number of it, e.g. `("minutes", 3)`.
+    pub fn unit(&self) -> (&'static str, u64) {
+        let seconds = self.seconds.unsigned_abs();
+        let (unit, size) = UNITS
+            .iter()
+            .rev()
+            .find(|(_, size)| seconds >= *size)
+            .unwrap_or(&UNITS[0]);
+        (unit, seconds / size)
+    }
+
+    /// Format the time in a locale, `translate` gets the text of a key in the locale.
+    pub fn format<'a>(
+        &self,
+        locale: &str,
+        translate: impl Fn(&str) -> Option<Cow<'a, str>>,
+    ) -> String {
+        if self.seconds == 0 {
+            return translate("relative_time.now")
+                .map(|text| text.into_owned())
+                .unwrap_or_else(|| "now".to_string());
+        }
+
+        let (unit, count) = self.unit();
+        let tense = if self.seconds < 0 { "past" } else { "future" };
+        let key = format!("relative_time.{}.{}", unit, tense);
+        let text = translate(&format!("{}.{}", key, plural_category(locale, count)))
+            .or_else(|| translate(&format!("{}.other", key)))
+            .map(|text| text.into_owned())
+            .unwrap_or_else(|| default_text(unit, tense, count));
+
+        text.replace("%{count}", &count.to_string())
+    }
+}
+
+/// The English text of a unit, e.g. `%{count} minutes ago`.
+fn default_text(unit: &str, tense: &str, count: u64) -> String {
+    let unit = if count == 1 {
+        unit.trim_end_matches('s')
+    } else {
+        unit
+    };
+    match tense {
+        "past" => format!("%{{count}} {} ago", unit),
+        _ => format!("in %{{count}} {}", unit),
+    }
+}
+
+impl From<i64> for RelativeTime {
+    fn from(seconds: i64) -> Self {
+        Self::from_seconds(seconds)
+    }
+}
+
+/// The time elapsed since, e.g. `Instant::elapsed`, it is in the past.
+impl From<Duration> for RelativeTime {
+    fn from(duration: Duration) -> Self {
+        Self::from_seconds(-(duration.as_secs().min(i64::MAX as u64) as i64))
+    }
+}
+
+/// The timestamp, relative to the current system time.
+#[cfg(feature = "std")]
+impl From<std::time::SystemTime> for RelativeTime {
+    fn from(time: std::time::SystemTime) -> Self {
+        let seconds = |duration: Duration| duration.as_secs().min(i64::MAX as u64) as i64;
+        match time.duration_since(std::time::SystemTime::now()) {
+            Ok(duration) => Self::from_seconds(seconds(duration)),
+            Err(err) => Self::from_seconds(-seconds(err.duration())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_time_unit() {
+        assert_eq!(RelativeTime::from_seconds(0).unit(), ("seconds", 0));
+        assert_eq!(RelativeTime::from_seconds(-59).unit(), ("seconds", 59));
+        assert_eq!(RelativeTime::from_seconds(60).unit(), ("minutes", 1));
+        assert_eq!(RelativeTime::from_seconds(-7199).unit(), ("hours", 1));
+        assert_eq!(RelativeTime::from_seconds(6 * 86400).unit(), ("days", 6));
+        assert_eq!(RelativeTime::from_seconds(29 * 86400).unit(), ("weeks", 4));
+        assert_eq!(
+            RelativeTime::from_seconds(-90 * 86400).unit(),
+            ("months", 3)
+        );
+        assert_eq!(RelativeTime::from_seconds(800 * 86400).unit(), ("years", 2));
+    }
+
+    #[test]
+    fn test_relative_time_format() {
+        let format = |seconds: i64| RelativeTime::from_seconds(seconds).format("en", |_| None);
+        assert_eq!(format(0), "now");
+        assert_eq!(format(-1), "1 second ago");
+        assert_eq!(format(-180), "3 minutes ago");
+        assert_eq!(format(3600), "in 1 hour");
+        assert_eq!(format(2 * 86400), "in 2 days");
+        assert_eq!(format(-400 * 86400), "1 year ago");
+
+        let translate = |key: &str| {
+            let text = match key {
+                "relative_time.now" => "сейчас",
+                "relative_time.minutes.past.one" => "%{count} минуту назад",
+                "relative_time.minutes.past.few" => "%{count} минуты назад",
+                "relative_time.minutes.past.other" => "%{count} минут назад",
+                _ => return None,
+            };
+            Some(Cow::Borrowed(text))
+        };
+        let format = |seconds: i64| RelativeTime::from_seconds(seconds).format("ru", translate);
+        assert_eq!(format(0), "сейчас");
+        assert_eq!(format(-21 * 60), "21 минуту назад");
+        assert_eq!(format(-3 * 60), "3 минуты назад");
+        // `many` is missing, the `other` text is used
+        assert_eq!(format(-5 * 60), "5 минут назад");
+        // The missing texts are the English ones
+        assert_eq!(format(5 * 60), "in 5 minutes");
+    }
+
+    #[test]
+    fn test_relative_time_from() {
+        assert_eq!(RelativeTime::from(Duration::from_secs(90)).seconds(), -90);
+        assert_eq!(RelativeTime::from(-5).seconds(), -5);
+
+        let now = std::time::SystemTime::now();
+        let time = RelativeTime::from(now + Duration::from_secs(3 * 3600 + 30));
+        assert_eq!(time.unit(), ("hours", 3));
+        assert!(time.seconds() > 0);
+        let time = RelativeTime::from(now - Duration::from_secs(2 * 86400));
+        assert_eq!(time.unit(), ("days", 2));
+        assert!(time.seconds() < 0);
+    }
+}
@@ -0,0 +1,160 @@
+/// Get the preferred locales of the operating system, in priority order, e.g. `["zh-CN", "en"]`.
+///
+/// - Unix: the `LANGUAGE`, `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables.
+/// - macOS: the preferred languages of `NSLocale`, then the environment variables.
+/// - Windows: `GetUserDefaultLocaleName`.
+pub fn system_locales() -> Vec<String> {
+    let mut locales = platform_locales();
+    for locale in env_locales(|name| std::env::var(name).ok()) {
+        if !locales.contains(&locale) {
+            locales.push(locale);
+        }
+    }
+    locales
+}
+
+/// Get the locales of the POSIX environment variables, `LANGUAGE` is a `:` separated list.
+fn env_locales(var: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let mut locales: Vec<String> = vec![];
+    let mut push = |value: &str| {
+        if let Some(locale) = normalize_posix_locale(value) {
+            if !locales.contains(&locale) {
+                locales.push(locale);
+            }
+        }
+    };
+
+    // `LANGUAGE` is ignored when the locale is `C`, like gettext.
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|name| var(name).filter(|value| !value.is_empty()));
+    if locale.as_deref().and_then(normalize_posix_locale).is_some() {
+        for value in var("LANGUAGE").unwrap_or_default().split(':') {
+            push(value);
+        }
+    }
+    if let Some(locale) = locale {
+        push(&locale);
+    }
+    locales
+}
+
+/// Convert a POSIX locale like `zh_CN.UTF-8@euro` to a language tag like `zh-CN`.
+fn normalize_posix_locale(value: &str) -> Option<String> {
+    let tag = value.split(['.', '@']).next()?.trim();
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    Some(tag.replace('_', "-"))
+}
+
+#[cfg(target_os = "windows")]
+fn platform_locales() -> Vec<String> {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetUserDefaultLocaleName(name: *mut u16, len: i32) -> i32;
+    }
+
+    // LOCALE_NAME_MAX_LENGTH
+    let mut buf = [0u16; 85];
+    let len = unsafe { GetUserDefaultLocaleName(buf.as_mut_ptr(), buf.len() as i32) };
+    if len <= 1 {
+        return vec![];
+    }
+    // The length includes the terminating null character.
+    vec![String::from_utf16_lossy(&buf[..len as usize - 1])]
+}
+
+#[cfg(target_os = "macos")]
+fn platform_locales() -> Vec<String> {
+    use std::ffi::{c_char, c_void, CStr};
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFLocaleCopyPreferredLanguages() -> *const c_void;
+        fn CFArrayGetCount(array: *const c_void) -> isize;
+        fn CFArrayGetValueAtIndex(array: *const c_void, index: isize) -> *const c_void;
+        fn CFStringGetCString(
+            string: *const c_void,
+            buf: *mut c_char,
+            len: isize,
+            encoding: u32,
+        ) -> u8;
+        fn CFRelease(cf: *const c_void);
+    }
+    const UTF8: u32 = 0x0800_0100;
+
+    let mut locales = vec![];
+    unsafe {
+        let languages = CFLocaleCopyPreferredLanguages();
+        if languages.is_null() {
+            return locales;
+        }
+        for i in 0..CFArrayGetCount(languages) {
+            let mut buf = [0 as c_char; 64];
+            let language = CFArrayGetValueAtIndex(languages, i);
+            if CFStringGetCString(language, buf.as_mut_ptr(), buf.len() as isize, UTF8) != 0 {
+                let language = CStr::from_ptr(buf.as_ptr()).to_string_lossy();
+                locales.push(language.replace('_', "-"));
+            }
+        }
+        CFRelease(languages);
+    }
+    locales
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_locales() -> Vec<String> {
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_posix_locale() {
+        assert_eq!(
+            normalize_posix_locale("zh_CN.UTF-8").as_deref(),
+            Some("zh-CN")
+        );
+        assert_eq!(
+            normalize_posix_locale("de_DE@euro").as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(normalize_posix_locale("en").as_deref(), Some("en"));
+        assert_eq!(normalize_posix_locale("C.UTF-8"), None);
+        assert_eq!(normalize_posix_locale("POSIX"), None);
+        assert_eq!(normalize_posix_locale(""), None);
+    }
+
+    #[test]
+    fn test_env_locales() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(env_locales(env(&[("LANG", "fr_FR.UTF-8")])), ["fr-FR"]);
+        assert_eq!(
+            env_locales(env(&[
+                ("LANG", "en_US.UTF-8"),
+                ("LC_ALL", "de_AT.UTF-8"),
+                ("LANGUAGE", "pt_BR:pt:en_US"),
+            ])),
+            ["pt-BR", "pt", "en-US", "de-AT"]
+        );
+        assert_eq!(
+            env_locales(env(&[("LC_ALL", "C"), ("LANGUAGE", "fr")])),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            env_locales(env(&[("LANG", ""), ("LC_MESSAGES", "ja_JP")])),
+            ["ja-JP"]
+        );
+        assert!(env_locales(env(&[])).is_empty());
+    }
+}
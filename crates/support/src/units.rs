@@ -0,0 +1,258 @@
+use alloc::{format, string::String};
+
+/// Languages using a decimal comma, e.g. `1,5 MB`.
+static DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "az", "be", "bg", "bs", "ca", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id",
+    "it", "kk", "lt", "lv", "nb", "nl", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr",
+    "uk", "vi",
+];
+
+/// Languages with a space before the percent sign, e.g. `45 %`.
+static SPACED_PERCENT_LANGUAGES: &[&str] = &[
+    "bg", "cs", "da", "de", "es", "et", "fi", "fr", "lt", "nb", "no", "ru", "sk", "sv", "uk",
+];
+
+/// Regions measuring distances in miles.
+static IMPERIAL_REGIONS: &[&str] = &["GB", "LR", "MM", "US"];
+
+static BYTE_UNITS: [&str; 7] = ["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+static BINARY_BYTE_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+/// The French units, in octets.
+static BYTE_UNITS_FR: [&str; 7] = ["o", "Ko", "Mo", "Go", "To", "Po", "Eo"];
+static BINARY_BYTE_UNITS_FR: [&str; 7] = ["o", "Kio", "Mio", "Gio", "Tio", "Pio", "Eio"];
+
+const METERS_PER_MILE: f64 = 1609.344;
+const FEET_PER_METER: f64 = 3.28084;
+
+/// The measurement system of the distances, see [`format_distance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeasurementSystem {
+    /// Meters and kilometers.
+    Metric,
+    /// Feet and miles.
+    Imperial,
+}
+
+impl MeasurementSystem {
+    /// Get the measurement system of a locale by its region, e.g. `Imperial` for `en-US`.
+    pub fn for_locale(locale: &str) -> Self {
+        let is_imperial = locale
+            .split(['-', '_'])
+            .skip(1)
+            .any(|subtag| IMPERIAL_REGIONS.contains(&subtag));
+        if is_imperial {
+            Self::Imperial
+        } else {
+            Self::Metric
+        }
+    }
+}
+
+fn language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or_default()
+}
+
+/// Format a number with at most `fraction_digits` digits after the decimal separator of the locale.
+fn format_number(locale: &str, value: f64, fraction_digits: usize) -> String {
+    let mut text = format!("{:.*}", fraction_digits, value);
+    if text.contains('.') {
+        text.truncate(text.trim_end_matches('0').trim_end_matches('.').len());
+    }
+    if text == "-0" {
+        text.remove(0);
+    }
+    if DECIMAL_COMMA_LANGUAGES.contains(&language(locale)) {
+        text = text.replace('.', ",");
+    }
+    text
+}
+
+fn format_scaled(locale: &str, value: u64, base: u64, units: &[&str; 7]) -> String {
+    let mut scaled = value as f64;
+    let mut unit = 0;
+    while scaled >= base as f64 && unit + 1 < units.len() {
+        scaled /= base as f64;
+        unit += 1;
+    }
+    format!("{} {}", format_number(locale, scaled, 1), units[unit])
+}
+
+/// Format a size in bytes with the decimal units, e.g. `1.5 MB` for `1_500_000`.
+///
+/// ```
+/// # use rust_i18n_support::format_bytes;
+/// assert_eq!(format_bytes("en", 1_500_000), "1.5 MB");
+/// assert_eq!(format_bytes("de", 1_500_000), "1,5 MB");
+/// assert_eq!(format_bytes("fr", 2_000), "2 Ko");
+/// ```
+pub fn format_bytes(locale: &str, bytes: u64) -> String {
+    let units = match language(locale) {
+        "fr" => &BYTE_UNITS_FR,
+        _ => &BYTE_UNITS,
+    };
+    format_scaled(locale, bytes, 1000, units)
+}
+
+/// Format a size in bytes with the binary units, e.g. `1.5 MiB` for `1_572_864`.
+///
+/// ```
+/// # use rust_i18n_support::format_binary_bytes;
+/// assert_eq!(format_binary_bytes("en", 1_572_864), "1.5 MiB");
+/// assert_eq!(format_binary_bytes("en", 512), "512 B");
+/// ```
+pub fn format_binary_bytes(locale: &str, bytes: u64) -> String {
+    let units = match language(locale) {
+        "fr" => &BINARY_BYTE_UNITS_FR,
+        _ => &BINARY_BYTE_UNITS,
+    };
+    format_scaled(locale, bytes, 1024, units)
+}
+
+/// Format a distance in meters, in `m` and `km`, or in `ft` and `mi`.
+///
+/// ```
+/// # use rust_i18n_support::{format_distance, MeasurementSystem};
+/// assert_eq!(format_distance("en", 850.0, MeasurementSystem::Metric), "850 m");
+/// assert_eq!(format_distance("de", 12_340.0, MeasurementSystem::Metric), "12,3 km");
+/// assert_eq!(format_distance("en-US", 3_218.688, MeasurementSystem::Imperial), "2 mi");
+/// ```
+pub fn format_distance(locale: &str, meters: f64, system: MeasurementSystem) -> String {
+    let (value, unit) = match system {
+        MeasurementSystem::Metric if meters < 1000.0 && meters > -1000.0 => (meters, "m"),
+        MeasurementSystem::Metric => (meters / 1000.0, "km"),
+        MeasurementSystem::Imperial => {
+            let miles = meters / METERS_PER_MILE;
+            if miles < 0.1 && miles > -0.1 {
+                (meters * FEET_PER_METER, "ft")
+            } else {
+                (miles, "mi")
+            }
+        }
+    };
+    let fraction_digits = if unit == "m" || unit == "ft" { 0 } else { 1 };
+    format!("{} {}", format_number(locale, value, fraction_digits), unit)
+}
+
+/// Format a ratio as a percentage, e.g. `45%` for `0.45`.
+///
+/// ```
+/// # use rust_i18n_support::format_percent;
+/// assert_eq!(format_percent("en", 0.45), "45%");
+/// assert_eq!(format_percent("de", 0.125), "12,5\u{a0}%");
+/// assert_eq!(format_percent("tr", 0.45), "%45");
+/// ```
+pub fn format_percent(locale: &str, ratio: f64) -> String {
+    let number = format_number(locale, ratio * 100.0, 1);
+    match language(locale) {
+        "tr" | "eu" => format!("%{}", number),
+        language if SPACED_PERCENT_LANGUAGES.contains(&language) => format!("{}\u{a0}%", number),
+        _ => format!("{}%", number),
+    }
+}
+
+/// Format a value by an interpolation format hint, e.g. `bytes` in `%{size:bytes}`.
+///
+/// The hints are `bytes`, `binary_bytes`, `distance` (in meters) and `percent` (a ratio),
+/// returns `None` for an unknown hint or a value that is not a number.
+pub fn format_unit(
+    locale: &str,
+    hint: &str,
+    value: &str,
+    system: MeasurementSystem,
+) -> Option<String> {
+    let value = value.trim();
+    match hint {
+        "bytes" => value.parse().ok().map(|bytes| format_bytes(locale, bytes)),
+        "binary_bytes" => value
+            .parse()
+            .ok()
+            .map(|bytes| format_binary_bytes(locale, bytes)),
+        "distance" => value
+            .parse()
+            .ok()
+            .map(|meters| format_distance(locale, meters, system)),
+        "percent" => value
+            .parse()
+            .ok()
+            .map(|ratio| format_percent(locale, ratio)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes("en", 0), "0 B");
+        assert_eq!(format_bytes("en", 999), "999 B");
+        assert_eq!(format_bytes("en", 1000), "1 KB");
+        assert_eq!(format_bytes("en", 1_234_567_890), "1.2 GB");
+        assert_eq!(format_bytes("pt-BR", 1_260_000), "1,3 MB");
+        assert_eq!(format_bytes("fr-CA", 3_000_000), "3 Mo");
+        assert_eq!(format_bytes("en", u64::MAX), "18.4 EB");
+        assert_eq!(format_binary_bytes("en", 1024), "1 KiB");
+        assert_eq!(format_binary_bytes("fr", 1536), "1,5 Kio");
+    }
+
+    #[test]
+    fn test_format_distance() {
+        assert_eq!(
+            MeasurementSystem::for_locale("en"),
+            MeasurementSystem::Metric
+        );
+        assert_eq!(
+            MeasurementSystem::for_locale("en-US"),
+            MeasurementSystem::Imperial
+        );
+        assert_eq!(
+            MeasurementSystem::for_locale("en_GB"),
+            MeasurementSystem::Imperial
+        );
+        assert_eq!(
+            MeasurementSystem::for_locale("de-DE"),
+            MeasurementSystem::Metric
+        );
+
+        let metric = MeasurementSystem::Metric;
+        assert_eq!(format_distance("en", 0.0, metric), "0 m");
+        assert_eq!(format_distance("en", 999.4, metric), "999 m");
+        assert_eq!(format_distance("en", 1500.0, metric), "1.5 km");
+        let imperial = MeasurementSystem::Imperial;
+        assert_eq!(format_distance("en", 100.0, imperial), "328 ft");
+        assert_eq!(format_distance("en", 5000.0, imperial), "3.1 mi");
+    }
+
+    #[test]
+    fn test_format_percent() {
+        assert_eq!(format_percent("en", 1.0), "100%");
+        assert_eq!(format_percent("en", 0.0), "0%");
+        assert_eq!(format_percent("en", -0.05), "-5%");
+        assert_eq!(format_percent("fr", 0.5), "50\u{a0}%");
+        assert_eq!(format_percent("zh-CN", 0.333), "33.3%");
+    }
+
+    #[test]
+    fn test_format_unit() {
+        let metric = MeasurementSystem::Metric;
+        assert_eq!(
+            format_unit("en", "bytes", "1500", metric).as_deref(),
+            Some("1.5 KB")
+        );
+        assert_eq!(
+            format_unit("en", "binary_bytes", "2048", metric).as_deref(),
+            Some("2 KiB")
+        );
+        assert_eq!(
+            format_unit("en", "distance", "2500", metric).as_deref(),
+            Some("2.5 km")
+        );
+        assert_eq!(
+            format_unit("en", "percent", "0.25", metric).as_deref(),
+            Some("25%")
+        );
+        assert_eq!(format_unit("en", "bytes", "many", metric), None);
+        assert_eq!(format_unit("en", "unknown", "1", metric), None);
+    }
+}
@@ -0,0 +1,87 @@
+use actix_web::{web, App, HttpRequest, HttpServer};
+use rust_i18n::{
+    http::{Catalog, HttpRequestExt, LocaleConfig, LocaleMiddleware, RequestLocale},
+    t,
+};
+
+rust_i18n::i18n!("locales", fallback = "en");
+
+async fn hello(path: web::Path<String>) -> String {
+    t!("hello", name = path.into_inner()).into()
+}
+
+async fn hello_req(req: HttpRequest) -> String {
+    req.t("hello", &[("name", &"actix")])
+}
+
+async fn locale(locale: RequestLocale) -> String {
+    t!("locale", code = locale).into()
+}
+
+fn config() -> LocaleConfig {
+    LocaleConfig::new(rust_i18n::available_locales!())
+        .default_locale("en")
+        .translator(rust_i18n::translator!())
+}
+
+fn catalog() -> Catalog {
+    Catalog::new(rust_i18n::backend!())
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    println!("Listening on http://127.0.0.1:8080/hello/world");
+    HttpServer::new(|| {
+        App::new()
+            .wrap(LocaleMiddleware::new(config()))
+            .route("/hello", web::get().to(hello_req))
+            .route("/hello/{name}", web::get().to(hello))
+            .route("/locale", web::get().to(locale))
+            .route("/i18n/{locale}", web::get().to(catalog()))
+    })
+    .bind(("127.0.0.1", 8080))?
+    .run()
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test;
+
+    async fn get(uri: &str, headers: &[(&str, &str)]) -> String {
+        let app = test::init_service(
+            App::new()
+                .wrap(LocaleMiddleware::new(config()))
+                .route("/hello", web::get().to(hello_req))
+                .route("/hello/{name}", web::get().to(hello))
+                .route("/locale", web::get().to(locale))
+                .route("/i18n/{locale}", web::get().to(catalog())),
+        )
+        .await;
+        let mut request = test::TestRequest::get().uri(uri);
+        for header in headers {
+            request = request.insert_header(*header);
+        }
+        let body = test::call_and_read_body(&app, request.to_request()).await;
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_request_locale() {
+        assert_eq!(get("/hello/Jason", &[]).await, "Hello, Jason!");
+        assert_eq!(
+            get("/hello/Jason", &[("accept-language", "zh-TW,en;q=0.5")]).await,
+            "你好，Jason！"
+        );
+        assert_eq!(get("/hello?locale=zh-CN", &[]).await, "你好，actix！");
+        assert_eq!(
+            get("/locale", &[("cookie", "locale=zh-CN")]).await,
+            "你的语言是 zh-CN。"
+        );
+        assert_eq!(
+            get("/i18n/zh-CN?ns=hello", &[]).await,
+            r#"{"hello":"你好，%{name}！"}"#
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use axum::{extract::Path, routing::get, Router};
+use rust_i18n::{
+    http::{Catalog, LocaleConfig, LocaleLayer, LocaleSource, RequestLocale},
+    t,
+};
+
+rust_i18n::i18n!("locales", fallback = "en");
+
+async fn hello(Path(name): Path<String>) -> String {
+    t!("hello", name = name).into()
+}
+
+async fn locale(locale: RequestLocale) -> String {
+    t!("locale", code = locale).into()
+}
+
+fn app() -> Router {
+    let config = LocaleConfig::new(rust_i18n::available_locales!())
+        .sources([
+            LocaleSource::Query("locale".into()),
+            LocaleSource::Cookie("locale".into()),
+            LocaleSource::AcceptLanguage,
+        ])
+        .default_locale("en");
+
+    Router::new()
+        .route("/hello/{name}", get(hello))
+        .route("/locale", get(locale))
+        .route_service("/i18n/{locale}", Catalog::new(rust_i18n::backend!()))
+        .layer(LocaleLayer::new(config))
+}
+
+#[tokio::main]
+async fn main() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+        .await
+        .unwrap();
+    println!("Listening on http://127.0.0.1:3000/hello/world");
+    axum::serve(listener, app()).await.unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    async fn get(uri: &str, headers: &[(&str, &str)]) -> String {
+        let mut request = Request::get(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let response = app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_locale() {
+        assert_eq!(get("/hello/Jason", &[]).await, "Hello, Jason!");
+        assert_eq!(
+            get("/hello/Jason", &[("accept-language", "zh-TW,en;q=0.5")]).await,
+            "你好，Jason！"
+        );
+        assert_eq!(
+            get("/hello/Jason?locale=en", &[("accept-language", "zh-CN")]).await,
+            "Hello, Jason!"
+        );
+        assert_eq!(
+            get("/locale", &[("cookie", "locale=zh-CN")]).await,
+            "你的语言是 zh-CN。"
+        );
+        assert_eq!(rust_i18n::locale().to_string(), "en");
+    }
+
+    #[tokio::test]
+    async fn test_catalog() {
+        assert_eq!(
+            get("/i18n/zh", &[]).await,
+            r#"{"hello":"你好，%{name}！","locale":"你的语言是 %{code}。"}"#
+        );
+        assert_eq!(
+            get("/i18n/en?ns=hello", &[]).await,
+            r#"{"hello":"Hello, %{name}!"}"#
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! A headless game, the `Label` is like the `Text` of `bevy_ui`.
+
+use bevy_app::{App, Startup};
+use bevy_ecs::prelude::*;
+use rust_i18n::bevy::{CurrentLocale, LocalizedText, RustI18nPlugin};
+
+rust_i18n::i18n!("locales", fallback = "en");
+
+#[derive(Component, Default)]
+struct Label(String);
+
+impl std::ops::Deref for Label {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for Label {
+    fn deref_mut(&mut self) -> &mut String {
+        &mut self.0
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((Label::default(), LocalizedText::new("menu.start")));
+    commands.spawn((
+        Label::default(),
+        LocalizedText::new("menu.score").arg("score", 42),
+    ));
+}
+
+fn app() -> App {
+    rust_i18n::set_locale("en");
+    let mut app = App::new();
+    app.add_plugins(RustI18nPlugin::new(rust_i18n::translator!()).text::<Label>())
+        .add_systems(Startup, setup);
+    app
+}
+
+fn labels(app: &mut App) -> Vec<String> {
+    let mut labels = app
+        .world_mut()
+        .query::<&Label>()
+        .iter(app.world())
+        .map(|label| label.0.clone())
+        .collect::<Vec<_>>();
+    labels.sort();
+    labels
+}
+
+fn main() {
+    let mut app = app();
+    app.update();
+    println!("{:?}", labels(&mut app));
+    app.world_mut().resource_mut::<CurrentLocale>().set("zh-CN");
+    app.update();
+    println!("{:?}", labels(&mut app));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_i18n::bevy::LocaleChanged;
+
+    #[test]
+    fn test_switch_locale() {
+        let mut app = app();
+        app.update();
+        assert_eq!(labels(&mut app), ["Score: 42", "Start"]);
+
+        app.world_mut().resource_mut::<CurrentLocale>().set("zh-CN");
+        app.update();
+        assert_eq!(labels(&mut app), ["开始", "得分：42"]);
+        assert_eq!(rust_i18n::locale().to_string(), "zh-CN");
+        let changed = app
+            .world_mut()
+            .resource_mut::<Messages<LocaleChanged>>()
+            .drain()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changed,
+            [LocaleChanged {
+                old: "en".into(),
+                new: "zh-CN".into()
+            }]
+        );
+
+        // Changing the key of a text
+        let mut query = app.world_mut().query::<&mut LocalizedText>();
+        for mut text in query.iter_mut(app.world_mut()) {
+            if text.key() == "menu.score" {
+                *text = LocalizedText::new("menu.score").arg("score", 100);
+            }
+        }
+        app.update();
+        assert_eq!(labels(&mut app), ["开始", "得分：100"]);
+    }
+}
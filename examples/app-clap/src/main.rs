@@ -0,0 +1,89 @@
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use rust_i18n::{
+    clap::{localize_command, localize_error},
+    t,
+};
+
+rust_i18n::i18n!("locales", fallback = "en");
+
+#[derive(Parser, Debug)]
+#[command(name = "app-clap")]
+struct Cli {
+    #[arg(short, long)]
+    verbose: bool,
+
+    #[arg(long, global = true, help = t!("cli.args.lang.help").to_string())]
+    lang: Option<String>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    Greet { name: String },
+}
+
+fn parse<I, T>(args: I) -> Result<Cli, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    localize_command(Cli::command(), "cli", rust_i18n::translator!())
+        .try_get_matches_from(args)
+        .and_then(|matches| Cli::from_arg_matches(&matches))
+        .map_err(|err| localize_error(err, "cli", rust_i18n::translator!()))
+}
+
+fn main() {
+    rust_i18n::set_locale_from_system!();
+    let cli = parse(std::env::args_os()).unwrap_or_else(|err| err.exit());
+    if let Some(lang) = &cli.lang {
+        rust_i18n::set_locale(lang);
+    }
+    match cli.command {
+        Commands::Greet { name } => println!("{}", t!("greeting", name = name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(args: &[&str]) -> String {
+        parse(args.iter().copied()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_localized_help() {
+        rust_i18n::set_locale("zh-CN");
+        let help = render(&["app-clap", "--help"]);
+        assert!(
+            help.starts_with("一个打招呼的工具\n\n用法： app-clap"),
+            "{}",
+            help
+        );
+        assert!(help.contains("命令:\n  greet  向某人打招呼"), "{}", help);
+        assert!(help.contains("选项:\n"), "{}", help);
+        assert!(help.contains("打印更多细节"), "{}", help);
+        assert!(help.contains("打印帮助"), "{}", help);
+        assert!(help.contains("输出的语言"), "{}", help);
+
+        let help = render(&["app-clap", "greet", "--help"]);
+        assert!(
+            help.starts_with("向某人打招呼\n\n用法： app-clap greet"),
+            "{}",
+            help
+        );
+        assert!(help.contains("要打招呼的名字"), "{}", help);
+
+        let error = render(&["app-clap", "--unknown"]);
+        assert!(error.contains("未知的参数 '--unknown'"), "{}", error);
+
+        rust_i18n::set_locale("en");
+        let help = render(&["app-clap", "--help"]);
+        assert!(help.contains("Print more details"), "{}", help);
+        let cli = parse(["app-clap", "-v", "greet", "Jason"]).unwrap();
+        assert!(cli.verbose);
+    }
+}
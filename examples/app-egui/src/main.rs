@@ -1,7 +1,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 use eframe::egui::{self, TextBuffer};
-use rust_i18n::t;
+use rust_i18n::{
+    egui::{install_fallback_fonts, LocaleSwitcher},
+    t,
+};
 
 rust_i18n::i18n!(
     "locales",
@@ -24,7 +27,7 @@ fn main() -> Result<(), eframe::Error> {
         Box::new(|cc| {
             // This gives us image support:
             // egui_extras::install_image_loaders(&cc.egui_ctx);
-            let _ = setup_custom_fonts(&cc.egui_ctx);
+            install_fallback_fonts(&cc.egui_ctx, rust_i18n::available_locales!());
             Box::<MyApp>::default()
         }),
     )
@@ -33,7 +36,6 @@ fn main() -> Result<(), eframe::Error> {
 struct MyApp {
     name: String,
     age: u32,
-    locale_id: usize,
 }
 
 impl Default for MyApp {
@@ -41,7 +43,6 @@ impl Default for MyApp {
         Self {
             name: t!("Arthur").into(),
             age: 42,
-            locale_id: 0,
         }
     }
 }
@@ -63,81 +64,12 @@ impl eframe::App for MyApp {
 
             ui.separator();
 
-            ui.horizontal(|ui| {
-                let locales = rust_i18n::available_locales!();
-                for (i, locale) in locales.iter().enumerate() {
-                    if ui
-                        .selectable_value(&mut self.locale_id, i, locale.clone())
-                        .changed()
-                    {
-                        rust_i18n::set_locale(locale);
-                        ui.ctx().send_viewport_cmd(egui::ViewportCommand::Title(
-                            t!("My egui App").to_string(),
-                        ));
-                    }
-                }
-            });
+            let switcher =
+                LocaleSwitcher::new(rust_i18n::available_locales!()).label(t!("Language"));
+            if ui.add(switcher).changed() {
+                ui.ctx()
+                    .send_viewport_cmd(egui::ViewportCommand::Title(t!("My egui App").to_string()));
+            }
         });
     }
 }
-
-#[cfg(windows)]
-fn try_load_system_font() -> Result<Vec<u8>, std::io::Error> {
-    let font_files = &[
-        "C:/Windows/Fonts/msyh.ttc",
-        "C:/Windows/Fonts/msjh.ttf",
-        "C:/Windows/Fonts/yugothr.ttc",
-        "C:/Windows/Fonts/malgun.ttf",
-    ];
-
-    for font in font_files {
-        if let Ok(font) = std::fs::read(font) {
-            return Ok(font);
-        }
-    }
-
-    Err(std::io::Error::new(
-        std::io::ErrorKind::NotFound,
-        "No system font found",
-    ))
-}
-
-#[cfg(windows)]
-fn setup_custom_fonts(ctx: &egui::Context) -> Result<(), std::io::Error> {
-    // Start with the default fonts (we will be adding to them rather than replacing them).
-    let mut fonts = egui::FontDefinitions::default();
-
-    // Install my own font (maybe supporting non-latin characters).
-    // .ttf and .otf files supported.
-    let font_bytes = try_load_system_font()?;
-    fonts
-        .font_data
-        .insert("my_font".to_owned(), egui::FontData::from_owned(font_bytes));
-
-    // Put my font first (highest priority) for proportional text:
-    fonts
-        .families
-        .entry(egui::FontFamily::Proportional)
-        .or_default()
-        .insert(0, "my_font".to_owned());
-
-    // Put my font as last fallback for monospace:
-    fonts
-        .families
-        .entry(egui::FontFamily::Monospace)
-        .or_default()
-        .push("my_font".to_owned());
-
-    // Tell egui to use these fonts:
-    ctx.set_fonts(fonts);
-
-    Ok(())
-}
-
-#[cfg(not(windows))]
-fn setup_custom_fonts(_ctx: &egui::Context) -> Result<(), std::io::Error> {
-    Err(std::io::Error::new(
-        std::io::ErrorKind::Unsupported,
-        "Custom fonts not supported on this platform",
-    ))
-}
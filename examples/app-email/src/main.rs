@@ -0,0 +1,138 @@
+//! Render the weekly digest emails of the users, each one in the locale of its recipient.
+//!
+//! The emails are rendered concurrently, `with_locale_async` keeps the locale of each one
+//! across the `.await`s, for `t!` and the `t` function of the Tera templates.
+use std::{sync::LazyLock, time::Duration};
+
+use lettre::{
+    message::{Mailbox, MultiPart},
+    Message,
+};
+use rust_i18n::t;
+use tera::{Context, Tera};
+
+rust_i18n::i18n!("locales", fallback = "en");
+
+struct User {
+    name: &'static str,
+    email: &'static str,
+    locale: &'static str,
+}
+
+static USERS: &[User] = &[
+    User {
+        name: "Jason",
+        email: "jason@example.com",
+        locale: "en",
+    },
+    User {
+        name: "李雷",
+        email: "lilei@example.com",
+        locale: "zh-CN",
+    },
+    User {
+        name: "Amélie",
+        email: "amelie@example.com",
+        locale: "fr",
+    },
+];
+
+static TEMPLATES: LazyLock<Tera> = LazyLock::new(|| {
+    let mut tera = Tera::default();
+    tera.add_raw_template("digest.html", include_str!("../templates/digest.html"))
+        .unwrap();
+    rust_i18n::tera::register(&mut tera, rust_i18n::translator!());
+    tera
+});
+
+/// Count the unread messages of a user, e.g. with a query of the database.
+async fn unread_messages(user: &User) -> usize {
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    user.name.chars().count()
+}
+
+fn render_html(user: &User, unread: usize) -> String {
+    let mut context = Context::new();
+    context.insert("name", user.name);
+    context.insert("unread", &unread);
+    TEMPLATES.render("digest.html", &context).unwrap()
+}
+
+/// Render the digest of a user in the current locale.
+async fn render_digest(user: &User) -> Message {
+    let unread = unread_messages(user).await;
+    let text = format!(
+        "{}\n\n{}\n\n{}",
+        t!("digest.greeting", name = user.name),
+        t!("digest.body"),
+        t!("digest.footer")
+    );
+    Message::builder()
+        .from("Acme <noreply@acme.test>".parse().unwrap())
+        .to(Mailbox::new(
+            Some(user.name.to_string()),
+            user.email.parse().unwrap(),
+        ))
+        .subject(t!("digest.subject", count = unread))
+        .multipart(MultiPart::alternative_plain_html(
+            text,
+            render_html(user, unread),
+        ))
+        .unwrap()
+}
+
+/// Render the digests of the users concurrently, each one in the locale of the user.
+async fn render_digests(users: &'static [User]) -> Vec<Message> {
+    let tasks = users
+        .iter()
+        .map(|user| {
+            tokio::spawn(rust_i18n::with_locale_async(
+                user.locale,
+                render_digest(user),
+            ))
+        })
+        .collect::<Vec<_>>();
+    let mut messages = vec![];
+    for task in tasks {
+        messages.push(task.await.unwrap());
+    }
+    messages
+}
+
+#[tokio::main]
+async fn main() {
+    for message in render_digests(USERS).await {
+        println!("{}", String::from_utf8_lossy(&message.formatted()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_render_digests() {
+        let subjects = render_digests(USERS)
+            .await
+            .iter()
+            .map(|message| message.headers().get_raw("Subject").unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            subjects,
+            ["5 new messages", "2 条新消息", "6 nouveaux messages"]
+        );
+        assert_eq!(&*rust_i18n::locale(), "en");
+    }
+
+    #[tokio::test]
+    async fn test_render_html() {
+        let html = rust_i18n::with_locale_async("fr", async { render_html(&USERS[2], 1) }).await;
+        assert_eq!(
+            html,
+            "<p>Bonjour Amélie,</p>\n\
+             <p>Voici ce qui s&#x27;est passé chez Acme cette semaine.</p>\n\
+             <p><strong>Un nouveau message</strong></p>\n\
+             <p><small>Vous recevez cet e-mail car vous avez un compte Acme.</small></p>\n"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use rocket::{get, launch, routes, Build, Rocket};
+use rust_i18n::{
+    http::{LocaleConfig, LocaleFairing, RequestLocale},
+    t,
+};
+
+rust_i18n::i18n!("locales", fallback = "en");
+
+#[get("/hello/<name>")]
+fn hello(locale: RequestLocale, name: &str) -> String {
+    t!("hello", name = name, locale = &locale).into()
+}
+
+#[get("/locale")]
+fn locale(locale: RequestLocale) -> String {
+    locale.t("locale", &[("code", &locale)])
+}
+
+fn rocket() -> Rocket<Build> {
+    let config = LocaleConfig::new(rust_i18n::available_locales!())
+        .default_locale("en")
+        .translator(rust_i18n::translator!());
+    rocket::build()
+        .attach(LocaleFairing::new(config))
+        .mount("/", routes![hello, locale])
+}
+
+#[launch]
+fn launch() -> _ {
+    rocket()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::{http::Header, local::blocking::Client};
+
+    fn get(uri: &str, headers: &[(&'static str, &'static str)]) -> String {
+        let client = Client::untracked(rocket()).unwrap();
+        let mut request = client.get(uri);
+        for (name, value) in headers {
+            request = request.header(Header::new(*name, *value));
+        }
+        request.dispatch().into_string().unwrap()
+    }
+
+    #[test]
+    fn test_request_locale() {
+        assert_eq!(get("/hello/Jason", &[]), "Hello, Jason!");
+        assert_eq!(
+            get("/hello/Jason", &[("Accept-Language", "zh-TW,en;q=0.5")]),
+            "你好，Jason！"
+        );
+        assert_eq!(get("/locale?locale=zh-CN", &[]), "你的语言是 zh-CN。");
+        assert_eq!(
+            get("/locale", &[("Cookie", "locale=en")]),
+            "Your locale is en."
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use rust_i18n::http::{GrpcRequestExt, LocaleConfig, LocaleInterceptor, LocaleSource, StatusExt};
+use tonic::{service::Interceptor, Request, Response, Status};
+
+rust_i18n::i18n!("locales", fallback = "en");
+
+/// A method of a gRPC service, e.g. generated by `tonic-build`, which would be wrapped by
+/// `GreeterServer::with_interceptor(service, interceptor())`.
+fn say_hello(request: Request<String>) -> Result<Response<String>, Status> {
+    let name = request.get_ref();
+    if name.is_empty() {
+        let locale = request.locale();
+        return Err(Status::not_found("errors.user_not_found").translate(&locale, &[("id", &0)]));
+    }
+    Ok(Response::new(request.t("hello", &[("name", name)])))
+}
+
+fn interceptor() -> LocaleInterceptor {
+    let config = LocaleConfig::new(rust_i18n::available_locales!())
+        .sources([
+            LocaleSource::Header("x-locale".into()),
+            LocaleSource::AcceptLanguage,
+        ])
+        .default_locale("en")
+        .translator(rust_i18n::translator!());
+    LocaleInterceptor::new(config)
+}
+
+/// Run a call through the interceptor, like the server does.
+fn call(name: &str, metadata: &[(&'static str, &'static str)]) -> Result<String, Status> {
+    let mut request = Request::new(());
+    for (key, value) in metadata {
+        request.metadata_mut().insert(*key, value.parse().unwrap());
+    }
+    let (metadata, extensions, ()) = interceptor().call(request)?.into_parts();
+    let request = Request::from_parts(metadata, extensions, name.to_string());
+    say_hello(request).map(Response::into_inner)
+}
+
+fn main() {
+    println!(
+        "{}",
+        call("Jason", &[("accept-language", "zh-CN")]).unwrap()
+    );
+    println!("{}", call("", &[]).unwrap_err().message());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interceptor() {
+        assert_eq!(call("Jason", &[]).unwrap(), "Hello, Jason!");
+        assert_eq!(
+            call("Jason", &[("accept-language", "zh-TW,en;q=0.5")]).unwrap(),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            call("Jason", &[("x-locale", "zh-CN"), ("accept-language", "en")]).unwrap(),
+            "你好，Jason！"
+        );
+
+        let status = call("", &[("x-locale", "zh-CN")]).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+        assert_eq!(status.message(), "找不到用户 0。");
+    }
+}
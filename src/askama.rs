@@ -0,0 +1,67 @@
+//! The `t` filter of the Askama templates, with the same keys and fallbacks as
+//! [`t!`](crate::t).
+//!
+//! The filters of Askama are found in the `filters` module next to the template, so re-export
+//! them there. The locale is the `locale` value of
+//! [`render_with_values`](askama::Template::render_with_values), or the current locale:
+//!
+//! ```ignore
+//! use askama::Template;
+//!
+//! mod filters {
+//!     pub use rust_i18n::askama::*;
+//! }
+//!
+//! #[derive(Template)]
+//! #[template(source = r#"<h1>{{ "title"|t }}</h1>"#, ext = "html")]
+//! struct Page;
+//!
+//! // Once, when the app starts
+//! rust_i18n::askama::set_translator(rust_i18n::translator!());
+//!
+//! let values: (&str, &dyn std::any::Any) = ("locale", &"zh-CN");
+//! Page.render_with_values(&values)?;
+//! ```
+//!
+//! The templates are Rust, so the texts with the arguments are just [`t!`](crate::t), e.g.
+//! `{{ t!("messages.hello", name = self.user.name) }}`.
+
+use std::{fmt, sync::RwLock};
+
+use askama::Values;
+
+use crate::TranslateFn;
+
+static TRANSLATOR: RwLock<Option<TranslateFn>> = RwLock::new(None);
+
+/// Set the translator of the [`t`] filter, the [`translator!`](crate::translator) of the
+/// crate, the keys are rendered as is without it.
+pub fn set_translator(translate: TranslateFn) {
+    *TRANSLATOR.write().unwrap_or_else(|err| err.into_inner()) = Some(translate);
+}
+
+/// Translate the key, e.g. `{{ "hello"|t }}`, in the `locale` of the values or the current
+/// locale.
+pub fn t<T: fmt::Display>(key: T, values: &dyn Values) -> askama::Result<String> {
+    let key = key.to_string();
+    let translator = *TRANSLATOR.read().unwrap_or_else(|err| err.into_inner());
+    let Some(translate) = translator else {
+        return Ok(key);
+    };
+    let locale = match askama::get_value::<String>(values, "locale") {
+        Ok(locale) => locale.clone(),
+        Err(_) => match askama::get_value::<&str>(values, "locale") {
+            Ok(locale) => locale.to_string(),
+            Err(_) => crate::locale().to_string(),
+        },
+    };
+    Ok(crate::translate_with(
+        translate,
+        &locale,
+        &key,
+        &key,
+        &[],
+        &[],
+        false,
+    ))
+}
@@ -0,0 +1,268 @@
+//! A Bevy plugin to localize the games with the same YAML files.
+//!
+//! The [`CurrentLocale`] resource is the locale of the game, changing it sets the locale of
+//! [`t!`](crate::t) and writes a [`LocaleChanged`] message. The entities with a
+//! [`LocalizedText`] get their text translated again when the locale or the key changes.
+//!
+//! ```ignore
+//! use bevy::prelude::*;
+//! use rust_i18n::bevy::{CurrentLocale, LocalizedText, RustI18nPlugin};
+//!
+//! App::new()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins(RustI18nPlugin::new(rust_i18n::translator!()).text::<Text>())
+//!     .add_systems(Startup, |mut commands: Commands| {
+//!         commands.spawn((Text::default(), LocalizedText::new("menu.start")));
+//!     })
+//!     .run();
+//!
+//! // In the settings menu
+//! fn switch_locale(mut locale: ResMut<CurrentLocale>) {
+//!     locale.set("zh-CN");
+//! }
+//! ```
+
+use std::{fmt, ops::DerefMut};
+
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::{
+    component::Mutable,
+    prelude::{
+        Component, DetectChanges, IntoScheduleConfigs, Message, MessageReader, MessageWriter,
+        Query, Ref, Res, Resource,
+    },
+};
+
+use crate::TranslateFn;
+
+/// The plugin of rust-i18n, with the [`translator!`](crate::translator) of the crate.
+pub struct RustI18nPlugin {
+    translate: TranslateFn,
+    texts: Vec<fn(&mut App)>,
+}
+
+impl RustI18nPlugin {
+    /// Create the plugin translating by `translate`, the [`translator!`](crate::translator)
+    /// of the crate.
+    pub fn new(translate: TranslateFn) -> Self {
+        Self {
+            translate,
+            texts: vec![],
+        }
+    }
+
+    /// Translate the [`LocalizedText`]s into the text component `T`, e.g. `Text` or `Text2d`,
+    /// or any component dereferencing to a `String`.
+    pub fn text<T>(mut self) -> Self
+    where
+        T: Component<Mutability = Mutable> + DerefMut<Target = String>,
+    {
+        self.texts.push(|app| {
+            app.add_systems(PreUpdate, update_texts::<T>.after(apply_locale));
+        });
+        self
+    }
+}
+
+impl Plugin for RustI18nPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CurrentLocale(crate::locale().to_string()))
+            .insert_resource(Translator(self.translate))
+            .add_message::<LocaleChanged>()
+            .add_systems(PreUpdate, apply_locale);
+        for text in &self.texts {
+            text(app);
+        }
+    }
+}
+
+/// The current locale of the game, change it to switch the locale.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct CurrentLocale(String);
+
+impl CurrentLocale {
+    /// The locale, e.g. `zh-CN`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Switch to `locale`, it's applied in the next [`PreUpdate`].
+    pub fn set(&mut self, locale: impl Into<String>) {
+        self.0 = locale.into();
+    }
+}
+
+/// The message written when the [`CurrentLocale`] is switched.
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub struct LocaleChanged {
+    /// The locale before.
+    pub old: String,
+    /// The locale after.
+    pub new: String,
+}
+
+/// A text translated by a key, into the text component of [`RustI18nPlugin::text`].
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct LocalizedText {
+    key: String,
+    args: Vec<(String, String)>,
+}
+
+impl LocalizedText {
+    /// Create a text translating `key`.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            args: vec![],
+        }
+    }
+
+    /// Add an argument for `%{name}` in the text.
+    pub fn arg(mut self, name: impl Into<String>, value: impl fmt::Display) -> Self {
+        self.args.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// The key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+#[derive(Resource)]
+struct Translator(TranslateFn);
+
+fn apply_locale(locale: Res<CurrentLocale>, mut changed: MessageWriter<LocaleChanged>) {
+    if !locale.is_changed() {
+        return;
+    }
+    let old = crate::locale().to_string();
+    if old == locale.0 {
+        return;
+    }
+    crate::set_locale(&locale.0);
+    changed.write(LocaleChanged {
+        old,
+        new: locale.0.clone(),
+    });
+}
+
+fn update_texts<T>(
+    locale: Res<CurrentLocale>,
+    translator: Res<Translator>,
+    mut changed: MessageReader<LocaleChanged>,
+    mut texts: Query<(Ref<LocalizedText>, &mut T)>,
+) where
+    T: Component<Mutability = Mutable> + DerefMut<Target = String>,
+{
+    let locale_changed = changed.read().count() > 0;
+    for (localized, mut text) in &mut texts {
+        if !locale_changed && !localized.is_changed() {
+            continue;
+        }
+        let (patterns, values): (Vec<&str>, Vec<String>) = localized
+            .args
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .unzip();
+        **text = crate::translate_with(
+            translator.0,
+            &locale.0,
+            &localized.key,
+            &localized.key,
+            &patterns,
+            &values,
+            true,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, ops::Deref};
+
+    use bevy_ecs::message::Messages;
+
+    use super::*;
+
+    #[derive(Component, Default)]
+    struct Label(String);
+
+    impl Deref for Label {
+        type Target = String;
+
+        fn deref(&self) -> &String {
+            &self.0
+        }
+    }
+
+    impl DerefMut for Label {
+        fn deref_mut(&mut self) -> &mut String {
+            &mut self.0
+        }
+    }
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        match (locale, key) {
+            ("en", "menu.start") => Some("Start".into()),
+            ("zh-CN", "menu.start") => Some("开始".into()),
+            ("en", "menu.level") => Some("Level %{level}".into()),
+            _ => None,
+        }
+    }
+
+    fn changes(app: &App) -> Vec<LocaleChanged> {
+        app.world()
+            .resource::<Messages<LocaleChanged>>()
+            .iter_current_update_messages()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_plugin() {
+        let _lock = crate::tests::lock_locale();
+        crate::set_locale("en");
+
+        let mut app = App::new();
+        app.add_plugins(RustI18nPlugin::new(translate).text::<Label>());
+        let start = app
+            .world_mut()
+            .spawn((Label::default(), LocalizedText::new("menu.start")))
+            .id();
+        let level = app
+            .world_mut()
+            .spawn((
+                Label::default(),
+                LocalizedText::new("menu.level").arg("level", 3),
+            ))
+            .id();
+        app.update();
+        assert_eq!(app.world().get::<Label>(start).unwrap().0, "Start");
+        assert_eq!(app.world().get::<Label>(level).unwrap().0, "Level 3");
+        assert!(changes(&app).is_empty());
+
+        app.world_mut().resource_mut::<CurrentLocale>().set("zh-CN");
+        app.update();
+        assert_eq!(&*crate::locale(), "zh-CN");
+        assert_eq!(
+            changes(&app),
+            [LocaleChanged {
+                old: "en".into(),
+                new: "zh-CN".into(),
+            }]
+        );
+        assert_eq!(app.world().get::<Label>(start).unwrap().0, "开始");
+        // Missing in the locale, the key is kept
+        assert_eq!(app.world().get::<Label>(level).unwrap().0, "menu.level");
+
+        // Changing the key translates the text again
+        *app.world_mut().get_mut::<LocalizedText>(level).unwrap() =
+            LocalizedText::new("menu.start");
+        app.update();
+        assert!(changes(&app).is_empty());
+        assert_eq!(app.world().get::<Label>(level).unwrap().0, "开始");
+
+        crate::set_locale("en");
+    }
+}
@@ -0,0 +1,263 @@
+//! Translate the help and the errors of the clap commands by keys.
+//!
+//! The texts of a command are translated by the keys under a prefix, e.g. `cli`:
+//!
+//! | Key                                            | Text                                      |
+//! | ---------------------------------------------- | ----------------------------------------- |
+//! | `cli.about`, `cli.long_about`                  | The about of the command                  |
+//! | `cli.before_help`, `cli.after_help`            | The texts before and after the help       |
+//! | `cli.args.<id>.help`, `cli.args.<id>.long_help`| The help of an argument, also `help` and `version` |
+//! | `cli.headings.usage`                           | `Usage:`, of the subcommands too          |
+//! | `cli.headings.options`, `cli.headings.commands`| `Options`, `Commands`, of the subcommands too |
+//! | `cli.errors.<kind>`                            | The message of an error, e.g. `cli.errors.unknown_argument` |
+//! | `cli.commands.<name>.*`                        | The texts of a subcommand                 |
+//!
+//! The texts without translations are kept, and the help written in the attributes can be
+//! translated by [`t!`](crate::t) too, e.g. `#[arg(help = t!("cli.verbose").to_string())]`,
+//! which is found by `cargo i18n`.
+//!
+//! ```no_run
+//! use clap::{CommandFactory, FromArgMatches, Parser};
+//!
+//! #[derive(Parser)]
+//! struct Cli {
+//!     #[arg(short, long)]
+//!     verbose: bool,
+//! }
+//!
+//! # macro_rules! translator { () => { |_: &str, _: &str| None } }
+//! let command = rust_i18n::clap::localize_command(Cli::command(), "cli", translator!());
+//! let cli = command
+//!     .try_get_matches()
+//!     .and_then(|matches| Cli::from_arg_matches(&matches))
+//!     .unwrap_or_else(|err| rust_i18n::clap::localize_error(err, "cli", translator!()).exit());
+//! ```
+
+use std::borrow::Cow;
+
+use clap::{
+    error::{ContextKind, ErrorKind},
+    Arg, Command, Error,
+};
+
+use crate::TranslateFn;
+
+/// The default template of the help, with the usage heading.
+const HELP_TEMPLATE: &str = "\
+{before-help}{about-with-newline}
+{usage-heading} {usage}
+
+{all-args}{after-help}";
+
+/// Translate the help of `cmd` and its subcommands by the keys under `prefix`, in the current
+/// locale, see the [module](self) for the keys.
+pub fn localize_command(mut cmd: Command, prefix: &str, translate: TranslateFn) -> Command {
+    // Add the `help` and `version` arguments
+    cmd.build();
+    let locale = crate::locale().to_string();
+    let heading = |name: &str| translate(&locale, &format!("{}.headings.{}", prefix, name));
+    let localizer = Localizer {
+        translate,
+        usage: heading("usage").map(|usage| HELP_TEMPLATE.replace("{usage-heading}", &usage)),
+        options: heading("options").map(Cow::into_owned),
+        commands: heading("commands").map(Cow::into_owned),
+        locale,
+    };
+    localizer.command(cmd, prefix)
+}
+
+/// Translate the message of `err` by the key `<prefix>.errors.<kind>` in the current locale,
+/// e.g. `cli.errors.unknown_argument`, with the context of the error as the arguments, e.g.
+/// `%{invalid_arg}`, the errors without a translation are kept.
+pub fn localize_error(err: Error, prefix: &str, translate: TranslateFn) -> Error {
+    let Some(kind_name) = error_kind_name(err.kind()) else {
+        return err;
+    };
+    let locale = crate::locale();
+    let key = format!("{}.errors.{}", prefix, kind_name);
+    let Some(message) = translate(&locale, &key) else {
+        return err;
+    };
+
+    let (patterns, values): (Vec<&str>, Vec<String>) = err
+        .context()
+        .filter_map(|(kind, value)| Some((context_kind_name(kind)?, value.to_string())))
+        .unzip();
+    let message = crate::replace_patterns_with_locale(&locale, &message, &patterns, &values);
+    Error::raw(err.kind(), format!("{}\n", message))
+}
+
+struct Localizer {
+    translate: TranslateFn,
+    locale: String,
+    usage: Option<String>,
+    options: Option<String>,
+    commands: Option<String>,
+}
+
+impl Localizer {
+    fn t(&self, prefix: &str, key: &str) -> Option<String> {
+        let text = (self.translate)(&self.locale, &format!("{}.{}", prefix, key))?;
+        Some(text.into_owned())
+    }
+
+    fn command(&self, mut cmd: Command, prefix: &str) -> Command {
+        if let Some(about) = self.t(prefix, "about") {
+            cmd = cmd.about(about);
+        }
+        if let Some(long_about) = self.t(prefix, "long_about") {
+            cmd = cmd.long_about(long_about);
+        }
+        if let Some(before_help) = self.t(prefix, "before_help") {
+            cmd = cmd.before_help(before_help);
+        }
+        if let Some(after_help) = self.t(prefix, "after_help") {
+            cmd = cmd.after_help(after_help);
+        }
+        if let Some(template) = &self.usage {
+            cmd = cmd.help_template(template);
+        }
+        if let Some(commands) = &self.commands {
+            cmd = cmd.subcommand_help_heading(commands);
+        }
+        cmd = cmd.mut_args(|arg| self.arg(arg, prefix));
+
+        let names = cmd
+            .get_subcommands()
+            .map(|subcmd| subcmd.get_name().to_string())
+            .collect::<Vec<_>>();
+        for name in names {
+            let prefix = format!("{}.commands.{}", prefix, name);
+            cmd = cmd.mut_subcommand(&name, |subcmd| self.command(subcmd, &prefix));
+        }
+        cmd
+    }
+
+    fn arg(&self, mut arg: Arg, prefix: &str) -> Arg {
+        let id = arg.get_id().to_string();
+        if let Some(help) = self.t(prefix, &format!("args.{}.help", id)) {
+            arg = arg.help(help);
+        }
+        if let Some(long_help) = self.t(prefix, &format!("args.{}.long_help", id)) {
+            arg = arg.long_help(long_help);
+        }
+        match &self.options {
+            Some(options) if !arg.is_positional() && arg.get_help_heading().is_none() => {
+                arg.help_heading(options)
+            }
+            _ => arg,
+        }
+    }
+}
+
+fn error_kind_name(kind: ErrorKind) -> Option<&'static str> {
+    Some(match kind {
+        ErrorKind::InvalidValue => "invalid_value",
+        ErrorKind::UnknownArgument => "unknown_argument",
+        ErrorKind::InvalidSubcommand => "invalid_subcommand",
+        ErrorKind::NoEquals => "no_equals",
+        ErrorKind::ValueValidation => "value_validation",
+        ErrorKind::TooManyValues => "too_many_values",
+        ErrorKind::TooFewValues => "too_few_values",
+        ErrorKind::WrongNumberOfValues => "wrong_number_of_values",
+        ErrorKind::ArgumentConflict => "argument_conflict",
+        ErrorKind::MissingRequiredArgument => "missing_required_argument",
+        ErrorKind::MissingSubcommand => "missing_subcommand",
+        ErrorKind::InvalidUtf8 => "invalid_utf8",
+        _ => return None,
+    })
+}
+
+fn context_kind_name(kind: ContextKind) -> Option<&'static str> {
+    Some(match kind {
+        ContextKind::InvalidSubcommand => "invalid_subcommand",
+        ContextKind::InvalidArg => "invalid_arg",
+        ContextKind::PriorArg => "prior_arg",
+        ContextKind::ValidSubcommand => "valid_subcommand",
+        ContextKind::ValidValue => "valid_value",
+        ContextKind::InvalidValue => "invalid_value",
+        ContextKind::ActualNumValues => "actual_num_values",
+        ContextKind::ExpectedNumValues => "expected_num_values",
+        ContextKind::MinValues => "min_values",
+        ContextKind::SuggestedCommand => "suggested_command",
+        ContextKind::SuggestedSubcommand => "suggested_subcommand",
+        ContextKind::SuggestedArg => "suggested_arg",
+        ContextKind::SuggestedValue => "suggested_value",
+        ContextKind::TrailingArg => "trailing_arg",
+        ContextKind::Usage => "usage",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ArgAction;
+
+    use super::*;
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        let text = match (locale, key) {
+            ("zh-CN", "cli.about") => "一个应用",
+            ("zh-CN", "cli.args.verbose.help") => "显示详细信息",
+            ("zh-CN", "cli.args.help.help") => "显示帮助",
+            ("zh-CN", "cli.headings.usage") => "用法：",
+            ("zh-CN", "cli.headings.options") => "选项",
+            ("zh-CN", "cli.headings.commands") => "命令",
+            ("zh-CN", "cli.commands.run.about") => "运行",
+            ("zh-CN", "cli.errors.unknown_argument") => "未知参数 %{invalid_arg}",
+            _ => return None,
+        };
+        Some(text.into())
+    }
+
+    fn command() -> Command {
+        Command::new("app")
+            .about("An app")
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::SetTrue)
+                    .help("Show the details"),
+            )
+            .subcommand(Command::new("run").about("Run it"))
+    }
+
+    #[test]
+    fn test_localize_command() {
+        let mut cmd = crate::with_locale("zh-CN", || localize_command(command(), "cli", translate));
+        let help = cmd.render_help().to_string();
+        assert!(help.starts_with("一个应用\n"), "{}", help);
+        assert!(help.contains("用法： app"), "{}", help);
+        assert!(help.contains("命令:"), "{}", help);
+        assert!(help.contains("  run   运行"), "{}", help);
+        assert!(help.contains("选项:"), "{}", help);
+        assert!(help.contains("显示详细信息"), "{}", help);
+        assert!(help.contains("显示帮助"), "{}", help);
+
+        // The texts without translations are kept
+        let mut cmd = crate::with_locale("en", || localize_command(command(), "cli", translate));
+        let help = cmd.render_help().to_string();
+        assert!(help.starts_with("An app\n"), "{}", help);
+        assert!(help.contains("Usage: app"), "{}", help);
+        assert!(help.contains("Show the details"), "{}", help);
+    }
+
+    #[test]
+    fn test_localize_error() {
+        let err = command()
+            .try_get_matches_from(["app", "--unknown"])
+            .unwrap_err();
+        let localized = crate::with_locale("zh-CN", || localize_error(err, "cli", translate));
+        assert_eq!(localized.kind(), ErrorKind::UnknownArgument);
+        assert_eq!(localized.to_string(), "error: 未知参数 --unknown\n");
+
+        // The errors without a translation are kept
+        let err = command()
+            .try_get_matches_from(["app", "--unknown"])
+            .unwrap_err();
+        let message = err.to_string();
+        let localized = crate::with_locale("en", || localize_error(err, "cli", translate));
+        assert_eq!(localized.to_string(), message);
+    }
+}
@@ -0,0 +1,151 @@
+//! The reactive locale of the Dioxus apps.
+//!
+//! [`use_i18n_provider`] provides the [`I18n`] context in the root component, its locale is a
+//! signal following [`set_locale`](crate::set_locale), so the components reading it with the
+//! hooks are rendered again when the locale changes:
+//!
+//! ```ignore
+//! use dioxus::prelude::*;
+//! use rust_i18n::dioxus::{use_i18n_provider, use_locale, use_t};
+//!
+//! fn App() -> Element {
+//!     use_i18n_provider(rust_i18n::translator!());
+//!     rsx! { Header {} }
+//! }
+//!
+//! fn Header() -> Element {
+//!     let title = use_t("title");
+//!     let locale = use_locale();
+//!     rsx! {
+//!         h1 { "{title}" }
+//!         p { {t!("messages.hello", locale = &locale, name = "Jason")} }
+//!         button { onclick: |_| rust_i18n::set_locale("zh-CN"), "中文" }
+//!     }
+//! }
+//! ```
+//!
+//! With the `FetchBackend` of the `wasm` feature, fetch the translations of a locale before
+//! switching to it, so the components are rendered with them.
+
+use std::{cell::Cell, fmt, rc::Rc};
+
+use dioxus_core::{use_drop, use_hook};
+use dioxus_hooks::{use_context, use_context_provider, use_signal_sync};
+use dioxus_signals::{ReadableExt, Signal, SyncStorage, WritableExt};
+
+use crate::TranslateFn;
+
+/// The context of [`use_i18n_provider`].
+#[derive(Clone, Copy)]
+pub struct I18n {
+    locale: Signal<String, SyncStorage>,
+    translate: TranslateFn,
+}
+
+impl I18n {
+    /// The current locale, the component reading it is rendered again when it changes.
+    pub fn locale(&self) -> String {
+        self.locale.cloned()
+    }
+
+    /// Translate a key in the current locale, with the arguments like `%{name}`, the component
+    /// calling it is rendered again when the locale changes.
+    pub fn t(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let (patterns, values): (Vec<&str>, Vec<String>) = args
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .unzip();
+        let locale = self.locale.read();
+        crate::translate_with(self.translate, &locale, key, key, &patterns, &values, true)
+    }
+}
+
+/// Provide the [`I18n`] context with the [`translator!`](crate::translator) of the crate, in
+/// the root component.
+///
+/// The locale follows [`set_locale`](crate::set_locale) until the component is dropped.
+pub fn use_i18n_provider(translate: TranslateFn) -> I18n {
+    let locale = use_signal_sync(|| crate::locale().to_string());
+    let subscription = use_hook(|| {
+        let subscription = crate::on_locale_change(move |_, new| {
+            let mut locale = locale;
+            if let Ok(mut locale) = locale.try_write() {
+                *locale = new.to_string();
+            };
+        });
+        Rc::new(Cell::new(Some(subscription)))
+    });
+    use_drop(move || {
+        if let Some(subscription) = subscription.take() {
+            subscription.unsubscribe();
+        }
+    });
+    use_context_provider(|| I18n { locale, translate })
+}
+
+/// The [`I18n`] context of [`use_i18n_provider`].
+///
+/// # Panics
+///
+/// Panics without the context.
+pub fn use_i18n() -> I18n {
+    use_context()
+}
+
+/// The current locale, see [`I18n::locale`].
+pub fn use_locale() -> String {
+    use_i18n().locale()
+}
+
+/// The translation of a key in the current locale, see [`I18n::t`].
+pub fn use_t(key: &str) -> String {
+    use_i18n().t(key, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, cell::RefCell};
+
+    use dioxus_core::{Element, VNode, VirtualDom};
+
+    use super::*;
+
+    thread_local! {
+        static RENDERED: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+    }
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        Some(
+            match (locale, key) {
+                ("en", "title") => "Title",
+                ("fr", "title") => "Titre",
+                _ => return None,
+            }
+            .into(),
+        )
+    }
+
+    fn app() -> Element {
+        use_i18n_provider(translate);
+        let text = format!("{} {}", use_locale(), use_t("title"));
+        RENDERED.with(|rendered| rendered.borrow_mut().push(text));
+        VNode::empty()
+    }
+
+    #[test]
+    fn test_use_i18n_provider() {
+        crate::set_locale("en");
+        let mut dom = VirtualDom::new(app);
+        dom.rebuild_in_place();
+        assert_eq!(RENDERED.take(), ["en Title"]);
+
+        dom.in_runtime(|| crate::set_locale("fr"));
+        dom.process_events();
+        dom.render_immediate_to_vec();
+        assert_eq!(RENDERED.take(), ["fr Titre"]);
+
+        drop(dom);
+        crate::set_locale("en");
+        assert!(RENDERED.take().is_empty());
+    }
+}
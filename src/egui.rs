@@ -0,0 +1,252 @@
+//! Helpers for the egui apps, a locale switcher and the fallback fonts of the locales.
+//!
+//! The texts are just [`t!`](crate::t), e.g. `ui.label(t!("hello"))`, they are translated in
+//! the new locale in the next frame.
+//!
+//! ```no_run
+//! use rust_i18n::egui::{install_fallback_fonts, LocaleSwitcher};
+//!
+//! fn setup(ctx: &egui::Context) {
+//!     install_fallback_fonts(ctx, &["en", "zh-CN", "ja"]);
+//! }
+//!
+//! fn update(ui: &mut egui::Ui) {
+//!     if ui.add(LocaleSwitcher::new(["en", "zh-CN", "ja"])).changed() {
+//!         // The locale is changed by `rust_i18n::set_locale`
+//!     }
+//! }
+//! ```
+
+use egui::{ComboBox, FontData, FontDefinitions, FontFamily, Response, Ui, Widget, WidgetText};
+
+use crate::locale_display_name;
+
+/// A combo box to switch the locale with [`set_locale`](crate::set_locale), showing the
+/// native names of the locales.
+///
+/// The response is changed when the locale is switched.
+pub struct LocaleSwitcher {
+    locales: Vec<String>,
+    label: Option<WidgetText>,
+}
+
+impl LocaleSwitcher {
+    /// Create a switcher of the locales, e.g. the
+    /// [`available_locales!`](crate::available_locales).
+    pub fn new(locales: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            locales: locales
+                .into_iter()
+                .map(|locale| locale.as_ref().to_string())
+                .collect(),
+            label: None,
+        }
+    }
+
+    /// Show a label next to the combo box.
+    pub fn label(self, label: impl Into<WidgetText>) -> Self {
+        Self {
+            label: Some(label.into()),
+            ..self
+        }
+    }
+}
+
+impl Widget for LocaleSwitcher {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let current = crate::locale().to_string();
+        let mut selected = current.clone();
+        let combo_box = match self.label {
+            Some(label) => ComboBox::new("rust_i18n_locale_switcher", label),
+            None => ComboBox::from_id_source("rust_i18n_locale_switcher"),
+        };
+        let mut response = combo_box
+            .selected_text(locale_display_name(&current).to_string())
+            .show_ui(ui, |ui| {
+                for locale in &self.locales {
+                    let name = locale_display_name(locale).to_string();
+                    ui.selectable_value(&mut selected, locale.clone(), name);
+                }
+            })
+            .response;
+        if selected != current {
+            crate::set_locale(&selected);
+            response.mark_changed();
+        }
+        response
+    }
+}
+
+/// The system font files which may have the glyphs of a locale, missing in the default fonts
+/// of egui, e.g. the CJK fonts for `zh-CN`, empty for the locales of the Latin, Greek and
+/// Cyrillic scripts.
+///
+/// ```
+/// # use rust_i18n::egui::fallback_fonts;
+/// assert!(fallback_fonts("en").is_empty());
+/// assert!(!fallback_fonts("ja").is_empty());
+/// ```
+pub fn fallback_fonts(locale: &str) -> &'static [&'static str] {
+    let locale = locale.to_ascii_lowercase().replace('_', "-");
+    let mut subtags = locale.split('-');
+    let script = match subtags.next().unwrap_or_default() {
+        "zh" if subtags.any(|subtag| matches!(subtag, "hant" | "tw" | "hk" | "mo")) => {
+            Script::TraditionalChinese
+        }
+        "zh" => Script::SimplifiedChinese,
+        "ja" => Script::Japanese,
+        "ko" => Script::Korean,
+        "ar" | "fa" | "ur" => Script::Arabic,
+        "he" | "yi" => Script::Hebrew,
+        "th" => Script::Thai,
+        "hi" | "mr" | "ne" => Script::Devanagari,
+        _ => return &[],
+    };
+    script.fonts()
+}
+
+/// Add the first found [`fallback_fonts`] of each locale to `fonts`, after the fonts of the
+/// proportional and monospace families, returns the added font files.
+pub fn add_fallback_fonts(
+    fonts: &mut FontDefinitions,
+    locales: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Vec<&'static str> {
+    let mut added = vec![];
+    for locale in locales {
+        let Some((path, data)) = fallback_fonts(locale.as_ref())
+            .iter()
+            .find_map(|path| Some((*path, std::fs::read(path).ok()?)))
+        else {
+            continue;
+        };
+        if added.contains(&path) {
+            continue;
+        }
+        let name = format!("rust-i18n:{}", path);
+        fonts
+            .font_data
+            .insert(name.clone(), FontData::from_owned(data));
+        for family in [FontFamily::Proportional, FontFamily::Monospace] {
+            fonts.families.entry(family).or_default().push(name.clone());
+        }
+        added.push(path);
+    }
+    added
+}
+
+/// Set the fonts of `ctx` to the default fonts of egui with the [`fallback_fonts`] of the
+/// locales, see [`add_fallback_fonts`].
+pub fn install_fallback_fonts(
+    ctx: &egui::Context,
+    locales: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Vec<&'static str> {
+    let mut fonts = FontDefinitions::default();
+    let added = add_fallback_fonts(&mut fonts, locales);
+    ctx.set_fonts(fonts);
+    added
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    SimplifiedChinese,
+    TraditionalChinese,
+    Japanese,
+    Korean,
+    Arabic,
+    Hebrew,
+    Thai,
+    Devanagari,
+}
+
+impl Script {
+    #[cfg(target_os = "windows")]
+    fn fonts(self) -> &'static [&'static str] {
+        match self {
+            Self::SimplifiedChinese => {
+                &["C:/Windows/Fonts/msyh.ttc", "C:/Windows/Fonts/simsun.ttc"]
+            }
+            Self::TraditionalChinese => {
+                &["C:/Windows/Fonts/msjh.ttc", "C:/Windows/Fonts/mingliu.ttc"]
+            }
+            Self::Japanese => &[
+                "C:/Windows/Fonts/YuGothR.ttc",
+                "C:/Windows/Fonts/meiryo.ttc",
+            ],
+            Self::Korean => &["C:/Windows/Fonts/malgun.ttf"],
+            Self::Arabic | Self::Hebrew => &["C:/Windows/Fonts/segoeui.ttf"],
+            Self::Thai => &[
+                "C:/Windows/Fonts/LeelawUI.ttf",
+                "C:/Windows/Fonts/tahoma.ttf",
+            ],
+            Self::Devanagari => &[
+                "C:/Windows/Fonts/Nirmala.ttc",
+                "C:/Windows/Fonts/Nirmala.ttf",
+            ],
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    fn fonts(self) -> &'static [&'static str] {
+        match self {
+            Self::SimplifiedChinese | Self::TraditionalChinese => &[
+                "/System/Library/Fonts/PingFang.ttc",
+                "/System/Library/Fonts/STHeiti Light.ttc",
+            ],
+            Self::Japanese => &[
+                "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+                "/System/Library/Fonts/Hiragino Sans GB.ttc",
+            ],
+            Self::Korean => &["/System/Library/Fonts/AppleSDGothicNeo.ttc"],
+            Self::Arabic => &["/System/Library/Fonts/GeezaPro.ttc"],
+            Self::Hebrew => &["/System/Library/Fonts/ArialHB.ttc"],
+            Self::Thai => &["/System/Library/Fonts/Thonburi.ttc"],
+            Self::Devanagari => &["/System/Library/Fonts/Kohinoor.ttc"],
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn fonts(self) -> &'static [&'static str] {
+        match self {
+            Self::SimplifiedChinese | Self::TraditionalChinese | Self::Japanese | Self::Korean => {
+                &[
+                    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+                    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+                    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+                    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+                ]
+            }
+            Self::Arabic => &["/usr/share/fonts/truetype/noto/NotoSansArabic-Regular.ttf"],
+            Self::Hebrew => &["/usr/share/fonts/truetype/noto/NotoSansHebrew-Regular.ttf"],
+            Self::Thai => &["/usr/share/fonts/truetype/noto/NotoSansThai-Regular.ttf"],
+            Self::Devanagari => &["/usr/share/fonts/truetype/noto/NotoSansDevanagari-Regular.ttf"],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_fonts() {
+        assert!(fallback_fonts("en").is_empty());
+        assert!(fallback_fonts("ru-RU").is_empty());
+        assert!(!fallback_fonts("zh-CN").is_empty());
+        assert!(!fallback_fonts("ko_KR").is_empty());
+        assert_eq!(
+            fallback_fonts("zh-Hant-TW"),
+            Script::TraditionalChinese.fonts()
+        );
+        assert_eq!(fallback_fonts("ZH-HK"), Script::TraditionalChinese.fonts());
+        assert_eq!(fallback_fonts("zh-SG"), Script::SimplifiedChinese.fonts());
+
+        let mut fonts = FontDefinitions::default();
+        let proportional = fonts.families[&FontFamily::Proportional].len();
+        let added = add_fallback_fonts(&mut fonts, ["en", "fr"]);
+        assert!(added.is_empty());
+        assert_eq!(
+            fonts.families[&FontFamily::Proportional].len(),
+            proportional
+        );
+    }
+}
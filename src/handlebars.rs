@@ -0,0 +1,147 @@
+//! The `t` helper of the Handlebars templates, with the same keys and fallbacks as
+//! [`t!`](crate::t), e.g. for the emails and the reports.
+//!
+//! The hash arguments are interpolated, e.g. `%{name}`, `count` selects the
+//! [intervals](crate#count-intervals), and the locale is the `locale` argument, the `locale`
+//! of the data, or the current locale:
+//!
+//! ```handlebars
+//! {{t "hello"}}
+//! {{t "messages.hello" name=user.name}}
+//! {{t "messages.inbox" count=unread locale="zh-CN"}}
+//! ```
+//!
+//! ```no_run
+//! # macro_rules! translator { () => { |_: &str, _: &str| None } }
+//! let mut handlebars = handlebars::Handlebars::new();
+//! rust_i18n::handlebars::register(&mut handlebars, translator!());
+//! ```
+
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, JsonValue, RenderContext, RenderError,
+    RenderErrorReason, ScopedJson,
+};
+
+use crate::TranslateFn;
+
+/// Register [`Translate`] as the `t` helper of `handlebars`.
+pub fn register(handlebars: &mut Handlebars<'_>, translate: TranslateFn) {
+    handlebars.register_helper("t", Box::new(Translate::new(translate)));
+}
+
+/// The `t` helper, translating the key of the first parameter with the hash arguments.
+#[derive(Clone, Copy)]
+pub struct Translate {
+    translate: TranslateFn,
+}
+
+impl Translate {
+    /// Create the helper with the [`translator!`](crate::translator) of the crate.
+    pub fn new(translate: TranslateFn) -> Self {
+        Self { translate }
+    }
+}
+
+impl HelperDef for Translate {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let key = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("t", 0))?;
+        let key = key.value().as_str().ok_or_else(|| {
+            RenderErrorReason::ParamTypeMismatchForName(
+                "t",
+                key.relative_path().cloned().unwrap_or_else(|| "0".into()),
+                "string".into(),
+            )
+        })?;
+        let locale = match h.hash_get("locale") {
+            Some(locale) => locale.value().as_str().map(str::to_string).ok_or_else(|| {
+                RenderErrorReason::ParamTypeMismatchForName("t", "locale".into(), "string".into())
+            })?,
+            None => match ctx.data().get("locale").and_then(JsonValue::as_str) {
+                Some(locale) => locale.to_string(),
+                None => crate::locale().to_string(),
+            },
+        };
+        let (patterns, values): (Vec<&str>, Vec<String>) = h
+            .hash()
+            .iter()
+            .filter(|(name, _)| **name != "locale")
+            .map(|(name, value)| {
+                let value = match value.value() {
+                    JsonValue::String(value) => value.clone(),
+                    value => value.to_string(),
+                };
+                (*name, value)
+            })
+            .unzip();
+        Ok(ScopedJson::Derived(JsonValue::String(
+            crate::translate_with(self.translate, &locale, key, key, &patterns, &values, true),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        Some(
+            match (locale, key) {
+                ("en", "hello") => "Hello, %{name}!",
+                ("zh-CN", "hello") => "你好，%{name}！",
+                ("en", "inbox._intervals") => "1,2..",
+                ("en", "inbox.1") => "One message",
+                ("en", "inbox.2..") => "%{count} messages",
+                _ => return None,
+            }
+            .into(),
+        )
+    }
+
+    #[test]
+    fn test_translate() {
+        let mut handlebars = Handlebars::new();
+        register(&mut handlebars, translate);
+        let render = |template: &str, data: JsonValue| {
+            crate::with_locale("en", || handlebars.render_template(template, &data))
+        };
+
+        let data = json!({ "name": "Jason", "unread": 3 });
+        assert_eq!(
+            render(r#"{{t "hello" name=name}}"#, data.clone()).unwrap(),
+            "Hello, Jason!"
+        );
+        assert_eq!(
+            render(r#"{{t "hello" name=name locale="zh-CN"}}"#, data.clone()).unwrap(),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            render(r#"{{t "inbox" count=unread}}"#, data.clone()).unwrap(),
+            "3 messages"
+        );
+        assert_eq!(
+            render(
+                r#"{{t "hello" name=name}}"#,
+                json!({ "name": "Jason", "locale": "zh-CN" })
+            )
+            .unwrap(),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            render(r#"{{t "missing"}}"#, data.clone()).unwrap(),
+            "missing"
+        );
+        assert!(render(r#"{{t}}"#, data).is_err());
+    }
+}
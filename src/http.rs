@@ -0,0 +1,322 @@
+//! Negotiate the locale of the HTTP requests, for the web frameworks.
+//!
+//! A [`LocaleConfig`] finds the locale of a request from its [`LocaleSource`]s, e.g. a query
+//! parameter, a cookie or the `Accept-Language` header, and negotiates it with the available
+//! locales. The integrations run the handlers with the locale of the request, so they just
+//! call `t!`.
+//!
+//! A [`Catalog`] serves the translations of a locale as JSON, for the single-page apps.
+
+use std::{fmt, ops::Deref, sync::Arc};
+
+use http::{HeaderMap, Uri};
+
+use crate::TranslateFn;
+
+#[cfg(feature = "actix-web")]
+mod actix;
+#[cfg(feature = "axum")]
+mod axum;
+mod catalog;
+#[cfg(feature = "rocket")]
+mod rocket;
+#[cfg(feature = "tonic")]
+mod tonic;
+#[cfg(feature = "actix-web")]
+pub use self::actix::{HttpRequestExt, LocaleMiddleware, LocaleMiddlewareService};
+#[cfg(feature = "axum")]
+pub use self::axum::{LocaleLayer, LocaleService};
+pub use self::catalog::Catalog;
+#[cfg(feature = "rocket")]
+pub use self::rocket::LocaleFairing;
+#[cfg(feature = "tonic")]
+pub use self::tonic::{GrpcRequestExt, LocaleInterceptor, StatusExt};
+
+/// Where to find the locale of a request, see [`LocaleConfig::sources`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LocaleSource {
+    /// A query parameter, e.g. `locale` for `/users?locale=zh-CN`.
+    Query(String),
+    /// A cookie, e.g. `locale` for `Cookie: locale=zh-CN`.
+    Cookie(String),
+    /// A header with a locale, e.g. `x-locale` for `X-Locale: zh-CN`.
+    Header(String),
+    /// A segment of the path, e.g. `0` for `/zh-CN/users`.
+    PathSegment(usize),
+    /// The `Accept-Language` header, e.g. `Accept-Language: zh-CN,zh;q=0.9,en;q=0.8`.
+    AcceptLanguage,
+}
+
+/// The configuration to find the locale of the requests.
+///
+/// ```
+/// use rust_i18n::http::{LocaleConfig, LocaleSource};
+///
+/// let config = LocaleConfig::new(["en", "zh-CN"])
+///     .sources([LocaleSource::PathSegment(0), LocaleSource::AcceptLanguage])
+///     .default_locale("en");
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert("accept-language", "zh-TW,zh;q=0.9".parse().unwrap());
+/// let uri = "/users".parse().unwrap();
+/// assert_eq!(config.resolve(&uri, &headers).as_str(), "zh-CN");
+/// let uri = "/en/users".parse().unwrap();
+/// assert_eq!(config.resolve(&uri, &headers).as_str(), "en");
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocaleConfig {
+    available_locales: Vec<String>,
+    sources: Vec<LocaleSource>,
+    default_locale: Option<String>,
+    translate: Option<TranslateFn>,
+}
+
+impl LocaleConfig {
+    /// Create a configuration negotiating with the available locales, e.g. the
+    /// [`available_locales!`](crate::available_locales).
+    ///
+    /// The sources are the `locale` query parameter, the `locale` cookie and the
+    /// `Accept-Language` header, in order.
+    pub fn new(available_locales: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            available_locales: available_locales
+                .into_iter()
+                .map(|locale| locale.as_ref().to_string())
+                .collect(),
+            sources: vec![
+                LocaleSource::Query("locale".into()),
+                LocaleSource::Cookie("locale".into()),
+                LocaleSource::AcceptLanguage,
+            ],
+            default_locale: None,
+            translate: None,
+        }
+    }
+
+    /// Find the locale in the sources, in order, the first one matching an available locale
+    /// is used.
+    pub fn sources(self, sources: impl IntoIterator<Item = LocaleSource>) -> Self {
+        Self {
+            sources: sources.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// The locale when no source matches, defaults to the locale of
+    /// [`set_locale`](crate::set_locale).
+    pub fn default_locale(self, locale: impl Into<String>) -> Self {
+        Self {
+            default_locale: Some(locale.into()),
+            ..self
+        }
+    }
+
+    /// Translate with the [`RequestLocale::t`] of the requests by `translate`, the
+    /// [`translator!`](crate::translator) of the crate.
+    pub fn translator(self, translate: TranslateFn) -> Self {
+        Self {
+            translate: Some(translate),
+            ..self
+        }
+    }
+
+    /// The available locales.
+    pub fn available_locales(&self) -> &[String] {
+        &self.available_locales
+    }
+
+    /// Find the locale of a request by its URI and headers.
+    pub fn resolve(&self, uri: &Uri, headers: &HeaderMap) -> RequestLocale {
+        self.resolve_with(uri.path(), uri.query(), |name| {
+            headers
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+        })
+    }
+
+    /// Find the locale of a request by its path, query and headers, for the frameworks with
+    /// their own request types, `headers` gets the values of a header by its lowercase name.
+    pub fn resolve_with<'a, I>(
+        &self,
+        path: &str,
+        query: Option<&str>,
+        headers: impl Fn(&str) -> I,
+    ) -> RequestLocale
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let locale = self
+            .sources
+            .iter()
+            .find_map(|source| {
+                let ranges = match source {
+                    LocaleSource::Query(name) => query_param(query, name).into_iter().collect(),
+                    LocaleSource::Cookie(name) => {
+                        cookie(headers("cookie"), name).into_iter().collect()
+                    }
+                    LocaleSource::Header(name) => headers(&name.to_ascii_lowercase())
+                        .into_iter()
+                        .take(1)
+                        .collect(),
+                    LocaleSource::PathSegment(index) => path
+                        .split('/')
+                        .filter(|segment| !segment.is_empty())
+                        .nth(*index)
+                        .into_iter()
+                        .collect(),
+                    LocaleSource::AcceptLanguage => headers("accept-language")
+                        .into_iter()
+                        .flat_map(crate::parse_accept_language)
+                        .collect::<Vec<_>>(),
+                };
+                crate::negotiate(&ranges, &self.available_locales)
+            })
+            .map(Arc::from)
+            .or_else(|| self.default_locale.as_deref().map(Arc::from))
+            .unwrap_or_else(|| Arc::from(&*crate::locale()));
+        RequestLocale {
+            locale,
+            translate: self.translate,
+        }
+    }
+}
+
+fn query_param<'a>(query: Option<&'a str>, name: &str) -> Option<&'a str> {
+    query?
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn cookie<'a>(values: impl IntoIterator<Item = &'a str>, name: &str) -> Option<&'a str> {
+    values
+        .into_iter()
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.trim_matches('"'))
+}
+
+/// The locale of a request, found by [`LocaleConfig::resolve`].
+///
+/// The integrations extract it in the handlers, e.g. to pass it to the templates.
+#[derive(Clone)]
+pub struct RequestLocale {
+    locale: Arc<str>,
+    translate: Option<TranslateFn>,
+}
+
+impl RequestLocale {
+    /// The locale, e.g. `zh-CN`.
+    pub fn as_str(&self) -> &str {
+        &self.locale
+    }
+
+    /// Translate a key in the locale, with the arguments like `%{name}`, by the
+    /// [`LocaleConfig::translator`], the key is returned without it.
+    ///
+    /// It's the same as [`t!`](crate::t) in the handlers of the integrations, which run with
+    /// the locale of the request.
+    pub fn t(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let (patterns, values): (Vec<&str>, Vec<String>) = args
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .unzip();
+        let translate = self.translate.unwrap_or(|_, _| None);
+        crate::translate_with(translate, self, key, key, &patterns, &values, true)
+    }
+}
+
+impl From<&str> for RequestLocale {
+    fn from(locale: &str) -> Self {
+        Self {
+            locale: locale.into(),
+            translate: None,
+        }
+    }
+}
+
+impl Deref for RequestLocale {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.locale
+    }
+}
+
+impl AsRef<str> for RequestLocale {
+    fn as_ref(&self) -> &str {
+        &self.locale
+    }
+}
+
+impl fmt::Display for RequestLocale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.locale)
+    }
+}
+
+impl fmt::Debug for RequestLocale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RequestLocale").field(&self.locale).finish()
+    }
+}
+
+impl PartialEq for RequestLocale {
+    fn eq(&self, other: &Self) -> bool {
+        self.locale == other.locale
+    }
+}
+
+impl Eq for RequestLocale {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(config: &LocaleConfig, uri: &str, headers: &[(&str, &str)]) -> String {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.append(
+                http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+        config
+            .resolve(&uri.parse().unwrap(), &header_map)
+            .to_string()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let config = LocaleConfig::new(["en", "zh-CN", "fr"]).default_locale("en");
+        assert_eq!(resolve(&config, "/?locale=fr", &[]), "fr");
+        assert_eq!(resolve(&config, "/?a=1&locale=zh", &[]), "zh-CN");
+        assert_eq!(
+            resolve(&config, "/?locale=ja", &[("cookie", "a=1; locale=fr")]),
+            "fr"
+        );
+        assert_eq!(
+            resolve(
+                &config,
+                "/",
+                &[("cookie", "a=1"), ("accept-language", "de, fr;q=0.5")]
+            ),
+            "fr"
+        );
+        assert_eq!(resolve(&config, "/", &[("accept-language", "de")]), "en");
+
+        let config = config.sources([
+            LocaleSource::PathSegment(1),
+            LocaleSource::Header("x-locale".into()),
+        ]);
+        assert_eq!(resolve(&config, "/api/zh-CN/users", &[]), "zh-CN");
+        assert_eq!(
+            resolve(&config, "/api/users", &[("x-locale", "fr-CA")]),
+            "fr"
+        );
+        assert_eq!(resolve(&config, "/?locale=fr", &[]), "en");
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    future::{ready, Ready},
+    sync::Arc,
+};
+
+use actix_web::{
+    dev::{forward_ready, Payload, Service, ServiceRequest, Transform},
+    http::{header::IF_NONE_MATCH, StatusCode},
+    FromRequest, Handler, HttpMessage, HttpRequest, HttpResponse,
+};
+
+use super::{catalog::locale_of_path, Catalog, LocaleConfig, RequestLocale};
+use crate::WithLocale;
+
+/// A middleware running the requests with their locale, found by a [`LocaleConfig`].
+///
+/// The handlers call `t!` with the locale of the request, and can extract the
+/// [`RequestLocale`], or get it with [`HttpRequestExt`].
+///
+/// ```no_run
+/// use actix_web::{web, App, HttpRequest};
+/// use rust_i18n::http::{HttpRequestExt, LocaleConfig, LocaleMiddleware};
+///
+/// async fn hello(req: HttpRequest) -> String {
+///     req.t("hello", &[("name", &"Jason")])
+/// }
+///
+/// let config = LocaleConfig::new(["en", "zh-CN"]);
+/// let app = App::new()
+///     .wrap(LocaleMiddleware::new(config))
+///     .route("/", web::get().to(hello));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocaleMiddleware {
+    config: Arc<LocaleConfig>,
+}
+
+impl LocaleMiddleware {
+    /// Create a middleware finding the locale of the requests by `config`.
+    pub fn new(config: LocaleConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<S> Transform<S, ServiceRequest> for LocaleMiddleware
+where
+    S: Service<ServiceRequest>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Transform = LocaleMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LocaleMiddlewareService {
+            service,
+            config: self.config.clone(),
+        }))
+    }
+}
+
+/// The service of [`LocaleMiddleware`].
+#[derive(Debug)]
+pub struct LocaleMiddlewareService<S> {
+    service: S,
+    config: Arc<LocaleConfig>,
+}
+
+impl<S> Service<ServiceRequest> for LocaleMiddlewareService<S>
+where
+    S: Service<ServiceRequest>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = WithLocale<S::Future>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let locale = self
+            .config
+            .resolve_with(req.path(), Some(req.query_string()), |name| {
+                req.headers()
+                    .get_all(name)
+                    .filter_map(|value| value.to_str().ok())
+            });
+        req.extensions_mut().insert(locale.clone());
+        crate::with_locale_async(locale, self.service.call(req))
+    }
+}
+
+/// Get the locale of an [`HttpRequest`], found by [`LocaleMiddleware`].
+pub trait HttpRequestExt {
+    /// The locale of the request, or the current locale without [`LocaleMiddleware`].
+    fn locale(&self) -> RequestLocale;
+
+    /// Translate a key in the locale of the request, see [`RequestLocale::t`].
+    fn t(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        self.locale().t(key, args)
+    }
+}
+
+impl HttpRequestExt for HttpRequest {
+    fn locale(&self) -> RequestLocale {
+        self.extensions()
+            .get::<RequestLocale>()
+            .cloned()
+            .unwrap_or_else(|| RequestLocale::from(&*crate::locale()))
+    }
+}
+
+/// Extract the locale of the request found by [`LocaleMiddleware`], or the current locale
+/// without it.
+impl FromRequest for RequestLocale {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok(req.locale()))
+    }
+}
+
+/// Serve the [`Catalog`] as a route of the locales.
+///
+/// ```no_run
+/// # macro_rules! backend { () => { &*Box::leak(Box::new(rust_i18n::SimpleBackend::new())) } }
+/// use actix_web::{web, App};
+/// use rust_i18n::http::Catalog;
+///
+/// let app = App::new().route("/i18n/{locale}", web::get().to(Catalog::new(backend!())));
+/// ```
+impl Handler<(HttpRequest,)> for Catalog {
+    type Output = HttpResponse;
+    type Future = Ready<HttpResponse>;
+
+    fn call(&self, (req,): (HttpRequest,)) -> Self::Future {
+        let if_none_match = req
+            .headers()
+            .get(IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok());
+        let query = Some(req.query_string()).filter(|query| !query.is_empty());
+        let response = self.respond(locale_of_path(req.path()), query, if_none_match);
+
+        let status = StatusCode::from_u16(response.status().as_u16())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut builder = HttpResponse::build(status);
+        for (name, value) in response.headers() {
+            if let Ok(value) = value.to_str() {
+                builder.insert_header((name.as_str(), value));
+            }
+        }
+        ready(builder.body(response.into_body()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    use actix_web::{http::header, test::TestRequest};
+
+    use super::*;
+    use crate::http::LocaleSource;
+
+    /// Replies with the current locale while polled, and the locale of the request.
+    struct Echo;
+
+    impl Service<ServiceRequest> for Echo {
+        type Response = (String, RequestLocale);
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Infallible>>>>;
+
+        fn poll_ready(&self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&self, req: ServiceRequest) -> Self::Future {
+            let locale = req.request().locale();
+            Box::pin(async move { Ok((crate::locale().to_string(), locale)) })
+        }
+    }
+
+    fn poll<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the future is pending"),
+        }
+    }
+
+    fn call(middleware: &LocaleMiddleware, req: TestRequest) -> (String, RequestLocale) {
+        let service = poll(middleware.new_transform(Echo)).unwrap();
+        poll(service.call(req.to_srv_request())).unwrap()
+    }
+
+    #[test]
+    fn test_locale_middleware() {
+        let middleware =
+            LocaleMiddleware::new(LocaleConfig::new(["en", "zh-CN", "fr"]).default_locale("en"));
+
+        let req = TestRequest::get().insert_header((header::ACCEPT_LANGUAGE, "zh-TW,zh;q=0.9"));
+        let (locale, request_locale) = call(&middleware, req);
+        assert_eq!(locale, "zh-CN");
+        assert_eq!(request_locale.as_str(), "zh-CN");
+
+        // The query and the cookie take priority over the header
+        let req = TestRequest::get()
+            .uri("/users?locale=fr")
+            .insert_header((header::ACCEPT_LANGUAGE, "zh-CN"));
+        assert_eq!(call(&middleware, req).0, "fr");
+        let req = TestRequest::get()
+            .insert_header((header::COOKIE, "theme=dark; locale=fr"))
+            .insert_header((header::ACCEPT_LANGUAGE, "zh-CN"));
+        assert_eq!(call(&middleware, req).0, "fr");
+
+        // The unavailable locales fall back to the default locale
+        let req = TestRequest::get()
+            .uri("/?locale=ja")
+            .insert_header((header::ACCEPT_LANGUAGE, "de"));
+        assert_eq!(call(&middleware, req).0, "en");
+
+        let middleware = LocaleMiddleware::new(
+            LocaleConfig::new(["en", "zh-CN"])
+                .sources([LocaleSource::PathSegment(0)])
+                .default_locale("en"),
+        );
+        let req = TestRequest::get().uri("/zh-CN/users");
+        assert_eq!(call(&middleware, req).0, "zh-CN");
+        let req = TestRequest::get().uri("/users?locale=zh-CN");
+        assert_eq!(call(&middleware, req).0, "en");
+    }
+
+    #[test]
+    fn test_request_locale() {
+        let req = TestRequest::get().to_http_request();
+        req.extensions_mut().insert(RequestLocale::from("zh-CN"));
+        assert_eq!(req.locale().as_str(), "zh-CN");
+        let locale = poll(RequestLocale::from_request(&req, &mut Payload::None)).unwrap();
+        assert_eq!(locale.as_str(), "zh-CN");
+        assert_eq!(
+            req.t("Hello, %{name}!", &[("name", &"Jason")]),
+            "Hello, Jason!"
+        );
+
+        // Without the middleware, the current locale
+        let req = TestRequest::get().to_http_request();
+        assert_eq!(crate::with_locale("fr", || req.locale()).as_str(), "fr");
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    convert::Infallible,
+    future::{ready, Ready},
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::extract::FromRequestParts;
+use http::{request::Parts, Request, Response};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use super::{Catalog, LocaleConfig, RequestLocale};
+use crate::WithLocale;
+
+/// A middleware running the requests with their locale, found by a [`LocaleConfig`].
+///
+/// The handlers call `t!` with the locale of the request, and can extract the
+/// [`RequestLocale`].
+///
+/// ```no_run
+/// use axum::{routing::get, Router};
+/// use rust_i18n::http::{LocaleConfig, LocaleLayer, RequestLocale};
+///
+/// # macro_rules! t { ($($all:tt)*) => { String::new() } }
+/// async fn hello(locale: RequestLocale) -> String {
+///     format!("{} ({})", t!("hello"), locale)
+/// }
+///
+/// let app: Router = Router::new()
+///     .route("/", get(hello))
+///     .layer(LocaleLayer::new(LocaleConfig::new(["en", "zh-CN"])));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocaleLayer {
+    config: Arc<LocaleConfig>,
+}
+
+impl LocaleLayer {
+    /// Create a middleware finding the locale of the requests by `config`.
+    pub fn new(config: LocaleConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<S> Layer<S> for LocaleLayer {
+    type Service = LocaleService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LocaleService {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+/// The service of [`LocaleLayer`].
+#[derive(Debug, Clone)]
+pub struct LocaleService<S> {
+    inner: S,
+    config: Arc<LocaleConfig>,
+}
+
+impl<S, B> Service<Request<B>> for LocaleService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = WithLocale<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let locale = self.config.resolve(req.uri(), req.headers());
+        req.extensions_mut().insert(locale.clone());
+        crate::with_locale_async(locale, self.inner.call(req))
+    }
+}
+
+/// Extract the locale of the request found by [`LocaleLayer`], or the current locale
+/// without it.
+impl<S: Send + Sync> FromRequestParts<S> for RequestLocale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<RequestLocale>()
+            .cloned()
+            .unwrap_or_else(|| RequestLocale::from(&*crate::locale())))
+    }
+}
+
+/// Serve the [`Catalog`] as a route of the locales.
+///
+/// ```no_run
+/// # macro_rules! backend { () => { &*Box::leak(Box::new(rust_i18n::SimpleBackend::new())) } }
+/// use axum::Router;
+/// use rust_i18n::http::Catalog;
+///
+/// let app: Router = Router::new().route_service("/i18n/{locale}", Catalog::new(backend!()));
+/// ```
+impl<B> Service<Request<B>> for Catalog {
+    type Response = Response<String>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        ready(Ok(self.handle(&req)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        pin::{pin, Pin},
+        task::Waker,
+    };
+
+    use http::header;
+
+    use super::*;
+    use crate::http::LocaleSource;
+
+    /// Replies with the current locale while polled, and the extension of the request.
+    struct Echo;
+
+    impl Service<Request<()>> for Echo {
+        type Response = (String, Option<RequestLocale>);
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Infallible>>>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: Request<()>) -> Self::Future {
+            let extension = req.extensions().get::<RequestLocale>().cloned();
+            Box::pin(async move { Ok((crate::locale().to_string(), extension)) })
+        }
+    }
+
+    fn poll<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the future is pending"),
+        }
+    }
+
+    fn call(layer: &LocaleLayer, req: http::request::Builder) -> (String, Option<RequestLocale>) {
+        let mut service = layer.layer(Echo);
+        poll(service.call(req.body(()).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn test_locale_layer() {
+        let layer = LocaleLayer::new(LocaleConfig::new(["en", "zh-CN", "fr"]).default_locale("en"));
+
+        let req = Request::get("/").header(header::ACCEPT_LANGUAGE, "zh-TW,zh;q=0.9,en;q=0.8");
+        let (locale, extension) = call(&layer, req);
+        assert_eq!(locale, "zh-CN");
+        assert_eq!(extension, Some(RequestLocale::from("zh-CN")));
+
+        // The query and the cookie take priority over the header
+        let req = Request::get("/users?locale=fr").header(header::ACCEPT_LANGUAGE, "zh-CN");
+        assert_eq!(call(&layer, req).0, "fr");
+        let req = Request::get("/users")
+            .header(header::COOKIE, "theme=dark; locale=fr")
+            .header(header::ACCEPT_LANGUAGE, "zh-CN");
+        assert_eq!(call(&layer, req).0, "fr");
+
+        // The unavailable locales fall back to the default locale
+        let req = Request::get("/?locale=ja").header(header::ACCEPT_LANGUAGE, "de");
+        assert_eq!(call(&layer, req).0, "en");
+
+        let layer = LocaleLayer::new(
+            LocaleConfig::new(["en", "zh-CN"])
+                .sources([LocaleSource::Header("X-Locale".into())])
+                .default_locale("en"),
+        );
+        assert_eq!(
+            call(&layer, Request::get("/").header("x-locale", "zh")).0,
+            "zh-CN"
+        );
+        assert_eq!(call(&layer, Request::get("/?locale=zh-CN")).0, "en");
+    }
+
+    #[test]
+    fn test_extract_request_locale() {
+        let (mut parts, ()) = Request::get("/").body(()).unwrap().into_parts();
+        parts.extensions.insert(RequestLocale::from("zh-CN"));
+        let locale = poll(RequestLocale::from_request_parts(&mut parts, &())).unwrap();
+        assert_eq!(locale.as_str(), "zh-CN");
+
+        // Without the layer, the current locale
+        let (mut parts, ()) = Request::get("/").body(()).unwrap().into_parts();
+        let locale = crate::with_locale("fr", || {
+            poll(RequestLocale::from_request_parts(&mut parts, &()))
+        });
+        assert_eq!(locale.unwrap().as_str(), "fr");
+    }
+}
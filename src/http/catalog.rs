@@ -0,0 +1,246 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use http::{header, HeaderValue, Request, Response, StatusCode};
+
+use crate::Backend;
+
+/// Serve the translations of a locale as a JSON object, on `GET /i18n/{locale}`, so the
+/// single-page apps use the catalog of the server instead of a copy of it.
+///
+/// The locale is negotiated with the available locales of the backend, e.g. `zh` serves
+/// `zh-CN`. The `ns` query parameter keeps the keys of some namespaces, e.g.
+/// `/i18n/en?ns=common,errors` serves `common.*` and `errors.*`, and the responses have an
+/// `ETag`, so the clients revalidate them with `If-None-Match`.
+///
+/// It's a handler of axum and actix-web with the `axum` and `actix-web` features, or call
+/// [`Catalog::handle`] with an [`http::Request`]:
+///
+/// ```no_run
+/// # macro_rules! backend { () => { &*Box::leak(Box::new(rust_i18n::SimpleBackend::new())) } }
+/// use rust_i18n::http::Catalog;
+///
+/// let catalog = Catalog::new(backend!()).namespaces(["common", "errors"]);
+/// let request = http::Request::get("/i18n/zh-CN?ns=common").body(()).unwrap();
+/// let response = catalog.handle(&request);
+/// ```
+#[derive(Clone)]
+pub struct Catalog {
+    backend: &'static dyn Backend,
+    namespaces: Option<Vec<String>>,
+}
+
+impl Catalog {
+    /// Create a handler serving the translations of `backend`, the [`backend!`](crate::backend)
+    /// of the crate.
+    pub fn new(backend: &'static dyn Backend) -> Self {
+        Self {
+            backend,
+            namespaces: None,
+        }
+    }
+
+    /// Serve only the keys of the namespaces, e.g. to keep the texts of the server private,
+    /// all the keys are served by default.
+    pub fn namespaces(self, namespaces: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            namespaces: Some(namespaces.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Respond to a request, the locale is the last segment of its path.
+    pub fn handle<B>(&self, request: &Request<B>) -> Response<String> {
+        let if_none_match = request
+            .headers()
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok());
+        self.respond(
+            locale_of_path(request.uri().path()),
+            request.uri().query(),
+            if_none_match,
+        )
+    }
+
+    /// Respond with the translations of a locale, filtered by the `ns` parameters of the
+    /// query, or `304 Not Modified` when `if_none_match` has the `ETag` of them.
+    ///
+    /// It's `404 Not Found` when the locale isn't available.
+    pub fn respond(
+        &self,
+        locale: &str,
+        query: Option<&str>,
+        if_none_match: Option<&str>,
+    ) -> Response<String> {
+        let available_locales = self.backend.available_locales();
+        let Some(messages) = crate::negotiate(&[locale], &available_locales)
+            .and_then(|locale| self.backend.messages_for_locale(locale))
+        else {
+            return response(StatusCode::NOT_FOUND, String::new());
+        };
+
+        let requested = query
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .filter_map(|param| param.strip_prefix("ns="))
+            .flat_map(|namespaces| namespaces.split(','))
+            .filter(|namespace| !namespace.is_empty())
+            .collect::<Vec<_>>();
+        let messages = messages
+            .iter()
+            .filter(|(key, _)| {
+                self.namespaces.as_ref().is_none_or(|namespaces| {
+                    namespaces
+                        .iter()
+                        .any(|namespace| in_namespace(key, namespace))
+                })
+            })
+            .filter(|(key, _)| {
+                requested.is_empty()
+                    || requested
+                        .iter()
+                        .any(|namespace| in_namespace(key, namespace))
+            })
+            .map(|(key, value)| (key.as_ref(), value.as_ref()))
+            .collect::<BTreeMap<&str, &str>>();
+        let body = serde_json::to_string(&messages).unwrap_or_default();
+
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        let etag = format!("\"{:016x}\"", hasher.finish());
+        let not_modified = if_none_match.is_some_and(|if_none_match| {
+            if_none_match.split(',').any(|tag| {
+                let tag = tag.trim();
+                tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag
+            })
+        });
+
+        let mut response = if not_modified {
+            response(StatusCode::NOT_MODIFIED, String::new())
+        } else {
+            let mut response = response(StatusCode::OK, body);
+            response.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            response
+        };
+        if let Ok(etag) = HeaderValue::from_str(&etag) {
+            response.headers_mut().insert(header::ETAG, etag);
+        }
+        response
+            .headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        response
+    }
+}
+
+impl fmt::Debug for Catalog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Catalog")
+            .field("namespaces", &self.namespaces)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The locale of `/i18n/{locale}`.
+pub(super) fn locale_of_path(path: &str) -> &str {
+    path.rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or_default()
+}
+
+fn in_namespace(key: &str, namespace: &str) -> bool {
+    key.strip_prefix(namespace)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+fn response(status: StatusCode, body: String) -> Response<String> {
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use crate::SimpleBackend;
+
+    use super::*;
+
+    static BACKEND: LazyLock<SimpleBackend> = LazyLock::new(|| {
+        let mut backend = SimpleBackend::new();
+        let translations = |messages: &[(&'static str, &'static str)]| {
+            messages
+                .iter()
+                .map(|(key, value)| ((*key).into(), (*value).into()))
+                .collect()
+        };
+        backend.add_translations(
+            "en".into(),
+            translations(&[
+                ("common.hello", "Hello"),
+                ("common.bye", "Bye"),
+                ("errors.not_found", "Not found"),
+                ("admin.title", "Admin"),
+            ]),
+        );
+        backend.add_translations("zh-CN".into(), translations(&[("common.hello", "你好")]));
+        backend
+    });
+
+    fn get(catalog: &Catalog, uri: &str, if_none_match: Option<&str>) -> Response<String> {
+        let mut request = Request::get(uri);
+        if let Some(if_none_match) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, if_none_match);
+        }
+        catalog.handle(&request.body(()).unwrap())
+    }
+
+    #[test]
+    fn test_handle() {
+        let catalog = Catalog::new(&*BACKEND).namespaces(["common", "errors"]);
+
+        let response = get(&catalog, "/i18n/en", None);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(
+            response.body(),
+            r#"{"common.bye":"Bye","common.hello":"Hello","errors.not_found":"Not found"}"#
+        );
+
+        let response = get(&catalog, "/i18n/zh", None);
+        assert_eq!(response.body(), r#"{"common.hello":"你好"}"#);
+        let response = get(&catalog, "/i18n/en?ns=errors,admin", None);
+        assert_eq!(response.body(), r#"{"errors.not_found":"Not found"}"#);
+        let response = get(&catalog, "/i18n/en?ns=common&ns=errors", None);
+        assert_eq!(response.body().matches(':').count(), 3);
+        assert_eq!(
+            get(&catalog, "/i18n/fr", None).status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn test_etag() {
+        let catalog = Catalog::new(&*BACKEND);
+        let response = get(&catalog, "/i18n/en", None);
+        let etag = response.headers()[header::ETAG].to_str().unwrap();
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+
+        let response = get(&catalog, "/i18n/en", Some(etag));
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert!(response.body().is_empty());
+        assert_eq!(response.headers()[header::ETAG], etag);
+        let response = get(&catalog, "/i18n/en", Some(&format!("\"a\", W/{etag}")));
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = get(&catalog, "/i18n/en?ns=common", Some(etag));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag);
+    }
+}
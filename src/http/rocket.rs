@@ -0,0 +1,155 @@
+use std::convert::Infallible;
+
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    request::{FromRequest, Outcome},
+    Data, Request,
+};
+
+use super::{LocaleConfig, RequestLocale};
+
+/// A fairing finding the locale of the requests by a [`LocaleConfig`].
+///
+/// The handlers get it with the [`RequestLocale`] request guard, to translate with
+/// [`RequestLocale::t`] or `t!("hello", locale = &locale)`. Rocket runs the handlers on a
+/// shared runtime, so unlike the other integrations, the current locale of `t!` is not set.
+///
+/// ```no_run
+/// use rocket::{get, routes};
+/// use rust_i18n::http::{LocaleConfig, LocaleFairing, RequestLocale};
+///
+/// #[get("/")]
+/// fn hello(locale: RequestLocale) -> String {
+///     locale.t("hello", &[("name", &"Jason")])
+/// }
+///
+/// let config = LocaleConfig::new(["en", "zh-CN"]);
+/// let rocket = rocket::build()
+///     .attach(LocaleFairing::new(config))
+///     .mount("/", routes![hello]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocaleFairing {
+    config: LocaleConfig,
+}
+
+impl LocaleFairing {
+    /// Create a fairing finding the locale of the requests by `config`.
+    pub fn new(config: LocaleConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for LocaleFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "rust-i18n locale",
+            kind: Kind::Request,
+        }
+    }
+
+    async fn on_request(&self, req: &mut Request<'_>, _data: &mut Data<'_>) {
+        let locale = self.config.resolve_with(
+            req.uri().path().as_str(),
+            req.uri().query().map(|query| query.as_str()),
+            |name| req.headers().get(name),
+        );
+        req.local_cache(|| locale);
+    }
+}
+
+/// Get the locale of the request found by [`LocaleFairing`], or the current locale without it.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestLocale {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(
+            req.local_cache(|| RequestLocale::from(&*crate::locale()))
+                .clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{get, http::Header, local::blocking::Client, routes};
+
+    use super::*;
+    use crate::http::LocaleSource;
+
+    #[get("/<_..>")]
+    fn echo(locale: RequestLocale) -> String {
+        locale.to_string()
+    }
+
+    fn local_client(config: Option<LocaleConfig>) -> Client {
+        let rocket = rocket::build().mount("/", routes![echo]);
+        let rocket = match config {
+            Some(config) => rocket.attach(LocaleFairing::new(config)),
+            None => rocket,
+        };
+        Client::untracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn test_locale_fairing() {
+        let client = local_client(Some(
+            LocaleConfig::new(["en", "zh-CN", "fr"]).default_locale("en"),
+        ));
+        let get = |uri: &'static str, headers: &[(&'static str, &'static str)]| {
+            let mut req = client.get(uri);
+            for (name, value) in headers {
+                req.add_header(Header::new(*name, *value));
+            }
+            req.dispatch().into_string().unwrap()
+        };
+
+        assert_eq!(get("/", &[("Accept-Language", "zh-TW,zh;q=0.9")]), "zh-CN");
+        // The query and the cookie take priority over the header
+        assert_eq!(
+            get("/users?locale=fr", &[("Accept-Language", "zh-CN")]),
+            "fr"
+        );
+        assert_eq!(
+            get(
+                "/users",
+                &[
+                    ("Cookie", "theme=dark; locale=fr"),
+                    ("Accept-Language", "zh-CN")
+                ]
+            ),
+            "fr"
+        );
+        // The unavailable locales fall back to the default locale
+        assert_eq!(get("/?locale=ja", &[("Accept-Language", "de")]), "en");
+
+        let client = local_client(Some(
+            LocaleConfig::new(["en", "zh-CN"])
+                .sources([LocaleSource::PathSegment(0)])
+                .default_locale("en"),
+        ));
+        assert_eq!(
+            client.get("/zh-CN/users").dispatch().into_string().unwrap(),
+            "zh-CN"
+        );
+        assert_eq!(
+            client
+                .get("/users?locale=zh-CN")
+                .dispatch()
+                .into_string()
+                .unwrap(),
+            "en"
+        );
+    }
+
+    #[test]
+    fn test_request_locale_without_fairing() {
+        let _lock = crate::tests::lock_locale();
+        crate::set_locale("fr");
+        let locale = local_client(None).get("/").dispatch().into_string();
+        crate::set_locale("en");
+        assert_eq!(locale.unwrap(), "fr");
+    }
+}
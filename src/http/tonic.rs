@@ -0,0 +1,180 @@
+use std::{fmt, sync::Arc};
+
+use tonic::{service::Interceptor, Request, Status};
+
+use super::{LocaleConfig, RequestLocale};
+
+/// An interceptor finding the locale of the gRPC calls by a [`LocaleConfig`].
+///
+/// The locale is found in the metadata, by the [`LocaleSource::Header`](super::LocaleSource)
+/// and [`LocaleSource::AcceptLanguage`](super::LocaleSource) sources, e.g. the
+/// `accept-language` key. The handlers get it with [`GrpcRequestExt`], and translate the
+/// errors with [`StatusExt`].
+///
+/// ```no_run
+/// use rust_i18n::http::{GrpcRequestExt, LocaleConfig, LocaleInterceptor, LocaleSource, StatusExt};
+/// use tonic::{Request, Status};
+///
+/// let interceptor = LocaleInterceptor::new(
+///     LocaleConfig::new(["en", "zh-CN"])
+///         .sources([LocaleSource::Header("x-locale".into()), LocaleSource::AcceptLanguage]),
+/// );
+///
+/// fn get_user(request: Request<u64>) -> Result<String, Status> {
+///     let locale = request.locale();
+///     Err(Status::not_found("errors.user_not_found").translate(&locale, &[]))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocaleInterceptor {
+    config: Arc<LocaleConfig>,
+}
+
+impl LocaleInterceptor {
+    /// Create an interceptor finding the locale of the calls by `config`.
+    pub fn new(config: LocaleConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl Interceptor for LocaleInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let metadata = request.metadata();
+        let locale = self.config.resolve_with("", None, |name| {
+            metadata
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+        });
+        request.extensions_mut().insert(locale);
+        Ok(request)
+    }
+}
+
+/// Get the locale of a gRPC [`Request`], found by [`LocaleInterceptor`].
+pub trait GrpcRequestExt {
+    /// The locale of the call, or the current locale without [`LocaleInterceptor`].
+    fn locale(&self) -> RequestLocale;
+
+    /// Translate a key in the locale of the call, see [`RequestLocale::t`].
+    fn t(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        self.locale().t(key, args)
+    }
+}
+
+impl<T> GrpcRequestExt for Request<T> {
+    fn locale(&self) -> RequestLocale {
+        self.extensions()
+            .get::<RequestLocale>()
+            .cloned()
+            .unwrap_or_else(|| RequestLocale::from(&*crate::locale()))
+    }
+}
+
+/// Translate the message of a [`Status`].
+pub trait StatusExt {
+    /// Translate the message as a key in `locale`, see [`RequestLocale::t`], the code, details
+    /// and metadata are kept.
+    fn translate(self, locale: &RequestLocale, args: &[(&str, &dyn fmt::Display)]) -> Status;
+}
+
+impl StatusExt for Status {
+    fn translate(self, locale: &RequestLocale, args: &[(&str, &dyn fmt::Display)]) -> Status {
+        Status::with_details_and_metadata(
+            self.code(),
+            locale.t(self.message(), args),
+            self.details().to_vec().into(),
+            self.metadata().clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use tonic::Code;
+
+    use super::*;
+    use crate::http::LocaleSource;
+
+    fn intercept(interceptor: &mut LocaleInterceptor, metadata: &[(&'static str, &str)]) -> String {
+        let mut request = Request::new(());
+        for (key, value) in metadata {
+            request.metadata_mut().insert(*key, value.parse().unwrap());
+        }
+        interceptor.call(request).unwrap().locale().to_string()
+    }
+
+    #[test]
+    fn test_locale_interceptor() {
+        let mut interceptor = LocaleInterceptor::new(
+            LocaleConfig::new(["en", "zh-CN", "fr"])
+                .sources([
+                    LocaleSource::Header("x-locale".into()),
+                    LocaleSource::AcceptLanguage,
+                ])
+                .default_locale("en"),
+        );
+
+        let accept_language = ("accept-language", "zh-TW,zh;q=0.9,en;q=0.8");
+        assert_eq!(intercept(&mut interceptor, &[accept_language]), "zh-CN");
+        // The header takes priority over `accept-language`
+        assert_eq!(
+            intercept(&mut interceptor, &[("x-locale", "fr"), accept_language]),
+            "fr"
+        );
+        assert_eq!(
+            intercept(&mut interceptor, &[("x-locale", "ja"), accept_language]),
+            "zh-CN"
+        );
+        // The unavailable locales fall back to the default locale
+        assert_eq!(
+            intercept(&mut interceptor, &[("accept-language", "de")]),
+            "en"
+        );
+        assert_eq!(intercept(&mut interceptor, &[]), "en");
+    }
+
+    #[test]
+    fn test_request_locale() {
+        // Without the interceptor, the current locale
+        let request = Request::new(());
+        assert_eq!(crate::with_locale("fr", || request.locale()).as_str(), "fr");
+    }
+
+    #[test]
+    fn test_status_translate() {
+        fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+            match (locale, key) {
+                ("zh-CN", "errors.not_found") => Some("找不到 %{id}".into()),
+                _ => None,
+            }
+        }
+
+        let mut interceptor =
+            LocaleInterceptor::new(LocaleConfig::new(["en", "zh-CN"]).translator(translate));
+        let request = interceptor
+            .call({
+                let mut request = Request::new(());
+                request
+                    .metadata_mut()
+                    .insert("accept-language", "zh-CN".parse().unwrap());
+                request
+            })
+            .unwrap();
+        assert_eq!(request.t("errors.not_found", &[("id", &1)]), "找不到 1");
+
+        let mut status = Status::not_found("errors.not_found");
+        status.metadata_mut().insert("x-id", "1".parse().unwrap());
+        let status = status.translate(&request.locale(), &[("id", &1)]);
+        assert_eq!(status.code(), Code::NotFound);
+        assert_eq!(status.message(), "找不到 1");
+        assert_eq!(status.metadata().get("x-id").unwrap(), "1");
+
+        let status = Status::internal("Missing %{id}").translate(&request.locale(), &[("id", &1)]);
+        assert_eq!(status.message(), "Missing 1");
+    }
+}
@@ -0,0 +1,129 @@
+//! A subscription of the locale changes for the iced apps.
+//!
+//! The views are just [`t!`](crate::t), [`locale_changes`] emits a message when
+//! [`set_locale`](crate::set_locale) changes the locale, so the app is updated and its views
+//! are rendered with the new locale:
+//!
+//! ```ignore
+//! use iced::{widget::button, Element, Subscription};
+//! use rust_i18n::iced::LocaleChanged;
+//!
+//! #[derive(Debug, Clone)]
+//! enum Message {
+//!     SwitchLocale(&'static str),
+//!     LocaleChanged(LocaleChanged),
+//! }
+//!
+//! fn update(state: &mut State, message: Message) {
+//!     match message {
+//!         Message::SwitchLocale(locale) => rust_i18n::set_locale(locale),
+//!         Message::LocaleChanged(_) => {}
+//!     }
+//! }
+//!
+//! fn view(state: &State) -> Element<'_, Message> {
+//!     button(text(t!("switch"))).on_press(Message::SwitchLocale("zh-CN")).into()
+//! }
+//!
+//! fn subscription(state: &State) -> Subscription<Message> {
+//!     rust_i18n::iced::locale_changes().map(Message::LocaleChanged)
+//! }
+//! ```
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use iced_futures::{
+    futures::{
+        channel::mpsc::{self, UnboundedReceiver},
+        Stream, StreamExt,
+    },
+    subscription::Subscription,
+};
+
+use crate::LocaleChangeSubscription;
+
+/// The message of [`locale_changes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocaleChanged {
+    /// The locale before.
+    pub old: String,
+    /// The locale after.
+    pub new: String,
+}
+
+/// A subscription emitting [`LocaleChanged`] each time [`set_locale`](crate::set_locale)
+/// changes the locale.
+pub fn locale_changes() -> Subscription<LocaleChanged> {
+    Subscription::run(locale_change_stream)
+}
+
+fn locale_change_stream() -> LocaleChangeStream {
+    let (sender, receiver) = mpsc::unbounded();
+    let subscription = crate::on_locale_change(move |old, new| {
+        let _ = sender.unbounded_send(LocaleChanged {
+            old: old.to_string(),
+            new: new.to_string(),
+        });
+    });
+    LocaleChangeStream {
+        receiver,
+        subscription: Some(subscription),
+    }
+}
+
+/// The stream of the subscription, unsubscribed when it's dropped.
+struct LocaleChangeStream {
+    receiver: UnboundedReceiver<LocaleChanged>,
+    subscription: Option<LocaleChangeSubscription>,
+}
+
+impl Stream for LocaleChangeStream {
+    type Item = LocaleChanged;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<LocaleChanged>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for LocaleChangeStream {
+    fn drop(&mut self) {
+        if let Some(subscription) = self.subscription.take() {
+            subscription.unsubscribe();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced_futures::futures::FutureExt;
+
+    use super::*;
+
+    #[test]
+    fn test_locale_change_stream() {
+        let mut stream = locale_change_stream();
+        crate::set_locale("x-iced-a");
+        crate::set_locale("x-iced-b");
+        crate::set_locale("en");
+
+        // Skip the changes of the other tests
+        let mut changes = vec![];
+        while let Some(Some(changed)) = stream.next().now_or_never() {
+            if changed.new.starts_with("x-iced") {
+                changes.push(changed);
+            }
+        }
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].new, "x-iced-a");
+        assert_eq!(
+            changes[1],
+            LocaleChanged {
+                old: "x-iced-a".into(),
+                new: "x-iced-b".into(),
+            }
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! The reactive locale of the Leptos apps.
+//!
+//! [`provide_i18n`] provides the [`I18n`] context in the root component, its locale is a
+//! signal following [`set_locale`](crate::set_locale), so the views reading it are rendered
+//! again when the locale changes:
+//!
+//! ```ignore
+//! use leptos::prelude::*;
+//! use rust_i18n::leptos::{provide_i18n, use_locale, use_t};
+//!
+//! #[component]
+//! fn App() -> impl IntoView {
+//!     provide_i18n(rust_i18n::translator!());
+//!     let title = use_t("title");
+//!     let locale = use_locale();
+//!     view! {
+//!         <h1>{title}</h1>
+//!         <p>{move || { locale.track(); t!("messages.hello", name = "Jason") }}</p>
+//!         <button on:click=|_| rust_i18n::set_locale("zh-CN")>"中文"</button>
+//!     }
+//! }
+//! ```
+//!
+//! With the `FetchBackend` of the `wasm` feature, fetch the translations of a locale before
+//! switching to it, so the views are rendered with them.
+
+use std::fmt;
+
+use reactive_graph::{
+    owner::{on_cleanup, provide_context, use_context},
+    signal::{ReadSignal, RwSignal},
+    traits::{Get, Set},
+    wrappers::read::Signal,
+};
+
+use crate::TranslateFn;
+
+/// The context of [`provide_i18n`].
+#[derive(Debug, Clone, Copy)]
+pub struct I18n {
+    locale: RwSignal<String>,
+    translate: TranslateFn,
+}
+
+impl I18n {
+    /// The current locale, tracked.
+    pub fn locale(&self) -> ReadSignal<String> {
+        self.locale.read_only()
+    }
+
+    /// Translate a key in the current locale, with the arguments like `%{name}`, it's tracked
+    /// so the closures calling it run again when the locale changes.
+    pub fn t(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let (patterns, values): (Vec<&str>, Vec<String>) = args
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .unzip();
+        let locale = self.locale.get();
+        crate::translate_with(self.translate, &locale, key, key, &patterns, &values, true)
+    }
+}
+
+/// Provide the [`I18n`] context with the [`translator!`](crate::translator) of the crate, in
+/// the root component.
+///
+/// The locale follows [`set_locale`](crate::set_locale) until the owner is cleaned up.
+pub fn provide_i18n(translate: TranslateFn) -> I18n {
+    let locale = RwSignal::new(crate::locale().to_string());
+    let subscription = crate::on_locale_change(move |_, new| {
+        locale.try_set(new.to_string());
+    });
+    on_cleanup(move || subscription.unsubscribe());
+
+    let i18n = I18n { locale, translate };
+    provide_context(i18n);
+    i18n
+}
+
+/// The [`I18n`] context of [`provide_i18n`].
+///
+/// # Panics
+///
+/// Panics without the context.
+pub fn use_i18n() -> I18n {
+    use_context().expect("The I18n context is not provided, call `provide_i18n` first")
+}
+
+/// The current locale, see [`I18n::locale`].
+pub fn use_locale() -> ReadSignal<String> {
+    use_i18n().locale()
+}
+
+/// A signal of the translation of a key in the current locale, see [`I18n::t`].
+pub fn use_t(key: impl Into<String>) -> Signal<String> {
+    let i18n = use_i18n();
+    let key = key.into();
+    Signal::derive(move || i18n.t(&key, &[]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use reactive_graph::owner::Owner;
+
+    use super::*;
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        Some(
+            match (locale, key) {
+                ("en", "hello") => "Hello, %{name}!",
+                ("fr", "hello") => "Bonjour, %{name} !",
+                ("en", "title") => "Title",
+                ("fr", "title") => "Titre",
+                _ => return None,
+            }
+            .into(),
+        )
+    }
+
+    #[test]
+    fn test_provide_i18n() {
+        crate::set_locale("en");
+        let owner = Owner::new();
+        owner.set();
+
+        let i18n = provide_i18n(translate);
+        let locale = use_locale();
+        let title = use_t("title");
+        assert_eq!(locale.get(), "en");
+        assert_eq!(title.get(), "Title");
+        assert_eq!(i18n.t("hello", &[("name", &"Jason")]), "Hello, Jason!");
+
+        crate::set_locale("fr");
+        assert_eq!(locale.get(), "fr");
+        assert_eq!(title.get(), "Titre");
+        assert_eq!(i18n.t("hello", &[("name", &"Jason")]), "Bonjour, Jason !");
+
+        owner.cleanup();
+        crate::set_locale("en");
+        assert_eq!(locale.try_get(), None);
+    }
+}
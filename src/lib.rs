@@ -1,31 +1,409 @@
 #![doc = include_str!("../README.md")]
 
-use std::{ops::Deref, sync::LazyLock};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
+        Arc, LazyLock, Mutex, RwLock,
+    },
+    task::{Context, Poll},
+};
 
+pub use rust_i18n_macro::LocalizedError;
 #[doc(hidden)]
 pub use rust_i18n_macro::{_minify_key, _tr, i18n};
-#[cfg(feature = "load-path")]
-pub use rust_i18n_support::try_load_locales;
+#[cfg(feature = "phf")]
+#[doc(hidden)]
+pub use rust_i18n_support::__phf;
+#[cfg(feature = "archive")]
+pub use rust_i18n_support::ArchiveBackend;
+#[cfg(feature = "bundle")]
+pub use rust_i18n_support::BundleBackend;
+#[cfg(feature = "wasm")]
+pub use rust_i18n_support::FetchBackend;
+#[cfg(feature = "ota")]
+pub use rust_i18n_support::OtaBackend;
 pub use rust_i18n_support::{
-    AtomicStr, Backend, BackendExt, CowStr, MinifyKey, NamespacedBackend, SimpleBackend,
-    DEFAULT_MINIFY_KEY, DEFAULT_MINIFY_KEY_LEN, DEFAULT_MINIFY_KEY_PREFIX,
-    DEFAULT_MINIFY_KEY_THRESH,
+    capitalize_with_locale, filter_locales, format_binary_bytes, format_bytes, format_distance,
+    format_percent, format_unit, is_rtl, locale_display_name, lookup_locale, matches_range,
+    negotiate, parse_accept_language, plural_category, resolve_references,
+    resolve_template_references, select_interval, system_locales, text_direction,
+    to_lower_with_locale, to_upper_with_locale, AsyncBackend, AtomicStr, Backend, BackendExt,
+    BackendMut, BackendStack, CachedBackend, CowStr, EnvBackend, Locale, LocaleDisplayName,
+    LocaleInfo, MeasurementSystem, MessageTemplate, MinifyKey, MissingKey, NamespacedBackend,
+    OverrideBackend, ParseLocaleError, RecordingBackend, RelativeTime, ScopedBackend, Segment,
+    SimpleBackend, SnapshotBackend, TextDirection, DEFAULT_MINIFY_KEY, DEFAULT_MINIFY_KEY_LEN,
+    DEFAULT_MINIFY_KEY_PREFIX, DEFAULT_MINIFY_KEY_THRESH,
 };
+#[cfg(feature = "load-path")]
+pub use rust_i18n_support::{try_load_locales, try_load_locales_from_files};
+#[cfg(feature = "phf")]
+pub use rust_i18n_support::{PhfBackend, PhfTranslations};
+
+#[cfg(feature = "askama")]
+pub mod askama;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "dioxus")]
+pub mod dioxus;
+#[cfg(feature = "egui")]
+pub mod egui;
+#[cfg(feature = "handlebars")]
+pub mod handlebars;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "iced")]
+pub mod iced;
+#[cfg(feature = "leptos")]
+pub mod leptos;
+#[cfg(feature = "minijinja")]
+pub mod minijinja;
+#[cfg(feature = "serde")]
+pub mod serde_t;
+#[cfg(feature = "slint")]
+pub mod slint;
+#[cfg(feature = "tauri")]
+pub mod tauri;
+#[cfg(feature = "tera")]
+pub mod tera;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+#[cfg(feature = "validator")]
+pub mod validator;
+#[cfg(feature = "yew")]
+pub mod yew;
 
 static CURRENT_LOCALE: LazyLock<AtomicStr> = LazyLock::new(|| AtomicStr::from("en"));
 
+type LocaleChangeCallback = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+static LOCALE_CHANGE_CALLBACKS: RwLock<Vec<(usize, LocaleChangeCallback)>> =
+    RwLock::new(Vec::new());
+static NEXT_LOCALE_CHANGE_ID: AtomicUsize = AtomicUsize::new(0);
+
 /// Set current locale
-pub fn set_locale(locale: &str) {
+///
+/// Accepts a `&str`, a `String` or a validated [`Locale`].
+///
+/// The callbacks of [`on_locale_change`] are called when the locale changes.
+pub fn set_locale(locale: impl AsRef<str>) {
+    let locale = locale.as_ref();
+    let old_locale = CURRENT_LOCALE.as_str().to_string();
     CURRENT_LOCALE.replace(locale);
+    if old_locale == locale {
+        return;
+    }
+
+    // Call without the lock, so the callbacks can subscribe or set the locale.
+    let callbacks = LOCALE_CHANGE_CALLBACKS
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .iter()
+        .map(|(_, callback)| callback.clone())
+        .collect::<Vec<_>>();
+    for callback in callbacks {
+        callback(&old_locale, locale);
+    }
+}
+
+/// A subscription of [`on_locale_change`].
+///
+/// Dropping it keeps the callback subscribed, call [`unsubscribe`](Self::unsubscribe) to
+/// remove it.
+#[derive(Debug)]
+pub struct LocaleChangeSubscription {
+    id: usize,
+}
+
+impl LocaleChangeSubscription {
+    /// Remove the callback.
+    pub fn unsubscribe(self) {
+        LOCALE_CHANGE_CALLBACKS
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// Call `callback` with the old and the new locale each time [`set_locale`] changes the
+/// locale, e.g. to re-render the views or invalidate the caches.
+///
+/// ```
+/// let subscription = rust_i18n::on_locale_change(|old, new| {
+///     println!("The locale is changed from {} to {}", old, new);
+/// });
+/// rust_i18n::set_locale("zh-CN");
+/// // => The locale is changed from en to zh-CN
+///
+/// subscription.unsubscribe();
+/// ```
+pub fn on_locale_change(
+    callback: impl Fn(&str, &str) + Send + Sync + 'static,
+) -> LocaleChangeSubscription {
+    let id = NEXT_LOCALE_CHANGE_ID.fetch_add(1, Ordering::Relaxed);
+    LOCALE_CHANGE_CALLBACKS
+        .write()
+        .unwrap_or_else(|err| err.into_inner())
+        .push((id, Arc::new(callback)));
+    LocaleChangeSubscription { id }
 }
 
 /// Get current locale
+///
+/// It's the locale of [`with_locale`] or [`with_locale_async`] when called in them, or else
+/// the locale of [`set_locale`].
 pub fn locale() -> impl Deref<Target = str> {
-    CURRENT_LOCALE.as_str()
+    match SCOPED_LOCALE.with(|scoped| scoped.borrow().clone()) {
+        Some(locale) => CurrentLocale::Scoped(locale),
+        None => CurrentLocale::Global(CURRENT_LOCALE.as_str()),
+    }
+}
+
+thread_local! {
+    static SCOPED_LOCALE: RefCell<Option<Arc<str>>> = const { RefCell::new(None) };
+}
+
+enum CurrentLocale<G> {
+    Scoped(Arc<str>),
+    Global(G),
+}
+
+impl<G: Deref<Target = str>> Deref for CurrentLocale<G> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Self::Scoped(locale) => locale,
+            Self::Global(locale) => locale,
+        }
+    }
+}
+
+/// Set the scoped locale of the thread until it's dropped, then restore the previous one.
+struct ScopedLocaleGuard(Option<Arc<str>>);
+
+impl ScopedLocaleGuard {
+    fn enter(locale: Arc<str>) -> Self {
+        Self(SCOPED_LOCALE.with(|scoped| scoped.replace(Some(locale))))
+    }
+}
+
+impl Drop for ScopedLocaleGuard {
+    fn drop(&mut self) {
+        SCOPED_LOCALE.with(|scoped| *scoped.borrow_mut() = self.0.take());
+    }
+}
+
+/// Run `f` with `locale` as the current locale, e.g. to render a text for another user.
+///
+/// The locale is only changed for `f` on this thread, the other threads still use the locale
+/// of [`set_locale`].
+///
+/// ```
+/// rust_i18n::set_locale("en");
+/// rust_i18n::with_locale("zh-CN", || {
+///     assert_eq!(&*rust_i18n::locale(), "zh-CN");
+/// });
+/// assert_eq!(&*rust_i18n::locale(), "en");
+/// ```
+pub fn with_locale<R>(locale: impl AsRef<str>, f: impl FnOnce() -> R) -> R {
+    let _guard = ScopedLocaleGuard::enter(locale.as_ref().into());
+    f()
+}
+
+/// Run a future with `locale` as the current locale, like [`with_locale`], e.g. for the
+/// handler of a request, the locale follows the future across the threads of the runtime.
+///
+/// The futures running concurrently keep their own locale, e.g. a batch job rendering an
+/// email for each recipient in the locale of the recipient.
+///
+/// ```
+/// # async fn example() {
+/// let text = rust_i18n::with_locale_async("zh-CN", async {
+///     rust_i18n::locale().to_string()
+/// })
+/// .await;
+/// assert_eq!(text, "zh-CN");
+/// # }
+/// ```
+pub fn with_locale_async<F: Future>(locale: impl AsRef<str>, future: F) -> WithLocale<F> {
+    WithLocale {
+        locale: locale.as_ref().into(),
+        future,
+    }
+}
+
+/// The future of [`with_locale_async`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WithLocale<F> {
+    locale: Arc<str>,
+    future: F,
+}
+
+impl<F: Future> Future for WithLocale<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `future` is pinned with `self`, it's never moved.
+        let this = unsafe { self.get_unchecked_mut() };
+        let _guard = ScopedLocaleGuard::enter(this.locale.clone());
+        unsafe { Pin::new_unchecked(&mut this.future) }.poll(cx)
+    }
+}
+
+/// Convert a text to uppercase with the rules of the current locale, e.g. the Turkish `i` is `İ`.
+///
+/// See [`to_upper_with_locale`].
+pub fn to_upper(s: &str) -> String {
+    to_upper_with_locale(&locale(), s)
+}
+
+/// Convert a text to lowercase with the rules of the current locale, e.g. the Turkish `I` is `ı`.
+///
+/// See [`to_lower_with_locale`].
+pub fn to_lower(s: &str) -> String {
+    to_lower_with_locale(&locale(), s)
+}
+
+/// Convert the first letter of a text to uppercase with the rules of the current locale.
+///
+/// See [`capitalize_with_locale`].
+pub fn capitalize(s: &str) -> String {
+    capitalize_with_locale(&locale(), s)
+}
+
+static OVERRIDES: LazyLock<OverrideBackend> = LazyLock::new(OverrideBackend::new);
+
+/// Override a translation at runtime, e.g. for live copy edits or A/B tests.
+///
+/// The overrides are consulted before the translations of [`i18n!`], the `key` is the one
+/// of the locale files (the minified key when `minify_key` is enabled).
+///
+/// ```no_run
+/// rust_i18n::set_translation("en", "hello", "Howdy");
+/// // t!("hello", locale = "en") => "Howdy"
+///
+/// rust_i18n::remove_translation("en", "hello");
+/// // t!("hello", locale = "en") => "Hello"
+/// ```
+pub fn set_translation(locale: impl AsRef<str>, key: impl AsRef<str>, value: impl AsRef<str>) {
+    OVERRIDES.set_translation(locale.as_ref(), key.as_ref(), value.as_ref());
+}
+
+/// Remove a translation set by [`set_translation`], returns whether it was present.
+pub fn remove_translation(locale: impl AsRef<str>, key: impl AsRef<str>) -> bool {
+    OVERRIDES.remove_translation(locale.as_ref(), key.as_ref())
+}
+
+/// The translations set by [`set_translation`].
+#[doc(hidden)]
+pub fn _rust_i18n_overrides() -> &'static OverrideBackend {
+    &OVERRIDES
+}
+
+static SHARED_BACKENDS: LazyLock<Mutex<HashMap<&'static str, &'static dyn Backend>>> =
+    LazyLock::new(Default::default);
+
+/// The backend of the translations of [`i18n!`] with `shared = true`, built by `init` at the
+/// first access of any crate, then shared by the crates with the same `key`.
+#[doc(hidden)]
+pub fn _rust_i18n_shared_backend(
+    key: &'static str,
+    init: impl FnOnce() -> Box<dyn Backend>,
+) -> &'static dyn Backend {
+    *SHARED_BACKENDS
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .entry(key)
+        .or_insert_with(|| Box::leak(init()))
+}
+
+/// The backend of the translations of [`i18n!`], the texts of `blob` by the ranges of `offsets`:
+/// the range of a locale and the number of its texts, then the ranges of each key and its text,
+/// for each locale.
+#[doc(hidden)]
+pub fn _rust_i18n_blob_backend(blob: &'static str, offsets: &[u32]) -> SimpleBackend {
+    let text = |i: usize| Cow::Borrowed(&blob[offsets[i] as usize..offsets[i + 1] as usize]);
+    let mut backend = SimpleBackend::new();
+    let mut i = 0;
+    while i < offsets.len() {
+        let len = offsets[i + 2] as usize;
+        let translations = (0..len)
+            .map(|n| (text(i + 3 + n * 4), text(i + 5 + n * 4)))
+            .collect();
+        backend.add_translations(text(i), translations);
+        i += 3 + len * 4;
+    }
+    backend
+}
+
+/// The text of a range of the blob of [`i18n!`], in `const` contexts, see
+/// [`_rust_i18n_blob_backend`].
+#[doc(hidden)]
+pub const fn _rust_i18n_blob_text(blob: &'static str, start: u32, end: u32) -> &'static str {
+    let (bytes, _) = blob.as_bytes().split_at(end as usize);
+    let (_, bytes) = bytes.split_at(start as usize);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(_) => panic!("the range of the text is not at char boundaries"),
+    }
+}
+
+static MEASUREMENT_SYSTEM: AtomicU8 = AtomicU8::new(0);
+
+/// Set the measurement system of the distances, e.g. a km vs miles toggle in the settings.
+///
+/// With `None`, the measurement system of the locale is used, see [`measurement_system`].
+pub fn set_measurement_system(system: Option<MeasurementSystem>) {
+    let value = match system {
+        None => 0,
+        Some(MeasurementSystem::Metric) => 1,
+        Some(MeasurementSystem::Imperial) => 2,
+    };
+    MEASUREMENT_SYSTEM.store(value, Ordering::Relaxed);
+}
+
+/// Get the measurement system of the distances in a locale, the one set by
+/// [`set_measurement_system`] or the one of the locale.
+pub fn measurement_system(locale: &str) -> MeasurementSystem {
+    match MEASUREMENT_SYSTEM.load(Ordering::Relaxed) {
+        1 => MeasurementSystem::Metric,
+        2 => MeasurementSystem::Imperial,
+        _ => MeasurementSystem::for_locale(locale),
+    }
+}
+
+static BIDI_ISOLATION: AtomicBool = AtomicBool::new(false);
+
+/// Wrap the interpolated values with the Unicode bidi isolates (FSI and PDI) in the right to
+/// left locales, default: `false`.
+///
+/// So a user name or a number in another direction doesn't scramble an Arabic or Hebrew
+/// sentence, see [`is_rtl`].
+///
+/// ```
+/// rust_i18n::set_bidi_isolation(true);
+/// let output = rust_i18n::replace_patterns_with_locale("ar", "مرحبا %{name}", &["name"], &["Bob".into()]);
+/// assert_eq!(output, "مرحبا \u{2068}Bob\u{2069}");
+/// ```
+pub fn set_bidi_isolation(enabled: bool) {
+    BIDI_ISOLATION.store(enabled, Ordering::Relaxed);
 }
 
 /// Replace patterns and return a new string.
 ///
+/// The format hints of the patterns, e.g. `%{size:bytes}`, are formatted with the current
+/// locale, see [`replace_patterns_with_locale`].
+///
 /// # Arguments
 ///
 /// * `input` - The input string, containing patterns like `%{name}`.
@@ -43,51 +421,257 @@ pub fn locale() -> impl Deref<Target = str> {
 /// assert_eq!(output, "Hello, world!");
 /// ```
 pub fn replace_patterns(input: &str, patterns: &[&str], values: &[String]) -> String {
-    let input_bytes = input.as_bytes();
-    let mut pattern_pos = smallvec::SmallVec::<[usize; 64]>::new();
-    let mut stage = 0;
-    for (i, &b) in input_bytes.iter().enumerate() {
-        match (stage, b) {
-            (1, b'{') => {
-                stage = 2;
-                pattern_pos.push(i);
-            }
-            (2, b'}') => {
-                stage = 0;
-                pattern_pos.push(i);
+    replace_patterns_with_locale(&locale(), input, patterns, values)
+}
+
+/// Replace patterns and return a new string, formatting the values by the format hints of
+/// the patterns in the locale.
+///
+/// The hints are `bytes`, `binary_bytes`, `distance` (in meters) and `percent` (a ratio),
+/// e.g. `%{size:bytes}`. The values which are not numbers are kept as is.
+///
+/// The values are wrapped with the bidi isolates in the right to left locales, when enabled by
+/// [`set_bidi_isolation`].
+///
+/// A `%%{` is a literal `%{`, e.g. `%%{name}` is kept as `%{name}`.
+///
+/// # Example
+///
+/// ```
+/// # use rust_i18n::replace_patterns_with_locale;
+/// let input = "Downloaded %{size:bytes} of %{name}";
+/// let values = &["1500000".to_string(), "video.mp4".to_string()];
+/// let output = replace_patterns_with_locale("de", input, &["size", "name"], values);
+/// assert_eq!(output, "Downloaded 1,5 MB of video.mp4");
+/// ```
+pub fn replace_patterns_with_locale(
+    locale: &str,
+    input: &str,
+    patterns: &[&str],
+    values: &[String],
+) -> String {
+    render_template(locale, &MessageTemplate::parse(input), patterns, values)
+}
+
+/// Join the segments of a template with the values of the patterns, like
+/// [`replace_patterns_with_locale`] without scanning the text again.
+///
+/// The values are any [`Display`](fmt::Display), written into the output without formatting
+/// them into strings first.
+///
+/// The templates of the translations are parsed once, when they're loaded, see
+/// [`Backend::template`].
+///
+/// # Example
+///
+/// ```
+/// # use rust_i18n::{render_template, MessageTemplate};
+/// # use std::fmt::Display;
+/// let template = MessageTemplate::parse("Hello, %{name}! You have %{count} messages.");
+/// let values: [&dyn Display; 2] = [&"world", &3];
+/// let output = render_template("en", &template, &["name", "count"], &values);
+/// assert_eq!(output, "Hello, world! You have 3 messages.");
+/// ```
+pub fn render_template<V: fmt::Display>(
+    locale: &str,
+    template: &MessageTemplate<'_>,
+    patterns: &[&str],
+    values: &[V],
+) -> String {
+    use fmt::Write as _;
+
+    let mut output = String::with_capacity(template.text().len() + 128);
+    let isolate = BIDI_ISOLATION.load(Ordering::Relaxed) && is_rtl(locale);
+    for segment in template.segments() {
+        let (name, hint, pattern) = match segment {
+            Segment::Text(text) => {
+                output.push_str(text);
+                continue;
             }
-            (_, b'%') => {
-                stage = 1;
+            Segment::Arg {
+                name,
+                hint,
+                pattern,
+            } => (name, hint, pattern),
+        };
+        let Some(value) = patterns
+            .iter()
+            .zip(values)
+            .find_map(|(&key, value)| (key == name).then_some(value))
+        else {
+            output.push_str(pattern);
+            continue;
+        };
+        if isolate {
+            output.push('\u{2068}');
+        }
+        // Only the values with format hints are formatted into strings
+        let formatted = hint.and_then(|hint| {
+            format_unit(locale, hint, &value.to_string(), measurement_system(locale))
+        });
+        match formatted {
+            Some(formatted) => output.push_str(&formatted),
+            None => {
+                let _ = write!(output, "{}", value);
             }
-            _ => {}
         }
+        if isolate {
+            output.push('\u{2069}');
+        }
+    }
+    output
+}
+
+/// Replace the escaped `%%{` with `%{`, for the texts without arguments.
+#[doc(hidden)]
+pub fn _unescape_patterns(text: Cow<'_, str>) -> Cow<'_, str> {
+    if text.contains("%%{") {
+        Cow::Owned(text.replace("%%{", "%{"))
+    } else {
+        text
     }
-    let mut output: Vec<u8> = Vec::with_capacity(input_bytes.len() + 128);
-    let mut prev_end = 0;
-    let pattern_values = patterns.iter().zip(values.iter());
-    for pos in pattern_pos.chunks_exact(2) {
-        let start = pos[0];
-        let end = pos[1];
-        let key = &input_bytes[start + 1..end];
-        if prev_end < start {
-            let prev_chunk = &input_bytes[prev_end..start - 1];
-            output.extend_from_slice(prev_chunk);
+}
+
+/// The function getting the text of a locale and a key of a crate, see [`translator!`].
+pub type TranslateFn = fn(&str, &str) -> Option<Cow<'static, str>>;
+
+/// Translate a key in a locale like [`t!`], with the arguments formatted.
+///
+/// The text is selected by the count intervals of the key when `select_interval`, and
+/// `default` is used when the key is missing.
+pub(crate) fn translate_with(
+    translate: TranslateFn,
+    locale: &str,
+    key: &str,
+    default: &str,
+    patterns: &[&str],
+    values: &[String],
+    select_interval: bool,
+) -> String {
+    let translated = translate(locale, key).or_else(|| {
+        if !select_interval {
+            return None;
         }
-        if let Some((_, v)) = pattern_values
-            .clone()
-            .find(|(&pattern, _)| pattern.as_bytes() == key)
-        {
-            output.extend_from_slice(v.as_bytes());
-        } else {
-            output.extend_from_slice(&input_bytes[start - 1..end + 1]);
+        let index = patterns.iter().position(|&pattern| pattern == "count")?;
+        let count = values[index].trim().parse::<u64>().ok()?;
+        crate::select_interval(key, count, |key| translate(locale, key))
+    });
+    #[cfg(feature = "tracing")]
+    if translated.is_none() {
+        crate::tracing::_missing(locale, key, default, None);
+    }
+    let text = translated.as_deref().unwrap_or(default);
+    replace_patterns_with_locale(locale, text, patterns, values)
+}
+
+/// An error with a message in each locale, derive it to translate the variants of an error
+/// enum by keys, instead of writing the messages in the `#[error("...")]` of `thiserror`.
+///
+/// ```ignore
+/// use rust_i18n::LocalizedError;
+///
+/// #[derive(Debug, thiserror::Error, LocalizedError)]
+/// #[i18n(prefix = "errors")]
+/// enum AppError {
+///     // errors.not_found: "User %{id} not found"
+///     #[error("not found")]
+///     NotFound { id: u64 },
+///     #[error("io")]
+///     #[i18n(key = "errors.io")]
+///     Io(#[i18n(skip)] std::io::Error),
+/// }
+///
+/// let err = AppError::NotFound { id: 42 };
+/// assert_eq!(err.localized_message("en"), "User 42 not found");
+/// ```
+///
+/// See [`derive@LocalizedError`] for the attributes.
+pub trait LocalizedError {
+    /// The message of the error in `locale`.
+    fn localized_message(&self, locale: &str) -> String;
+
+    /// The message of the error in the current locale.
+    fn to_localized_string(&self) -> String {
+        self.localized_message(&locale())
+    }
+}
+
+/// A translation resolved when it's displayed, created by [`t_lazy!`].
+///
+/// The key and the arguments are captured when it's created, the text is looked up with the
+/// locale of the display time, e.g. for an error created in a library and rendered later with
+/// the locale of the request.
+#[derive(Clone)]
+pub struct LazyTranslation {
+    key: String,
+    default: String,
+    locale: Option<String>,
+    patterns: Vec<&'static str>,
+    values: Vec<String>,
+    select_interval: bool,
+    translate: TranslateFn,
+}
+
+impl LazyTranslation {
+    #[doc(hidden)]
+    pub fn _new(
+        key: String,
+        default: String,
+        locale: Option<String>,
+        patterns: Vec<&'static str>,
+        values: Vec<String>,
+        select_interval: bool,
+        translate: TranslateFn,
+    ) -> Self {
+        Self {
+            key,
+            default,
+            locale,
+            patterns,
+            values,
+            select_interval,
+            translate,
         }
-        prev_end = end + 1;
     }
-    if prev_end < input_bytes.len() {
-        let remaining = &input_bytes[prev_end..];
-        output.extend_from_slice(remaining);
+
+    /// The key of the translation.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Get the text in a locale, instead of the current locale or the locale given to [`t_lazy!`].
+    pub fn to_string_in(&self, locale: &str) -> String {
+        translate_with(
+            self.translate,
+            locale,
+            &self.key,
+            &self.default,
+            &self.patterns,
+            &self.values,
+            self.select_interval,
+        )
+    }
+}
+
+impl fmt::Display for LazyTranslation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match &self.locale {
+            Some(locale) => self.to_string_in(locale),
+            None => self.to_string_in(&locale()),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl fmt::Debug for LazyTranslation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyTranslation")
+            .field("key", &self.key)
+            .field("locale", &self.locale)
+            .field("patterns", &self.patterns)
+            .field("values", &self.values)
+            .finish()
     }
-    unsafe { String::from_utf8_unchecked(output) }
 }
 
 /// Get I18n text
@@ -102,7 +686,7 @@ pub fn replace_patterns(input: &str, patterns: &[&str], values: &[String]) -> St
 ///   - The variable names in the message should be wrapped in `%{}`, like `"Hello, %{name}!"`.
 ///   - Dynamic messages are also supported, such as `t!(format!("Hello, {}!", name))`.
 ///     However, if `minify_key` is enabled, the entire message will be hashed and used as a key for every lookup, which may consume more CPU cycles.
-/// * `locale` - The locale to use. If not specified, the current locale will be used.
+/// * `locale` - The locale to use, a `&str` or a [`Locale`]. If not specified, the current locale will be used.
 /// * `args` - The arguments to be replaced in the translated text.
 ///    - These should be passed in the format `key = value` or `key => value`.
 ///    - Alternatively, you can specify the value format using the `key = value : {:format_specifier}` syntax.
@@ -146,6 +730,75 @@ macro_rules! t {
     }
 }
 
+/// Get I18n text lazily, like [`t!`] but the text is looked up when it's displayed.
+///
+/// It returns a [`LazyTranslation`], the arguments are formatted when it's created, and the
+/// text is in the locale current when it's displayed, unless a `locale` is given.
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate rust_i18n;
+///
+/// # macro_rules! t_lazy { ($($all:tt)*) => { "" } }
+/// # fn main() {
+/// let error = t_lazy!("errors.not_found", id = 42);
+///
+/// rust_i18n::set_locale("de");
+/// println!("{}", error);
+/// // errors.not_found: "%{id} nicht gefunden" => "42 nicht gefunden"
+/// # }
+/// ```
+#[macro_export]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! t_lazy {
+    ($($all:tt)*) => {
+        crate::_rust_i18n_t_lazy!($($all)*)
+    }
+}
+
+/// Get the [`TranslateFn`] of the crate, for the APIs translating outside of [`t!`], e.g.
+/// [`LocaleConfig::translator`](crate::http::LocaleConfig::translator).
+///
+/// ```no_run
+/// #[macro_use] extern crate rust_i18n;
+///
+/// # macro_rules! translator { () => { |_: &str, _: &str| None } }
+/// # fn main() {
+/// let translate: rust_i18n::TranslateFn = translator!();
+/// assert_eq!(translate("en", "hello").as_deref(), Some("Hello world"));
+/// # }
+/// ```
+#[macro_export]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! translator {
+    () => {{
+        let translate: $crate::TranslateFn =
+            |locale, key| crate::_rust_i18n_try_translate(locale, key);
+        translate
+    }};
+}
+
+/// Get the [`Backend`] of the crate, with the translations of [`i18n!`], e.g. for
+/// [`Catalog`](crate::http::Catalog).
+///
+/// ```no_run
+/// #[macro_use] extern crate rust_i18n;
+///
+/// # macro_rules! backend { () => { &rust_i18n::SimpleBackend::new() } }
+/// # fn main() {
+/// let backend: &dyn rust_i18n::Backend = backend!();
+/// let messages = backend.messages_for_locale("en");
+/// # }
+/// ```
+#[macro_export]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! backend {
+    () => {
+        crate::_rust_i18n_backend()
+    };
+}
+
 /// A macro that generates a translation key and corresponding value pair from a given input value.
 ///
 /// It's useful when you want to use a long string as a key, but you don't want to type it twice.
@@ -178,6 +831,26 @@ macro_rules! tkv {
     };
 }
 
+/// Mark a string literal as a translation key, it returns the literal as is.
+///
+/// `cargo i18n` extracts the keys written as literals in `t!`, use `key!` for the keys kept
+/// somewhere else and translated later, e.g. in tables, so they are extracted too.
+/// The `const` and `static` keys, e.g. `const TITLE: &str = "messages.title"` used as
+/// `t!(TITLE)`, are resolved by the extractor without it.
+///
+/// ```
+/// use rust_i18n::key;
+///
+/// static ERRORS: &[&str] = &[key!("errors.not_found"), key!("errors.forbidden")];
+/// assert_eq!(ERRORS[0], "errors.not_found");
+/// ```
+#[macro_export]
+macro_rules! key {
+    ($key:literal) => {
+        $key
+    };
+}
+
 /// Get available locales
 ///
 /// ```no_run
@@ -196,6 +869,93 @@ macro_rules! available_locales {
     };
 }
 
+/// Set the current locale to the best available locale for the preferences of the operating
+/// system, see [`system_locales`], returns the locale, or `None` if none is available.
+///
+/// The preferred locales are matched with [`available_locales!`] by [`negotiate`].
+///
+/// ```no_run
+/// #[macro_use] extern crate rust_i18n;
+/// # pub fn _rust_i18n_available_locales() -> Vec<&'static str> { todo!() }
+/// # fn main() {
+/// // LANG=zh_TW.UTF-8, the available locales are `en` and `zh-Hant`
+/// rust_i18n::set_locale_from_system!();
+/// // => Some("zh-Hant")
+/// # }
+/// ```
+#[macro_export]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! set_locale_from_system {
+    () => {{
+        let available_locales = crate::_rust_i18n_available_locales();
+        let locale = $crate::negotiate(&$crate::system_locales(), &available_locales)
+            .map(|locale| locale.to_string());
+        if let Some(locale) = &locale {
+            $crate::set_locale(locale);
+        }
+        locale
+    }};
+}
+
+/// Get available locales with their metadata, see [`LocaleInfo`].
+///
+/// Useful for building a language picker from the embedded translations.
+///
+/// ```no_run
+/// #[macro_use] extern crate rust_i18n;
+/// # pub fn _rust_i18n_available_locales() -> Vec<&'static str> { todo!() }
+/// # fn main() {
+/// for info in rust_i18n::locales!() {
+///     println!("{} - {:?} ({:?})", info.code, info.native_name, info.direction);
+/// }
+/// # }
+/// // => en - Some("English") (Ltr)
+/// // => zh-CN - Some("简体中文") (Ltr)
+/// ```
+#[macro_export(local_inner_macros)]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! locales {
+    () => {
+        crate::_rust_i18n_available_locales()
+            .iter()
+            .map(|locale| $crate::LocaleInfo::new(locale))
+            .collect::<Vec<_>>()
+    };
+}
+
+/// Format a time relative to now, e.g. `3 minutes ago` or `in 2 days`, see [`RelativeTime`].
+///
+/// Accepts the seconds from now as `i64` (in the past when negative), an elapsed
+/// [`Duration`](std::time::Duration) or a [`SystemTime`](std::time::SystemTime) timestamp.
+/// The texts are the `relative_time.*` keys of the locale files, the number is chosen by the
+/// plural rules of the locale.
+///
+/// ```no_run
+/// #[macro_use] extern crate rust_i18n;
+/// # macro_rules! format_relative { ($($all:tt)*) => {} }
+/// # fn main() {
+/// format_relative!(std::time::Duration::from_secs(180));
+/// // relative_time.minutes.past.other: "%{count} minutes ago" => "3 minutes ago"
+///
+/// format_relative!(2 * 24 * 60 * 60, locale = "zh-CN");
+/// // relative_time.days.future.other: "%{count} 天后" => "2 天后"
+/// # }
+/// ```
+#[macro_export]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! format_relative {
+    ($value:expr) => {{
+        let locale = $crate::locale();
+        $crate::format_relative!($value, locale = &*locale)
+    }};
+    ($value:expr, locale = $locale:expr) => {{
+        let locale = &$locale;
+        let locale: &str = ::core::convert::AsRef::<str>::as_ref(locale);
+        $crate::RelativeTime::from($value)
+            .format(locale, |key| crate::_rust_i18n_try_translate(locale, key))
+    }};
+}
+
 /// Extend a dependency's translations with the matching crate namespace from
 /// the current crate's backend.
 ///
@@ -211,24 +971,138 @@ macro_rules! available_locales {
 /// ```
 ///
 /// If both miss, the existing locale fallback rules continue as usual.
+///
+/// Given a backend, e.g. an [`OverrideBackend`] edited at runtime or the locale files
+/// loaded at startup, `extend!(ui_component, backend)` extends the dependency with the
+/// translations below the `ui_component` key of that backend instead, so the application
+/// can override the dependency's texts or add locales after the build:
+///
+/// ```rs, ignore
+/// static UI_COMPONENT: LazyLock<OverrideBackend> = LazyLock::new(OverrideBackend::new);
+///
+/// rust_i18n::extend!(ui_component, &*UI_COMPONENT);
+/// UI_COMPONENT.set_translation("fr", "ui_component.Calendar.today", "Aujourd'hui");
+/// ```
+///
+/// The backend is a `&'static dyn Backend`, e.g. a reference to a `static`, it's never
+/// copied nor leaked.
+///
+/// A dependency can only be extended once, the first call wins and returns `true`, the
+/// later calls are ignored and return `false`. Combine the backends with
+/// [`BackendExt::extend`] to extend it with several.
 #[macro_export]
+#[allow(clippy::crate_in_macro_def)]
 macro_rules! extend {
     ($target:ident) => {
         $target::_rust_i18n_extend(crate::_rust_i18n_backend(), stringify!($target))
     };
+    ($target:ident, $backend:expr) => {
+        $target::_rust_i18n_extend($backend, stringify!($target))
+    };
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{Mutex, MutexGuard};
+
     use crate::{locale, CURRENT_LOCALE};
 
+    /// Held by the tests that read or change the global locale, which run in parallel.
+    pub(crate) fn lock_locale() -> MutexGuard<'static, ()> {
+        static LOCK: Mutex<()> = Mutex::new(());
+        LOCK.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
     fn assert_locale_type(s: &str, val: &str) {
         assert_eq!(s, val);
     }
 
     #[test]
     fn test_locale() {
+        let _lock = lock_locale();
         assert_locale_type(&locale(), &CURRENT_LOCALE.as_str());
         assert_eq!(&*locale(), "en");
     }
+
+    #[test]
+    fn test_case_conversion() {
+        assert_eq!(crate::to_upper("istanbul"), "ISTANBUL");
+        assert_eq!(crate::to_lower("ISTANBUL"), "istanbul");
+        assert_eq!(crate::capitalize("istanbul"), "Istanbul");
+    }
+
+    #[test]
+    fn test_measurement_system() {
+        use crate::{measurement_system, set_measurement_system, MeasurementSystem};
+
+        assert_eq!(measurement_system("en-US"), MeasurementSystem::Imperial);
+        set_measurement_system(Some(MeasurementSystem::Metric));
+        assert_eq!(measurement_system("en-US"), MeasurementSystem::Metric);
+        set_measurement_system(None);
+        assert_eq!(measurement_system("de"), MeasurementSystem::Metric);
+    }
+
+    #[test]
+    fn test_on_locale_change() {
+        use std::sync::Arc;
+
+        let _lock = lock_locale();
+        crate::set_locale("en");
+        let changes = Arc::new(Mutex::new(vec![]));
+        let subscription = crate::on_locale_change({
+            let changes = changes.clone();
+            move |old, new| changes.lock().unwrap().push(format!("{old} -> {new}"))
+        });
+
+        crate::set_locale("fr");
+        crate::set_locale("fr");
+        crate::set_locale("en");
+        subscription.unsubscribe();
+        crate::set_locale("de");
+        crate::set_locale("en");
+
+        assert_eq!(*changes.lock().unwrap(), ["en -> fr", "fr -> en"]);
+    }
+
+    #[test]
+    fn test_set_locale_with_locale_type() {
+        let _lock = lock_locale();
+        let zh: crate::Locale = "zh_cn".parse().unwrap();
+        crate::set_locale(&zh);
+        assert_eq!(&*locale(), "zh-CN");
+        crate::set_locale("en");
+    }
+
+    #[test]
+    fn test_blob_backend() {
+        use crate::{_rust_i18n_blob_backend, _rust_i18n_blob_text, Backend};
+
+        // The locales share the texts of the blob, e.g. `hello` and `Hi` of `en` and `fr`
+        static BLOB: &str = "enhelloHifr你好zh";
+        static OFFSETS: [u32; 14] = [
+            0, 2, 1, 2, 7, 7, 9, // en: hello => Hi
+            9, 11, 1, 2, 7, 7, 9, // fr: hello => Hi
+        ];
+        let backend = _rust_i18n_blob_backend(BLOB, &OFFSETS);
+        assert_eq!(backend.translate("en", "hello").as_deref(), Some("Hi"));
+        assert_eq!(backend.translate("fr", "hello").as_deref(), Some("Hi"));
+        assert_eq!(backend.available_locales(), vec!["en", "fr"]);
+
+        // The ranges are in bytes
+        static ZH: [u32; 7] = [17, 19, 1, 2, 7, 11, 17];
+        let backend = _rust_i18n_blob_backend(BLOB, &ZH);
+        assert_eq!(backend.translate("zh", "hello").as_deref(), Some("你好"));
+        assert_eq!(_rust_i18n_blob_text(BLOB, 11, 17), "你好");
+        assert_eq!(_rust_i18n_blob_text(BLOB, 11, 11), "");
+
+        assert!(_rust_i18n_blob_backend("", &[])
+            .available_locales()
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "char boundaries")]
+    fn test_blob_text_in_char() {
+        crate::_rust_i18n_blob_text("你好", 0, 1);
+    }
 }
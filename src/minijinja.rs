@@ -0,0 +1,128 @@
+//! The `t` function and filter of the MiniJinja templates, with the same keys and fallbacks as
+//! [`t!`](crate::t).
+//!
+//! The keyword arguments are interpolated, e.g. `%{name}`, `count` selects the
+//! [intervals](crate#count-intervals), and the locale is the `locale` argument, the `locale`
+//! of the template context, or the current locale:
+//!
+//! ```jinja
+//! {{ t("hello") }}
+//! {{ t("messages.hello", name=user.name) }}
+//! {{ "messages.inbox" | t(count=messages | length, locale="zh-CN") }}
+//! ```
+//!
+//! ```no_run
+//! # macro_rules! translator { () => { |_: &str, _: &str| None } }
+//! let mut env = minijinja::Environment::new();
+//! rust_i18n::minijinja::register(&mut env, translator!());
+//! ```
+
+use minijinja::{
+    value::{Kwargs, Value},
+    Environment, Error, ErrorKind, State,
+};
+
+use crate::TranslateFn;
+
+/// Register the `t` function and filter of `env`.
+pub fn register(env: &mut Environment<'_>, translate: TranslateFn) {
+    let t = move |state: &State, key: &str, kwargs: Kwargs| {
+        translate_key(translate, state, key, kwargs)
+    };
+    env.add_function("t", t);
+    env.add_filter("t", t);
+}
+
+fn translate_key(
+    translate: TranslateFn,
+    state: &State,
+    key: &str,
+    kwargs: Kwargs,
+) -> Result<String, Error> {
+    let locale = match kwargs.get::<Option<String>>("locale")? {
+        Some(locale) => locale,
+        None => match state.lookup("locale") {
+            Some(locale) if !locale.is_undefined() && !locale.is_none() => {
+                locale.as_str().map(str::to_string).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidOperation,
+                        "the `locale` of the context must be a string",
+                    )
+                })?
+            }
+            _ => crate::locale().to_string(),
+        },
+    };
+    let mut patterns = vec![];
+    let mut values = vec![];
+    for name in kwargs.args().filter(|name| *name != "locale") {
+        patterns.push(name);
+        values.push(kwargs.get::<Value>(name)?.to_string());
+    }
+    kwargs.assert_all_used()?;
+    Ok(crate::translate_with(
+        translate, &locale, key, key, &patterns, &values, true,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use minijinja::context;
+
+    use super::*;
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        Some(
+            match (locale, key) {
+                ("en", "hello") => "Hello, %{name}!",
+                ("zh-CN", "hello") => "你好，%{name}！",
+                ("en", "inbox._intervals") => "1,2..",
+                ("en", "inbox.1") => "One message",
+                ("en", "inbox.2..") => "%{count} messages",
+                _ => return None,
+            }
+            .into(),
+        )
+    }
+
+    #[test]
+    fn test_translate() {
+        let mut env = Environment::new();
+        register(&mut env, translate);
+        let render = |template: &str, ctx: Value| {
+            crate::with_locale("en", || env.render_str(template, ctx)).unwrap()
+        };
+
+        assert_eq!(
+            render(
+                r#"{{ t("hello", name=name) }}"#,
+                context! { name => "Jason" }
+            ),
+            "Hello, Jason!"
+        );
+        assert_eq!(
+            render(
+                r#"{{ "hello" | t(name=name, locale="zh-CN") }}"#,
+                context! { name => "Jason" }
+            ),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            render(
+                r#"{{ t("hello", name=name) }}"#,
+                context! { name => "Jason", locale => "zh-CN" }
+            ),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            render(
+                r#"{{ "inbox" | t(count=messages | length) }}"#,
+                context! { messages => [1, 2, 3] }
+            ),
+            "3 messages"
+        );
+        assert_eq!(render(r#"{{ t("missing") }}"#, context! {}), "missing");
+    }
+}
@@ -0,0 +1,123 @@
+//! Translate the string fields with keys when they are serialized, e.g. the labels of the API
+//! responses.
+//!
+//! The fields are translated in the current locale, e.g. the one of the request with
+//! [`with_locale`](crate::with_locale), by the translator of [`set_translator`]:
+//!
+//! ```
+//! use rust_i18n::serde_t::Translated;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct Status {
+//!     // Translated by the key, e.g. "status.active"
+//!     #[serde(with = "rust_i18n::serde_t")]
+//!     label: String,
+//!     title: Translated<&'static str>,
+//!     code: u32,
+//! }
+//!
+//! rust_i18n::serde_t::set_translator(|locale, key| match (locale, key) {
+//!     ("en", "status.active") => Some("Active".into()),
+//!     ("zh-CN", "status.active") => Some("活跃".into()),
+//!     _ => None,
+//! });
+//! let status = Status {
+//!     label: "status.active".into(),
+//!     title: Translated("status.missing"),
+//!     code: 1,
+//! };
+//! let json = rust_i18n::with_locale("zh-CN", || serde_json::to_string(&status).unwrap());
+//! assert_eq!(json, r#"{"label":"活跃","title":"status.missing","code":1}"#);
+//! ```
+
+use std::{fmt, ops::Deref, sync::RwLock};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::TranslateFn;
+
+static TRANSLATOR: RwLock<Option<TranslateFn>> = RwLock::new(None);
+
+/// Set the translator of the serialized keys, the [`translator!`](crate::translator) of the
+/// crate, the keys are serialized as is without it.
+///
+/// ```no_run
+/// # macro_rules! translator { () => { |_: &str, _: &str| None } }
+/// rust_i18n::serde_t::set_translator(translator!());
+/// ```
+pub fn set_translator(translate: TranslateFn) {
+    *TRANSLATOR.write().unwrap_or_else(|err| err.into_inner()) = Some(translate);
+}
+
+/// Translate a key in the current locale by the translator of [`set_translator`], the key is
+/// returned without a translation.
+pub fn translate(key: &str) -> String {
+    let translator = *TRANSLATOR.read().unwrap_or_else(|err| err.into_inner());
+    match translator {
+        Some(translate) => {
+            crate::translate_with(translate, &crate::locale(), key, key, &[], &[], false)
+        }
+        None => key.to_string(),
+    }
+}
+
+/// Serialize a key as its translation, for `#[serde(with = "rust_i18n::serde_t")]`.
+pub fn serialize<T, S>(key: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<str> + ?Sized,
+    S: Serializer,
+{
+    serializer.serialize_str(&translate(key.as_ref()))
+}
+
+/// Deserialize the field as is, for `#[serde(with = "rust_i18n::serde_t")]`, the
+/// translations can't be turned back into keys.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer)
+}
+
+/// A key serialized as its translation in the current locale, see [`set_translator`].
+///
+/// It's deserialized as is.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct Translated<T>(pub T);
+
+impl<T: AsRef<str>> Translated<T> {
+    /// The key.
+    pub fn key(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl<T> Deref for Translated<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for Translated<T> {
+    fn from(key: T) -> Self {
+        Self(key)
+    }
+}
+
+impl<T: AsRef<str>> Serialize for Translated<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.key(), serializer)
+    }
+}
+
+/// Display the translation in the current locale.
+impl<T: AsRef<str>> fmt::Display for Translated<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&translate(self.key()))
+    }
+}
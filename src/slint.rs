@@ -0,0 +1,101 @@
+//! The `tr` callback of the Slint apps, translating the keys with the same fallbacks as
+//! [`t!`](crate::t).
+//!
+//! Declare a global with the locale and the callback, and read the locale in the bindings so
+//! they're evaluated again when it changes:
+//!
+//! ```slint
+//! export global I18n {
+//!     in property <string> locale;
+//!     pure callback tr(string, string) -> string;
+//! }
+//!
+//! export component App inherits Window {
+//!     Text { text: I18n.tr("title", I18n.locale); }
+//! }
+//! ```
+//!
+//! Then bridge the callback with [`translator`] and keep the locale in sync with
+//! [`sync_locale`]:
+//!
+//! ```ignore
+//! let app = App::new()?;
+//! let i18n = app.global::<I18n>();
+//! i18n.set_locale(rust_i18n::locale().to_string().into());
+//! i18n.on_tr(rust_i18n::slint::translator(rust_i18n::translator!()));
+//!
+//! let weak = app.as_weak();
+//! let _subscription = rust_i18n::slint::sync_locale(move |locale| {
+//!     if let Some(app) = weak.upgrade() {
+//!         app.global::<I18n>().set_locale(locale);
+//!     }
+//! });
+//! app.run()?;
+//! ```
+
+use std::sync::Arc;
+
+use slint::SharedString;
+
+use crate::{LocaleChangeSubscription, TranslateFn};
+
+/// The handler of the `tr(key, locale)` callback, with the [`translator!`](crate::translator)
+/// of the crate.
+///
+/// The key itself is returned for the missing translations, and the current locale is used
+/// for an empty locale.
+pub fn translator(translate: TranslateFn) -> impl Fn(SharedString, SharedString) -> SharedString {
+    move |key, locale| {
+        let locale = if locale.is_empty() {
+            crate::locale().to_string()
+        } else {
+            locale.to_string()
+        };
+        crate::translate_with(translate, &locale, &key, &key, &[], &[], true).into()
+    }
+}
+
+/// Call `set_locale` in the event loop each time [`set_locale`](crate::set_locale) changes the
+/// locale, until the returned subscription is unsubscribed.
+///
+/// The locale changes before the event loop is running are skipped.
+pub fn sync_locale(
+    set_locale: impl Fn(SharedString) + Send + Sync + 'static,
+) -> LocaleChangeSubscription {
+    let set_locale = Arc::new(set_locale);
+    crate::on_locale_change(move |_, new| {
+        let set_locale = set_locale.clone();
+        let locale = SharedString::from(new);
+        let _ = slint::invoke_from_event_loop(move || set_locale(locale));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        Some(
+            match (locale, key) {
+                ("en", "title") => "Title",
+                ("fr", "title") => "Titre",
+                _ => return None,
+            }
+            .into(),
+        )
+    }
+
+    #[test]
+    fn test_translator() {
+        let tr = translator(translate);
+        assert_eq!(tr("title".into(), "en".into()), "Title");
+        assert_eq!(tr("title".into(), "fr".into()), "Titre");
+        assert_eq!(tr("missing".into(), "fr".into()), "missing");
+        assert_eq!(
+            crate::with_locale("fr", || tr("title".into(), "".into())),
+            "Titre"
+        );
+    }
+}
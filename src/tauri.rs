@@ -0,0 +1,220 @@
+//! A Tauri plugin sharing the translations of the crate with the webview.
+//!
+//! The frontend calls the commands of the `i18n` plugin, and listens to the
+//! [`LOCALE_CHANGED`] event, emitted when [`set_locale`](crate::set_locale) changes the
+//! locale, from the frontend or the backend:
+//!
+//! ```js
+//! import { invoke } from "@tauri-apps/api/core";
+//! import { listen } from "@tauri-apps/api/event";
+//!
+//! await invoke("plugin:i18n|t", { key: "hello", args: { name: "Jason" } });
+//! await invoke("plugin:i18n|set_locale", { locale: "zh-CN" });
+//! await invoke("plugin:i18n|locale");
+//! await invoke("plugin:i18n|available_locales");
+//! await listen("i18n://locale-changed", (event) => render(event.payload));
+//! ```
+//!
+//! The plugin is a part of the app, so its commands are allowed by the `build.rs` of the app:
+//!
+//! ```ignore
+//! tauri_build::try_build(tauri_build::Attributes::new().plugin(
+//!     "i18n",
+//!     tauri_build::InlinedPlugin::new()
+//!         .commands(rust_i18n::tauri::COMMANDS)
+//!         .default_permission(tauri_build::DefaultPermissionRule::AllowAllCommands),
+//! ))
+//! .unwrap();
+//! ```
+
+use std::{collections::HashMap, sync::Mutex};
+
+use tauri::{
+    plugin::{Builder, TauriPlugin},
+    Emitter, Manager, Runtime, State,
+};
+
+use crate::{LocaleChangeSubscription, TranslateFn};
+
+/// The commands of the plugin.
+pub const COMMANDS: &[&str] = &["t", "locale", "set_locale", "available_locales"];
+
+/// The event emitted with the new locale when the locale changes.
+pub const LOCALE_CHANGED: &str = "i18n://locale-changed";
+
+struct I18n {
+    translate: TranslateFn,
+    available_locales: Vec<String>,
+    subscription: Mutex<Option<LocaleChangeSubscription>>,
+}
+
+/// Create the plugin with the [`translator!`](crate::translator) and the
+/// [`available_locales!`](crate::available_locales) of the crate.
+///
+/// ```ignore
+/// tauri::Builder::default()
+///     .plugin(rust_i18n::tauri::init(
+///         rust_i18n::translator!(),
+///         rust_i18n::available_locales!(),
+///     ))
+///     .run(tauri::generate_context!())
+///     .unwrap();
+/// ```
+pub fn init<R: Runtime>(
+    translate: TranslateFn,
+    available_locales: impl IntoIterator<Item = impl AsRef<str>>,
+) -> TauriPlugin<R> {
+    let locales = available_locales
+        .into_iter()
+        .map(|locale| locale.as_ref().to_string())
+        .collect::<Vec<_>>();
+    Builder::new("i18n")
+        .invoke_handler(tauri::generate_handler![
+            t,
+            locale,
+            set_locale,
+            available_locales
+        ])
+        .setup(move |app, _api| {
+            let handle = app.clone();
+            let subscription = crate::on_locale_change(move |_, locale| {
+                let _ = handle.emit(LOCALE_CHANGED, locale.to_string());
+            });
+            app.manage(I18n {
+                translate,
+                available_locales: locales,
+                subscription: Mutex::new(Some(subscription)),
+            });
+            Ok(())
+        })
+        .on_drop(|app| {
+            if let Some(i18n) = app.try_state::<I18n>() {
+                let subscription = i18n
+                    .subscription
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .take();
+                if let Some(subscription) = subscription {
+                    subscription.unsubscribe();
+                }
+            }
+        })
+        .build()
+}
+
+impl I18n {
+    fn t(
+        &self,
+        key: &str,
+        args: &HashMap<String, serde_json::Value>,
+        locale: Option<&str>,
+    ) -> String {
+        let locale = match locale {
+            Some(locale) => locale.to_string(),
+            None => crate::locale().to_string(),
+        };
+        let (patterns, values): (Vec<&str>, Vec<String>) = args
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    serde_json::Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                };
+                (name.as_str(), value)
+            })
+            .unzip();
+        crate::translate_with(self.translate, &locale, key, key, &patterns, &values, true)
+    }
+
+    fn set_locale(&self, locale: &str) -> Result<String, String> {
+        let locale = crate::negotiate(&[locale], &self.available_locales)
+            .ok_or_else(|| format!("The locale {} is not available", locale))?;
+        crate::set_locale(locale);
+        Ok(locale.to_string())
+    }
+}
+
+/// Translate a key with the arguments like `%{name}`, in the current locale or `locale`.
+#[tauri::command]
+fn t(
+    i18n: State<'_, I18n>,
+    key: String,
+    args: Option<HashMap<String, serde_json::Value>>,
+    locale: Option<String>,
+) -> String {
+    i18n.t(&key, &args.unwrap_or_default(), locale.as_deref())
+}
+
+/// The current locale.
+#[tauri::command]
+fn locale() -> String {
+    crate::locale().to_string()
+}
+
+/// Set the current locale to the best available locale for `locale`, returns it.
+#[tauri::command]
+fn set_locale(i18n: State<'_, I18n>, locale: String) -> Result<String, String> {
+    i18n.set_locale(&locale)
+}
+
+/// The available locales.
+#[tauri::command]
+fn available_locales(i18n: State<'_, I18n>) -> Vec<String> {
+    i18n.available_locales.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        match (locale, key) {
+            ("en", "hello") => Some("Hello, %{name}! (%{count})".into()),
+            ("zh-CN", "hello") => Some("你好，%{name}！(%{count})".into()),
+            _ => None,
+        }
+    }
+
+    fn i18n() -> I18n {
+        I18n {
+            translate,
+            available_locales: vec!["en".into(), "zh-CN".into()],
+            subscription: Mutex::new(None),
+        }
+    }
+
+    #[test]
+    fn test_t() {
+        let i18n = i18n();
+        let args = HashMap::from([
+            ("name".to_string(), serde_json::json!("Jason")),
+            ("count".to_string(), serde_json::json!(3)),
+        ]);
+
+        assert_eq!(i18n.t("hello", &args, Some("en")), "Hello, Jason! (3)");
+        // Without the locale, the current locale
+        assert_eq!(
+            crate::with_locale("zh-CN", || i18n.t("hello", &args, None)),
+            "你好，Jason！(3)"
+        );
+        // The missing keys are returned
+        assert_eq!(i18n.t("bye", &HashMap::new(), Some("en")), "bye");
+    }
+
+    #[test]
+    fn test_set_locale() {
+        let i18n = i18n();
+        let _lock = crate::tests::lock_locale();
+
+        assert_eq!(i18n.set_locale("zh"), Ok("zh-CN".to_string()));
+        assert_eq!(&*crate::locale(), "zh-CN");
+        assert_eq!(
+            i18n.set_locale("ja"),
+            Err("The locale ja is not available".to_string())
+        );
+        assert_eq!(&*crate::locale(), "zh-CN");
+        crate::set_locale("en");
+    }
+}
@@ -0,0 +1,152 @@
+//! The `t` function and filter of the Tera templates, with the same keys and fallbacks as
+//! [`t!`](crate::t).
+//!
+//! The other arguments are interpolated, e.g. `%{name}`, `count` selects the
+//! [intervals](crate#count-intervals), and `locale` overrides the current locale:
+//!
+//! ```jinja
+//! {{ t(key="hello") }}
+//! {{ t(key="messages.hello", name=user.name) }}
+//! {{ "messages.inbox" | t(count=messages | length, locale="zh-CN") }}
+//! ```
+//!
+//! ```no_run
+//! # macro_rules! translator { () => { |_: &str, _: &str| None } }
+//! let mut tera = tera::Tera::new("templates/**/*.html").unwrap();
+//! rust_i18n::tera::register(&mut tera, translator!());
+//! ```
+
+use std::collections::HashMap;
+
+use tera::{Filter, Function, Tera, Value};
+
+use crate::TranslateFn;
+
+/// Register [`Translate`] as the `t` function and filter of `tera`.
+pub fn register(tera: &mut Tera, translate: TranslateFn) {
+    tera.register_function("t", Translate::new(translate));
+    tera.register_filter("t", Translate::new(translate));
+}
+
+/// The `t` function and filter, translating a key with the arguments in the current locale or
+/// the `locale` argument.
+#[derive(Clone, Copy)]
+pub struct Translate {
+    translate: TranslateFn,
+}
+
+impl Translate {
+    /// Create the function with the [`translator!`](crate::translator) of the crate.
+    pub fn new(translate: TranslateFn) -> Self {
+        Self { translate }
+    }
+
+    fn call_with_key(&self, key: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+        let Some(key) = key.as_str() else {
+            return Err(tera::Error::msg(format!(
+                "The key of `t` must be a string, got {}",
+                key
+            )));
+        };
+        let locale = match args.get("locale") {
+            Some(Value::String(locale)) => locale.clone(),
+            Some(locale) => {
+                return Err(tera::Error::msg(format!(
+                    "The locale of `t` must be a string, got {}",
+                    locale
+                )))
+            }
+            None => crate::locale().to_string(),
+        };
+        let (patterns, values): (Vec<&str>, Vec<String>) = args
+            .iter()
+            .filter(|(name, _)| !matches!(name.as_str(), "key" | "locale"))
+            .map(|(name, value)| {
+                let value = match value {
+                    Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                };
+                (name.as_str(), value)
+            })
+            .unzip();
+        Ok(Value::String(crate::translate_with(
+            self.translate,
+            &locale,
+            key,
+            key,
+            &patterns,
+            &values,
+            true,
+        )))
+    }
+}
+
+impl Function for Translate {
+    fn call(&self, args: &HashMap<String, Value>) -> tera::Result<Value> {
+        match args.get("key") {
+            Some(key) => self.call_with_key(key, args),
+            None => Err(tera::Error::msg(
+                "The function `t` requires a `key` argument",
+            )),
+        }
+    }
+}
+
+impl Filter for Translate {
+    fn filter(&self, key: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+        self.call_with_key(key, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use tera::Context;
+
+    use super::*;
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        Some(
+            match (locale, key) {
+                ("en", "hello") => "Hello, %{name}!",
+                ("zh-CN", "hello") => "你好，%{name}！",
+                ("en", "inbox._intervals") => "1,2..",
+                ("en", "inbox.1") => "One message",
+                ("en", "inbox.2..") => "%{count} messages",
+                _ => return None,
+            }
+            .into(),
+        )
+    }
+
+    #[test]
+    fn test_translate() {
+        let mut tera = Tera::default();
+        register(&mut tera, translate);
+        let mut context = Context::new();
+        context.insert("name", "Jason");
+        context.insert("messages", &[1, 2, 3]);
+        let mut render = |template: &str| {
+            crate::with_locale("en", || tera.render_str(template, &context))
+                .map_err(|err| format!("{:?}", err))
+        };
+
+        assert_eq!(
+            render(r#"{{ t(key="hello", name=name) }}"#).unwrap(),
+            "Hello, Jason!"
+        );
+        assert_eq!(
+            render(r#"{{ "hello" | t(name=name, locale="zh-CN") }}"#).unwrap(),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            render(r#"{{ "inbox" | t(count=messages | length) }}"#).unwrap(),
+            "3 messages"
+        );
+        assert_eq!(render(r#"{{ t(key="missing") }}"#).unwrap(), "missing");
+        assert!(render(r#"{{ t(name=name) }}"#)
+            .unwrap_err()
+            .contains("requires a `key` argument"));
+    }
+}
@@ -0,0 +1,190 @@
+//! The `tracing` events of the lookups, to alert on the untranslated texts in production.
+//!
+//! The events have the `rust_i18n` target:
+//!
+//! - `WARN` "missing translation", with `locale`, `key` and `default`, and `file` and `line`
+//!   of [`t!`](crate::t), when a key isn't translated in any locale, so the default is shown.
+//! - `DEBUG` "fallback translation", with `locale`, `key` and `fallback`, when a key is
+//!   translated in a fallback locale instead, e.g. `zh` for `zh-CN`, or the `fallback` of
+//!   [`i18n!`](crate::i18n).
+//! - `WARN` "slow translation lookup", with `locale`, `key` and `elapsed`, when a lookup of
+//!   the backend is slower than [`set_slow_lookup_threshold`], 1ms by default.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! rust_i18n::tracing::set_slow_lookup_threshold(Duration::from_millis(5));
+//! ```
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+const TARGET: &str = "rust_i18n";
+
+static SLOW_LOOKUP_THRESHOLD_MICROS: AtomicU64 = AtomicU64::new(1_000);
+
+/// Set the threshold of the "slow translation lookup" events, 1ms by default.
+pub fn set_slow_lookup_threshold(threshold: Duration) {
+    let micros = u64::try_from(threshold.as_micros()).unwrap_or(u64::MAX);
+    SLOW_LOOKUP_THRESHOLD_MICROS.store(micros, Ordering::Relaxed);
+}
+
+/// The threshold of the "slow translation lookup" events.
+pub fn slow_lookup_threshold() -> Duration {
+    Duration::from_micros(SLOW_LOOKUP_THRESHOLD_MICROS.load(Ordering::Relaxed))
+}
+
+/// Emit the "missing translation" event, with the location of `t!`.
+#[doc(hidden)]
+pub fn _missing(locale: &str, key: &str, default: &str, location: Option<(&str, u32)>) {
+    match location {
+        Some((file, line)) => tracing::warn!(
+            target: TARGET,
+            locale,
+            key,
+            default,
+            file,
+            line,
+            "missing translation"
+        ),
+        None => tracing::warn!(target: TARGET, locale, key, default, "missing translation"),
+    }
+}
+
+/// Emit the "fallback translation" event.
+#[doc(hidden)]
+pub fn _fallback(locale: &str, key: &str, fallback: &str) {
+    tracing::debug!(target: TARGET, locale, key, fallback, "fallback translation");
+}
+
+/// Time a lookup of the backend, and emit the "slow translation lookup" event.
+#[doc(hidden)]
+#[inline]
+pub fn _lookup<T>(locale: &str, key: &str, lookup: impl FnOnce() -> T) -> T {
+    // `Instant` isn't supported in the browsers.
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        let _ = (locale, key);
+        lookup()
+    }
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    {
+        let started = std::time::Instant::now();
+        let value = lookup();
+        let elapsed = started.elapsed();
+        if elapsed >= slow_lookup_threshold() {
+            tracing::warn!(
+                target: TARGET,
+                locale,
+                key,
+                elapsed = ?elapsed,
+                "slow translation lookup"
+            );
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Level, Metadata, Subscriber,
+    };
+
+    use super::*;
+
+    /// Record the level and the fields of the events.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<(Level, String)>>>);
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if !self.0.is_empty() {
+                self.0.push(' ');
+            }
+            self.0.push_str(&format!("{}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == TARGET
+        }
+
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            self.0
+                .lock()
+                .unwrap()
+                .push((*event.metadata().level(), fields.0));
+        }
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    #[test]
+    fn test_events() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            _missing("fr", "hello", "Hello", Some(("src/main.rs", 3)));
+            _missing("fr", "hello", "Hello", None);
+            _fallback("zh-CN", "hello", "zh");
+
+            set_slow_lookup_threshold(Duration::MAX);
+            assert_eq!(_lookup("fr", "hello", || 1), 1);
+
+            set_slow_lookup_threshold(Duration::ZERO);
+            _lookup("fr", "hello", || ());
+            set_slow_lookup_threshold(Duration::from_millis(1));
+        });
+
+        let events = recorder.0.lock().unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            events[0],
+            (
+                Level::WARN,
+                r#"message=missing translation locale="fr" key="hello" default="Hello" file="src/main.rs" line=3"#.into()
+            )
+        );
+        assert_eq!(
+            events[1],
+            (
+                Level::WARN,
+                r#"message=missing translation locale="fr" key="hello" default="Hello""#.into()
+            )
+        );
+        assert_eq!(
+            events[2],
+            (
+                Level::DEBUG,
+                r#"message=fallback translation locale="zh-CN" key="hello" fallback="zh""#.into()
+            )
+        );
+        assert_eq!(events[3].0, Level::WARN);
+        assert!(events[3]
+            .1
+            .starts_with(r#"message=slow translation lookup locale="fr" key="hello" elapsed="#));
+    }
+}
@@ -0,0 +1,243 @@
+//! Translate the errors of the [validator](https://docs.rs/validator) crate by their codes, so
+//! the form APIs return the messages in the locale of the request.
+//!
+//! The message of an error of a field is looked up by the keys under a prefix, e.g.
+//! `validation`, with the params of the error as the arguments, e.g. `%{min}` and `%{max}`:
+//!
+//! | Key                                  | Text                                          |
+//! | ------------------------------------ | --------------------------------------------- |
+//! | `validation.<field>.<code>`          | The message of a field, e.g. `validation.email.email` |
+//! | `validation.<code>`                  | The message of a code, e.g. `validation.length` |
+//! | `validation.fields.<field>`          | The name of a field, as `%{field}`            |
+//!
+//! The fields of the nested structs are paths like `address.city`, the items of the lists are
+//! left out of the keys, e.g. `items.name` for `items[0].name`. The `message` of an error, or
+//! its code, is used without a translation.
+//!
+//! ```
+//! use std::borrow::Cow;
+//! use validator::{ValidationError, ValidationErrors};
+//!
+//! let mut error = ValidationError::new("length");
+//! error.add_param(Cow::from("min"), &8);
+//! let mut errors = ValidationErrors::new();
+//! errors.add("password", error);
+//!
+//! let messages = rust_i18n::validator::localize_errors(&errors, "validation", |_, key| {
+//!     match key {
+//!         "validation.length" => Some("%{field} must be at least %{min} characters".into()),
+//!         "validation.fields.password" => Some("Password".into()),
+//!         _ => None,
+//!     }
+//! });
+//! assert_eq!(
+//!     messages["password"],
+//!     ["Password must be at least 8 characters"]
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::TranslateFn;
+
+/// Translate all the errors in the current locale, by the keys under `prefix`, see the
+/// [module](self) for the keys.
+///
+/// The messages are grouped by the paths of the fields, e.g. `address.city` or
+/// `items[0].name`, and the errors of the struct itself are under `__all__`.
+pub fn localize_errors(
+    errors: &ValidationErrors,
+    prefix: &str,
+    translate: TranslateFn,
+) -> BTreeMap<String, Vec<String>> {
+    let locale = crate::locale();
+    let mut messages = BTreeMap::new();
+    collect_errors(
+        &locale,
+        errors,
+        prefix,
+        translate,
+        &mut Path::default(),
+        &mut messages,
+    );
+    messages
+}
+
+/// Translate an error of `field` in the current locale, by the keys under `prefix`, see the
+/// [module](self) for the keys.
+pub fn localize_error(
+    field: &str,
+    error: &ValidationError,
+    prefix: &str,
+    translate: TranslateFn,
+) -> String {
+    localize_error_with_locale(&crate::locale(), field, error, prefix, translate)
+}
+
+fn localize_error_with_locale(
+    locale: &str,
+    field: &str,
+    error: &ValidationError,
+    prefix: &str,
+    translate: TranslateFn,
+) -> String {
+    let message = translate(locale, &format!("{}.{}.{}", prefix, field, error.code))
+        .or_else(|| translate(locale, &format!("{}.{}", prefix, error.code)))
+        .or_else(|| error.message.clone())
+        .unwrap_or_else(|| error.code.clone());
+
+    let name = translate(locale, &format!("{}.fields.{}", prefix, field))
+        .map(|name| name.into_owned())
+        .unwrap_or_else(|| field.to_string());
+    let mut patterns = vec!["field"];
+    let mut values = vec![name];
+    for (param, value) in &error.params {
+        patterns.push(param);
+        values.push(match value {
+            serde_json::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        });
+    }
+    crate::replace_patterns_with_locale(locale, &message, &patterns, &values)
+}
+
+/// The path of a field, for the messages and for the keys without the indexes of the lists.
+#[derive(Default)]
+struct Path {
+    full: String,
+    key: String,
+}
+
+fn collect_errors(
+    locale: &str,
+    errors: &ValidationErrors,
+    prefix: &str,
+    translate: TranslateFn,
+    path: &mut Path,
+    messages: &mut BTreeMap<String, Vec<String>>,
+) {
+    for (field, kind) in errors.errors() {
+        let (full_len, key_len) = (path.full.len(), path.key.len());
+        if !path.full.is_empty() {
+            path.full.push('.');
+            path.key.push('.');
+        }
+        path.full.push_str(field);
+        path.key.push_str(field);
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                let field_messages = messages.entry(path.full.clone()).or_default();
+                for error in errors {
+                    field_messages.push(localize_error_with_locale(
+                        locale, &path.key, error, prefix, translate,
+                    ));
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => {
+                collect_errors(locale, errors, prefix, translate, path, messages);
+            }
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    let len = path.full.len();
+                    path.full.push_str(&format!("[{}]", index));
+                    collect_errors(locale, errors, prefix, translate, path, messages);
+                    path.full.truncate(len);
+                }
+            }
+        }
+
+        path.full.truncate(full_len);
+        path.key.truncate(key_len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, collections::BTreeMap};
+
+    use super::*;
+
+    fn translate(locale: &str, key: &str) -> Option<Cow<'static, str>> {
+        Some(
+            match (locale, key) {
+                ("en", "validation.length") => "%{field} must be %{min} to %{max} characters",
+                ("en", "validation.email") => "%{field} is not a valid email",
+                ("en", "validation.address.city.required") => "Please enter the city",
+                ("en", "validation.fields.email") => "Email",
+                ("zh-CN", "validation.email") => "%{field} 不是有效的邮箱：%{value}",
+                ("zh-CN", "validation.fields.email") => "邮箱",
+                _ => return None,
+            }
+            .into(),
+        )
+    }
+
+    fn error(code: &'static str, params: &[(&'static str, serde_json::Value)]) -> ValidationError {
+        let mut error = ValidationError::new(code);
+        for (name, value) in params {
+            error.add_param(Cow::from(*name), value);
+        }
+        error
+    }
+
+    #[test]
+    fn test_localize_errors() {
+        let mut address = ValidationErrors::new();
+        address.add("city", error("required", &[]));
+        let mut item = ValidationErrors::new();
+        item.add(
+            "name",
+            error("length", &[("min", 1.into()), ("max", 10.into())]),
+        );
+
+        let mut errors = ValidationErrors::new();
+        errors.add("email", error("email", &[("value", "foo".into())]));
+        errors.add(
+            "nickname",
+            error("custom", &[]).with_message(Cow::from("Taken")),
+        );
+        errors.add("code", error("invalid_code", &[]));
+        errors.errors_mut().insert(
+            "address".into(),
+            ValidationErrorsKind::Struct(address.into()),
+        );
+        errors.errors_mut().insert(
+            "items".into(),
+            ValidationErrorsKind::List(BTreeMap::from([(2, item.into())])),
+        );
+
+        let messages =
+            crate::with_locale("en", || localize_errors(&errors, "validation", translate));
+        assert_eq!(messages["email"], ["Email is not a valid email"]);
+        assert_eq!(messages["nickname"], ["Taken"]);
+        assert_eq!(messages["code"], ["invalid_code"]);
+        assert_eq!(messages["address.city"], ["Please enter the city"]);
+        assert_eq!(
+            messages["items[2].name"],
+            ["items.name must be 1 to 10 characters"]
+        );
+        assert_eq!(messages.len(), 5);
+
+        let messages = crate::with_locale("zh-CN", || {
+            localize_errors(&errors, "validation", translate)
+        });
+        assert_eq!(messages["email"], ["邮箱 不是有效的邮箱：foo"]);
+    }
+
+    #[test]
+    fn test_localize_error() {
+        let error = error("email", &[]);
+        assert_eq!(
+            crate::with_locale("en", || localize_error(
+                "contact",
+                &error,
+                "validation",
+                translate
+            )),
+            "contact is not a valid email"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! The reactive locale of the Yew apps.
+//!
+//! The [`I18nProvider`] component provides the [`I18n`] context, its locale follows
+//! [`set_locale`](crate::set_locale), so the components using the hooks are rendered again
+//! when the locale changes:
+//!
+//! ```ignore
+//! use rust_i18n::yew::{use_locale, use_t, I18nProvider};
+//! use yew::prelude::*;
+//!
+//! #[function_component]
+//! fn App() -> Html {
+//!     html! {
+//!         <I18nProvider translate={rust_i18n::translator!()}>
+//!             <Header />
+//!         </I18nProvider>
+//!     }
+//! }
+//!
+//! #[function_component]
+//! fn Header() -> Html {
+//!     let title = use_t("title");
+//!     let locale = use_locale();
+//!     html! {
+//!         <>
+//!             <h1>{ title }</h1>
+//!             <p>{ t!("messages.hello", locale = &locale, name = "Jason") }</p>
+//!             <button onclick={|_| rust_i18n::set_locale("zh-CN")}>{ "中文" }</button>
+//!         </>
+//!     }
+//! }
+//! ```
+//!
+//! With the `FetchBackend` of the `wasm` feature, fetch the translations of a locale before
+//! switching to it, so the components are rendered with them.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use yew::{
+    function_component, hook, html, use_context, use_effect_with, use_state, Callback,
+    ContextProvider, Html, Properties,
+};
+
+use crate::TranslateFn;
+
+thread_local! {
+    // The state of the providers isn't `Send`, the callbacks of `on_locale_change` find it by
+    // the id in the thread calling `set_locale`, the thread of the app.
+    static PROVIDERS: RefCell<HashMap<usize, Callback<String>>> = RefCell::new(HashMap::new());
+}
+
+static NEXT_PROVIDER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The context of [`I18nProvider`].
+#[derive(Clone)]
+pub struct I18n {
+    locale: String,
+    translate: TranslateFn,
+}
+
+impl I18n {
+    /// The current locale.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Translate a key in the current locale, with the arguments like `%{name}`.
+    pub fn t(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let (patterns, values): (Vec<&str>, Vec<String>) = args
+            .iter()
+            .map(|(name, value)| (*name, value.to_string()))
+            .unzip();
+        crate::translate_with(
+            self.translate,
+            &self.locale,
+            key,
+            key,
+            &patterns,
+            &values,
+            true,
+        )
+    }
+}
+
+/// The translator is the same in an app, the context changes with the locale.
+impl PartialEq for I18n {
+    fn eq(&self, other: &Self) -> bool {
+        self.locale == other.locale
+    }
+}
+
+/// The properties of [`I18nProvider`].
+#[derive(Properties, Clone)]
+pub struct I18nProviderProps {
+    /// The [`translator!`](crate::translator) of the crate.
+    pub translate: TranslateFn,
+    /// The components using the context.
+    #[prop_or_default]
+    pub children: Html,
+}
+
+impl PartialEq for I18nProviderProps {
+    fn eq(&self, other: &Self) -> bool {
+        self.children == other.children
+    }
+}
+
+/// Provide the [`I18n`] context to the children, in the root component.
+///
+/// The locale follows [`set_locale`](crate::set_locale) until the component is destroyed.
+#[function_component]
+pub fn I18nProvider(props: &I18nProviderProps) -> Html {
+    let locale = use_state(|| crate::locale().to_string());
+    {
+        let locale = locale.clone();
+        use_effect_with((), move |_| {
+            let id = NEXT_PROVIDER_ID.fetch_add(1, Ordering::Relaxed);
+            let set_locale = Callback::from(move |new: String| locale.set(new));
+            PROVIDERS.with(|providers| providers.borrow_mut().insert(id, set_locale));
+            let subscription = crate::on_locale_change(move |_, new| {
+                let set_locale = PROVIDERS.with(|providers| providers.borrow().get(&id).cloned());
+                if let Some(set_locale) = set_locale {
+                    set_locale.emit(new.to_string());
+                }
+            });
+            move || {
+                subscription.unsubscribe();
+                PROVIDERS.with(|providers| providers.borrow_mut().remove(&id));
+            }
+        });
+    }
+
+    let i18n = I18n {
+        locale: (*locale).clone(),
+        translate: props.translate,
+    };
+    html! {
+        <ContextProvider<I18n> context={i18n}>
+            { props.children.clone() }
+        </ContextProvider<I18n>>
+    }
+}
+
+/// The [`I18n`] context of [`I18nProvider`].
+///
+/// # Panics
+///
+/// Panics without the context.
+#[hook]
+pub fn use_i18n() -> I18n {
+    use_context::<I18n>().expect("The I18n context is not provided, use `I18nProvider` first")
+}
+
+/// The current locale, see [`I18n::locale`].
+#[hook]
+pub fn use_locale() -> String {
+    use_i18n().locale
+}
+
+/// The translation of a key in the current locale, see [`I18n::t`].
+#[hook]
+pub fn use_t(key: &str) -> String {
+    use_i18n().t(key, &[])
+}
@@ -0,0 +1,58 @@
+rust_i18n::i18n!("./tests/locales", fallback = "en", inline = true);
+
+#[cfg(test)]
+mod tests {
+    use rust_i18n::t;
+
+    #[test]
+    fn test_t_inlined() {
+        assert_eq!(t!("hello", locale = "zh-CN"), "Bar - 你好世界！");
+        assert_eq!(
+            t!("messages.hello", locale = "zh-CN", name = "Jason"),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            t!("a.very.nested.message", locale = "en", name = "Jason", msg = 42 : {:>4}),
+            "Hello, Jason. Your message is:   42"
+        );
+        assert_eq!(
+            t!("messages.hello", locale = "en", other = "Jason"),
+            "Hello, %{name}!"
+        );
+        assert_eq!(
+            t!("syntax", locale = "en", name = "Jason", example = "Hi"),
+            "Write %{name} to insert the name, e.g. Hi"
+        );
+        assert_eq!(
+            t!("brand.welcome", locale = "en", name = "Jason"),
+            "Welcome to Acme Cloud, Jason!"
+        );
+        // Not inlined, looked up with the fallbacks
+        assert_eq!(
+            t!("missing.default", locale = "zh-CN"),
+            "This is missing key fallbacked to en."
+        );
+        assert_eq!(t!("not.exists", locale = "en"), "not.exists");
+    }
+
+    #[test]
+    fn test_t_inlined_with_overrides() {
+        assert_eq!(
+            t!("custom.greeting", locale = "en", name = "Jason"),
+            "Hello, Jason!!!"
+        );
+
+        rust_i18n::set_translation("en", "custom.greeting", "Hi, %{name}");
+        assert!(!crate::_rust_i18n_inlined());
+        assert_eq!(
+            t!("custom.greeting", locale = "en", name = "Jason"),
+            "Hi, Jason"
+        );
+
+        assert!(rust_i18n::remove_translation("en", "custom.greeting"));
+        assert_eq!(
+            t!("custom.greeting", locale = "en", name = "Jason"),
+            "Hello, Jason!!!"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "phf")]
+
+rust_i18n::i18n!("./tests/locales", fallback = "en", codegen = "phf");
+
+mod hashmap {
+    rust_i18n::i18n!("./tests/locales", fallback = "en");
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_i18n::t;
+
+    #[test]
+    fn test_t() {
+        assert_eq!(t!("hello", locale = "en"), "Bar - Hello, World!");
+        assert_eq!(t!("hello", locale = "zh-CN"), "Bar - 你好世界！");
+        assert_eq!(
+            t!("messages.hello", locale = "zh-CN", name = "Jason"),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            t!("missing.default", locale = "zh-CN"),
+            "This is missing key fallbacked to en."
+        );
+        assert_eq!(t!("not.exists", locale = "en"), "not.exists");
+    }
+
+    #[test]
+    fn test_backend() {
+        let backend = crate::_rust_i18n_backend();
+        assert!(backend
+            .available_locales()
+            .windows(2)
+            .all(|locales| locales[0] < locales[1]));
+        assert!(backend.available_locales().contains(&"zh-CN".into()));
+        assert_eq!(backend.translate("x-unknown", "hello"), None);
+
+        let messages = backend.messages_for_locale("zh-CN").unwrap();
+        assert!(messages.contains(&("hello".into(), "Bar - 你好世界！".into())));
+        assert!(backend.messages_for_locale("x-unknown").is_none());
+    }
+
+    #[test]
+    fn test_same_as_hashmap() {
+        let fixtures = rust_i18n_support::load_locales("./tests/locales", |_| false);
+        let phf = crate::_rust_i18n_backend();
+        let hashmap = crate::hashmap::_rust_i18n_backend();
+
+        let mut locales = phf.available_locales();
+        locales.sort();
+        assert_eq!(locales, hashmap.available_locales());
+        assert!(locales.iter().eq(fixtures.keys()));
+
+        for (locale, texts) in &fixtures {
+            let sorted = |mut messages: Vec<(_, _)>| {
+                messages.sort();
+                messages
+            };
+            let phf_messages = sorted(phf.messages_for_locale(locale).unwrap());
+            let hashmap_messages = sorted(hashmap.messages_for_locale(locale).unwrap());
+            assert_eq!(phf_messages, hashmap_messages);
+            assert_eq!(
+                phf_messages,
+                texts
+                    .iter()
+                    .map(|(key, text)| (key.into(), text.into()))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
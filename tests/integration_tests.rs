@@ -46,7 +46,9 @@ rust_i18n::i18n!(
 
 #[cfg(test)]
 mod tests {
-    use rust_i18n::t;
+    use std::borrow::Cow;
+
+    use rust_i18n::{t, t_lazy};
     use rust_i18n_support::load_locales;
 
     mod test0 {
@@ -99,6 +101,138 @@ mod tests {
 
     mod test5 {
         rust_i18n::i18n!();
+
+        #[test]
+        fn test_empty() {
+            assert!(crate::tests::test5::_rust_i18n_available_locales().is_empty());
+            assert_eq!(
+                crate::tests::test5::_rust_i18n_translate("en", "hello"),
+                "en.hello"
+            );
+        }
+    }
+
+    mod test6 {
+        rust_i18n::i18n!("./tests/locales", locales = ["en", "zh-CN"]);
+
+        #[test]
+        fn test_locales_subset() {
+            assert_eq!(
+                crate::tests::test6::_rust_i18n_available_locales(),
+                &["en", "zh-CN"]
+            );
+            assert_eq!(
+                crate::tests::test6::_rust_i18n_translate("zh-CN", "hello"),
+                "Bar - 你好世界！"
+            );
+            assert_eq!(
+                crate::tests::test6::_rust_i18n_translate("de", "hello"),
+                "de.hello"
+            );
+        }
+    }
+
+    mod test7 {
+        rust_i18n::i18n!("./tests/locales", fallback = "en", shared = true);
+
+        pub mod shared {
+            rust_i18n::i18n!("./tests/locales", fallback = "en", shared = true);
+        }
+
+        #[test]
+        fn test_shared() {
+            assert_eq!(
+                crate::tests::test7::_rust_i18n_translate("zh-CN", "hello"),
+                "Bar - 你好世界！"
+            );
+            assert_eq!(
+                crate::tests::test7::shared::_rust_i18n_translate("en", "missing.default"),
+                "This is missing key fallbacked to en."
+            );
+
+            let backend = rust_i18n::_rust_i18n_shared_backend("test7", || {
+                Box::new(rust_i18n::SimpleBackend::new())
+            });
+            let other = rust_i18n::_rust_i18n_shared_backend("test7", || unreachable!());
+            assert!(std::ptr::addr_eq(backend, other));
+        }
+    }
+
+    pub mod test8 {
+        rust_i18n::i18n!("./tests/locales", fallback = "en");
+    }
+
+    #[test]
+    fn test_extend_with_backend() {
+        use rust_i18n::{BackendMut, OverrideBackend};
+        use std::sync::LazyLock;
+
+        static TEST8: LazyLock<OverrideBackend> = LazyLock::new(OverrideBackend::new);
+        static OTHER: LazyLock<OverrideBackend> = LazyLock::new(OverrideBackend::new);
+        assert!(rust_i18n::extend!(test8, &*TEST8));
+        assert!(!rust_i18n::extend!(test8, &*OTHER));
+        assert!(!rust_i18n::extend!(test8));
+
+        OTHER.set_translation("en", "test8.hello", "Ignored");
+        TEST8.set_translation("en", "test8.hello", "Howdy");
+        TEST8.set_translation("eo", "test8.hello", "Saluton");
+        TEST8.set_translation("en", "hello", "Not namespaced");
+        assert_eq!(test8::_rust_i18n_translate("en", "hello"), "Howdy");
+        assert_eq!(test8::_rust_i18n_translate("eo", "hello"), "Saluton");
+        assert!(test8::_rust_i18n_available_locales().contains(&"eo".into()));
+        assert_eq!(
+            test1::_rust_i18n_translate("en", "hello"),
+            "Bar - Hello, World!"
+        );
+
+        assert!(TEST8.remove_translation("en", "test8.hello"));
+        assert_eq!(
+            test8::_rust_i18n_translate("en", "hello"),
+            "Bar - Hello, World!"
+        );
+    }
+
+    mod test9 {
+        rust_i18n::i18n!("./tests/locales", fallback = "en");
+
+        #[test]
+        fn test_init_in_with_locale() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            use std::sync::Arc;
+
+            rust_i18n::set_locale("en");
+            let changes = Arc::new(AtomicUsize::new(0));
+            let subscription = rust_i18n::on_locale_change({
+                let changes = changes.clone();
+                move |_, _| {
+                    changes.fetch_add(1, Ordering::SeqCst);
+                }
+            });
+
+            // The first lookup initializes the backend
+            let text = rust_i18n::with_locale("zh-CN", || {
+                _rust_i18n_translate(&rust_i18n::locale(), "hello")
+            });
+            subscription.unsubscribe();
+
+            assert_eq!(text, "Bar - 你好世界！");
+            assert_eq!(&*rust_i18n::locale(), "en");
+            assert_eq!(changes.load(Ordering::SeqCst), 0);
+        }
+    }
+
+    mod test10 {
+        rust_i18n::i18n!("./tests/locales", fallback = "en", negotiate = true);
+
+        #[test]
+        fn test_negotiate() {
+            assert_eq!(
+                _rust_i18n_translate("zh-Hans-CN", "hello"),
+                "Bar - 你好世界！"
+            );
+            assert_eq!(_rust_i18n_translate("zh-*-CN", "hello"), "Bar - 你好世界！");
+            assert_eq!(_rust_i18n_translate("fr", "hello"), "Bar - Hello, World!");
+        }
     }
 
     #[test]
@@ -133,6 +267,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_locales() {
+        let locales = rust_i18n::locales!();
+        assert_eq!(locales.len(), 10);
+        assert_eq!(locales[1].code, "en");
+        assert_eq!(locales[1].native_name, Some("English"));
+        assert_eq!(locales[9].code, "zh-CN");
+        assert_eq!(locales[9].region.as_deref(), Some("CN"));
+        assert_eq!(locales[9].native_name, Some("简体中文"));
+        assert_eq!(locales[9].direction, rust_i18n::TextDirection::Ltr);
+    }
+
     #[test]
     fn test_t() {
         rust_i18n::set_locale("en");
@@ -159,6 +305,17 @@ mod tests {
         assert_eq!(t!("messages.hello", name = "world"), "Hello, world!");
     }
 
+    #[test]
+    fn test_t_borrowed() {
+        let text: Cow<'static, str> = t!("hello", locale = "en");
+        assert!(matches!(text, Cow::Borrowed("Bar - Hello, World!")));
+        assert!(matches!(
+            t!("missing.default", locale = "zh-CN"),
+            Cow::Borrowed("This is missing key fallbacked to en.")
+        ));
+        assert!(matches!(t!("not.exists", locale = "en"), Cow::Borrowed(_)));
+    }
+
     #[test]
     fn test_t_with_tt_val() {
         rust_i18n::set_locale("en");
@@ -180,6 +337,32 @@ mod tests {
         assert_eq!(t!("messages.other", count = a / 2), "You have 50 messages.");
     }
 
+    #[test]
+    fn test_t_with_display_args() {
+        struct Name(&'static str);
+
+        impl std::fmt::Display for Name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "<{}>", self.0)
+            }
+        }
+
+        let name = Name("Jason");
+        assert_eq!(
+            t!("messages.hello", locale = "en", name = name),
+            "Hello, <Jason>!"
+        );
+        assert_eq!(
+            t!("messages.hello", locale = "en", name = Name("Lee")),
+            "Hello, <Lee>!"
+        );
+        assert_eq!(
+            t!("not.exists %{name}", locale = "en", name = name),
+            "not.exists <Jason>"
+        );
+        assert_eq!(name.0, "Jason");
+    }
+
     #[test]
     fn test_t_with_locale_and_args() {
         rust_i18n::set_locale("en");
@@ -203,6 +386,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_t_with_locale_type() {
+        rust_i18n::set_locale("en");
+
+        let locale: rust_i18n::Locale = "zh_cn".parse().unwrap();
+        assert_eq!(t!("hello", locale = locale), "Bar - 你好世界！");
+        assert_eq!(
+            t!("messages.hello", locale = &locale, name = "Jason"),
+            "你好，Jason！"
+        );
+
+        rust_i18n::set_locale(&locale);
+        assert_eq!(t!("hello"), "Bar - 你好世界！");
+        rust_i18n::set_locale("en");
+    }
+
+    #[test]
+    fn test_t_with_locale_range() {
+        rust_i18n::set_locale("en");
+
+        assert_eq!(t!("hello", locale = "zh-*-CN"), "Bar - 你好世界！");
+        // Not negotiated without `negotiate = true`, the configured fallback is used
+        assert_eq!(t!("hello", locale = "zh-Hans-CN"), "Bar - Hello, World!");
+        assert_eq!(
+            rust_i18n::negotiate(&["zh-Hans-CN", "en"], &rust_i18n::available_locales!()),
+            Some("zh-CN")
+        );
+    }
+
     #[test]
     fn test_t_with_hash_args() {
         rust_i18n::set_locale("en");
@@ -223,6 +435,24 @@ mod tests {
         assert_eq!(t!("messages.user.title"), "Message User Title");
     }
 
+    #[test]
+    fn test_set_translation() {
+        assert_eq!(t!("hello", locale = "fr"), "Bar - Hello, World!");
+
+        rust_i18n::set_translation("fr", "hello", "Salut !");
+        rust_i18n::set_translation("fr", "overrides.only", "Nouveau %{name}");
+        assert_eq!(t!("hello", locale = "fr"), "Salut !");
+        assert_eq!(
+            t!("overrides.only", locale = "fr", name = "texte"),
+            "Nouveau texte"
+        );
+
+        assert!(rust_i18n::remove_translation("fr", "hello"));
+        assert!(rust_i18n::remove_translation("fr", "overrides.only"));
+        assert!(!rust_i18n::remove_translation("fr", "hello"));
+        assert_eq!(t!("hello", locale = "fr"), "Bar - Hello, World!");
+    }
+
     #[test]
     fn test_support_expr() {
         rust_i18n::set_locale("en");
@@ -329,6 +559,316 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_relative() {
+        use std::time::Duration;
+
+        assert_eq!(
+            rust_i18n::format_relative!(Duration::from_secs(180), locale = "en"),
+            "3 minutes ago"
+        );
+        assert_eq!(
+            rust_i18n::format_relative!(Duration::from_secs(180), locale = "zh-CN"),
+            "3 分钟前"
+        );
+        assert_eq!(
+            rust_i18n::format_relative!(2 * 24 * 60 * 60, locale = "zh-CN"),
+            "2 天后"
+        );
+        assert_eq!(rust_i18n::format_relative!(0, locale = "zh-CN"), "刚刚");
+        assert!(!rust_i18n::format_relative!(Duration::from_secs(1)).is_empty());
+        assert_eq!(
+            rust_i18n::format_relative!(-3600, locale = "zh-CN"),
+            "1 hour ago"
+        );
+    }
+
+    #[test]
+    fn test_t_with_format_hints() {
+        assert_eq!(
+            t!(
+                "Downloaded %{size:bytes} (%{progress:percent})",
+                locale = "en",
+                size = 1_500_000,
+                progress = 0.5
+            ),
+            "Downloaded 1.5 MB (50%)"
+        );
+        assert_eq!(
+            t!(
+                "%{size:binary_bytes}, %{distance:distance}",
+                locale = "de",
+                size = 1536,
+                distance = 2500
+            ),
+            "1,5 KiB, 2,5 km"
+        );
+        assert_eq!(
+            t!(
+                "%{distance:distance}",
+                locale = "en-US",
+                distance = 3_218.688
+            ),
+            "2 mi"
+        );
+        assert_eq!(
+            t!(
+                "%{name:bytes}, %{name:unknown}",
+                locale = "en",
+                name = "Foo"
+            ),
+            "Foo, Foo"
+        );
+    }
+
+    #[test]
+    fn test_t_with_bidi_isolation() {
+        assert_eq!(t!("שלום %{name}", locale = "he", name = "Bob"), "שלום Bob");
+
+        rust_i18n::set_bidi_isolation(true);
+        assert_eq!(
+            t!("שלום %{name}", locale = "he", name = "Bob"),
+            "שלום \u{2068}Bob\u{2069}"
+        );
+        assert_eq!(
+            t!("%{count} ملف", locale = "ar-EG", count = 3),
+            "\u{2068}3\u{2069} ملف"
+        );
+        assert_eq!(
+            t!("Hello %{name}", locale = "en", name = "Bob"),
+            "Hello Bob"
+        );
+        rust_i18n::set_bidi_isolation(false);
+    }
+
+    #[test]
+    fn test_set_locale_from_system() {
+        rust_i18n::set_locale("en");
+        std::env::set_var("LC_ALL", "ja_JP.UTF-8");
+        assert_eq!(rust_i18n::set_locale_from_system!().as_deref(), Some("ja"));
+        assert_eq!(&*rust_i18n::locale(), "ja");
+
+        std::env::set_var("LC_ALL", "pt_BR.UTF-8");
+        assert_eq!(rust_i18n::set_locale_from_system!().as_deref(), Some("pt"));
+
+        std::env::set_var("LC_ALL", "tlh");
+        assert_eq!(rust_i18n::set_locale_from_system!(), None);
+        assert_eq!(&*rust_i18n::locale(), "pt");
+
+        std::env::remove_var("LC_ALL");
+        rust_i18n::set_locale("en");
+    }
+
+    #[test]
+    fn test_t_with_count_intervals() {
+        assert_eq!(
+            t!("inbox", locale = "en", count = 0),
+            "Your inbox is empty."
+        );
+        assert_eq!(
+            t!("inbox", locale = "en", count = 1),
+            "You have one message."
+        );
+        assert_eq!(
+            t!("inbox", locale = "en", count = 25),
+            "You have 25 messages."
+        );
+        assert_eq!(t!("inbox", locale = "zh-CN", count = 3), "你有 3 条消息。");
+        // No interval of zh-CN contains 1
+        assert_eq!(t!("inbox", locale = "zh-CN", count = 1), "inbox");
+        assert_eq!(t!("inbox", locale = "en", count = "many"), "inbox");
+    }
+
+    #[test]
+    fn test_t_with_escaped_patterns() {
+        assert_eq!(
+            t!("syntax", locale = "en", name = "Jason", example = "Hi"),
+            "Write %{name} to insert the name, e.g. Hi"
+        );
+        assert_eq!(
+            t!("syntax", locale = "zh-CN"),
+            "写 %{name} 插入名字，%{@brand.name} 是引用"
+        );
+        assert_eq!(t!("100%% {not} %%{a}"), "100%% {not} %{a}");
+        assert_eq!(t!("%%{a} %{a}", a = 1), "%{a} 1");
+        assert_eq!(t!("50% {a} %{a}", a = 1), "50% {a} 1");
+    }
+
+    #[test]
+    fn test_t_with_references() {
+        assert_eq!(t!("brand.name", locale = "en"), "Acme Cloud");
+        assert_eq!(
+            t!("brand.welcome", locale = "en", name = "Jason"),
+            "Welcome to Acme Cloud, Jason!"
+        );
+        // `brand.edition` falls back to en
+        assert_eq!(
+            t!("brand.welcome", locale = "zh-CN", name = "Jason"),
+            "欢迎使用艾克美 Cloud，Jason！"
+        );
+        assert_eq!(t!("brand.loop", locale = "en"), "Loop Loop %{@brand.loop}");
+    }
+
+    #[test]
+    fn test_with_locale() {
+        use std::{
+            future::Future,
+            pin::pin,
+            task::{Context, Poll, Waker},
+        };
+
+        rust_i18n::set_locale("en");
+        let text = rust_i18n::with_locale("zh-CN", || {
+            let nested = rust_i18n::with_locale("en", || t!("hello"));
+            assert_eq!(nested, "Bar - Hello, World!");
+            t!("hello")
+        });
+        assert_eq!(text, "Bar - 你好世界！");
+        assert_eq!(t!("hello"), "Bar - Hello, World!");
+
+        let mut future = pin!(rust_i18n::with_locale_async("zh-CN", async {
+            t!("hello").to_string()
+        }));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(
+            future.as_mut().poll(&mut cx),
+            Poll::Ready("Bar - 你好世界！".to_string())
+        );
+        assert_eq!(&*rust_i18n::locale(), "en");
+    }
+
+    #[test]
+    fn test_t_lazy() {
+        rust_i18n::set_locale("en");
+        let name = String::from("Jason");
+        let hello = t_lazy!("messages.hello", name = name);
+        let inbox = t_lazy!("inbox", count = 25);
+        let fixed = t_lazy!("messages.hello", locale = "en", name = "world");
+        drop(name);
+        assert_eq!(hello.key(), "messages.hello");
+        assert_eq!(hello.to_string(), "Hello, Jason!");
+        assert_eq!(inbox.to_string(), "You have 25 messages.");
+
+        rust_i18n::set_locale("zh-CN");
+        assert_eq!(hello.to_string(), "你好，Jason！");
+        assert_eq!(inbox.to_string(), "你有 25 条消息。");
+        assert_eq!(fixed.to_string(), "Hello, world!");
+        assert_eq!(hello.to_string_in("en"), "Hello, Jason!");
+        assert_eq!(format!("{}", t_lazy!("hello")), "Bar - 你好世界！");
+        assert_eq!(t_lazy!("Missing %{x}", x = 1).to_string(), "Missing 1");
+        rust_i18n::set_locale("en");
+    }
+
+    #[test]
+    fn test_localized_error() {
+        use rust_i18n::LocalizedError;
+
+        #[allow(dead_code)]
+        #[derive(Debug, LocalizedError)]
+        #[i18n(prefix = "messages")]
+        enum Error {
+            Hello {
+                name: String,
+                #[i18n(skip)]
+                source: Option<std::io::Error>,
+            },
+            #[i18n(key = "a.very.nested.message")]
+            Nested {
+                name: &'static str,
+                msg: u32,
+            },
+            #[i18n(key = "Missing %{0} and %{1}")]
+            Missing(u8, char),
+            Zero,
+        }
+
+        let err = Error::Hello {
+            name: "Jason".into(),
+            source: None,
+        };
+        assert_eq!(err.localized_message("en"), "Hello, Jason!");
+        assert_eq!(err.localized_message("zh-CN"), "你好，Jason！");
+        assert_eq!(
+            rust_i18n::with_locale("zh-CN", || err.to_localized_string()),
+            "你好，Jason！"
+        );
+        assert_eq!(
+            Error::Nested {
+                name: "Jason",
+                msg: 3
+            }
+            .localized_message("en"),
+            "Hello, Jason. Your message is: 3"
+        );
+        // Like `t!`, the missing keys are prefixed with the locale
+        assert_eq!(
+            Error::Missing(1, 'x').localized_message("en"),
+            "en.Missing 1 and x"
+        );
+        assert_eq!(Error::Zero.localized_message("en"), "You have no messages.");
+    }
+
+    #[cfg(feature = "askama")]
+    #[test]
+    fn test_askama_filters() {
+        use askama::Template;
+
+        mod filters {
+            pub use rust_i18n::askama::*;
+        }
+
+        #[derive(Template)]
+        #[template(
+            source = r#"{{ "hello"|t }} / {{ t!("messages.hello", name = self.name) }}"#,
+            ext = "txt"
+        )]
+        struct Page<'a> {
+            name: &'a str,
+        }
+
+        rust_i18n::askama::set_translator(rust_i18n::translator!());
+        let page = Page { name: "Jason" };
+        assert_eq!(
+            rust_i18n::with_locale("en", || page.render().unwrap()),
+            "Bar - Hello, World! / Hello, Jason!"
+        );
+        let values: (&str, &dyn std::any::Any) = ("locale", &"zh-CN");
+        assert_eq!(
+            rust_i18n::with_locale("en", || page.render_with_values(&values).unwrap()),
+            "Bar - 你好世界！ / Hello, Jason!"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_translated() {
+        use rust_i18n::serde_t::Translated;
+
+        #[derive(serde::Serialize)]
+        struct Response {
+            #[serde(with = "rust_i18n::serde_t")]
+            title: String,
+            label: Translated<&'static str>,
+            missing: Translated<String>,
+        }
+
+        let response = Response {
+            title: "hello".into(),
+            label: Translated("messages.hello"),
+            missing: Translated("messages.missing".into()),
+        };
+        rust_i18n::serde_t::set_translator(rust_i18n::translator!());
+        let json = rust_i18n::with_locale("zh-CN", || serde_json::to_string(&response).unwrap());
+        assert_eq!(
+            json,
+            r#"{"title":"Bar - 你好世界！","label":"你好，%{name}！","missing":"messages.missing"}"#
+        );
+        assert_eq!(
+            rust_i18n::with_locale("en", || response.label.to_string()),
+            "Hello, %{name}!"
+        );
+    }
+
     // https://github.com/longbridge/rust-i18n/issues/87
     #[test]
     fn test_set_locale_on_initialize() {
@@ -0,0 +1,47 @@
+#![cfg(feature = "wasm")]
+
+use std::sync::LazyLock;
+
+use rust_i18n::FetchBackend;
+
+static BACKEND: LazyLock<FetchBackend> = LazyLock::new(|| {
+    FetchBackend::from_slice(
+        br#"{ "fr": { "hello": "Bonjour", "messages": { "hello": "Bonjour, %{name}!" } } }"#,
+    )
+    .unwrap()
+});
+
+rust_i18n::i18n!(
+    "./tests/locales",
+    fallback = "en",
+    backend = BACKEND.clone()
+);
+
+#[cfg(test)]
+mod tests {
+    use rust_i18n::{set_locale, t};
+
+    use super::BACKEND;
+
+    // Like on `wasm32-unknown-unknown`, everything runs on the current thread:
+    // nothing here spawns a thread or blocks on another one.
+    #[test]
+    fn test_t_without_threads() {
+        set_locale("fr");
+        assert_eq!(&*rust_i18n::locale(), "fr");
+        assert_eq!(t!("hello"), "Bonjour");
+        assert_eq!(t!("messages.hello", name = "Jason"), "Bonjour, Jason!");
+        assert_eq!(t!("hello", locale = "en"), "Bar - Hello, World!");
+
+        BACKEND
+            .add_locale("de", br#"{ "hello": "Hallo" }"#)
+            .unwrap();
+        set_locale("de");
+        assert_eq!(t!("hello"), "Hallo");
+        // Missing in the fetched locale, falls back to `en`
+        assert_eq!(t!("messages.hello", name = "Jason"), "Hello, Jason!");
+
+        set_locale("en");
+        assert_eq!(t!("hello"), "Bar - Hello, World!");
+    }
+}